@@ -49,10 +49,11 @@ impl App {
     fn handle_messages(&mut self, receiver: &mpsc::Receiver<Message>) {
         if let Ok(message) = receiver.recv() {
             match message {
-                Message::Input(key) => match key {
-                    Key::Char('q') => self.exit(),
-                    _ => {}
-                },
+                Message::Input(key) => {
+                    if key == Key::Char('q') {
+                        self.exit()
+                    }
+                }
                 Message::Resize => {}
             }
         }
@@ -1,9 +1,10 @@
 use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
 
+#[allow(dead_code)]
 pub struct Window {
     active: bool,
 }
 
 impl Widget for &Window {
-    fn render(self, area: Rect, buf: &mut Buffer) {}
+    fn render(self, _area: Rect, _buf: &mut Buffer) {}
 }
@@ -0,0 +1,33 @@
+use std::fs;
+
+use sha2::{Digest, Sha256};
+
+const DMI_ID_DIR: &str = "/sys/class/dmi/id";
+// drives and add-in cards come and go on a bench, so only identifiers of the machine
+// itself are used
+const DMI_ID_FIELDS: [&str; 4] = [
+    "product_uuid",
+    "product_serial",
+    "board_serial",
+    "sys_vendor",
+];
+
+/// Identifies the machine the client runs on, for binding a station credential to it.
+/// `None` if the firmware exposes no identifiers, reading them usually requires root.
+pub fn get_hardware_fingerprint() -> Option<String> {
+    let values = DMI_ID_FIELDS.map(|field| {
+        fs::read_to_string(format!("{DMI_ID_DIR}/{field}"))
+            .map(|value| value.trim().to_string())
+            .unwrap_or_default()
+    });
+    if values[..3].iter().all(String::is_empty) {
+        return None;
+    }
+    let mut hasher = Sha256::new();
+    for value in values {
+        hasher.update(value.as_bytes());
+        hasher.update([0]);
+    }
+    let digest = hasher.finalize();
+    Some(digest.iter().map(|byte| format!("{byte:02x}")).collect())
+}
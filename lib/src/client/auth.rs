@@ -0,0 +1,179 @@
+use base64::{Engine, prelude::BASE64_STANDARD};
+use uuid::Uuid;
+
+use crate::schemas::{
+    mfa::{MfaChallengeResponse, PostMfaVerifyRequest},
+    oidc::GetOidcResponse,
+    password_reset::{PostPasswordResetConfirmRequest, PostPasswordResetRequest},
+    token::{LoginResponse, PostSwitchOrganizationRequest, RefreshResponse},
+};
+
+use super::{
+    ApiClient, Credentials, Error, HttpBackend, HttpResponse, Method, REFRESH_TOKEN_COOKIE,
+    error_for_status,
+};
+
+const ACCEPTED: u16 = 202;
+
+pub enum LoginOutcome {
+    LoggedIn,
+    /// The password was right, the login completes with [`ApiClient::verify_mfa`].
+    MfaRequired(MfaChallengeResponse),
+}
+
+impl<B: HttpBackend> ApiClient<B> {
+    pub async fn login(&self, email: &str, password: &str) -> Result<LoginOutcome, Error> {
+        let encoded_credentials = BASE64_STANDARD.encode(format!("{email}:{password}"));
+        let response = self
+            .send_public(
+                Method::Post,
+                "/auth/login",
+                None,
+                Some(("Authorization", format!("Basic {encoded_credentials}"))),
+            )
+            .await?;
+        if response.status == ACCEPTED {
+            return Ok(LoginOutcome::MfaRequired(serde_json::from_slice(
+                &response.body,
+            )?));
+        }
+        let login_response: LoginResponse = serde_json::from_slice(&response.body)?;
+        self.set_tokens(
+            login_response.access_token,
+            Some(login_response.refresh_token),
+        );
+        Ok(LoginOutcome::LoggedIn)
+    }
+
+    pub async fn verify_mfa(&self, challenge_token: String, code: String) -> Result<(), Error> {
+        let body = serde_json::to_string(&PostMfaVerifyRequest {
+            challenge_token,
+            code,
+        })?;
+        let response = self
+            .send_public(Method::Post, "/auth/mfa/verify", Some(body), None)
+            .await?;
+        let login_response: LoginResponse = serde_json::from_slice(&response.body)?;
+        self.set_tokens(
+            login_response.access_token,
+            Some(login_response.refresh_token),
+        );
+        Ok(())
+    }
+
+    /// Exchanges the refresh token for new tokens. A rejected refresh token logs the
+    /// client out.
+    pub async fn refresh(&self) -> Result<(), Error> {
+        let _refresh_guard = self.refresh_lock.lock().await;
+        self.refresh_tokens().await
+    }
+
+    /// [`ApiClient::refresh`] for callers that already hold the refresh lock.
+    pub(super) async fn refresh_tokens(&self) -> Result<(), Error> {
+        let result = self
+            .send_public(Method::Post, "/auth/refresh", None, self.refresh_cookie())
+            .await;
+        let response = match result {
+            Ok(response) => response,
+            Err(error) => {
+                if let Error::Api { .. } = error {
+                    self.clear_credentials();
+                }
+                return Err(error);
+            }
+        };
+        let refresh_response: RefreshResponse = serde_json::from_slice(&response.body)?;
+        self.set_tokens(
+            refresh_response.access_token,
+            Some(refresh_response.refresh_token),
+        );
+        Ok(())
+    }
+
+    /// Makes the session act for another organization of the user, which later
+    /// refreshes keep.
+    pub async fn switch_organization(&self, organization_id: Uuid) -> Result<(), Error> {
+        let _refresh_guard = self.refresh_lock.lock().await;
+        let body = serde_json::to_string(&PostSwitchOrganizationRequest { organization_id })?;
+        let response = self
+            .send_public(
+                Method::Post,
+                "/auth/switch-organization",
+                Some(body),
+                self.refresh_cookie(),
+            )
+            .await?;
+        let refresh_response: RefreshResponse = serde_json::from_slice(&response.body)?;
+        self.set_tokens(
+            refresh_response.access_token,
+            Some(refresh_response.refresh_token),
+        );
+        Ok(())
+    }
+
+    /// Ends the session on the server. The client forgets its tokens even when that fails.
+    pub async fn logout(&self) -> Result<(), Error> {
+        let result = self
+            .send_public(Method::Post, "/auth/logout", None, self.refresh_cookie())
+            .await;
+        self.clear_credentials();
+        result.map(|_| ())
+    }
+
+    pub async fn get_oidc(&self) -> Result<GetOidcResponse, Error> {
+        let response = self
+            .send_public(Method::Get, "/auth/oidc", None, None)
+            .await?;
+        Ok(serde_json::from_slice(&response.body)?)
+    }
+
+    pub async fn request_password_reset(&self, email: String) -> Result<(), Error> {
+        let body = serde_json::to_string(&PostPasswordResetRequest { email })?;
+        self.send_public(Method::Post, "/auth/password-reset", Some(body), None)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn confirm_password_reset(
+        &self,
+        token: String,
+        new_password: String,
+    ) -> Result<(), Error> {
+        let body = serde_json::to_string(&PostPasswordResetConfirmRequest {
+            token,
+            new_password,
+        })?;
+        self.send_public(
+            Method::Post,
+            "/auth/password-reset/confirm",
+            Some(body),
+            None,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Browsers ignore the header and send the cookie that the server set themselves.
+    fn refresh_cookie(&self) -> Option<(&'static str, String)> {
+        match self.credentials() {
+            Credentials::Tokens {
+                refresh_token: Some(refresh_token),
+                ..
+            } => Some(("Cookie", format!("{REFRESH_TOKEN_COOKIE}={refresh_token}"))),
+            _ => None,
+        }
+    }
+
+    /// Sends a request of the auth routes, which don't take the credentials of the client.
+    async fn send_public(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<String>,
+        header: Option<(&'static str, String)>,
+    ) -> Result<HttpResponse, Error> {
+        let mut request = self.build_request(method, path, body, &Credentials::None);
+        request.headers.extend(header);
+        error_for_status(self.backend.send(request).await?)
+    }
+}
@@ -0,0 +1,36 @@
+use gloo_net::http::{Method as GlooMethod, RequestBuilder};
+
+use super::{Error, HttpBackend, HttpRequest, HttpResponse, Method};
+
+/// Backend of the browser, on top of `fetch`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GlooBackend;
+
+impl HttpBackend for GlooBackend {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, Error> {
+        let method = match request.method {
+            Method::Get => GlooMethod::GET,
+            Method::Post => GlooMethod::POST,
+            Method::Patch => GlooMethod::PATCH,
+            Method::Delete => GlooMethod::DELETE,
+        };
+        let mut builder = RequestBuilder::new(&request.url).method(method);
+        for (name, value) in &request.headers {
+            builder = builder.header(name, value);
+        }
+        let request = match request.body {
+            Some(body) => builder.body(body),
+            None => builder.build(),
+        }
+        .map_err(transport_error)?;
+        let response = request.send().await.map_err(transport_error)?;
+        Ok(HttpResponse {
+            status: response.status(),
+            body: response.binary().await.map_err(transport_error)?,
+        })
+    }
+}
+
+fn transport_error(error: gloo_net::Error) -> Error {
+    Error::Transport(error.to_string())
+}
@@ -0,0 +1,39 @@
+use uuid::Uuid;
+
+use crate::schemas::image::{GetImagesResponse, PostImageVariantRequest, PostImageVariantResponse};
+
+use super::{ApiClient, Error, HttpBackend, Method};
+
+impl<B: HttpBackend> ApiClient<B> {
+    pub async fn get_images(&self) -> Result<GetImagesResponse, Error> {
+        self.get("/images").await
+    }
+
+    pub async fn get_image_variants(&self, id: Uuid) -> Result<GetImagesResponse, Error> {
+        self.get(&format!("/images/{id}/variants")).await
+    }
+
+    pub async fn post_image_variant(
+        &self,
+        id: Uuid,
+        request: &PostImageVariantRequest,
+    ) -> Result<PostImageVariantResponse, Error> {
+        self.post(&format!("/images/{id}/variants"), request).await
+    }
+
+    /// Checksums of every image in the format `sha256sum --check` reads.
+    pub async fn get_image_checksums(&self) -> Result<String, Error> {
+        let response = self
+            .execute(Method::Get, "/images/SHA256SUMS", None)
+            .await?;
+        Ok(String::from_utf8_lossy(&response.body).into_owned())
+    }
+
+    /// The whole ISO of the image, held in memory.
+    pub async fn download_image(&self, id: Uuid) -> Result<Vec<u8>, Error> {
+        let response = self
+            .execute(Method::Get, &format!("/images/{id}/download"), None)
+            .await?;
+        Ok(response.body)
+    }
+}
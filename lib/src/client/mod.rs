@@ -0,0 +1,293 @@
+//! Typed client of the server API. It is generic over the [`HttpBackend`] that sends the
+//! requests, so the same client runs in the browser and natively.
+
+use std::{
+    fmt,
+    future::Future,
+    sync::{Arc, Mutex},
+};
+
+use async_lock::Mutex as AsyncMutex;
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::schemas::error::ErrorResponse;
+
+mod auth;
+#[cfg(feature = "client-gloo")]
+mod gloo_backend;
+mod images;
+mod reports;
+#[cfg(feature = "client-reqwest")]
+mod reqwest_backend;
+mod users;
+
+pub use auth::LoginOutcome;
+#[cfg(feature = "client-gloo")]
+pub use gloo_backend::GlooBackend;
+#[cfg(feature = "client-reqwest")]
+pub use reqwest_backend::ReqwestBackend;
+
+pub const API_KEY_HEADER: &str = "x-api-key";
+pub const REFRESH_TOKEN_COOKIE: &str = "refresh_token";
+pub const STATION_FINGERPRINT_HEADER: &str = "x-station-fingerprint";
+
+const API_PATH: &str = "/api";
+const UNAUTHORIZED: u16 = 401;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    Get,
+    Post,
+    Patch,
+    Delete,
+}
+
+#[derive(Debug, Clone)]
+pub struct HttpRequest {
+    pub method: Method,
+    pub url: String,
+    pub headers: Vec<(&'static str, String)>,
+    pub body: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+/// Sends requests for the [`ApiClient`].
+pub trait HttpBackend {
+    /// Only fails when there is no response at all, error statuses are returned as is.
+    fn send(&self, request: HttpRequest) -> impl Future<Output = Result<HttpResponse, Error>>;
+}
+
+#[derive(Debug)]
+pub enum Error {
+    /// The request didn't get a response.
+    Transport(String),
+    /// The server answered with an error status. `error` is `None` when the body isn't
+    /// an error envelope, e.g. for errors of a proxy in front of the server.
+    Api {
+        status: u16,
+        error: Option<ErrorResponse>,
+    },
+    /// A successful response whose body doesn't match the expected schema.
+    Decode(serde_json::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Transport(message) => write!(f, "request failed: {message}"),
+            Self::Api {
+                error: Some(error), ..
+            } => write!(f, "{}", error.message),
+            Self::Api {
+                status,
+                error: None,
+            } => write!(f, "request failed with status {status}"),
+            Self::Decode(error) => write!(f, "unexpected response body: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<serde_json::Error> for Error {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Decode(value)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+enum Credentials {
+    #[default]
+    None,
+    /// The refresh token is only known natively, browsers keep it in a cookie that
+    /// scripts can't read and send it on their own.
+    Tokens {
+        access_token: String,
+        refresh_token: Option<String>,
+    },
+    ApiKey {
+        key: String,
+        hardware_fingerprint: Option<String>,
+    },
+}
+
+/// Clones share their credentials, so a refresh through one of them updates all.
+#[derive(Clone)]
+pub struct ApiClient<B> {
+    backend: B,
+    base_url: String,
+    credentials: Arc<Mutex<Credentials>>,
+    /// Held while refreshing. Refresh tokens are single use, so requests that find their
+    /// access token expired at the same time must not each spend it.
+    refresh_lock: Arc<AsyncMutex<()>>,
+}
+
+impl<B: HttpBackend> ApiClient<B> {
+    /// `base_url` is the origin of the server without the `/api` prefix, empty in the
+    /// browser to send requests to the origin of the page.
+    pub fn new(backend: B, base_url: impl Into<String>) -> Self {
+        Self {
+            backend,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            credentials: Arc::default(),
+            refresh_lock: Arc::default(),
+        }
+    }
+
+    /// Authenticates with an API key instead of a login. Keys of enrolled stations
+    /// also need the hardware fingerprint of the station.
+    pub fn with_api_key(self, key: String, hardware_fingerprint: Option<String>) -> Self {
+        self.set_credentials(Credentials::ApiKey {
+            key,
+            hardware_fingerprint,
+        });
+        self
+    }
+
+    pub fn access_token(&self) -> Option<String> {
+        match self.credentials() {
+            Credentials::Tokens { access_token, .. } => Some(access_token),
+            _ => None,
+        }
+    }
+
+    /// Only known natively, see [`ApiClient::refresh`].
+    pub fn refresh_token(&self) -> Option<String> {
+        match self.credentials() {
+            Credentials::Tokens { refresh_token, .. } => refresh_token,
+            _ => None,
+        }
+    }
+
+    /// Replaces the credentials with tokens obtained outside of the client.
+    pub fn set_tokens(&self, access_token: String, refresh_token: Option<String>) {
+        self.set_credentials(Credentials::Tokens {
+            access_token,
+            refresh_token,
+        });
+    }
+
+    pub fn clear_credentials(&self) {
+        self.set_credentials(Credentials::None);
+    }
+
+    fn credentials(&self) -> Credentials {
+        self.credentials.lock().unwrap().clone()
+    }
+
+    fn set_credentials(&self, credentials: Credentials) {
+        *self.credentials.lock().unwrap() = credentials;
+    }
+
+    fn build_request(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<String>,
+        credentials: &Credentials,
+    ) -> HttpRequest {
+        let mut headers = Vec::new();
+        if body.is_some() {
+            headers.push(("Content-Type", String::from("application/json")));
+        }
+        match credentials {
+            Credentials::None => {}
+            Credentials::Tokens { access_token, .. } => {
+                headers.push(("Authorization", format!("Bearer {access_token}")));
+            }
+            Credentials::ApiKey {
+                key,
+                hardware_fingerprint,
+            } => {
+                headers.push((API_KEY_HEADER, key.clone()));
+                if let Some(hardware_fingerprint) = hardware_fingerprint {
+                    headers.push((STATION_FINGERPRINT_HEADER, hardware_fingerprint.clone()));
+                }
+            }
+        }
+        HttpRequest {
+            method,
+            url: format!("{}{API_PATH}{path}", self.base_url),
+            headers,
+            body,
+        }
+    }
+
+    /// Sends an authenticated request. An expired access token is refreshed once and
+    /// the request repeated with the new one.
+    async fn execute(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<String>,
+    ) -> Result<HttpResponse, Error> {
+        let credentials = self.credentials();
+        let request = self.build_request(method, path, body.clone(), &credentials);
+        let mut response = self.backend.send(request).await?;
+        if response.status == UNAUTHORIZED
+            && let Credentials::Tokens { access_token, .. } = &credentials
+            && self.refresh_expired(access_token).await
+        {
+            let request = self.build_request(method, path, body, &self.credentials());
+            response = self.backend.send(request).await?;
+        }
+        error_for_status(response)
+    }
+
+    /// Refreshes the tokens unless another request already replaced `expired_token`
+    /// while this one waited for the lock. `false` if there is no new token to retry with.
+    async fn refresh_expired(&self, expired_token: &str) -> bool {
+        let _refresh_guard = self.refresh_lock.lock().await;
+        match self.credentials() {
+            Credentials::Tokens { access_token, .. } if access_token != expired_token => true,
+            Credentials::Tokens { .. } => self.refresh_tokens().await.is_ok(),
+            _ => false,
+        }
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, Error> {
+        let response = self.execute(Method::Get, path, None).await?;
+        Ok(serde_json::from_slice(&response.body)?)
+    }
+
+    async fn post<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &impl Serialize,
+    ) -> Result<T, Error> {
+        let body = serde_json::to_string(body)?;
+        let response = self.execute(Method::Post, path, Some(body)).await?;
+        Ok(serde_json::from_slice(&response.body)?)
+    }
+
+    async fn patch<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &impl Serialize,
+    ) -> Result<T, Error> {
+        let body = serde_json::to_string(body)?;
+        let response = self.execute(Method::Patch, path, Some(body)).await?;
+        Ok(serde_json::from_slice(&response.body)?)
+    }
+}
+
+fn error_for_status(response: HttpResponse) -> Result<HttpResponse, Error> {
+    if response.is_success() {
+        return Ok(response);
+    }
+    Err(Error::Api {
+        status: response.status,
+        error: serde_json::from_slice(&response.body).ok(),
+    })
+}
@@ -0,0 +1,18 @@
+use uuid::Uuid;
+
+use crate::schemas::report::{GetReportResponse, PostReportRequest, PostReportResponse};
+
+use super::{ApiClient, Error, HttpBackend};
+
+impl<B: HttpBackend> ApiClient<B> {
+    pub async fn get_report(&self, id: Uuid) -> Result<GetReportResponse, Error> {
+        self.get(&format!("/reports/{id}")).await
+    }
+
+    pub async fn post_report(
+        &self,
+        request: &PostReportRequest,
+    ) -> Result<PostReportResponse, Error> {
+        self.post("/reports", request).await
+    }
+}
@@ -0,0 +1,40 @@
+use super::{Error, HttpBackend, HttpRequest, HttpResponse, Method};
+
+/// Native backend. Clones share the connection pool.
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestBackend {
+    client: reqwest::Client,
+}
+
+impl ReqwestBackend {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+}
+
+impl HttpBackend for ReqwestBackend {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, Error> {
+        let method = match request.method {
+            Method::Get => reqwest::Method::GET,
+            Method::Post => reqwest::Method::POST,
+            Method::Patch => reqwest::Method::PATCH,
+            Method::Delete => reqwest::Method::DELETE,
+        };
+        let mut builder = self.client.request(method, &request.url);
+        for (name, value) in request.headers {
+            builder = builder.header(name, value);
+        }
+        if let Some(body) = request.body {
+            builder = builder.body(body);
+        }
+        let response = builder.send().await.map_err(transport_error)?;
+        Ok(HttpResponse {
+            status: response.status().as_u16(),
+            body: response.bytes().await.map_err(transport_error)?.to_vec(),
+        })
+    }
+}
+
+fn transport_error(error: reqwest::Error) -> Error {
+    Error::Transport(error.to_string())
+}
@@ -0,0 +1,49 @@
+use uuid::Uuid;
+
+use crate::schemas::user::{
+    GetUserResponse, PatchUserRequest, PatchUserResponse, PostPasswordRequest, PostUserRequest,
+    PostUserResponse,
+};
+
+use super::{ApiClient, Error, HttpBackend, Method};
+
+impl<B: HttpBackend> ApiClient<B> {
+    pub async fn get_me(&self) -> Result<GetUserResponse, Error> {
+        self.get("/users/me").await
+    }
+
+    pub async fn get_user(&self, id: Uuid) -> Result<GetUserResponse, Error> {
+        self.get(&format!("/users/{id}")).await
+    }
+
+    pub async fn post_user(&self, request: &PostUserRequest) -> Result<PostUserResponse, Error> {
+        self.post("/users", request).await
+    }
+
+    pub async fn patch_user(
+        &self,
+        id: Uuid,
+        request: &PatchUserRequest,
+    ) -> Result<PatchUserResponse, Error> {
+        self.patch(&format!("/users/{id}"), request).await
+    }
+
+    pub async fn delete_user(&self, id: Uuid) -> Result<(), Error> {
+        self.execute(Method::Delete, &format!("/users/{id}"), None)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn post_password(&self, request: &PostPasswordRequest) -> Result<(), Error> {
+        let body = serde_json::to_string(request)?;
+        self.execute(Method::Post, "/users/me/password", Some(body))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn unlock_user(&self, id: Uuid) -> Result<(), Error> {
+        self.execute(Method::Post, &format!("/users/{id}/unlock"), None)
+            .await?;
+        Ok(())
+    }
+}
@@ -1,5 +1,8 @@
 #[cfg(feature = "audit")]
 pub mod audit;
 
+#[cfg(feature = "client")]
+pub mod client;
+
 #[cfg(feature = "schemas")]
 pub mod schemas;
@@ -0,0 +1,103 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::schemas::{device::GetDeviceResponse, report::GetReportResponse};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum BatchStatus {
+    Open,
+    InProgress,
+    Completed,
+    Cancelled,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct GetBatchResponse {
+    pub id: Uuid,
+    pub customer_reference: String,
+    pub expected_device_count: u32,
+    pub status: BatchStatus,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(transparent)]
+pub struct GetBatchesResponse(pub Vec<GetBatchResponse>);
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PostBatchRequest {
+    pub customer_reference: String,
+    pub expected_device_count: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PostBatchResponse {
+    pub id: Uuid,
+    pub customer_reference: String,
+    pub expected_device_count: u32,
+    pub status: BatchStatus,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PatchBatchRequest {
+    pub customer_reference: Option<String>,
+    pub expected_device_count: Option<u32>,
+    pub status: Option<BatchStatus>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PatchBatchResponse {
+    pub id: Uuid,
+    pub customer_reference: String,
+    pub expected_device_count: u32,
+    pub status: BatchStatus,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct DeleteBatchResponse {
+    pub id: Uuid,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct GetBatchSummaryResponse {
+    pub id: Uuid,
+    pub customer_reference: String,
+    pub status: BatchStatus,
+    pub expected_device_count: u32,
+    pub device_count: u32,
+    pub erased_device_count: u32,
+    pub failed_device_count: u32,
+    pub pending_device_count: u32,
+    pub outstanding_device_count: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct BatchCertificateEntry {
+    pub device: GetDeviceResponse,
+    pub report: Option<GetReportResponse>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct GetBatchCertificateResponse {
+    pub issued_at: DateTime<Utc>,
+    pub summary: GetBatchSummaryResponse,
+    pub entries: Vec<BatchCertificateEntry>,
+}
@@ -1,10 +1,138 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Where base images reserve room for the client config, relative to the root of the
+/// ISO. The live system finds it under `/run/archiso/bootmnt`.
+pub const CLIENT_CONFIG_PATH: &str = "open-erase/config.toml";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum Architecture {
+    X86_64,
+    Aarch64,
+}
 
 #[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct GetImageResponse {
-    pub name: String,
+    pub id: Uuid,
+    pub version: String,
+    pub architecture: Architecture,
+    pub size_bytes: u64,
+    /// Hex encoded SHA-256 of the ISO.
+    pub sha256: String,
+    pub release_notes: Option<String>,
+    pub is_active: bool,
+    /// Set on site variants, which are copies of this base image with a client config.
+    pub base_image_id: Option<Uuid>,
+    pub variant_name: Option<String>,
+    pub uploaded_at: DateTime<Utc>,
 }
 
 #[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[serde(transparent)]
 pub struct GetImagesResponse(pub Vec<GetImageResponse>);
+
+/// Multipart form that uploads an image. The file is written to disk as it arrives,
+/// so it should be the last part.
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PostImageForm {
+    pub version: String,
+    pub architecture: Architecture,
+    pub release_notes: Option<String>,
+    #[cfg_attr(feature = "openapi", schema(value_type = String, format = Binary))]
+    pub file: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PostImageResponse {
+    pub id: Uuid,
+    pub version: String,
+    pub architecture: Architecture,
+    pub size_bytes: u64,
+    pub sha256: String,
+    pub release_notes: Option<String>,
+    pub is_active: bool,
+    /// Set on site variants, which are copies of this base image with a client config.
+    pub base_image_id: Option<Uuid>,
+    pub variant_name: Option<String>,
+    pub uploaded_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PatchImageRequest {
+    pub release_notes: Option<String>,
+    /// Activating an image deactivates the previous one of its architecture.
+    pub is_active: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PatchImageResponse {
+    pub id: Uuid,
+    pub version: String,
+    pub architecture: Architecture,
+    pub size_bytes: u64,
+    pub sha256: String,
+    pub release_notes: Option<String>,
+    pub is_active: bool,
+    /// Set on site variants, which are copies of this base image with a client config.
+    pub base_image_id: Option<Uuid>,
+    pub variant_name: Option<String>,
+    pub uploaded_at: DateTime<Utc>,
+}
+
+/// Builds a site variant of a base image. `server_url` defaults to the public URL of the
+/// server. With `station_name`, a station of that name is registered in the organization
+/// and its enrollment code is written into the ISO, which then only downloads for users
+/// allowed to manage stations.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PostImageVariantRequest {
+    pub name: String,
+    pub server_url: Option<String>,
+    /// PEM encoded certificate the client trusts for `server_url`.
+    pub ca_certificate: Option<String>,
+    pub station_name: Option<String>,
+    pub default_erase_method: Option<String>,
+    /// e.g. `de_DE.UTF-8`
+    pub locale: Option<String>,
+    pub release_notes: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PostImageVariantResponse {
+    pub id: Uuid,
+    pub version: String,
+    pub architecture: Architecture,
+    pub size_bytes: u64,
+    pub sha256: String,
+    pub release_notes: Option<String>,
+    pub is_active: bool,
+    pub base_image_id: Option<Uuid>,
+    pub variant_name: Option<String>,
+    /// The station registered for `station_name`.
+    pub station_id: Option<Uuid>,
+    pub uploaded_at: DateTime<Utc>,
+}
+
+/// TOML file at [`CLIENT_CONFIG_PATH`] in site variants, padded with trailing spaces to
+/// the size of the placeholder it replaces.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClientConfig {
+    pub server_url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_certificate: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enrollment_code: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_erase_method: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+}
@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+
+/// Returned by login instead of tokens when the account has a second factor.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct MfaChallengeResponse {
+    pub challenge_token: String,
+}
+
+/// `code` is either the current TOTP code or one of the recovery codes.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PostMfaVerifyRequest {
+    pub challenge_token: String,
+    pub code: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct GetMfaResponse {
+    pub totp_enabled: bool,
+    pub recovery_codes_remaining: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PostTotpResponse {
+    /// Base32 encoded secret for authenticator apps that cannot scan `otpauth_uri`.
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PostTotpConfirmRequest {
+    pub code: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PostTotpConfirmResponse {
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PostRecoveryCodesResponse {
+    pub recovery_codes: Vec<String>,
+}
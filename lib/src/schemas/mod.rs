@@ -1,3 +1,15 @@
+pub mod api_key;
+pub mod batch;
+pub mod device;
+pub mod error;
 pub mod image;
+pub mod mfa;
+pub mod oidc;
+pub mod organization;
+pub mod password_reset;
+pub mod report;
+pub mod session;
+pub mod signing_key;
+pub mod station;
 pub mod token;
 pub mod user;
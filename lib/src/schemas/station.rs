@@ -0,0 +1,85 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct GetStationResponse {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub is_enrolled: bool,
+    pub is_disabled: bool,
+    pub enrollment_code_expires_at: Option<DateTime<Utc>>,
+    pub enrolled_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(transparent)]
+pub struct GetStationsResponse(pub Vec<GetStationResponse>);
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PostStationRequest {
+    pub name: String,
+}
+
+/// `enrollment_code` is only ever shown in this response.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PostStationResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub enrollment_code: String,
+    pub enrollment_code_expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PatchStationRequest {
+    pub name: Option<String>,
+    pub is_disabled: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PatchStationResponse {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub is_enrolled: bool,
+    pub is_disabled: bool,
+    pub enrollment_code_expires_at: Option<DateTime<Utc>>,
+    pub enrolled_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Issuing a new code revokes the credential of the station.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PostStationEnrollmentCodeResponse {
+    pub id: Uuid,
+    pub enrollment_code: String,
+    pub enrollment_code_expires_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PostEnrollRequest {
+    pub enrollment_code: String,
+    pub hardware_fingerprint: String,
+}
+
+/// `api_key` is sent in `x-api-key` together with the fingerprint in
+/// `x-station-fingerprint`.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PostEnrollResponse {
+    pub station_id: Uuid,
+    pub api_key: String,
+}
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct LoginResponse {
     pub access_token: String,
     pub refresh_token: String,
@@ -19,6 +21,7 @@ impl LoginResponse {
 }
 
 #[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct RefreshResponse {
     pub access_token: String,
     pub refresh_token: String,
@@ -35,3 +38,10 @@ impl RefreshResponse {
         }
     }
 }
+
+/// Makes the session act for another organization of its user from now on.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PostSwitchOrganizationRequest {
+    pub organization_id: Uuid,
+}
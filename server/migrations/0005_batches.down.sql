@@ -0,0 +1,11 @@
+DROP INDEX reports_device_id_idx;
+ALTER TABLE reports
+    DROP COLUMN finished_at,
+    DROP COLUMN started_at,
+    DROP COLUMN result,
+    DROP COLUMN method,
+    DROP COLUMN device_id;
+DROP TYPE erasure_result;
+DROP TABLE devices;
+DROP TABLE batches;
+DROP TYPE batch_status;
@@ -0,0 +1,62 @@
+CREATE TYPE batch_status AS ENUM ('open', 'in_progress', 'completed', 'cancelled');
+
+CREATE TABLE batches (
+    id UUID PRIMARY KEY DEFAULT uuidv7(),
+    customer_reference VARCHAR(255) NOT NULL,
+    expected_device_count INTEGER NOT NULL DEFAULT 0 CHECK (expected_device_count >= 0),
+    status batch_status NOT NULL DEFAULT 'open',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TRIGGER update_batches_updated_at
+    BEFORE UPDATE ON batches
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+CREATE TABLE devices (
+    id UUID PRIMARY KEY DEFAULT uuidv7(),
+    batch_id UUID REFERENCES batches(id) ON DELETE SET NULL,
+    serial_number VARCHAR(255) NOT NULL,
+    manufacturer VARCHAR(255),
+    model VARCHAR(255),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX devices_batch_id_idx ON devices(batch_id);
+
+CREATE TRIGGER update_devices_updated_at
+    BEFORE UPDATE ON devices
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+CREATE TYPE erasure_result AS ENUM ('passed', 'failed');
+
+ALTER TABLE reports
+    ADD COLUMN device_id UUID REFERENCES devices(id) ON DELETE CASCADE,
+    ADD COLUMN method VARCHAR(255),
+    ADD COLUMN result erasure_result,
+    ADD COLUMN started_at TIMESTAMPTZ,
+    ADD COLUMN finished_at TIMESTAMPTZ;
+
+-- Reports from before batches never recorded what was erased or how. Each gets a
+-- device of its own and counts as failed, so it can never vouch for an erasure.
+INSERT INTO devices (id, serial_number, created_at)
+    SELECT id, 'unknown', created_at FROM reports;
+
+UPDATE reports SET
+    device_id = id,
+    method = 'unknown',
+    result = 'failed',
+    started_at = created_at,
+    finished_at = created_at;
+
+ALTER TABLE reports
+    ALTER COLUMN device_id SET NOT NULL,
+    ALTER COLUMN method SET NOT NULL,
+    ALTER COLUMN result SET NOT NULL,
+    ALTER COLUMN started_at SET NOT NULL,
+    ALTER COLUMN finished_at SET NOT NULL;
+
+CREATE INDEX reports_device_id_idx ON reports(device_id);
@@ -0,0 +1,5 @@
+ALTER TABLE reports DROP COLUMN organization_id;
+ALTER TABLE devices DROP COLUMN organization_id;
+ALTER TABLE batches DROP COLUMN organization_id;
+DROP TABLE organization_members;
+DROP TABLE organizations;
@@ -0,0 +1,44 @@
+CREATE TABLE organizations (
+    id UUID PRIMARY KEY DEFAULT uuidv7(),
+    name VARCHAR(255) UNIQUE NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TRIGGER update_organizations_updated_at
+    BEFORE UPDATE ON organizations
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+CREATE TABLE organization_members (
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (organization_id, user_id)
+);
+
+CREATE INDEX organization_members_user_id_idx ON organization_members(user_id);
+
+-- existing data and the system user belong to a default organization
+INSERT INTO organizations (name) VALUES ('default');
+
+INSERT INTO organization_members (organization_id, user_id)
+SELECT organizations.id, users.id
+FROM organizations, users
+WHERE organizations.name = 'default' AND users.email = 'system';
+
+ALTER TABLE batches ADD COLUMN organization_id UUID REFERENCES organizations(id) ON DELETE CASCADE;
+ALTER TABLE devices ADD COLUMN organization_id UUID REFERENCES organizations(id) ON DELETE CASCADE;
+ALTER TABLE reports ADD COLUMN organization_id UUID REFERENCES organizations(id) ON DELETE CASCADE;
+
+UPDATE batches SET organization_id = (SELECT id FROM organizations WHERE name = 'default');
+UPDATE devices SET organization_id = (SELECT id FROM organizations WHERE name = 'default');
+UPDATE reports SET organization_id = (SELECT id FROM organizations WHERE name = 'default');
+
+ALTER TABLE batches ALTER COLUMN organization_id SET NOT NULL;
+ALTER TABLE devices ALTER COLUMN organization_id SET NOT NULL;
+ALTER TABLE reports ALTER COLUMN organization_id SET NOT NULL;
+
+CREATE INDEX batches_organization_id_idx ON batches(organization_id);
+CREATE INDEX devices_organization_id_idx ON devices(organization_id);
+CREATE INDEX reports_organization_id_idx ON reports(organization_id);
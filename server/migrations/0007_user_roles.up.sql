@@ -0,0 +1,5 @@
+CREATE TYPE user_role AS ENUM ('admin', 'operator', 'auditor', 'read_only');
+
+ALTER TABLE users ADD COLUMN role user_role NOT NULL DEFAULT 'read_only';
+
+UPDATE users SET role = 'admin' WHERE email = 'system';
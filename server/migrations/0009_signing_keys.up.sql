@@ -0,0 +1,16 @@
+CREATE TYPE signing_algorithm AS ENUM ('hs256', 'eddsa');
+
+CREATE TABLE signing_keys (
+    id UUID PRIMARY KEY DEFAULT uuidv7(),
+    algorithm signing_algorithm NOT NULL,
+    private_key BYTEA NOT NULL,
+    public_key BYTEA,
+    retires_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TRIGGER update_signing_keys_updated_at
+    BEFORE UPDATE ON signing_keys
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
@@ -0,0 +1,6 @@
+ALTER TABLE refresh_tokens
+    DROP CONSTRAINT refresh_tokens_parent_id_fkey,
+    ADD CONSTRAINT refresh_tokens_parent_id_fkey
+        FOREIGN KEY (parent_id) REFERENCES refresh_tokens(id) ON DELETE SET NULL;
+
+CREATE INDEX refresh_tokens_expires_at_idx ON refresh_tokens(expires_at);
@@ -0,0 +1,2 @@
+DROP TABLE login_throttles;
+DROP TYPE login_throttle_kind;
@@ -0,0 +1,16 @@
+CREATE TYPE login_throttle_kind AS ENUM ('account', 'ip_address');
+
+CREATE TABLE login_throttles (
+    kind login_throttle_kind NOT NULL,
+    subject VARCHAR(255) NOT NULL,
+    failed_attempts INTEGER NOT NULL DEFAULT 0,
+    locked_until TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (kind, subject)
+);
+
+CREATE TRIGGER update_login_throttles_updated_at
+    BEFORE UPDATE ON login_throttles
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
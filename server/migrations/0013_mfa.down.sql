@@ -0,0 +1,3 @@
+ALTER TABLE sessions DROP COLUMN is_mfa_verified;
+DROP TABLE recovery_codes;
+DROP TABLE totp_credentials;
@@ -0,0 +1,3 @@
+ALTER TABLE api_keys DROP COLUMN station_id;
+
+DROP TABLE stations;
@@ -0,0 +1,25 @@
+CREATE TABLE stations (
+    id UUID PRIMARY KEY DEFAULT uuidv7(),
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    -- the credential of the station acts with the role of the user who enrolled it last
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    hardware_fingerprint VARCHAR(128),
+    enrollment_code_hash VARCHAR(255) UNIQUE,
+    enrollment_code_expires_at TIMESTAMPTZ,
+    enrolled_at TIMESTAMPTZ,
+    disabled_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX stations_organization_id_idx ON stations(organization_id);
+
+CREATE TRIGGER update_stations_updated_at
+    BEFORE UPDATE ON stations
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+ALTER TABLE api_keys ADD COLUMN station_id UUID REFERENCES stations(id) ON DELETE CASCADE;
+
+CREATE INDEX api_keys_station_id_idx ON api_keys(station_id);
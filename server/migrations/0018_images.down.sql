@@ -0,0 +1,2 @@
+DROP TABLE images;
+DROP TYPE image_architecture;
@@ -0,0 +1,25 @@
+CREATE TYPE image_architecture AS ENUM ('x86_64', 'aarch64');
+
+CREATE TABLE images (
+    -- chosen by the server, the file in the images directory is named after it
+    id UUID PRIMARY KEY,
+    version VARCHAR(64) NOT NULL,
+    architecture image_architecture NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    sha256 CHAR(64) NOT NULL,
+    release_notes TEXT,
+    is_active BOOLEAN NOT NULL DEFAULT FALSE,
+    uploaded_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    uploaded_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (version, architecture)
+);
+
+-- stations boot the one active image of their architecture
+CREATE UNIQUE INDEX images_active_architecture_idx ON images(architecture) WHERE is_active;
+
+CREATE TRIGGER update_images_updated_at
+    BEFORE UPDATE ON images
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
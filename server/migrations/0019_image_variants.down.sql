@@ -0,0 +1,12 @@
+DELETE FROM images WHERE base_image_id IS NOT NULL;
+
+DROP INDEX images_base_image_variant_idx;
+DROP INDEX images_version_architecture_idx;
+
+ALTER TABLE images
+    DROP CONSTRAINT images_variant_inactive_check,
+    DROP CONSTRAINT images_variant_check,
+    DROP COLUMN variant_name,
+    DROP COLUMN organization_id,
+    DROP COLUMN base_image_id,
+    ADD CONSTRAINT images_version_architecture_key UNIQUE (version, architecture);
@@ -0,0 +1,19 @@
+-- site variants are copies of a base image with a client config written into the ISO,
+-- made by and only visible to one organization
+ALTER TABLE images
+    ADD COLUMN base_image_id UUID REFERENCES images(id) ON DELETE CASCADE,
+    ADD COLUMN organization_id UUID REFERENCES organizations(id) ON DELETE CASCADE,
+    ADD COLUMN variant_name VARCHAR(64),
+    ADD CONSTRAINT images_variant_check CHECK (
+        (base_image_id IS NULL) = (variant_name IS NULL)
+        AND (base_image_id IS NULL) = (organization_id IS NULL)
+    ),
+    -- variants carry enrollment codes and must not be served for network boot
+    ADD CONSTRAINT images_variant_inactive_check CHECK (base_image_id IS NULL OR NOT is_active),
+    DROP CONSTRAINT images_version_architecture_key;
+
+-- variants share the version of their base image
+CREATE UNIQUE INDEX images_version_architecture_idx ON images(version, architecture)
+    WHERE base_image_id IS NULL;
+CREATE UNIQUE INDEX images_base_image_variant_idx
+    ON images(base_image_id, organization_id, variant_name);
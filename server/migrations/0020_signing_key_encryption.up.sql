@@ -0,0 +1,3 @@
+-- keys created before a key encryption key was configured stay in plaintext until the
+-- server encrypts them on its next load
+ALTER TABLE signing_keys ADD COLUMN private_key_encrypted BOOLEAN NOT NULL DEFAULT FALSE;
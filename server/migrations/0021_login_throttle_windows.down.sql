@@ -0,0 +1,2 @@
+DROP INDEX login_throttles_window_started_at_idx;
+ALTER TABLE login_throttles DROP COLUMN window_started_at;
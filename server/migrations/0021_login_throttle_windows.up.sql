@@ -0,0 +1,4 @@
+-- failed attempts only count within a window that starts with the first failure
+ALTER TABLE login_throttles ADD COLUMN window_started_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+
+CREATE INDEX login_throttles_window_started_at_idx ON login_throttles(window_started_at);
@@ -0,0 +1,2 @@
+ALTER TABLE sessions DROP COLUMN organization_id;
+DROP TABLE organization_invitations;
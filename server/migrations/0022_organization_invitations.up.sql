@@ -0,0 +1,13 @@
+-- users who share no organization with an admin join only by accepting an invitation
+CREATE TABLE organization_invitations (
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    invited_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (organization_id, user_id)
+);
+
+CREATE INDEX organization_invitations_user_id_idx ON organization_invitations(user_id);
+
+-- the organization a session acts for, NULL for the one its user joined first
+ALTER TABLE sessions ADD COLUMN organization_id UUID REFERENCES organizations(id) ON DELETE SET NULL;
@@ -0,0 +1,5 @@
+-- roles apply within every organization of a user, so resources shared by all tenants
+-- such as signing keys and base images are left to server admins
+ALTER TABLE users ADD COLUMN is_server_admin BOOLEAN NOT NULL DEFAULT FALSE;
+
+UPDATE users SET is_server_admin = TRUE WHERE email = 'system';
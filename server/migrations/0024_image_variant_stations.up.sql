@@ -0,0 +1,6 @@
+-- a variant made for a station carries its enrollment code until the station is gone
+ALTER TABLE images
+    ADD COLUMN station_id UUID REFERENCES stations(id) ON DELETE SET NULL,
+    ADD CONSTRAINT images_station_variant_check CHECK (
+        station_id IS NULL OR base_image_id IS NOT NULL
+    );
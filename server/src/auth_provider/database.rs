@@ -0,0 +1,31 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    auth_provider::AuthProvider, error::ServiceResult, models::User,
+    repositories::user::UserRepository, services::auth::is_valid_password,
+};
+
+/// Checks passwords against the Argon2 hashes stored with the users.
+pub struct DatabaseAuthProvider {
+    user_repository: Arc<dyn UserRepository>,
+}
+
+impl DatabaseAuthProvider {
+    pub fn new(user_repository: Arc<dyn UserRepository>) -> Self {
+        Self { user_repository }
+    }
+}
+
+#[async_trait]
+impl AuthProvider for DatabaseAuthProvider {
+    async fn authenticate(&self, email: &str, password: &str) -> ServiceResult<Option<User>> {
+        if let Some(user) = self.user_repository.find_by_email(email).await?
+            && is_valid_password(password, &user.password_hash)
+        {
+            return Ok(Some(user));
+        };
+        Ok(None)
+    }
+}
@@ -0,0 +1,171 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use ldap3::{LdapConnAsync, LdapConnSettings, LdapError, Scope, SearchEntry, ldap_escape};
+
+use crate::{
+    auth_provider::AuthProvider,
+    config::LdapConfig,
+    error::ServiceResult,
+    models::{Role, User},
+    repositories::{
+        organization::OrganizationRepository, user::UserRepository,
+        user_identity::UserIdentityRepository,
+    },
+    services::auth::generate_unusable_password_hash,
+};
+
+// result code of a bind with a wrong password
+const INVALID_CREDENTIALS: u32 = 49;
+
+/// Binds as the user found for the email and keeps the local account in sync with
+/// the directory entry. Accounts are linked to their entry through an identity with
+/// the server URL as issuer and the entry DN as subject.
+pub struct LdapAuthProvider {
+    user_repository: Arc<dyn UserRepository>,
+    user_identity_repository: Arc<dyn UserIdentityRepository>,
+    organization_repository: Arc<dyn OrganizationRepository>,
+    ldap_config: LdapConfig,
+}
+
+impl LdapAuthProvider {
+    pub fn new(
+        user_repository: Arc<dyn UserRepository>,
+        user_identity_repository: Arc<dyn UserIdentityRepository>,
+        organization_repository: Arc<dyn OrganizationRepository>,
+        ldap_config: LdapConfig,
+    ) -> Self {
+        Self {
+            user_repository,
+            user_identity_repository,
+            organization_repository,
+            ldap_config,
+        }
+    }
+
+    async fn bind_user(&self, email: &str, password: &str) -> ServiceResult<Option<SearchEntry>> {
+        let settings = LdapConnSettings::new()
+            .set_conn_timeout(self.ldap_config.timeout())
+            .set_starttls(self.ldap_config.starttls);
+        let (connection, mut ldap) =
+            LdapConnAsync::with_settings(settings, &self.ldap_config.url).await?;
+        ldap3::drive!(connection);
+        ldap.with_timeout(self.ldap_config.timeout());
+        if !self.ldap_config.bind_dn.is_empty() {
+            ldap.simple_bind(&self.ldap_config.bind_dn, &self.ldap_config.bind_password)
+                .await?
+                .success()?;
+        }
+        let filter = self
+            .ldap_config
+            .user_filter
+            .replace("{email}", &ldap_escape(email));
+        let attributes = vec![
+            self.ldap_config.email_attribute.as_str(),
+            self.ldap_config.group_attribute.as_str(),
+        ];
+        let (mut entries, _) = ldap
+            .search(
+                &self.ldap_config.base_dn,
+                Scope::Subtree,
+                &filter,
+                attributes,
+            )
+            .await?
+            .success()?;
+        // an ambiguous filter must not let one user sign in as another
+        if entries.len() != 1 {
+            ldap.unbind().await?;
+            return Ok(None);
+        }
+        let entry = SearchEntry::construct(entries.remove(0));
+        let is_bound = match ldap.simple_bind(&entry.dn, password).await?.success() {
+            Ok(_) => true,
+            Err(LdapError::LdapResult { result }) if result.rc == INVALID_CREDENTIALS => false,
+            Err(error) => return Err(error.into()),
+        };
+        ldap.unbind().await?;
+        Ok(is_bound.then_some(entry))
+    }
+
+    /// Creates the local account on first login and updates its role from the groups
+    /// of the entry on every login. A local account that already uses the email was not
+    /// provisioned from the directory and is left alone, so a directory entry can't take
+    /// it over.
+    async fn sync_user(&self, email: &str, entry: &SearchEntry) -> ServiceResult<Option<User>> {
+        let email = entry
+            .attrs
+            .get(&self.ldap_config.email_attribute)
+            .and_then(|emails| emails.first())
+            .map_or(email, String::as_str);
+        let groups = entry
+            .attrs
+            .get(&self.ldap_config.group_attribute)
+            .into_iter()
+            .flatten()
+            .map(String::as_str);
+        let role = Role::highest_mapped(&self.ldap_config.role_mappings, groups)
+            .unwrap_or(self.ldap_config.default_role);
+        let user_identity = self
+            .user_identity_repository
+            .find_by_subject(&self.ldap_config.url, &entry.dn)
+            .await?;
+        let user = match user_identity {
+            Some(user_identity) => {
+                self.user_repository
+                    .find_by_id(user_identity.user_id)
+                    .await?
+            }
+            None => self.provision_user(email, &entry.dn, role).await?,
+        };
+        match user {
+            Some(user) if user.role != role => Ok(Some(
+                self.user_repository
+                    .update(user.id, None, Some(role))
+                    .await?,
+            )),
+            user => Ok(user),
+        }
+    }
+
+    async fn provision_user(
+        &self,
+        email: &str,
+        dn: &str,
+        role: Role,
+    ) -> ServiceResult<Option<User>> {
+        if self.user_repository.find_by_email(email).await?.is_some() {
+            tracing::warn!(%dn, "ldap entry matches a local account that is not linked to it");
+            return Ok(None);
+        }
+        let password_hash = generate_unusable_password_hash()?;
+        let user = self
+            .user_repository
+            .create(email.to_string(), password_hash, role)
+            .await?;
+        self.user_identity_repository
+            .create(user.id, self.ldap_config.url.clone(), dn.to_string())
+            .await?;
+        if let Some(organization_id) = self.ldap_config.organization_id {
+            self.organization_repository
+                .add_member(organization_id, user.id)
+                .await?;
+        }
+        tracing::info!(user_id = %user.id, "provisioned user from ldap login");
+        Ok(Some(user))
+    }
+}
+
+#[async_trait]
+impl AuthProvider for LdapAuthProvider {
+    async fn authenticate(&self, email: &str, password: &str) -> ServiceResult<Option<User>> {
+        // servers accept a bind without password as anonymous, which proves nothing
+        if password.is_empty() {
+            return Ok(None);
+        }
+        let Some(entry) = self.bind_user(email, password).await? else {
+            return Ok(None);
+        };
+        self.sync_user(email, &entry).await
+    }
+}
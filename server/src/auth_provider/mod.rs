@@ -0,0 +1,47 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    config::LdapConfig,
+    error::ServiceResult,
+    models::User,
+    repositories::{
+        organization::OrganizationRepository, user::UserRepository,
+        user_identity::UserIdentityRepository,
+    },
+};
+
+mod database;
+mod ldap;
+
+pub use database::DatabaseAuthProvider;
+pub use ldap::LdapAuthProvider;
+
+/// Checks the email and password of a basic auth login.
+#[async_trait]
+pub trait AuthProvider: Send + Sync {
+    /// `None` if the credentials aren't valid for this provider.
+    async fn authenticate(&self, email: &str, password: &str) -> ServiceResult<Option<User>>;
+}
+
+/// Local accounts are always checked first, so they keep working while a directory is
+/// unreachable.
+pub fn from_config(
+    ldap_config: &LdapConfig,
+    user_repository: Arc<dyn UserRepository>,
+    user_identity_repository: Arc<dyn UserIdentityRepository>,
+    organization_repository: Arc<dyn OrganizationRepository>,
+) -> Vec<Arc<dyn AuthProvider>> {
+    let mut auth_providers: Vec<Arc<dyn AuthProvider>> =
+        vec![Arc::new(DatabaseAuthProvider::new(user_repository.clone()))];
+    if ldap_config.enabled {
+        auth_providers.push(Arc::new(LdapAuthProvider::new(
+            user_repository,
+            user_identity_repository,
+            organization_repository,
+            ldap_config.clone(),
+        )));
+    }
+    auth_providers
+}
@@ -0,0 +1,803 @@
+use std::{
+    collections::HashMap,
+    env, fmt, fs, io,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+use base64::{Engine, prelude::BASE64_STANDARD};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{models::Role, validation::MAX_PASSWORD_LENGTH};
+
+const CONFIG_PATH_ENV: &str = "OPEN_ERASE_CONFIG";
+const DEFAULT_CONFIG_PATH: &str = "config.toml";
+/// Keeps configured durations within what `Instant` and `DateTime` can add and subtract.
+const MAX_DURATION_SECS: u64 = 60 * 60 * 24 * 365 * 100;
+
+/// Server settings, read from a TOML file and overridden by environment variables.
+/// Every field has a default so the file only needs to list what differs.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub server: ServerConfig,
+    pub database: DatabaseConfig,
+    pub auth: AuthConfig,
+    pub images: ImagesConfig,
+    pub boot: BootConfig,
+    pub cleanup: CleanupConfig,
+    pub lockout: LockoutConfig,
+    pub rate_limit: RateLimitConfig,
+    pub mail: MailConfig,
+    pub oidc: OidcConfig,
+    pub ldap: LdapConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    pub bind_address: SocketAddr,
+    pub request_timeout_secs: u64,
+    pub static_assets_dir: PathBuf,
+    /// Address the web app is reachable at, used for links in outgoing emails.
+    pub public_url: String,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            bind_address: SocketAddr::from(([0, 0, 0, 0], 3000)),
+            request_timeout_secs: 5,
+            static_assets_dir: PathBuf::from("/web/dist"),
+            public_url: String::from("http://localhost:3000"),
+        }
+    }
+}
+
+impl ServerConfig {
+    pub fn request_timeout(&self) -> Duration {
+        Duration::from_secs(self.request_timeout_secs)
+    }
+
+    pub fn index_html_path(&self) -> PathBuf {
+        self.static_assets_dir.join("index.html")
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseConfig {
+    pub user: String,
+    pub password: String,
+    pub host: String,
+    pub port: u16,
+    pub name: String,
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            user: String::from("postgres"),
+            password: String::from("postgres"),
+            host: String::from("postgres"),
+            port: 5432,
+            name: String::from("postgres"),
+        }
+    }
+}
+
+impl DatabaseConfig {
+    pub fn url(&self) -> String {
+        format!(
+            "postgres://{}:{}@{}:{}/{}",
+            self.user, self.password, self.host, self.port, self.name
+        )
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuthConfig {
+    pub access_token_lifetime_secs: u64,
+    pub refresh_token_lifetime_secs: u64,
+    /// How long a second factor may be entered after the password was accepted.
+    pub mfa_challenge_lifetime_secs: u64,
+    /// Issuer shown for the account in authenticator apps.
+    pub totp_issuer: String,
+    pub password_reset_token_lifetime_secs: u64,
+    /// How long a station enrollment code may be redeemed.
+    pub enrollment_code_lifetime_secs: u64,
+    /// Fewest characters a new password may have.
+    pub password_min_length: usize,
+    /// Ed25519 key in PKCS#8 PEM that signs every access token instead of the keys in the
+    /// database, which can then no longer be rotated through the API.
+    pub signing_key_file: Option<PathBuf>,
+    /// Base64 of 32 random bytes. Private signing keys are stored encrypted with it.
+    pub key_encryption_key: String,
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        Self {
+            access_token_lifetime_secs: 60 * 15,               // 15 minutes
+            refresh_token_lifetime_secs: 60 * 60 * 24 * 7 * 8, // 8 weeks
+            mfa_challenge_lifetime_secs: 60 * 5,               // 5 minutes
+            totp_issuer: String::from("Open Erase"),
+            password_reset_token_lifetime_secs: 60 * 60, // 1 hour
+            enrollment_code_lifetime_secs: 60 * 60 * 24, // 1 day
+            password_min_length: 8,
+            signing_key_file: None,
+            key_encryption_key: String::new(),
+        }
+    }
+}
+
+impl AuthConfig {
+    pub fn access_token_lifetime(&self) -> Duration {
+        Duration::from_secs(self.access_token_lifetime_secs)
+    }
+
+    pub fn refresh_token_lifetime(&self) -> Duration {
+        Duration::from_secs(self.refresh_token_lifetime_secs)
+    }
+
+    pub fn mfa_challenge_lifetime(&self) -> Duration {
+        Duration::from_secs(self.mfa_challenge_lifetime_secs)
+    }
+
+    pub fn password_reset_token_lifetime(&self) -> Duration {
+        Duration::from_secs(self.password_reset_token_lifetime_secs)
+    }
+
+    pub fn enrollment_code_lifetime(&self) -> Duration {
+        Duration::from_secs(self.enrollment_code_lifetime_secs)
+    }
+
+    /// `None` if unset or not the base64 of 32 bytes, which `Config::validate` rejects.
+    pub fn key_encryption_key(&self) -> Option<[u8; 32]> {
+        BASE64_STANDARD
+            .decode(&self.key_encryption_key)
+            .ok()?
+            .try_into()
+            .ok()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ImagesConfig {
+    pub directory: PathBuf,
+    pub max_upload_size_bytes: u64,
+    /// Replaces `server.request_timeout_secs` for uploads, which stream a whole ISO.
+    pub upload_timeout_secs: u64,
+}
+
+impl Default for ImagesConfig {
+    fn default() -> Self {
+        Self {
+            directory: PathBuf::from("/dist/iso"),
+            max_upload_size_bytes: 8 * 1024 * 1024 * 1024,
+            upload_timeout_secs: 3600,
+        }
+    }
+}
+
+impl ImagesConfig {
+    pub fn upload_timeout(&self) -> Duration {
+        Duration::from_secs(self.upload_timeout_secs)
+    }
+}
+
+/// Network boot of the active images. iPXE fetches the script, kernel, initrd and
+/// squashfs over HTTP without credentials, since firmware cannot log in.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BootConfig {
+    pub enabled: bool,
+    pub tftp_enabled: bool,
+    pub tftp_bind_address: SocketAddr,
+    /// iPXE binaries such as `undionly.kpxe` and `ipxe.efi` that TFTP hands out.
+    pub ipxe_directory: PathBuf,
+    /// Appended to the kernel command line of the archiso boot.
+    pub kernel_parameters: String,
+}
+
+impl Default for BootConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            tftp_enabled: false,
+            tftp_bind_address: SocketAddr::from(([0, 0, 0, 0], 69)),
+            ipxe_directory: PathBuf::from("/dist/ipxe"),
+            kernel_parameters: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CleanupConfig {
+    pub interval_secs: u64,
+    pub retention_secs: u64,
+}
+
+impl Default for CleanupConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: 60 * 60,           // 1 hour
+            retention_secs: 60 * 60 * 24 * 7, // 7 days
+        }
+    }
+}
+
+impl CleanupConfig {
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_secs)
+    }
+
+    pub fn retention(&self) -> Duration {
+        Duration::from_secs(self.retention_secs)
+    }
+}
+
+/// Failed logins beyond the free attempts lock the account or client address for
+/// `base_lockout_secs`, doubling with every further failure up to `max_lockout_secs`.
+/// Failures are counted within `failure_window_secs` of the first one.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LockoutConfig {
+    pub account_free_attempts: u32,
+    pub ip_address_free_attempts: u32,
+    pub base_lockout_secs: u64,
+    pub max_lockout_secs: u64,
+    pub failure_window_secs: u64,
+}
+
+impl Default for LockoutConfig {
+    fn default() -> Self {
+        Self {
+            account_free_attempts: 5,
+            ip_address_free_attempts: 20,
+            base_lockout_secs: 30,
+            max_lockout_secs: 60 * 60,    // 1 hour
+            failure_window_secs: 60 * 60, // 1 hour
+        }
+    }
+}
+
+impl LockoutConfig {
+    pub fn failure_window(&self) -> Duration {
+        Duration::from_secs(self.failure_window_secs)
+    }
+}
+
+/// Token buckets per client and route group. A bucket holds up to `capacity` requests
+/// and regains `refill_per_minute` of them every minute.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BucketConfig {
+    pub capacity: u32,
+    pub refill_per_minute: u32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RateLimitConfig {
+    pub enabled: bool,
+    pub auth: BucketConfig,
+    pub reports: BucketConfig,
+    pub default: BucketConfig,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            auth: BucketConfig {
+                capacity: 20,
+                refill_per_minute: 20,
+            },
+            reports: BucketConfig {
+                capacity: 60,
+                refill_per_minute: 120,
+            },
+            default: BucketConfig {
+                capacity: 300,
+                refill_per_minute: 600,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MailTransport {
+    /// Sends no email, so password resets are refused.
+    Disabled,
+    /// Writes every email to `directory` and logs it instead of sending it. Reset links
+    /// end up readable on disk, so this is only meant for development.
+    Log,
+    Smtp,
+}
+
+impl FromStr for MailTransport {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "disabled" => Ok(Self::Disabled),
+            "log" => Ok(Self::Log),
+            "smtp" => Ok(Self::Smtp),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Empty SMTP credentials connect without authentication.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MailConfig {
+    pub transport: MailTransport,
+    pub from: String,
+    pub directory: PathBuf,
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    pub smtp_username: String,
+    pub smtp_password: String,
+    pub smtp_starttls: bool,
+}
+
+impl Default for MailConfig {
+    fn default() -> Self {
+        Self {
+            transport: MailTransport::Disabled,
+            from: String::from("Open Erase <no-reply@localhost>"),
+            directory: PathBuf::from("/tmp/open-erase/mail"),
+            smtp_host: String::from("localhost"),
+            smtp_port: 587,
+            smtp_username: String::new(),
+            smtp_password: String::new(),
+            smtp_starttls: true,
+        }
+    }
+}
+
+/// Single sign-on through an OpenID Connect provider. Users signing in for the first
+/// time are created in `jit_organization_id` if `jit_provisioning` is enabled. An
+/// identity whose email already belongs to a local account is refused rather than linked.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OidcConfig {
+    pub enabled: bool,
+    pub issuer_url: String,
+    pub client_id: String,
+    pub client_secret: String,
+    pub scopes: Vec<String>,
+    /// ID token claim listing the groups of a user.
+    pub groups_claim: String,
+    /// Groups granting a role, the most privileged match wins on every login. Users
+    /// without a matching group fall back to `default_role`. Roles are left alone if
+    /// this is empty.
+    pub role_mappings: HashMap<String, Role>,
+    pub default_role: Role,
+    pub jit_provisioning: bool,
+    pub jit_organization_id: Option<Uuid>,
+}
+
+impl Default for OidcConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            issuer_url: String::new(),
+            client_id: String::new(),
+            client_secret: String::new(),
+            scopes: vec![
+                String::from("openid"),
+                String::from("email"),
+                String::from("profile"),
+            ],
+            groups_claim: String::from("groups"),
+            role_mappings: HashMap::new(),
+            default_role: Role::ReadOnly,
+            jit_provisioning: false,
+            jit_organization_id: None,
+        }
+    }
+}
+
+/// Checks basic auth logins against a directory after the local accounts. The service
+/// account in `bind_dn` searches for the user, whose entry is then bound with the
+/// password given. Users are created in `organization_id` on their first login and their
+/// role follows their groups on every login. An entry whose email already belongs to a
+/// local account is refused rather than linked.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LdapConfig {
+    pub enabled: bool,
+    pub url: String,
+    pub starttls: bool,
+    pub timeout_secs: u64,
+    /// Empty to search anonymously.
+    pub bind_dn: String,
+    pub bind_password: String,
+    pub base_dn: String,
+    /// `{email}` is replaced with the escaped login email.
+    pub user_filter: String,
+    pub email_attribute: String,
+    pub group_attribute: String,
+    /// Group DNs granting a role, the most privileged match wins. Users without a
+    /// matching group get `default_role`.
+    pub role_mappings: HashMap<String, Role>,
+    pub default_role: Role,
+    pub organization_id: Option<Uuid>,
+}
+
+impl Default for LdapConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: String::from("ldap://localhost:389"),
+            starttls: false,
+            timeout_secs: 5,
+            bind_dn: String::new(),
+            bind_password: String::new(),
+            base_dn: String::new(),
+            user_filter: String::from("(&(objectClass=person)(mail={email}))"),
+            email_attribute: String::from("mail"),
+            group_attribute: String::from("memberOf"),
+            role_mappings: HashMap::new(),
+            default_role: Role::ReadOnly,
+            organization_id: None,
+        }
+    }
+}
+
+impl LdapConfig {
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_secs)
+    }
+}
+
+impl Config {
+    /// Reads the file named by `OPEN_ERASE_CONFIG`, falling back to `config.toml` in the
+    /// working directory if it exists, then applies environment overrides and validates.
+    pub fn load() -> Result<Self, ConfigError> {
+        let mut config = match env::var(CONFIG_PATH_ENV) {
+            Ok(path) => Self::from_file(Path::new(&path))?,
+            Err(_) if Path::new(DEFAULT_CONFIG_PATH).exists() => {
+                Self::from_file(Path::new(DEFAULT_CONFIG_PATH))?
+            }
+            Err(_) => Self::default(),
+        };
+        config.apply_envs()?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, ConfigError> {
+        let contents = fs::read_to_string(path)?;
+        Ok(toml::from_str(&contents)?)
+    }
+
+    fn apply_envs(&mut self) -> Result<(), ConfigError> {
+        override_from_env("OPEN_ERASE_BIND_ADDRESS", &mut self.server.bind_address)?;
+        override_from_env(
+            "OPEN_ERASE_REQUEST_TIMEOUT_SECS",
+            &mut self.server.request_timeout_secs,
+        )?;
+        override_from_env(
+            "OPEN_ERASE_STATIC_ASSETS_DIR",
+            &mut self.server.static_assets_dir,
+        )?;
+        override_from_env("OPEN_ERASE_PUBLIC_URL", &mut self.server.public_url)?;
+        override_from_env("POSTGRES_USER", &mut self.database.user)?;
+        override_from_env("POSTGRES_PASSWORD", &mut self.database.password)?;
+        override_from_env("POSTGRES_HOST", &mut self.database.host)?;
+        override_from_env("POSTGRES_PORT", &mut self.database.port)?;
+        override_from_env("POSTGRES_DB", &mut self.database.name)?;
+        override_from_env(
+            "OPEN_ERASE_ACCESS_TOKEN_LIFETIME_SECS",
+            &mut self.auth.access_token_lifetime_secs,
+        )?;
+        override_from_env(
+            "OPEN_ERASE_REFRESH_TOKEN_LIFETIME_SECS",
+            &mut self.auth.refresh_token_lifetime_secs,
+        )?;
+        override_from_env(
+            "OPEN_ERASE_MFA_CHALLENGE_LIFETIME_SECS",
+            &mut self.auth.mfa_challenge_lifetime_secs,
+        )?;
+        override_from_env("OPEN_ERASE_TOTP_ISSUER", &mut self.auth.totp_issuer)?;
+        override_from_env(
+            "OPEN_ERASE_PASSWORD_RESET_TOKEN_LIFETIME_SECS",
+            &mut self.auth.password_reset_token_lifetime_secs,
+        )?;
+        override_from_env(
+            "OPEN_ERASE_ENROLLMENT_CODE_LIFETIME_SECS",
+            &mut self.auth.enrollment_code_lifetime_secs,
+        )?;
+        override_from_env(
+            "OPEN_ERASE_PASSWORD_MIN_LENGTH",
+            &mut self.auth.password_min_length,
+        )?;
+        override_option_from_env(
+            "OPEN_ERASE_SIGNING_KEY_FILE",
+            &mut self.auth.signing_key_file,
+        )?;
+        override_from_env(
+            "OPEN_ERASE_KEY_ENCRYPTION_KEY",
+            &mut self.auth.key_encryption_key,
+        )?;
+        override_from_env("OPEN_ERASE_IMAGES_DIR", &mut self.images.directory)?;
+        override_from_env(
+            "OPEN_ERASE_IMAGES_MAX_UPLOAD_SIZE_BYTES",
+            &mut self.images.max_upload_size_bytes,
+        )?;
+        override_from_env(
+            "OPEN_ERASE_IMAGES_UPLOAD_TIMEOUT_SECS",
+            &mut self.images.upload_timeout_secs,
+        )?;
+        override_from_env("OPEN_ERASE_BOOT_ENABLED", &mut self.boot.enabled)?;
+        override_from_env("OPEN_ERASE_TFTP_ENABLED", &mut self.boot.tftp_enabled)?;
+        override_from_env(
+            "OPEN_ERASE_TFTP_BIND_ADDRESS",
+            &mut self.boot.tftp_bind_address,
+        )?;
+        override_from_env("OPEN_ERASE_IPXE_DIR", &mut self.boot.ipxe_directory)?;
+        override_from_env(
+            "OPEN_ERASE_BOOT_KERNEL_PARAMETERS",
+            &mut self.boot.kernel_parameters,
+        )?;
+        override_from_env(
+            "OPEN_ERASE_CLEANUP_INTERVAL_SECS",
+            &mut self.cleanup.interval_secs,
+        )?;
+        override_from_env(
+            "OPEN_ERASE_CLEANUP_RETENTION_SECS",
+            &mut self.cleanup.retention_secs,
+        )?;
+        override_from_env(
+            "OPEN_ERASE_LOCKOUT_ACCOUNT_FREE_ATTEMPTS",
+            &mut self.lockout.account_free_attempts,
+        )?;
+        override_from_env(
+            "OPEN_ERASE_LOCKOUT_IP_ADDRESS_FREE_ATTEMPTS",
+            &mut self.lockout.ip_address_free_attempts,
+        )?;
+        override_from_env(
+            "OPEN_ERASE_LOCKOUT_BASE_SECS",
+            &mut self.lockout.base_lockout_secs,
+        )?;
+        override_from_env(
+            "OPEN_ERASE_LOCKOUT_MAX_SECS",
+            &mut self.lockout.max_lockout_secs,
+        )?;
+        override_from_env(
+            "OPEN_ERASE_LOCKOUT_FAILURE_WINDOW_SECS",
+            &mut self.lockout.failure_window_secs,
+        )?;
+        override_from_env(
+            "OPEN_ERASE_RATE_LIMIT_ENABLED",
+            &mut self.rate_limit.enabled,
+        )?;
+        override_from_env("OPEN_ERASE_MAIL_TRANSPORT", &mut self.mail.transport)?;
+        override_from_env("OPEN_ERASE_MAIL_FROM", &mut self.mail.from)?;
+        override_from_env("OPEN_ERASE_MAIL_DIR", &mut self.mail.directory)?;
+        override_from_env("OPEN_ERASE_SMTP_HOST", &mut self.mail.smtp_host)?;
+        override_from_env("OPEN_ERASE_SMTP_PORT", &mut self.mail.smtp_port)?;
+        override_from_env("OPEN_ERASE_SMTP_USERNAME", &mut self.mail.smtp_username)?;
+        override_from_env("OPEN_ERASE_SMTP_PASSWORD", &mut self.mail.smtp_password)?;
+        override_from_env("OPEN_ERASE_SMTP_STARTTLS", &mut self.mail.smtp_starttls)?;
+        override_from_env("OPEN_ERASE_OIDC_ENABLED", &mut self.oidc.enabled)?;
+        override_from_env("OPEN_ERASE_OIDC_ISSUER_URL", &mut self.oidc.issuer_url)?;
+        override_from_env("OPEN_ERASE_OIDC_CLIENT_ID", &mut self.oidc.client_id)?;
+        override_from_env(
+            "OPEN_ERASE_OIDC_CLIENT_SECRET",
+            &mut self.oidc.client_secret,
+        )?;
+        override_from_env(
+            "OPEN_ERASE_OIDC_JIT_PROVISIONING",
+            &mut self.oidc.jit_provisioning,
+        )?;
+        override_from_env("OPEN_ERASE_LDAP_ENABLED", &mut self.ldap.enabled)?;
+        override_from_env("OPEN_ERASE_LDAP_URL", &mut self.ldap.url)?;
+        override_from_env("OPEN_ERASE_LDAP_STARTTLS", &mut self.ldap.starttls)?;
+        override_from_env("OPEN_ERASE_LDAP_BIND_DN", &mut self.ldap.bind_dn)?;
+        override_from_env(
+            "OPEN_ERASE_LDAP_BIND_PASSWORD",
+            &mut self.ldap.bind_password,
+        )?;
+        override_from_env("OPEN_ERASE_LDAP_BASE_DN", &mut self.ldap.base_dn)?;
+        override_from_env("OPEN_ERASE_LDAP_USER_FILTER", &mut self.ldap.user_filter)?;
+        Ok(())
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.server.request_timeout_secs == 0 {
+            return Err(ConfigError::Invalid(
+                "server.request_timeout_secs must not be 0",
+            ));
+        }
+        if self.auth.access_token_lifetime_secs == 0
+            || self.auth.access_token_lifetime_secs > MAX_DURATION_SECS
+        {
+            return Err(ConfigError::Invalid(
+                "auth.access_token_lifetime_secs must be between 1 and 100 years",
+            ));
+        }
+        if self.auth.refresh_token_lifetime_secs <= self.auth.access_token_lifetime_secs {
+            return Err(ConfigError::Invalid(
+                "auth.refresh_token_lifetime_secs must exceed auth.access_token_lifetime_secs",
+            ));
+        }
+        if self.auth.refresh_token_lifetime_secs > MAX_DURATION_SECS {
+            return Err(ConfigError::Invalid(
+                "auth.refresh_token_lifetime_secs must be at most 100 years",
+            ));
+        }
+        if self.auth.mfa_challenge_lifetime_secs == 0
+            || self.auth.mfa_challenge_lifetime_secs > MAX_DURATION_SECS
+        {
+            return Err(ConfigError::Invalid(
+                "auth.mfa_challenge_lifetime_secs must be between 1 and 100 years",
+            ));
+        }
+        if self.auth.password_reset_token_lifetime_secs == 0
+            || self.auth.password_reset_token_lifetime_secs > MAX_DURATION_SECS
+        {
+            return Err(ConfigError::Invalid(
+                "auth.password_reset_token_lifetime_secs must be between 1 and 100 years",
+            ));
+        }
+        if self.auth.enrollment_code_lifetime_secs == 0
+            || self.auth.enrollment_code_lifetime_secs > MAX_DURATION_SECS
+        {
+            return Err(ConfigError::Invalid(
+                "auth.enrollment_code_lifetime_secs must be between 1 and 100 years",
+            ));
+        }
+        if self.auth.password_min_length == 0 || self.auth.password_min_length > MAX_PASSWORD_LENGTH
+        {
+            return Err(ConfigError::Invalid(
+                "auth.password_min_length must be between 1 and 128",
+            ));
+        }
+        if !self.auth.key_encryption_key.is_empty() && self.auth.key_encryption_key().is_none() {
+            return Err(ConfigError::Invalid(
+                "auth.key_encryption_key must be the base64 of 32 bytes",
+            ));
+        }
+        if self.images.max_upload_size_bytes == 0 {
+            return Err(ConfigError::Invalid(
+                "images.max_upload_size_bytes must not be 0",
+            ));
+        }
+        if self.images.upload_timeout_secs == 0 {
+            return Err(ConfigError::Invalid(
+                "images.upload_timeout_secs must not be 0",
+            ));
+        }
+        if self.boot.tftp_enabled && !self.boot.enabled {
+            return Err(ConfigError::Invalid(
+                "boot.enabled must be set if boot.tftp_enabled is",
+            ));
+        }
+        if self.cleanup.interval_secs == 0 || self.cleanup.interval_secs > MAX_DURATION_SECS {
+            return Err(ConfigError::Invalid(
+                "cleanup.interval_secs must be between 1 and 100 years",
+            ));
+        }
+        if self.cleanup.retention_secs > MAX_DURATION_SECS {
+            return Err(ConfigError::Invalid(
+                "cleanup.retention_secs must be at most 100 years",
+            ));
+        }
+        if self.lockout.max_lockout_secs < self.lockout.base_lockout_secs {
+            return Err(ConfigError::Invalid(
+                "lockout.max_lockout_secs must not be below lockout.base_lockout_secs",
+            ));
+        }
+        if self.lockout.max_lockout_secs > MAX_DURATION_SECS {
+            return Err(ConfigError::Invalid(
+                "lockout.max_lockout_secs must be at most 100 years",
+            ));
+        }
+        if self.lockout.failure_window_secs == 0
+            || self.lockout.failure_window_secs > MAX_DURATION_SECS
+        {
+            return Err(ConfigError::Invalid(
+                "lockout.failure_window_secs must be between 1 and 100 years",
+            ));
+        }
+        let buckets = [
+            &self.rate_limit.auth,
+            &self.rate_limit.reports,
+            &self.rate_limit.default,
+        ];
+        if buckets
+            .iter()
+            .any(|bucket| bucket.capacity == 0 || bucket.refill_per_minute == 0)
+        {
+            return Err(ConfigError::Invalid(
+                "rate_limit buckets need a capacity and refill_per_minute above 0",
+            ));
+        }
+        if self.oidc.enabled && (self.oidc.issuer_url.is_empty() || self.oidc.client_id.is_empty())
+        {
+            return Err(ConfigError::Invalid(
+                "oidc.issuer_url and oidc.client_id must be set if oidc is enabled",
+            ));
+        }
+        if self.oidc.jit_provisioning && self.oidc.jit_organization_id.is_none() {
+            return Err(ConfigError::Invalid(
+                "oidc.jit_organization_id must be set if oidc.jit_provisioning is enabled",
+            ));
+        }
+        if self.ldap.enabled
+            && (self.ldap.base_dn.is_empty() || self.ldap.organization_id.is_none())
+        {
+            return Err(ConfigError::Invalid(
+                "ldap.base_dn and ldap.organization_id must be set if ldap is enabled",
+            ));
+        }
+        if self.ldap.enabled && !self.ldap.user_filter.contains("{email}") {
+            return Err(ConfigError::Invalid(
+                "ldap.user_filter must contain the {email} placeholder",
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn override_from_env<T: FromStr>(name: &'static str, value: &mut T) -> Result<(), ConfigError> {
+    if let Ok(raw) = env::var(name) {
+        *value = raw.parse().map_err(|_| ConfigError::Env(name))?;
+    }
+    Ok(())
+}
+
+fn override_option_from_env<T: FromStr>(
+    name: &'static str,
+    value: &mut Option<T>,
+) -> Result<(), ConfigError> {
+    if let Ok(raw) = env::var(name) {
+        *value = Some(raw.parse().map_err(|_| ConfigError::Env(name))?);
+    }
+    Ok(())
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(io::Error),
+    Parse(toml::de::Error),
+    Env(&'static str),
+    Invalid(&'static str),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(error) => write!(f, "could not read config file: {error}"),
+            ConfigError::Parse(error) => write!(f, "could not parse config file: {error}"),
+            ConfigError::Env(name) => write!(f, "environment variable {name} has an invalid value"),
+            ConfigError::Invalid(message) => write!(f, "invalid config: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl From<io::Error> for ConfigError {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<toml::de::Error> for ConfigError {
+    fn from(value: toml::de::Error) -> Self {
+        Self::Parse(value)
+    }
+}
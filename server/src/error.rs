@@ -1,13 +1,18 @@
-use std::{io, sync::Arc};
+use std::{io, sync::Arc, time::Duration};
 
 use axum::{
-    body::Body,
+    Json,
+    http::{HeaderValue, StatusCode, header},
     response::{IntoResponse, Response},
 };
+use open_erase_lib::schemas::error::{ErrorCode, ErrorResponse, FieldError};
+
+use crate::middleware::request_id::current_request_id;
 
 pub type AppResult<T> = Result<T, AppError>;
 pub type ServiceResult<T> = Result<T, ServiceError>;
 pub type RepositoryResult<T> = Result<T, RepositoryError>;
+pub type MailResult<T> = Result<T, MailError>;
 
 pub enum AppError {
     Client(ClientError),
@@ -31,40 +36,101 @@ impl From<ClientError> for AppError {
 
 impl From<ServiceError> for AppError {
     fn from(value: ServiceError) -> Self {
-        Self::Service(value)
+        match value {
+            ServiceError::Validation(details) => Self::Client(ClientError::Validation(details)),
+            service_error => Self::Service(service_error),
+        }
     }
 }
 
 pub enum ClientError {
+    Conflict,
+    Forbidden,
+    MalformedBody,
+    /// A query parameter or header that doesn't parse.
+    MalformedRequest,
     MethodNotAllowed,
     NotFound,
+    PayloadTooLarge,
+    /// Carries the size of the resource, which the response reports in `Content-Range`.
+    RangeNotSatisfiable(u64),
+    /// The client used up its request budget for a route group.
+    RateLimited(Duration),
+    /// Too many failed logins against the account or from the address.
+    TooManyRequests(Duration),
     Unauthorized,
+    /// No API route matches the path, unlike `NotFound` for a resource that doesn't exist.
+    UnknownPath,
+    UnsupportedMediaType,
+    Validation(Vec<FieldError>),
 }
 
 impl IntoResponse for ClientError {
     fn into_response(self) -> Response {
-        let error_response = match &self {
-            ClientError::MethodNotAllowed => ErrorResponse::method_not_allowed(),
-            ClientError::NotFound => ErrorResponse::not_found(),
-            ClientError::Unauthorized => ErrorResponse::unauthorized(),
+        let error_response = match self {
+            ClientError::Conflict => ServerErrorResponse::conflict(),
+            ClientError::Forbidden => ServerErrorResponse::forbidden(),
+            ClientError::MalformedBody => ServerErrorResponse::malformed_body(),
+            ClientError::MalformedRequest => ServerErrorResponse::malformed_request(),
+            ClientError::MethodNotAllowed => ServerErrorResponse::method_not_allowed(),
+            ClientError::NotFound => ServerErrorResponse::not_found(),
+            ClientError::PayloadTooLarge => ServerErrorResponse::payload_too_large(),
+            ClientError::RangeNotSatisfiable(size) => {
+                let mut response = ServerErrorResponse::range_not_satisfiable().into_response();
+                response.headers_mut().insert(
+                    header::CONTENT_RANGE,
+                    HeaderValue::from_str(&format!("bytes */{size}")).unwrap(),
+                );
+                return response;
+            }
+            ClientError::RateLimited(retry_after) => {
+                return with_retry_after(ServerErrorResponse::rate_limited(), retry_after);
+            }
+            ClientError::TooManyRequests(retry_after) => {
+                return with_retry_after(ServerErrorResponse::too_many_requests(), retry_after);
+            }
+            ClientError::Unauthorized => ServerErrorResponse::unauthorized(),
+            ClientError::UnknownPath => ServerErrorResponse::unknown_path(),
+            ClientError::UnsupportedMediaType => ServerErrorResponse::unsupported_media_type(),
+            ClientError::Validation(details) => ServerErrorResponse::validation_failed(details),
         };
         error_response.into_response()
     }
 }
 
+fn with_retry_after(error_response: ServerErrorResponse, retry_after: Duration) -> Response {
+    let mut response = error_response.into_response();
+    // Retry-After only has second precision, round up so clients don't retry early
+    let retry_after_secs = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+    response
+        .headers_mut()
+        .insert(header::RETRY_AFTER, HeaderValue::from(retry_after_secs));
+    response
+}
+
 #[derive(Debug)]
 pub enum ServiceError {
     Repository(RepositoryError),
     Hash(argon2::password_hash::Error),
     Token(jsonwebtoken::errors::Error),
+    Key(ed25519_dalek::pkcs8::Error),
+    Encryption(aes_gcm::Error),
+    Mail(MailError),
+    Http(reqwest::Error),
+    Ldap(ldap3::LdapError),
     Uuid(uuid::Error),
     Serialization(serde_json::Error),
-    Validation,
+    Io(io::Error),
+    /// Input the request validation could not catch, answered like it as a 422.
+    Validation(Vec<FieldError>),
 }
 
 impl IntoResponse for ServiceError {
     fn into_response(self) -> Response {
-        let mut error_response = ErrorResponse::internal_server_error().into_response();
+        if let ServiceError::Validation(details) = self {
+            return ClientError::Validation(details).into_response();
+        }
+        let mut error_response = ServerErrorResponse::internal_server_error().into_response();
         error_response.extensions_mut().insert(Arc::new(self));
         error_response
     }
@@ -88,12 +154,48 @@ impl From<jsonwebtoken::errors::Error> for ServiceError {
     }
 }
 
+impl From<ed25519_dalek::pkcs8::Error> for ServiceError {
+    fn from(value: ed25519_dalek::pkcs8::Error) -> Self {
+        Self::Key(value)
+    }
+}
+
+impl From<aes_gcm::Error> for ServiceError {
+    fn from(value: aes_gcm::Error) -> Self {
+        Self::Encryption(value)
+    }
+}
+
+impl From<MailError> for ServiceError {
+    fn from(value: MailError) -> Self {
+        Self::Mail(value)
+    }
+}
+
+impl From<reqwest::Error> for ServiceError {
+    fn from(value: reqwest::Error) -> Self {
+        Self::Http(value)
+    }
+}
+
+impl From<ldap3::LdapError> for ServiceError {
+    fn from(value: ldap3::LdapError) -> Self {
+        Self::Ldap(value)
+    }
+}
+
 impl From<uuid::Error> for ServiceError {
     fn from(value: uuid::Error) -> Self {
         Self::Uuid(value)
     }
 }
 
+impl From<io::Error> for ServiceError {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
 impl From<serde_json::Error> for ServiceError {
     fn from(value: serde_json::Error) -> Self {
         Self::Serialization(value)
@@ -120,46 +222,197 @@ impl From<io::Error> for RepositoryError {
     }
 }
 
-struct ErrorResponse {
-    status_code: u16,
-    message: String,
+#[allow(dead_code)]
+#[derive(Debug)]
+pub enum MailError {
+    Address(lettre::address::AddressError),
+    Message(lettre::error::Error),
+    Smtp(lettre::transport::smtp::Error),
+    File(lettre::transport::file::Error),
+    Io(io::Error),
+}
+
+impl From<lettre::address::AddressError> for MailError {
+    fn from(value: lettre::address::AddressError) -> Self {
+        Self::Address(value)
+    }
 }
 
-impl ErrorResponse {
-    pub fn unauthorized() -> Self {
+impl From<lettre::error::Error> for MailError {
+    fn from(value: lettre::error::Error) -> Self {
+        Self::Message(value)
+    }
+}
+
+impl From<lettre::transport::smtp::Error> for MailError {
+    fn from(value: lettre::transport::smtp::Error) -> Self {
+        Self::Smtp(value)
+    }
+}
+
+impl From<lettre::transport::file::Error> for MailError {
+    fn from(value: lettre::transport::file::Error) -> Self {
+        Self::File(value)
+    }
+}
+
+impl From<io::Error> for MailError {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl std::fmt::Display for MailError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl std::error::Error for MailError {}
+
+struct ServerErrorResponse {
+    status_code: StatusCode,
+    error: ErrorResponse,
+}
+
+impl ServerErrorResponse {
+    fn new(status_code: StatusCode, code: ErrorCode, message: &str) -> Self {
         Self {
-            status_code: 401,
-            message: String::from("unauthorized access requested"),
+            status_code,
+            error: ErrorResponse {
+                code,
+                message: String::from(message),
+                request_id: current_request_id(),
+                details: Vec::new(),
+            },
         }
     }
 
+    pub fn unauthorized() -> Self {
+        Self::new(
+            StatusCode::UNAUTHORIZED,
+            ErrorCode::Unauthorized,
+            "unauthorized access requested",
+        )
+    }
+
+    pub fn forbidden() -> Self {
+        Self::new(
+            StatusCode::FORBIDDEN,
+            ErrorCode::Forbidden,
+            "insufficient permissions for the requested resource",
+        )
+    }
+
     pub fn not_found() -> Self {
-        Self {
-            status_code: 404,
-            message: String::from("the requested resource was not found"),
-        }
+        Self::new(
+            StatusCode::NOT_FOUND,
+            ErrorCode::NotFound,
+            "the requested resource was not found",
+        )
+    }
+
+    pub fn unknown_path() -> Self {
+        Self::new(
+            StatusCode::NOT_FOUND,
+            ErrorCode::NotFound,
+            "no api route matches the requested path",
+        )
+    }
+
+    pub fn malformed_body() -> Self {
+        Self::new(
+            StatusCode::BAD_REQUEST,
+            ErrorCode::MalformedBody,
+            "the request body is not valid json",
+        )
+    }
+
+    pub fn malformed_request() -> Self {
+        Self::new(
+            StatusCode::BAD_REQUEST,
+            ErrorCode::MalformedRequest,
+            "the request contains a malformed query parameter or header",
+        )
     }
 
     pub fn method_not_allowed() -> Self {
-        Self {
-            status_code: 405,
-            message: String::from("used http method is not allowed for the requested resource"),
-        }
+        Self::new(
+            StatusCode::METHOD_NOT_ALLOWED,
+            ErrorCode::MethodNotAllowed,
+            "used http method is not allowed for the requested resource",
+        )
+    }
+
+    pub fn conflict() -> Self {
+        Self::new(
+            StatusCode::CONFLICT,
+            ErrorCode::Conflict,
+            "the request conflicts with the current state of the resource",
+        )
+    }
+
+    pub fn payload_too_large() -> Self {
+        Self::new(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            ErrorCode::PayloadTooLarge,
+            "the request body is too large",
+        )
+    }
+
+    pub fn range_not_satisfiable() -> Self {
+        Self::new(
+            StatusCode::RANGE_NOT_SATISFIABLE,
+            ErrorCode::RangeNotSatisfiable,
+            "the requested range lies outside of the resource",
+        )
+    }
+
+    pub fn unsupported_media_type() -> Self {
+        Self::new(
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            ErrorCode::UnsupportedMediaType,
+            "the request body must be sent as application/json",
+        )
+    }
+
+    pub fn validation_failed(details: Vec<FieldError>) -> Self {
+        let mut error_response = Self::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            ErrorCode::ValidationFailed,
+            "the request body contains invalid fields",
+        );
+        error_response.error.details = details;
+        error_response
+    }
+
+    pub fn rate_limited() -> Self {
+        Self::new(
+            StatusCode::TOO_MANY_REQUESTS,
+            ErrorCode::TooManyRequests,
+            "too many requests, try again later",
+        )
+    }
+
+    pub fn too_many_requests() -> Self {
+        Self::new(
+            StatusCode::TOO_MANY_REQUESTS,
+            ErrorCode::TooManyRequests,
+            "too many failed attempts, try again later",
+        )
     }
 
     pub fn internal_server_error() -> Self {
-        Self {
-            status_code: 500,
-            message: String::from("an unexpected error occured"),
-        }
+        Self::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            ErrorCode::InternalServerError,
+            "an unexpected error occured",
+        )
     }
 }
 
-impl IntoResponse for ErrorResponse {
+impl IntoResponse for ServerErrorResponse {
     fn into_response(self) -> Response {
-        Response::builder()
-            .status(self.status_code)
-            .body(Body::new(self.message.to_string()))
-            .unwrap()
+        (self.status_code, Json(self.error)).into_response()
     }
 }
@@ -0,0 +1,103 @@
+use axum::{Extension, extract::State};
+use open_erase_lib::schemas::{
+    api_key::{GetApiKeysResponse, PostApiKeyRequest, PostApiKeyResponse},
+    error::ErrorResponse,
+};
+use uuid::Uuid;
+
+use crate::{
+    error::{AppResult, ClientError},
+    schemas::api_key::{
+        ServerDeleteApiKeyResponse, ServerGetApiKeysResponse, ServerPostApiKeyRequest,
+        ServerPostApiKeyResponse,
+    },
+    services::auth::Claims,
+    state::AppState,
+    validation::{ValidatedJson, ValidatedPath},
+};
+
+#[axum::debug_handler]
+#[utoipa::path(
+    get,
+    path = "/api-keys",
+    tag = "api-keys",
+    responses(
+        (status = 200, description = "Api keys of the organization", body = GetApiKeysResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn get_api_keys(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<ServerGetApiKeysResponse> {
+    let api_keys = state
+        .api_key_service
+        .find_by_organization_id(claims.org)
+        .await?;
+    Ok(api_keys.into())
+}
+
+/// Keys act for the organization of the caller with the role of the caller.
+#[axum::debug_handler]
+#[utoipa::path(
+    post,
+    path = "/api-keys",
+    tag = "api-keys",
+    request_body = PostApiKeyRequest,
+    responses(
+        (status = 201, description = "Api key created", body = PostApiKeyResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 422, description = "Invalid request body", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn post_api_key(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    ValidatedJson(api_key): ValidatedJson<ServerPostApiKeyRequest>,
+) -> AppResult<ServerPostApiKeyResponse> {
+    let (api_key, key) = state
+        .api_key_service
+        .create(
+            claims.org,
+            claims.user_id()?,
+            None,
+            api_key.0.name,
+            api_key.0.scopes.into_iter().map(Into::into).collect(),
+            api_key.0.expires_at,
+        )
+        .await?;
+    Ok(ServerPostApiKeyResponse::new(api_key, key))
+}
+
+#[axum::debug_handler]
+#[utoipa::path(
+    delete,
+    path = "/api-keys/{uuid}",
+    tag = "api-keys",
+    params(
+        ("uuid" = Uuid, Path, description = "Id of the api key"),
+    ),
+    responses(
+        (status = 204, description = "Api key revoked"),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn delete_api_key(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    ValidatedPath(id): ValidatedPath<Uuid>,
+) -> AppResult<ServerDeleteApiKeyResponse> {
+    let api_key = state
+        .api_key_service
+        .revoke(claims.org, id)
+        .await?
+        .ok_or(ClientError::NotFound)?;
+    Ok(api_key.into())
+}
@@ -1,44 +1,313 @@
 use axum::{Extension, extract::State};
+use axum_extra::{either::Either, extract::CookieJar};
+use open_erase_lib::schemas::{
+    error::ErrorResponse,
+    mfa::{MfaChallengeResponse, PostMfaVerifyRequest},
+    oidc::GetOidcResponse,
+    password_reset::{PostPasswordResetConfirmRequest, PostPasswordResetRequest},
+    signing_key::GetJwksResponse,
+    token::{LoginResponse, PostSwitchOrganizationRequest, RefreshResponse},
+};
 
 use crate::{
-    error::AppResult,
+    error::{AppResult, ClientError},
     models::{RefreshToken, User},
-    schemas::token::{ServerLoginResponse, ServerLogoutResponse, ServerRefreshResponse},
+    schemas::{
+        mfa::{ServerMfaChallengeResponse, ServerPostMfaVerifyRequest},
+        oidc::{
+            OIDC_LOGIN_COOKIE, OidcCallbackQuery, ServerGetOidcResponse,
+            ServerOidcCallbackResponse, ServerOidcLoginResponse,
+        },
+        password_reset::{
+            ServerPostPasswordResetConfirmRequest, ServerPostPasswordResetConfirmResponse,
+            ServerPostPasswordResetRequest, ServerPostPasswordResetResponse,
+        },
+        session::ClientInfo,
+        signing_key::ServerGetJwksResponse,
+        token::{
+            ServerLoginResponse, ServerLogoutResponse, ServerPostSwitchOrganizationRequest,
+            ServerRefreshResponse,
+        },
+    },
     state::AppState,
+    validation::{ValidatedJson, ValidatedQuery},
 };
 
+/// Users with a second factor get a challenge token to pass to `verify_mfa` instead of
+/// the login response.
 #[axum::debug_handler]
-#[utoipa::path(post, path = "/auth/login")]
+#[utoipa::path(
+    post,
+    path = "/auth/login",
+    tag = "auth",
+    responses(
+        (status = 200, description = "Logged in, the refresh token is also set as a cookie", body = LoginResponse),
+        (status = 202, description = "Password accepted, a second factor is required", body = MfaChallengeResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 429, description = "Locked out after too many failed attempts", body = ErrorResponse),
+    ),
+    security(("basic" = [])),
+)]
 pub async fn login(
     State(state): State<AppState>,
     Extension(user): Extension<User>,
+    client_info: ClientInfo,
+) -> AppResult<Either<ServerLoginResponse, ServerMfaChallengeResponse>> {
+    if state.mfa_service.is_enabled(user.id).await? {
+        let challenge_token = state
+            .auth_service
+            .generate_mfa_challenge_token(&user)
+            .await?;
+        return Ok(Either::E2(ServerMfaChallengeResponse::new(challenge_token)));
+    }
+    let login_response = complete_login(&state, &user, client_info, false).await?;
+    Ok(Either::E1(login_response))
+}
+
+/// Failed codes count towards the same lockout as failed passwords.
+#[axum::debug_handler]
+#[utoipa::path(
+    post,
+    path = "/auth/mfa/verify",
+    tag = "auth",
+    request_body = PostMfaVerifyRequest,
+    responses(
+        (status = 200, description = "Logged in, the refresh token is also set as a cookie", body = LoginResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 422, description = "Invalid request body", body = ErrorResponse),
+        (status = 429, description = "Locked out after too many failed attempts", body = ErrorResponse),
+    ),
+)]
+pub async fn verify_mfa(
+    State(state): State<AppState>,
+    client_info: ClientInfo,
+    ValidatedJson(verify): ValidatedJson<ServerPostMfaVerifyRequest>,
+) -> AppResult<ServerLoginResponse> {
+    let user_id = state
+        .auth_service
+        .get_mfa_challenge_user_id(&verify.0.challenge_token)
+        .await?
+        .ok_or(ClientError::Unauthorized)?;
+    let user = state
+        .user_service
+        .find_user_by_id(user_id)
+        .await?
+        .ok_or(ClientError::Unauthorized)?;
+    let ip_address = client_info.ip_address.as_deref();
+    if let Some(retry_after) = state
+        .login_throttle_service
+        .find_lockout(&user.email, ip_address)
+        .await?
+    {
+        return Err(ClientError::TooManyRequests(retry_after).into());
+    }
+    if !state.mfa_service.verify(user.id, &verify.0.code).await? {
+        if let Some(retry_after) = state
+            .login_throttle_service
+            .record_failure(&user.email, ip_address)
+            .await?
+        {
+            return Err(ClientError::TooManyRequests(retry_after).into());
+        }
+        return Err(ClientError::Unauthorized.into());
+    }
+    state
+        .login_throttle_service
+        .record_success(&user.email)
+        .await?;
+    complete_login(&state, &user, client_info, true).await
+}
+
+async fn complete_login(
+    state: &AppState,
+    user: &User,
+    client_info: ClientInfo,
+    is_mfa_verified: bool,
 ) -> AppResult<ServerLoginResponse> {
-    let access_token = state.auth_service.generate_access_token(user.id)?;
+    let organization = state
+        .auth_service
+        .find_default_organization(user.id)
+        .await?
+        .ok_or(ClientError::Unauthorized)?;
+    let access_token = state
+        .auth_service
+        .generate_access_token(user, organization.id, is_mfa_verified)
+        .await?;
     let refresh_token = state
         .auth_service
-        .generate_refresh_token_from_login(user.id)
+        .generate_refresh_token_from_login(user.id, client_info, is_mfa_verified)
         .await?;
     Ok(ServerLoginResponse::new(access_token, refresh_token))
 }
 
+/// Tells the web app whether to offer single sign-on.
+#[axum::debug_handler]
+#[utoipa::path(
+    get,
+    path = "/auth/oidc",
+    tag = "auth",
+    responses(
+        (status = 200, description = "Whether single sign-on is offered", body = GetOidcResponse),
+    ),
+)]
+pub async fn get_oidc(State(state): State<AppState>) -> ServerGetOidcResponse {
+    ServerGetOidcResponse::new(state.oidc_service.is_enabled())
+}
+
+#[axum::debug_handler]
+#[utoipa::path(
+    get,
+    path = "/auth/oidc/login",
+    tag = "auth",
+    responses(
+        (status = 303, description = "Redirect to the identity provider"),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+    ),
+)]
+pub async fn oidc_login(State(state): State<AppState>) -> AppResult<ServerOidcLoginResponse> {
+    if !state.oidc_service.is_enabled() {
+        return Err(ClientError::NotFound.into());
+    }
+    let authorization = state.oidc_service.start_login().await?;
+    Ok(ServerOidcLoginResponse(authorization))
+}
+
+/// The provider redirects the browser here after sign in, which is sent on to the web
+/// app with a refresh token cookie.
+#[axum::debug_handler]
+#[utoipa::path(
+    get,
+    path = "/auth/oidc/callback",
+    tag = "auth",
+    params(
+        ("code" = Option<String>, Query, description = "Authorization code issued by the identity provider"),
+        ("state" = Option<String>, Query, description = "State sent along with the login"),
+        ("error" = Option<String>, Query, description = "Set by the identity provider if the login failed"),
+    ),
+    responses(
+        (status = 303, description = "Redirect to the web app, the refresh token is set as a cookie"),
+        (status = 400, description = "Malformed query parameter or header", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+    ),
+)]
+pub async fn oidc_callback(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    client_info: ClientInfo,
+    ValidatedQuery(query): ValidatedQuery<OidcCallbackQuery>,
+) -> AppResult<ServerOidcCallbackResponse> {
+    if !state.oidc_service.is_enabled() {
+        return Err(ClientError::NotFound.into());
+    }
+    if let Some(error) = query.error {
+        tracing::info!(error, "oidc provider reported a failed login");
+        return Err(ClientError::Unauthorized.into());
+    }
+    let (Some(state_cookie), Some(oidc_state), Some(code)) =
+        (jar.get(OIDC_LOGIN_COOKIE), query.state, query.code)
+    else {
+        return Err(ClientError::Unauthorized.into());
+    };
+    let login = state
+        .oidc_service
+        .finish_login(state_cookie.value(), &oidc_state, &code)
+        .await?
+        .ok_or(ClientError::Unauthorized)?;
+    let login_response =
+        complete_login(&state, &login.user, client_info, login.is_mfa_verified).await?;
+    Ok(ServerOidcCallbackResponse {
+        refresh_token: login_response.0.refresh_token,
+    })
+}
+
+/// Access tokens act for the organization the session was last switched to.
 #[axum::debug_handler]
-#[utoipa::path(post, path = "/auth/refresh")]
+#[utoipa::path(
+    post,
+    path = "/auth/refresh",
+    tag = "auth",
+    responses(
+        (status = 200, description = "New tokens, the refresh token is also set as a cookie", body = RefreshResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+    ),
+    security(("refresh_token" = [])),
+)]
 pub async fn refresh(
     State(state): State<AppState>,
     Extension(refresh_token): Extension<RefreshToken>,
+    client_info: ClientInfo,
 ) -> AppResult<ServerRefreshResponse> {
+    cycle_tokens(&state, &refresh_token, client_info).await
+}
+
+/// Switches the session to another organization of its user, which later refreshes keep.
+#[axum::debug_handler]
+#[utoipa::path(
+    post,
+    path = "/auth/switch-organization",
+    tag = "auth",
+    request_body = PostSwitchOrganizationRequest,
+    responses(
+        (status = 200, description = "New tokens for the organization, the refresh token is also set as a cookie", body = RefreshResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+        (status = 422, description = "Invalid request body", body = ErrorResponse),
+    ),
+    security(("refresh_token" = [])),
+)]
+pub async fn switch_organization(
+    State(state): State<AppState>,
+    Extension(refresh_token): Extension<RefreshToken>,
+    client_info: ClientInfo,
+    ValidatedJson(switch): ValidatedJson<ServerPostSwitchOrganizationRequest>,
+) -> AppResult<ServerRefreshResponse> {
+    state
+        .auth_service
+        .switch_organization(&refresh_token, switch.0.organization_id)
+        .await?
+        .ok_or(ClientError::NotFound)?;
+    cycle_tokens(&state, &refresh_token, client_info).await
+}
+
+async fn cycle_tokens(
+    state: &AppState,
+    refresh_token: &RefreshToken,
+    client_info: ClientInfo,
+) -> AppResult<ServerRefreshResponse> {
+    let user = state
+        .user_service
+        .find_user_by_id(refresh_token.user_id)
+        .await?
+        .ok_or(ClientError::Unauthorized)?;
+    let organization = state
+        .auth_service
+        .find_session_organization(refresh_token)
+        .await?
+        .ok_or(ClientError::Unauthorized)?;
+    let is_mfa_verified = state.auth_service.is_mfa_verified(refresh_token).await?;
     let access_token = state
         .auth_service
-        .generate_access_token(refresh_token.user_id)?;
+        .generate_access_token(&user, organization.id, is_mfa_verified)
+        .await?;
     let new_refresh_token = state
         .auth_service
-        .cycle_refresh_token(&refresh_token)
+        .cycle_refresh_token(refresh_token, client_info)
         .await?;
     Ok(ServerRefreshResponse::new(access_token, new_refresh_token))
 }
 
 #[axum::debug_handler]
-#[utoipa::path(post, path = "/auth/logout")]
+#[utoipa::path(
+    post,
+    path = "/auth/logout",
+    tag = "auth",
+    responses(
+        (status = 200, description = "Logged out, the refresh token cookie is cleared"),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+    ),
+    security(("refresh_token" = [])),
+)]
 pub async fn logout(
     State(state): State<AppState>,
     Extension(refresh_token): Extension<RefreshToken>,
@@ -49,3 +318,64 @@ pub async fn logout(
         .await?;
     Ok(ServerLogoutResponse)
 }
+
+#[axum::debug_handler]
+#[utoipa::path(
+    get,
+    path = "/auth/jwks",
+    tag = "auth",
+    responses(
+        (status = 200, description = "Public keys that access tokens are signed with", body = GetJwksResponse),
+    ),
+)]
+pub async fn jwks(State(state): State<AppState>) -> AppResult<ServerGetJwksResponse> {
+    let signing_keys = state.signing_key_service.find_verification_keys().await?;
+    Ok(signing_keys.into())
+}
+
+#[axum::debug_handler]
+#[utoipa::path(
+    post,
+    path = "/auth/password-reset",
+    tag = "auth",
+    request_body = PostPasswordResetRequest,
+    responses(
+        (status = 202, description = "Sent whether or not the email belongs to an account"),
+        (status = 404, description = "Sending email is disabled", body = ErrorResponse),
+        (status = 422, description = "Invalid request body", body = ErrorResponse),
+    ),
+)]
+pub async fn request_password_reset(
+    State(state): State<AppState>,
+    ValidatedJson(reset): ValidatedJson<ServerPostPasswordResetRequest>,
+) -> AppResult<ServerPostPasswordResetResponse> {
+    if !state.password_reset_service.is_enabled() {
+        return Err(ClientError::NotFound.into());
+    }
+    state.password_reset_service.request_reset(&reset.0.email);
+    Ok(ServerPostPasswordResetResponse)
+}
+
+#[axum::debug_handler]
+#[utoipa::path(
+    post,
+    path = "/auth/password-reset/confirm",
+    tag = "auth",
+    request_body = PostPasswordResetConfirmRequest,
+    responses(
+        (status = 204, description = "Password changed"),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 422, description = "Invalid request body", body = ErrorResponse),
+    ),
+)]
+pub async fn confirm_password_reset(
+    State(state): State<AppState>,
+    ValidatedJson(confirm): ValidatedJson<ServerPostPasswordResetConfirmRequest>,
+) -> AppResult<ServerPostPasswordResetConfirmResponse> {
+    state
+        .password_reset_service
+        .confirm_reset(&confirm.0.token, &confirm.0.new_password)
+        .await?
+        .ok_or(ClientError::Unauthorized)?;
+    Ok(ServerPostPasswordResetConfirmResponse)
+}
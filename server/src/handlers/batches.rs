@@ -0,0 +1,294 @@
+use axum::{Extension, extract::State};
+use open_erase_lib::schemas::{
+    batch::{
+        GetBatchCertificateResponse, GetBatchResponse, GetBatchSummaryResponse, GetBatchesResponse,
+        PatchBatchRequest, PatchBatchResponse, PostBatchRequest, PostBatchResponse,
+    },
+    device::{GetDevicesResponse, PostDeviceRequest, PostDeviceResponse},
+    error::ErrorResponse,
+    report::GetReportsResponse,
+};
+use uuid::Uuid;
+
+use crate::{
+    error::{AppResult, ClientError},
+    models::Batch,
+    schemas::{
+        batch::{
+            ServerDeleteBatchResponse, ServerGetBatchCertificateResponse, ServerGetBatchResponse,
+            ServerGetBatchSummaryResponse, ServerGetBatchesResponse, ServerPatchBatchRequest,
+            ServerPatchBatchResponse, ServerPostBatchRequest, ServerPostBatchResponse,
+        },
+        device::{ServerGetDevicesResponse, ServerPostDeviceRequest, ServerPostDeviceResponse},
+        report::ServerGetReportsResponse,
+    },
+    services::auth::Claims,
+    state::AppState,
+    validation::{ValidatedJson, ValidatedPath},
+};
+
+#[axum::debug_handler]
+#[utoipa::path(
+    get,
+    path = "/batches",
+    tag = "batches",
+    responses(
+        (status = 200, description = "Batches of the organization", body = GetBatchesResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn get_batches(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<ServerGetBatchesResponse> {
+    Ok(state.batch_service.get_all(claims.org).await?.into())
+}
+
+#[axum::debug_handler]
+#[utoipa::path(
+    get,
+    path = "/batches/{uuid}",
+    tag = "batches",
+    params(
+        ("uuid" = Uuid, Path, description = "Id of the batch"),
+    ),
+    responses(
+        (status = 200, description = "The batch", body = GetBatchResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn get_batch(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    ValidatedPath(id): ValidatedPath<Uuid>,
+) -> AppResult<ServerGetBatchResponse> {
+    let batch = find_batch(&state, &claims, id).await?;
+    Ok(batch.into())
+}
+
+#[axum::debug_handler]
+#[utoipa::path(
+    post,
+    path = "/batches",
+    tag = "batches",
+    request_body = PostBatchRequest,
+    responses(
+        (status = 201, description = "Batch created", body = PostBatchResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 422, description = "Invalid request body", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn post_batch(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    ValidatedJson(batch): ValidatedJson<ServerPostBatchRequest>,
+) -> AppResult<ServerPostBatchResponse> {
+    let batch = state.batch_service.create_batch(claims.org, batch).await?;
+    Ok(batch.into())
+}
+
+#[axum::debug_handler]
+#[utoipa::path(
+    patch,
+    path = "/batches/{uuid}",
+    tag = "batches",
+    params(
+        ("uuid" = Uuid, Path, description = "Id of the batch"),
+    ),
+    request_body = PatchBatchRequest,
+    responses(
+        (status = 200, description = "Batch updated", body = PatchBatchResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+        (status = 422, description = "Invalid request body", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn patch_batch(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    ValidatedPath(id): ValidatedPath<Uuid>,
+    ValidatedJson(batch_patch): ValidatedJson<ServerPatchBatchRequest>,
+) -> AppResult<ServerPatchBatchResponse> {
+    let batch = find_batch(&state, &claims, id).await?;
+    let batch = state
+        .batch_service
+        .update_batch(claims.org, batch.id, batch_patch)
+        .await?;
+    Ok(batch.into())
+}
+
+#[axum::debug_handler]
+#[utoipa::path(
+    delete,
+    path = "/batches/{uuid}",
+    tag = "batches",
+    params(
+        ("uuid" = Uuid, Path, description = "Id of the batch"),
+    ),
+    responses(
+        (status = 204, description = "Batch deleted"),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn delete_batch(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    ValidatedPath(id): ValidatedPath<Uuid>,
+) -> AppResult<ServerDeleteBatchResponse> {
+    let batch = find_batch(&state, &claims, id).await?;
+    let batch = state
+        .batch_service
+        .delete_batch(claims.org, batch.id)
+        .await?;
+    Ok(batch.into())
+}
+
+#[axum::debug_handler]
+#[utoipa::path(
+    get,
+    path = "/batches/{uuid}/devices",
+    tag = "batches",
+    params(
+        ("uuid" = Uuid, Path, description = "Id of the batch"),
+    ),
+    responses(
+        (status = 200, description = "Devices of the batch", body = GetDevicesResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn get_batch_devices(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    ValidatedPath(id): ValidatedPath<Uuid>,
+) -> AppResult<ServerGetDevicesResponse> {
+    let batch = find_batch(&state, &claims, id).await?;
+    Ok(state.batch_service.find_devices(&batch).await?.into())
+}
+
+#[axum::debug_handler]
+#[utoipa::path(
+    post,
+    path = "/batches/{uuid}/devices",
+    tag = "batches",
+    params(
+        ("uuid" = Uuid, Path, description = "Id of the batch"),
+    ),
+    request_body = PostDeviceRequest,
+    responses(
+        (status = 201, description = "Device added", body = PostDeviceResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+        (status = 422, description = "Invalid request body", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn post_batch_device(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    ValidatedPath(id): ValidatedPath<Uuid>,
+    ValidatedJson(device): ValidatedJson<ServerPostDeviceRequest>,
+) -> AppResult<ServerPostDeviceResponse> {
+    let batch = find_batch(&state, &claims, id).await?;
+    let device = state.batch_service.add_device(&batch, device).await?;
+    Ok(device.into())
+}
+
+#[axum::debug_handler]
+#[utoipa::path(
+    get,
+    path = "/batches/{uuid}/reports",
+    tag = "batches",
+    params(
+        ("uuid" = Uuid, Path, description = "Id of the batch"),
+    ),
+    responses(
+        (status = 200, description = "Reports of the devices of the batch", body = GetReportsResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn get_batch_reports(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    ValidatedPath(id): ValidatedPath<Uuid>,
+) -> AppResult<ServerGetReportsResponse> {
+    let batch = find_batch(&state, &claims, id).await?;
+    Ok(state.batch_service.find_reports(&batch).await?.into())
+}
+
+#[axum::debug_handler]
+#[utoipa::path(
+    get,
+    path = "/batches/{uuid}/summary",
+    tag = "batches",
+    params(
+        ("uuid" = Uuid, Path, description = "Id of the batch"),
+    ),
+    responses(
+        (status = 200, description = "Progress of the batch", body = GetBatchSummaryResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn get_batch_summary(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    ValidatedPath(id): ValidatedPath<Uuid>,
+) -> AppResult<ServerGetBatchSummaryResponse> {
+    let batch = find_batch(&state, &claims, id).await?;
+    Ok(state.batch_service.summarize(batch).await?.into())
+}
+
+#[axum::debug_handler]
+#[utoipa::path(
+    get,
+    path = "/batches/{uuid}/certificate",
+    tag = "batches",
+    params(
+        ("uuid" = Uuid, Path, description = "Id of the batch"),
+    ),
+    responses(
+        (status = 200, description = "Erasure certificate of the batch", body = GetBatchCertificateResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn get_batch_certificate(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    ValidatedPath(id): ValidatedPath<Uuid>,
+) -> AppResult<ServerGetBatchCertificateResponse> {
+    let batch = find_batch(&state, &claims, id).await?;
+    Ok(state.batch_service.issue_certificate(batch).await?.into())
+}
+
+async fn find_batch(state: &AppState, claims: &Claims, id: Uuid) -> AppResult<Batch> {
+    let batch = state
+        .batch_service
+        .find_batch_by_id(claims.org, id)
+        .await?
+        .ok_or(ClientError::NotFound)?;
+    Ok(batch)
+}
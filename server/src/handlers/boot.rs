@@ -0,0 +1,67 @@
+use axum::{body::Body, extract::State};
+use open_erase_lib::schemas::error::ErrorResponse;
+use tokio_util::io::ReaderStream;
+use uuid::Uuid;
+
+use crate::{
+    error::{AppResult, ClientError},
+    models::Architecture,
+    schemas::boot::{ServerBootArtifact, ServerIpxeScript},
+    state::AppState,
+    validation::ValidatedPath,
+};
+
+#[axum::debug_handler]
+#[utoipa::path(
+    get,
+    path = "/boot/{architecture}/boot.ipxe",
+    tag = "boot",
+    params(
+        ("architecture" = String, Path, description = "`x86_64` or `aarch64`"),
+    ),
+    responses(
+        (status = 200, description = "iPXE script booting the active image", body = String, content_type = "text/plain"),
+        (status = 404, description = "Network boot is disabled or no image is active", body = ErrorResponse),
+    ),
+)]
+pub async fn get_ipxe_script(
+    State(state): State<AppState>,
+    ValidatedPath(architecture): ValidatedPath<String>,
+) -> AppResult<ServerIpxeScript> {
+    let architecture: Architecture = architecture.parse().map_err(|_| ClientError::NotFound)?;
+    let script = state
+        .boot_service
+        .ipxe_script(architecture)
+        .await?
+        .ok_or(ClientError::NotFound)?;
+    Ok(ServerIpxeScript(script))
+}
+
+#[axum::debug_handler]
+#[utoipa::path(
+    get,
+    path = "/boot/images/{uuid}/{path}",
+    tag = "boot",
+    params(
+        ("uuid" = Uuid, Path, description = "Id of the active image"),
+        ("path" = String, Path, description = "File inside the archiso install directory, e.g. `arch/x86_64/airootfs.sfs`"),
+    ),
+    responses(
+        (status = 200, description = "The file", body = Vec<u8>, content_type = "application/octet-stream"),
+        (status = 404, description = "Network boot is disabled, the image is not active or lacks the file", body = ErrorResponse),
+    ),
+)]
+pub async fn get_boot_artifact(
+    State(state): State<AppState>,
+    ValidatedPath((id, path)): ValidatedPath<(Uuid, String)>,
+) -> AppResult<ServerBootArtifact> {
+    let artifact = state
+        .boot_service
+        .open_artifact(id, &path)
+        .await?
+        .ok_or(ClientError::NotFound)?;
+    Ok(ServerBootArtifact {
+        size: artifact.size,
+        body: Body::from_stream(ReaderStream::new(artifact.content)),
+    })
+}
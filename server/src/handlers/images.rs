@@ -1,9 +1,420 @@
-use axum::extract::State;
+use axum::{
+    Extension,
+    body::Body,
+    extract::{
+        State,
+        multipart::{Field, Multipart, MultipartError, MultipartRejection},
+    },
+    http::StatusCode,
+};
+use axum_extra::headers::{IfNoneMatch, IfRange, Range};
+use open_erase_lib::schemas::{
+    error::{ErrorResponse, FieldError},
+    image::{
+        CLIENT_CONFIG_PATH, GetImageResponse, GetImagesResponse, PatchImageRequest,
+        PatchImageResponse, PostImageForm, PostImageResponse, PostImageVariantRequest,
+        PostImageVariantResponse,
+    },
+};
+use tokio_util::io::ReaderStream;
+use uuid::Uuid;
 
-use crate::{error::AppResult, schemas::image::ServerGetImagesResponse, state::AppState};
+use crate::{
+    error::{AppResult, ClientError},
+    models::Permission,
+    schemas::image::{
+        ByteRange, MAX_RELEASE_NOTES_LENGTH, ServerGetImageResponse, ServerGetImagesResponse,
+        ServerImageChecksums, ServerImageDownload, ServerPatchImageRequest,
+        ServerPatchImageResponse, ServerPostImageForm, ServerPostImageResponse,
+        ServerPostImageVariantRequest, ServerPostImageVariantResponse, image_etag,
+    },
+    services::{auth::Claims, image::VariantError},
+    state::AppState,
+    validation::{Validate, ValidatedHeader, ValidatedJson, ValidatedPath, Validator},
+};
 
+/// Bytes a UTF-8 text field of the upload form may take up at most.
+const MAX_TEXT_FIELD_BYTES: usize = 4 * MAX_RELEASE_NOTES_LENGTH;
+
+#[axum::debug_handler]
+#[utoipa::path(
+    get,
+    path = "/images",
+    tag = "images",
+    responses(
+        (status = 200, description = "Base images, newest first", body = GetImagesResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn get_images(State(state): State<AppState>) -> AppResult<ServerGetImagesResponse> {
+    Ok(state.image_service.find_all().await?.into())
+}
+
+#[axum::debug_handler]
+#[utoipa::path(
+    get,
+    path = "/images/{uuid}",
+    tag = "images",
+    params(
+        ("uuid" = Uuid, Path, description = "Id of the image"),
+    ),
+    responses(
+        (status = 200, description = "The image", body = GetImageResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn get_image(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    ValidatedPath(id): ValidatedPath<Uuid>,
+) -> AppResult<ServerGetImageResponse> {
+    let image = state
+        .image_service
+        .find_visible(claims.org, id)
+        .await?
+        .ok_or(ClientError::NotFound)?;
+    Ok(image.into())
+}
+
+/// `sha256sum --check` compatible checksums of every base image under its download name.
+#[axum::debug_handler]
+#[utoipa::path(
+    get,
+    path = "/images/SHA256SUMS",
+    tag = "images",
+    responses(
+        (status = 200, description = "Checksums of the base images", body = String, content_type = "text/plain"),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn get_image_checksums(State(state): State<AppState>) -> AppResult<ServerImageChecksums> {
+    Ok(ServerImageChecksums(state.image_service.find_all().await?))
+}
+
+/// Serves a single byte range so that interrupted downloads can be resumed. Variants
+/// that carry an enrollment code need the permission to manage stations.
+#[axum::debug_handler]
+#[utoipa::path(
+    get,
+    path = "/images/{uuid}/download",
+    tag = "images",
+    params(
+        ("uuid" = Uuid, Path, description = "Id of the image"),
+        ("range" = Option<String>, Header, description = "Single byte range to download, e.g. `bytes=1048576-`"),
+        ("if-range" = Option<String>, Header, description = "Only honour `range` if the ETag still matches"),
+        ("if-none-match" = Option<String>, Header, description = "ETag of an already downloaded image"),
+    ),
+    responses(
+        (status = 200, description = "The ISO, with its SHA-256 in `repr-digest`", body = Vec<u8>, content_type = "application/x-iso9660-image"),
+        (status = 206, description = "The requested range of the ISO", body = Vec<u8>, content_type = "application/x-iso9660-image"),
+        (status = 304, description = "The ETag in `if-none-match` still matches"),
+        (status = 400, description = "Malformed query parameter or header", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+        (status = 416, description = "The range lies outside of the image", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn download_image(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    ValidatedPath(id): ValidatedPath<Uuid>,
+    range: Option<ValidatedHeader<Range>>,
+    if_range: Option<ValidatedHeader<IfRange>>,
+    if_none_match: Option<ValidatedHeader<IfNoneMatch>>,
+) -> AppResult<ServerImageDownload> {
+    let image = state
+        .image_service
+        .find_visible(claims.org, id)
+        .await?
+        .ok_or(ClientError::NotFound)?;
+    // the enrollment code in the ISO is as good as the station itself
+    if image.station_id.is_some() && !claims.has_permission(Permission::ManageStations) {
+        return Err(ClientError::Forbidden.into());
+    }
+    let etag = image_etag(&image);
+    if let Some(ValidatedHeader(if_none_match)) = if_none_match
+        && !if_none_match.precondition_passes(&etag)
+    {
+        return Ok(ServerImageDownload::not_modified(image));
+    }
+    let size = image.size_bytes as u64;
+    let range = match if_range {
+        Some(ValidatedHeader(if_range)) if if_range.is_modified(Some(&etag), None) => {
+            ByteRange::Full
+        }
+        _ => ByteRange::resolve(range.as_ref().map(|ValidatedHeader(range)| range), size),
+    };
+    let (offset, length) = match range {
+        ByteRange::Full => (0, size),
+        ByteRange::Partial(start, end) => (start, end - start + 1),
+        ByteRange::Unsatisfiable => return Err(ClientError::RangeNotSatisfiable(size).into()),
+    };
+    let file = state.image_service.open(&image, offset, length).await?;
+    let body = Body::from_stream(ReaderStream::new(file));
+    Ok(ServerImageDownload::new(image, range, body))
+}
+
+/// The ISO is hashed while it is written to disk, so it is never held in memory. Base
+/// images are shared by all organizations and uploaded by server admins.
 #[axum::debug_handler]
-#[utoipa::path(get, path = "/images")]
-pub async fn images(State(state): State<AppState>) -> AppResult<ServerGetImagesResponse> {
-    Ok(state.image_service.get_all().await?.into())
+#[utoipa::path(
+    post,
+    path = "/images",
+    tag = "images",
+    request_body(content = PostImageForm, content_type = "multipart/form-data"),
+    responses(
+        (status = 201, description = "Image uploaded", body = PostImageResponse),
+        (status = 400, description = "Malformed request body", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 409, description = "The version already exists for the architecture", body = ErrorResponse),
+        (status = 413, description = "The image exceeds the upload limit", body = ErrorResponse),
+        (status = 415, description = "The request body is not a multipart form", body = ErrorResponse),
+        (status = 422, description = "Invalid request body", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn post_image(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    multipart: Result<Multipart, MultipartRejection>,
+) -> AppResult<ServerPostImageResponse> {
+    let mut multipart = multipart.map_err(|_| ClientError::UnsupportedMediaType)?;
+    let mut form = ServerPostImageForm::default();
+    let mut upload = None;
+    while let Some(mut field) = multipart.next_field().await.map_err(into_client_error)? {
+        match field.name() {
+            Some("version") => form.version = Some(read_text(field).await?),
+            Some("architecture") => form.architecture = Some(read_text(field).await?),
+            Some("release_notes") => form.release_notes = Some(read_text(field).await?),
+            Some("file") if upload.is_none() => {
+                let mut image_upload = state.image_service.start_upload().await?;
+                while let Some(chunk) = field.chunk().await.map_err(into_client_error)? {
+                    if !image_upload.write(&chunk).await? {
+                        return Err(ClientError::PayloadTooLarge.into());
+                    }
+                }
+                form.has_file = !image_upload.is_empty();
+                upload = Some(image_upload);
+            }
+            _ => {}
+        }
+    }
+    let mut validator = Validator::new(&state.config.auth);
+    form.validate(&mut validator);
+    validator.finish()?;
+    let architecture = form.architecture();
+    // validation made sure that all of them are there
+    let (Some(upload), Some(version), Some(architecture)) = (upload, form.version, architecture)
+    else {
+        return Err(ClientError::MalformedBody.into());
+    };
+    let image = state
+        .image_service
+        .finish_upload(
+            upload,
+            claims.user_id()?,
+            version,
+            architecture,
+            form.release_notes,
+        )
+        .await?
+        .ok_or(ClientError::Conflict)?;
+    Ok(image.into())
+}
+
+#[axum::debug_handler]
+#[utoipa::path(
+    get,
+    path = "/images/{uuid}/variants",
+    tag = "images",
+    params(
+        ("uuid" = Uuid, Path, description = "Id of the base image"),
+    ),
+    responses(
+        (status = 200, description = "Site variants the organization made of the image, newest first", body = GetImagesResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn get_image_variants(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    ValidatedPath(id): ValidatedPath<Uuid>,
+) -> AppResult<ServerGetImagesResponse> {
+    state
+        .image_service
+        .find_visible(claims.org, id)
+        .await?
+        .ok_or(ClientError::NotFound)?;
+    Ok(state
+        .image_service
+        .find_variants(claims.org, id)
+        .await?
+        .into())
+}
+
+/// Writes a client config into a copy of the image, whose base ISO has to reserve room
+/// for it at `open-erase/config.toml`. Registering a station for the variant also takes
+/// the permission to manage stations; the station is disabled again if the variant
+/// can't be built.
+#[axum::debug_handler]
+#[utoipa::path(
+    post,
+    path = "/images/{uuid}/variants",
+    tag = "images",
+    params(
+        ("uuid" = Uuid, Path, description = "Id of the base image"),
+    ),
+    request_body = PostImageVariantRequest,
+    responses(
+        (status = 201, description = "Variant created", body = PostImageVariantResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+        (status = 409, description = "The organization already has a variant of that name", body = ErrorResponse),
+        (status = 422, description = "Invalid request body, or the config doesn't fit into the base image", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn post_image_variant(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    ValidatedPath(id): ValidatedPath<Uuid>,
+    ValidatedJson(variant): ValidatedJson<ServerPostImageVariantRequest>,
+) -> AppResult<ServerPostImageVariantResponse> {
+    let base_image = state
+        .image_service
+        .find_visible(claims.org, id)
+        .await?
+        .ok_or(ClientError::NotFound)?;
+    let user_id = claims.user_id()?;
+    let (station, enrollment_code) = match variant.0.station_name.clone() {
+        Some(station_name) => {
+            if !claims.has_permission(Permission::ManageStations) {
+                return Err(ClientError::Forbidden.into());
+            }
+            let (station, enrollment_code) = state
+                .station_service
+                .create(claims.org, user_id, station_name)
+                .await?;
+            (Some(station), Some(enrollment_code))
+        }
+        None => (None, None),
+    };
+    let config = variant.client_config(&state.config.server.public_url, enrollment_code);
+    let result = state
+        .image_service
+        .create_variant(
+            &base_image,
+            claims.org,
+            user_id,
+            variant.0.name,
+            &config,
+            station.as_ref().map(|station| station.id),
+            variant.0.release_notes,
+        )
+        .await;
+    // the station only exists for the variant, so it goes with it
+    if !matches!(result, Ok(Ok(_)))
+        && let Some(station) = &station
+    {
+        state.station_service.delete(claims.org, station.id).await?;
+    }
+    let field_error = |field: &str, message: String| {
+        ClientError::Validation(vec![FieldError {
+            field: String::from(field),
+            message,
+        }])
+    };
+    match result? {
+        Ok(image) => Ok(ServerPostImageVariantResponse::new(image, station)),
+        Err(VariantError::NameTaken) => Err(ClientError::Conflict.into()),
+        Err(VariantError::MissingPlaceholder) => Err(field_error(
+            "uuid",
+            format!("image has no placeholder at {CLIENT_CONFIG_PATH}"),
+        )
+        .into()),
+        Err(VariantError::ConfigTooLarge(size)) => Err(field_error(
+            "ca_certificate",
+            format!("makes the config exceed the {size} bytes the image reserves"),
+        )
+        .into()),
+    }
+}
+
+/// Base images are shared by all organizations, so changing them is up to server admins.
+#[axum::debug_handler]
+#[utoipa::path(
+    patch,
+    path = "/images/{uuid}",
+    tag = "images",
+    params(
+        ("uuid" = Uuid, Path, description = "Id of the image"),
+    ),
+    request_body = PatchImageRequest,
+    responses(
+        (status = 200, description = "Image updated", body = PatchImageResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+        (status = 422, description = "Invalid request body", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn patch_image(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    ValidatedPath(id): ValidatedPath<Uuid>,
+    ValidatedJson(image_patch): ValidatedJson<ServerPatchImageRequest>,
+) -> AppResult<ServerPatchImageResponse> {
+    let image = state
+        .image_service
+        .find_visible(claims.org, id)
+        .await?
+        .ok_or(ClientError::NotFound)?;
+    if image.base_image_id.is_none() && !claims.has_permission(Permission::ManageBaseImages) {
+        return Err(ClientError::Forbidden.into());
+    }
+    let image = state
+        .image_service
+        .update(
+            claims.org,
+            image.id,
+            image_patch.0.release_notes,
+            image_patch.0.is_active,
+        )
+        .await?
+        .ok_or(ClientError::NotFound)?;
+    Ok(image.into())
+}
+
+/// Reads a text field of the upload form without buffering more than it may hold.
+async fn read_text(mut field: Field<'_>) -> Result<String, ClientError> {
+    let mut bytes = Vec::new();
+    while let Some(chunk) = field.chunk().await.map_err(into_client_error)? {
+        if bytes.len() + chunk.len() > MAX_TEXT_FIELD_BYTES {
+            return Err(ClientError::PayloadTooLarge);
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    String::from_utf8(bytes).map_err(|_| ClientError::MalformedBody)
+}
+
+fn into_client_error(error: MultipartError) -> ClientError {
+    match error.status() {
+        StatusCode::PAYLOAD_TOO_LARGE => ClientError::PayloadTooLarge,
+        _ => ClientError::MalformedBody,
+    }
 }
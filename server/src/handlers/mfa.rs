@@ -0,0 +1,183 @@
+use axum::{Extension, extract::State};
+use open_erase_lib::schemas::{
+    error::ErrorResponse,
+    mfa::{
+        GetMfaResponse, PostRecoveryCodesResponse, PostTotpConfirmRequest, PostTotpConfirmResponse,
+        PostTotpResponse,
+    },
+};
+
+use crate::{
+    error::{AppResult, ClientError},
+    schemas::mfa::{
+        ServerDeleteMfaResponse, ServerGetMfaResponse, ServerPostRecoveryCodesResponse,
+        ServerPostTotpConfirmRequest, ServerPostTotpConfirmResponse, ServerPostTotpResponse,
+    },
+    schemas::session::ClientInfo,
+    services::auth::Claims,
+    state::AppState,
+    validation::ValidatedJson,
+};
+
+#[axum::debug_handler]
+#[utoipa::path(
+    get,
+    path = "/users/me/mfa",
+    tag = "mfa",
+    responses(
+        (status = 200, description = "Second factors of the calling user", body = GetMfaResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn get_my_mfa(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<ServerGetMfaResponse> {
+    let status = state.mfa_service.find_status(claims.user_id()?).await?;
+    Ok(status.into())
+}
+
+#[axum::debug_handler]
+#[utoipa::path(
+    post,
+    path = "/users/me/mfa/totp",
+    tag = "mfa",
+    responses(
+        (status = 201, description = "Secret to confirm with a first code", body = PostTotpResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+        (status = 409, description = "Conflicts with the current state", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn post_my_totp(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<ServerPostTotpResponse> {
+    let user = state
+        .user_service
+        .find_user_by_id(claims.user_id()?)
+        .await?
+        .ok_or(ClientError::NotFound)?;
+    let enrollment = state
+        .mfa_service
+        .start_totp_enrollment(&user)
+        .await?
+        .ok_or(ClientError::Conflict)?;
+    Ok(enrollment.into())
+}
+
+/// Failed codes count towards the same lockout as failed logins.
+#[axum::debug_handler]
+#[utoipa::path(
+    post,
+    path = "/users/me/mfa/totp/confirm",
+    tag = "mfa",
+    request_body = PostTotpConfirmRequest,
+    responses(
+        (status = 200, description = "TOTP enabled", body = PostTotpConfirmResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 422, description = "Invalid request body", body = ErrorResponse),
+        (status = 429, description = "Locked out after too many failed attempts", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn post_my_totp_confirm(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    client_info: ClientInfo,
+    ValidatedJson(confirm): ValidatedJson<ServerPostTotpConfirmRequest>,
+) -> AppResult<ServerPostTotpConfirmResponse> {
+    let user = state
+        .user_service
+        .find_user_by_id(claims.user_id()?)
+        .await?
+        .ok_or(ClientError::NotFound)?;
+    let ip_address = client_info.ip_address.as_deref();
+    if let Some(retry_after) = state
+        .login_throttle_service
+        .find_lockout(&user.email, ip_address)
+        .await?
+    {
+        return Err(ClientError::TooManyRequests(retry_after).into());
+    }
+    let Some(recovery_codes) = state
+        .mfa_service
+        .confirm_totp_enrollment(user.id, &confirm.0.code)
+        .await?
+    else {
+        if let Some(retry_after) = state
+            .login_throttle_service
+            .record_failure(&user.email, ip_address)
+            .await?
+        {
+            return Err(ClientError::TooManyRequests(retry_after).into());
+        }
+        return Err(ClientError::Unauthorized.into());
+    };
+    state
+        .login_throttle_service
+        .record_success(&user.email)
+        .await?;
+    Ok(recovery_codes.into())
+}
+
+/// Only a login that passed the second factor may replace its recovery codes.
+#[axum::debug_handler]
+#[utoipa::path(
+    post,
+    path = "/users/me/mfa/recovery-codes",
+    tag = "mfa",
+    responses(
+        (status = 200, description = "Recovery codes replaced", body = PostRecoveryCodesResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn post_my_recovery_codes(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<ServerPostRecoveryCodesResponse> {
+    if !claims.mfa {
+        return Err(ClientError::Forbidden.into());
+    }
+    let recovery_codes = state
+        .mfa_service
+        .regenerate_recovery_codes(claims.user_id()?)
+        .await?
+        .ok_or(ClientError::NotFound)?;
+    Ok(recovery_codes.into())
+}
+
+/// Only a login that passed the second factor may remove it.
+#[axum::debug_handler]
+#[utoipa::path(
+    delete,
+    path = "/users/me/mfa",
+    tag = "mfa",
+    responses(
+        (status = 204, description = "Second factor removed"),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn delete_my_mfa(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<ServerDeleteMfaResponse> {
+    if !claims.mfa {
+        return Err(ClientError::Forbidden.into());
+    }
+    if !state.mfa_service.disable(claims.user_id()?).await? {
+        return Err(ClientError::NotFound.into());
+    }
+    Ok(ServerDeleteMfaResponse)
+}
@@ -1,3 +1,12 @@
+pub mod api_keys;
 pub mod auth;
+pub mod batches;
+pub mod boot;
 pub mod images;
+pub mod mfa;
+pub mod organizations;
+pub mod reports;
+pub mod sessions;
+pub mod signing_keys;
+pub mod stations;
 pub mod users;
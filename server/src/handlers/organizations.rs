@@ -0,0 +1,329 @@
+use axum::{Extension, extract::State};
+use open_erase_lib::schemas::{
+    error::ErrorResponse,
+    organization::{
+        GetOrganizationInvitationsResponse, GetOrganizationMembersResponse,
+        GetOrganizationResponse, GetOrganizationsResponse, PostOrganizationInvitationRequest,
+        PostOrganizationInvitationResponse, PostOrganizationMemberRequest,
+        PostOrganizationMemberResponse, PostOrganizationRequest, PostOrganizationResponse,
+    },
+};
+use uuid::Uuid;
+
+use crate::{
+    error::{AppResult, ClientError},
+    models::Organization,
+    schemas::organization::{
+        ServerDeleteOrganizationInvitationResponse, ServerDeleteOrganizationMemberResponse,
+        ServerGetOrganizationInvitationsResponse, ServerGetOrganizationMembersResponse,
+        ServerGetOrganizationResponse, ServerGetOrganizationsResponse,
+        ServerPostOrganizationInvitationRequest, ServerPostOrganizationInvitationResponse,
+        ServerPostOrganizationMemberRequest, ServerPostOrganizationMemberResponse,
+        ServerPostOrganizationRequest, ServerPostOrganizationResponse,
+    },
+    services::{auth::Claims, organization::MembershipError},
+    state::AppState,
+    validation::{ValidatedJson, ValidatedPath},
+};
+
+#[axum::debug_handler]
+#[utoipa::path(
+    get,
+    path = "/organizations",
+    tag = "organizations",
+    responses(
+        (status = 200, description = "Organizations of the calling user", body = GetOrganizationsResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn get_organizations(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<ServerGetOrganizationsResponse> {
+    let organizations = state
+        .organization_service
+        .find_organizations_by_user_id(claims.user_id()?)
+        .await?;
+    Ok(organizations.into())
+}
+
+#[axum::debug_handler]
+#[utoipa::path(
+    get,
+    path = "/organizations/{uuid}",
+    tag = "organizations",
+    params(
+        ("uuid" = Uuid, Path, description = "Id of the organization"),
+    ),
+    responses(
+        (status = 200, description = "The organization", body = GetOrganizationResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn get_organization(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    ValidatedPath(id): ValidatedPath<Uuid>,
+) -> AppResult<ServerGetOrganizationResponse> {
+    let organization = find_organization(&state, &claims, id).await?;
+    Ok(organization.into())
+}
+
+#[axum::debug_handler]
+#[utoipa::path(
+    post,
+    path = "/organizations",
+    tag = "organizations",
+    request_body = PostOrganizationRequest,
+    responses(
+        (status = 201, description = "Organization created", body = PostOrganizationResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 422, description = "Invalid request body", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn post_organization(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    ValidatedJson(organization): ValidatedJson<ServerPostOrganizationRequest>,
+) -> AppResult<ServerPostOrganizationResponse> {
+    let organization = state
+        .organization_service
+        .create_organization(organization, claims.user_id()?)
+        .await?;
+    Ok(organization.into())
+}
+
+#[axum::debug_handler]
+#[utoipa::path(
+    get,
+    path = "/organizations/{uuid}/members",
+    tag = "organizations",
+    params(
+        ("uuid" = Uuid, Path, description = "Id of the organization"),
+    ),
+    responses(
+        (status = 200, description = "Members of the organization", body = GetOrganizationMembersResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn get_organization_members(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    ValidatedPath(id): ValidatedPath<Uuid>,
+) -> AppResult<ServerGetOrganizationMembersResponse> {
+    let organization = find_organization(&state, &claims, id).await?;
+    let members = state
+        .organization_service
+        .find_members(&organization)
+        .await?;
+    Ok(members.into())
+}
+
+/// Only users who already share an organization with the caller can be added, anyone
+/// else has to accept an invitation.
+#[axum::debug_handler]
+#[utoipa::path(
+    post,
+    path = "/organizations/{uuid}/members",
+    tag = "organizations",
+    params(
+        ("uuid" = Uuid, Path, description = "Id of the organization"),
+    ),
+    request_body = PostOrganizationMemberRequest,
+    responses(
+        (status = 201, description = "Member added", body = PostOrganizationMemberResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+        (status = 409, description = "The user is a member already", body = ErrorResponse),
+        (status = 422, description = "Invalid request body", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn post_organization_member(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    ValidatedPath(id): ValidatedPath<Uuid>,
+    ValidatedJson(member): ValidatedJson<ServerPostOrganizationMemberRequest>,
+) -> AppResult<ServerPostOrganizationMemberResponse> {
+    let organization = find_organization(&state, &claims, id).await?;
+    let member = state
+        .organization_service
+        .add_member(&organization, member.0.user_id, claims.user_id()?)
+        .await?
+        .map_err(into_client_error)?;
+    Ok(member.into())
+}
+
+#[axum::debug_handler]
+#[utoipa::path(
+    delete,
+    path = "/organizations/{uuid}/members/{user_uuid}",
+    tag = "organizations",
+    params(
+        ("uuid" = Uuid, Path, description = "Id of the organization"),
+        ("user_uuid" = Uuid, Path, description = "Id of the member"),
+    ),
+    responses(
+        (status = 204, description = "Member removed"),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn delete_organization_member(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    ValidatedPath((id, user_id)): ValidatedPath<(Uuid, Uuid)>,
+) -> AppResult<ServerDeleteOrganizationMemberResponse> {
+    let organization = find_organization(&state, &claims, id).await?;
+    let member = state
+        .organization_service
+        .remove_member(&organization, user_id)
+        .await?
+        .ok_or(ClientError::NotFound)?;
+    Ok(member.into())
+}
+
+#[axum::debug_handler]
+#[utoipa::path(
+    post,
+    path = "/organizations/{uuid}/invitations",
+    tag = "organizations",
+    params(
+        ("uuid" = Uuid, Path, description = "Id of the organization"),
+    ),
+    request_body = PostOrganizationInvitationRequest,
+    responses(
+        (status = 201, description = "User invited", body = PostOrganizationInvitationResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+        (status = 409, description = "The user is a member or invited already", body = ErrorResponse),
+        (status = 422, description = "Invalid request body", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn post_organization_invitation(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    ValidatedPath(id): ValidatedPath<Uuid>,
+    ValidatedJson(invitation): ValidatedJson<ServerPostOrganizationInvitationRequest>,
+) -> AppResult<ServerPostOrganizationInvitationResponse> {
+    let organization = find_organization(&state, &claims, id).await?;
+    let invitation = state
+        .organization_service
+        .invite(&organization, invitation.0.user_id, claims.user_id()?)
+        .await?
+        .map_err(into_client_error)?;
+    Ok(invitation.into())
+}
+
+#[axum::debug_handler]
+#[utoipa::path(
+    get,
+    path = "/organizations/invitations",
+    tag = "organizations",
+    responses(
+        (status = 200, description = "Invitations to the calling user", body = GetOrganizationInvitationsResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn get_my_organization_invitations(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<ServerGetOrganizationInvitationsResponse> {
+    let invitations = state
+        .organization_service
+        .find_invitations(claims.user_id()?)
+        .await?;
+    Ok(invitations.into())
+}
+
+/// Joins the organization. Use `/auth/switch-organization` to act for it.
+#[axum::debug_handler]
+#[utoipa::path(
+    post,
+    path = "/organizations/invitations/{uuid}/accept",
+    tag = "organizations",
+    params(
+        ("uuid" = Uuid, Path, description = "Id of the organization"),
+    ),
+    responses(
+        (status = 200, description = "The organization joined", body = GetOrganizationResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn accept_organization_invitation(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    ValidatedPath(id): ValidatedPath<Uuid>,
+) -> AppResult<ServerGetOrganizationResponse> {
+    let organization = state
+        .organization_service
+        .accept_invitation(id, claims.user_id()?)
+        .await?
+        .ok_or(ClientError::NotFound)?;
+    Ok(organization.into())
+}
+
+#[axum::debug_handler]
+#[utoipa::path(
+    delete,
+    path = "/organizations/invitations/{uuid}",
+    tag = "organizations",
+    params(
+        ("uuid" = Uuid, Path, description = "Id of the organization"),
+    ),
+    responses(
+        (status = 204, description = "Invitation declined"),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn decline_organization_invitation(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    ValidatedPath(id): ValidatedPath<Uuid>,
+) -> AppResult<ServerDeleteOrganizationInvitationResponse> {
+    let invitation = state
+        .organization_service
+        .decline_invitation(id, claims.user_id()?)
+        .await?
+        .ok_or(ClientError::NotFound)?;
+    Ok(invitation.into())
+}
+
+async fn find_organization(state: &AppState, claims: &Claims, id: Uuid) -> AppResult<Organization> {
+    let organization = state
+        .organization_service
+        .find_organization_for_member(id, claims.user_id()?)
+        .await?
+        .ok_or(ClientError::NotFound)?;
+    Ok(organization)
+}
+
+fn into_client_error(error: MembershipError) -> ClientError {
+    match error {
+        MembershipError::UnknownUser => ClientError::NotFound,
+        MembershipError::AlreadyMember | MembershipError::AlreadyInvited => ClientError::Conflict,
+    }
+}
@@ -0,0 +1,71 @@
+use axum::{Extension, extract::State};
+use open_erase_lib::schemas::{
+    error::ErrorResponse,
+    report::{GetReportResponse, PostReportRequest, PostReportResponse},
+};
+use uuid::Uuid;
+
+use crate::{
+    error::{AppResult, ClientError},
+    schemas::report::{ServerGetReportResponse, ServerPostReportRequest, ServerPostReportResponse},
+    services::auth::Claims,
+    state::AppState,
+    validation::{ValidatedJson, ValidatedPath},
+};
+
+#[axum::debug_handler]
+#[utoipa::path(
+    get,
+    path = "/reports/{uuid}",
+    tag = "reports",
+    params(
+        ("uuid" = Uuid, Path, description = "Id of the report"),
+    ),
+    responses(
+        (status = 200, description = "The report", body = GetReportResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn get_report(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    ValidatedPath(id): ValidatedPath<Uuid>,
+) -> AppResult<ServerGetReportResponse> {
+    let report = state
+        .report_service
+        .find_report_by_id(claims.org, id)
+        .await?
+        .ok_or(ClientError::NotFound)?;
+    Ok(report.into())
+}
+
+#[axum::debug_handler]
+#[utoipa::path(
+    post,
+    path = "/reports",
+    tag = "reports",
+    request_body = PostReportRequest,
+    responses(
+        (status = 201, description = "Report uploaded", body = PostReportResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+        (status = 422, description = "Invalid request body", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+)]
+pub async fn post_report(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    ValidatedJson(report): ValidatedJson<ServerPostReportRequest>,
+) -> AppResult<ServerPostReportResponse> {
+    let report = state
+        .report_service
+        .create_report(claims.org, report)
+        .await?
+        .ok_or(ClientError::NotFound)?;
+    Ok(report.into())
+}
@@ -0,0 +1,176 @@
+use axum::{Extension, extract::State};
+use open_erase_lib::schemas::{error::ErrorResponse, session::GetSessionsResponse};
+use uuid::Uuid;
+
+use crate::{
+    error::{AppResult, ClientError},
+    handlers::users::find_user,
+    schemas::session::{
+        ServerDeleteSessionResponse, ServerDeleteSessionsResponse, ServerGetSessionsResponse,
+    },
+    services::auth::Claims,
+    state::AppState,
+    validation::ValidatedPath,
+};
+
+#[axum::debug_handler]
+#[utoipa::path(
+    get,
+    path = "/users/me/sessions",
+    tag = "sessions",
+    responses(
+        (status = 200, description = "Sessions of the calling user", body = GetSessionsResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn get_my_sessions(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<ServerGetSessionsResponse> {
+    let sessions = state
+        .session_service
+        .find_active_sessions(claims.user_id()?)
+        .await?;
+    Ok(sessions.into())
+}
+
+#[axum::debug_handler]
+#[utoipa::path(
+    delete,
+    path = "/users/me/sessions",
+    tag = "sessions",
+    responses(
+        (status = 204, description = "Signed out everywhere"),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn delete_my_sessions(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<ServerDeleteSessionsResponse> {
+    state
+        .session_service
+        .revoke_all_sessions(claims.user_id()?)
+        .await?;
+    Ok(ServerDeleteSessionsResponse)
+}
+
+#[axum::debug_handler]
+#[utoipa::path(
+    delete,
+    path = "/users/me/sessions/{session_uuid}",
+    tag = "sessions",
+    params(
+        ("session_uuid" = Uuid, Path, description = "Id of the session"),
+    ),
+    responses(
+        (status = 204, description = "Session ended"),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn delete_my_session(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    ValidatedPath(id): ValidatedPath<Uuid>,
+) -> AppResult<ServerDeleteSessionResponse> {
+    revoke_session(&state, claims.user_id()?, id).await
+}
+
+#[axum::debug_handler]
+#[utoipa::path(
+    get,
+    path = "/users/{uuid}/sessions",
+    tag = "sessions",
+    params(
+        ("uuid" = Uuid, Path, description = "Id of the user"),
+    ),
+    responses(
+        (status = 200, description = "Sessions of the user", body = GetSessionsResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn get_user_sessions(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    ValidatedPath(user_id): ValidatedPath<Uuid>,
+) -> AppResult<ServerGetSessionsResponse> {
+    let user = find_user(&state, &claims, user_id).await?;
+    let sessions = state.session_service.find_active_sessions(user.id).await?;
+    Ok(sessions.into())
+}
+
+#[axum::debug_handler]
+#[utoipa::path(
+    delete,
+    path = "/users/{uuid}/sessions",
+    tag = "sessions",
+    params(
+        ("uuid" = Uuid, Path, description = "Id of the user"),
+    ),
+    responses(
+        (status = 204, description = "User signed out everywhere"),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn delete_user_sessions(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    ValidatedPath(user_id): ValidatedPath<Uuid>,
+) -> AppResult<ServerDeleteSessionsResponse> {
+    let user = find_user(&state, &claims, user_id).await?;
+    state.session_service.revoke_all_sessions(user.id).await?;
+    Ok(ServerDeleteSessionsResponse)
+}
+
+#[axum::debug_handler]
+#[utoipa::path(
+    delete,
+    path = "/users/{uuid}/sessions/{session_uuid}",
+    tag = "sessions",
+    params(
+        ("uuid" = Uuid, Path, description = "Id of the user"),
+        ("session_uuid" = Uuid, Path, description = "Id of the session"),
+    ),
+    responses(
+        (status = 204, description = "Session ended"),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn delete_user_session(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    ValidatedPath((user_id, id)): ValidatedPath<(Uuid, Uuid)>,
+) -> AppResult<ServerDeleteSessionResponse> {
+    let user = find_user(&state, &claims, user_id).await?;
+    revoke_session(&state, user.id, id).await
+}
+
+async fn revoke_session(
+    state: &AppState,
+    user_id: Uuid,
+    id: Uuid,
+) -> AppResult<ServerDeleteSessionResponse> {
+    let session = state
+        .session_service
+        .find_session(user_id, id)
+        .await?
+        .ok_or(ClientError::NotFound)?;
+    state.session_service.revoke_session(&session).await?;
+    Ok(session.into())
+}
@@ -0,0 +1,60 @@
+use axum::extract::State;
+use open_erase_lib::schemas::{
+    error::ErrorResponse,
+    signing_key::{GetSigningKeysResponse, PostSigningKeyRequest, PostSigningKeyResponse},
+};
+
+use crate::{
+    error::{AppResult, ClientError},
+    schemas::signing_key::{
+        ServerGetSigningKeysResponse, ServerPostSigningKeyRequest, ServerPostSigningKeyResponse,
+    },
+    state::AppState,
+    validation::ValidatedJson,
+};
+
+#[axum::debug_handler]
+#[utoipa::path(
+    get,
+    path = "/signing-keys",
+    tag = "signing-keys",
+    responses(
+        (status = 200, description = "Keys access tokens are signed with", body = GetSigningKeysResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn get_signing_keys(
+    State(state): State<AppState>,
+) -> AppResult<ServerGetSigningKeysResponse> {
+    let signing_keys = state.signing_key_service.find_active_signing_keys().await?;
+    Ok(signing_keys.into())
+}
+
+#[axum::debug_handler]
+#[utoipa::path(
+    post,
+    path = "/signing-keys",
+    tag = "signing-keys",
+    request_body = PostSigningKeyRequest,
+    responses(
+        (status = 201, description = "Signing key rotated", body = PostSigningKeyResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 409, description = "The signing key is set in the config", body = ErrorResponse),
+        (status = 422, description = "Invalid request body", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn post_signing_key(
+    State(state): State<AppState>,
+    ValidatedJson(signing_key): ValidatedJson<ServerPostSigningKeyRequest>,
+) -> AppResult<ServerPostSigningKeyResponse> {
+    let signing_key = state
+        .signing_key_service
+        .rotate(signing_key.0.algorithm.into())
+        .await?
+        .ok_or(ClientError::Conflict)?;
+    Ok(signing_key.into())
+}
@@ -0,0 +1,193 @@
+use axum::{Extension, extract::State};
+use open_erase_lib::schemas::{
+    error::ErrorResponse,
+    station::{
+        GetStationResponse, GetStationsResponse, PatchStationRequest, PatchStationResponse,
+        PostEnrollRequest, PostEnrollResponse, PostStationEnrollmentCodeResponse,
+        PostStationRequest, PostStationResponse,
+    },
+};
+use uuid::Uuid;
+
+use crate::{
+    error::{AppResult, ClientError},
+    schemas::station::{
+        ServerGetStationResponse, ServerGetStationsResponse, ServerPatchStationRequest,
+        ServerPatchStationResponse, ServerPostEnrollRequest, ServerPostEnrollResponse,
+        ServerPostStationEnrollmentCodeResponse, ServerPostStationRequest,
+        ServerPostStationResponse,
+    },
+    services::auth::Claims,
+    state::AppState,
+    validation::{ValidatedJson, ValidatedPath},
+};
+
+#[axum::debug_handler]
+#[utoipa::path(
+    get,
+    path = "/stations",
+    tag = "stations",
+    responses(
+        (status = 200, description = "Stations of the organization", body = GetStationsResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn get_stations(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<ServerGetStationsResponse> {
+    let stations = state
+        .station_service
+        .find_by_organization_id(claims.org)
+        .await?;
+    Ok(stations.into())
+}
+
+#[axum::debug_handler]
+#[utoipa::path(
+    get,
+    path = "/stations/{uuid}",
+    tag = "stations",
+    params(
+        ("uuid" = Uuid, Path, description = "Id of the station"),
+    ),
+    responses(
+        (status = 200, description = "The station", body = GetStationResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn get_station(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    ValidatedPath(id): ValidatedPath<Uuid>,
+) -> AppResult<ServerGetStationResponse> {
+    let station = state
+        .station_service
+        .find_by_id(claims.org, id)
+        .await?
+        .ok_or(ClientError::NotFound)?;
+    Ok(station.into())
+}
+
+/// The station acts for the organization of the caller with the role of the caller.
+#[axum::debug_handler]
+#[utoipa::path(
+    post,
+    path = "/stations",
+    tag = "stations",
+    request_body = PostStationRequest,
+    responses(
+        (status = 201, description = "Station registered", body = PostStationResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 422, description = "Invalid request body", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn post_station(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    ValidatedJson(station): ValidatedJson<ServerPostStationRequest>,
+) -> AppResult<ServerPostStationResponse> {
+    let (station, enrollment_code) = state
+        .station_service
+        .create(claims.org, claims.user_id()?, station.0.name)
+        .await?;
+    Ok(ServerPostStationResponse::new(station, enrollment_code))
+}
+
+#[axum::debug_handler]
+#[utoipa::path(
+    patch,
+    path = "/stations/{uuid}",
+    tag = "stations",
+    params(
+        ("uuid" = Uuid, Path, description = "Id of the station"),
+    ),
+    request_body = PatchStationRequest,
+    responses(
+        (status = 200, description = "Station updated", body = PatchStationResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+        (status = 422, description = "Invalid request body", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn patch_station(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    ValidatedPath(id): ValidatedPath<Uuid>,
+    ValidatedJson(station): ValidatedJson<ServerPatchStationRequest>,
+) -> AppResult<ServerPatchStationResponse> {
+    let station = state
+        .station_service
+        .update(claims.org, id, station.0.name, station.0.is_disabled)
+        .await?
+        .ok_or(ClientError::NotFound)?;
+    Ok(station.into())
+}
+
+#[axum::debug_handler]
+#[utoipa::path(
+    post,
+    path = "/stations/{uuid}/enrollment-code",
+    tag = "stations",
+    params(
+        ("uuid" = Uuid, Path, description = "Id of the station"),
+    ),
+    responses(
+        (status = 201, description = "New enrollment code issued", body = PostStationEnrollmentCodeResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn post_station_enrollment_code(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    ValidatedPath(id): ValidatedPath<Uuid>,
+) -> AppResult<ServerPostStationEnrollmentCodeResponse> {
+    let (station, enrollment_code) = state
+        .station_service
+        .reenroll(claims.org, id, claims.user_id()?)
+        .await?
+        .ok_or(ClientError::NotFound)?;
+    Ok(ServerPostStationEnrollmentCodeResponse::new(
+        station,
+        enrollment_code,
+    ))
+}
+
+#[axum::debug_handler]
+#[utoipa::path(
+    post,
+    path = "/auth/enroll",
+    tag = "stations",
+    request_body = PostEnrollRequest,
+    responses(
+        (status = 201, description = "Station enrolled", body = PostEnrollResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 422, description = "Invalid request body", body = ErrorResponse),
+    ),
+)]
+pub async fn enroll(
+    State(state): State<AppState>,
+    ValidatedJson(enrollment): ValidatedJson<ServerPostEnrollRequest>,
+) -> AppResult<ServerPostEnrollResponse> {
+    let (station, api_key) = state
+        .station_service
+        .enroll(
+            &enrollment.0.enrollment_code,
+            &enrollment.0.hardware_fingerprint,
+        )
+        .await?
+        .ok_or(ClientError::Unauthorized)?;
+    Ok(ServerPostEnrollResponse::new(station, api_key))
+}
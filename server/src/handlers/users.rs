@@ -1,38 +1,69 @@
-use axum::{
-    Extension, Json,
-    extract::{Path, State},
+use axum::{Extension, extract::State};
+use open_erase_lib::schemas::{
+    error::ErrorResponse,
+    user::{
+        GetUserResponse, PatchUserRequest, PatchUserResponse, PostPasswordRequest, PostUserRequest,
+        PostUserResponse,
+    },
 };
 use uuid::Uuid;
 
 use crate::{
     error::{AppResult, ClientError},
+    models::User,
     schemas::user::{
         ServerDeleteUserResponse, ServerGetUserResponse, ServerPatchUserRequest,
-        ServerPatchUserResponse, ServerPostUserRequest, ServerPostUserResponse,
+        ServerPatchUserResponse, ServerPostPasswordRequest, ServerPostPasswordResponse,
+        ServerPostUnlockUserResponse, ServerPostUserRequest, ServerPostUserResponse,
     },
     services::auth::Claims,
     state::AppState,
+    validation::{ValidatedJson, ValidatedPath},
 };
 
 #[axum::debug_handler]
+#[utoipa::path(
+    get,
+    path = "/users/{uuid}",
+    tag = "users",
+    params(
+        ("uuid" = Uuid, Path, description = "Id of the user"),
+    ),
+    responses(
+        (status = 200, description = "The user", body = GetUserResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
 pub async fn get_user(
     State(state): State<AppState>,
-    Path(id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+    ValidatedPath(id): ValidatedPath<Uuid>,
 ) -> AppResult<ServerGetUserResponse> {
-    let user = state
-        .user_service
-        .find_user_by_id(id)
-        .await?
-        .ok_or(ClientError::NotFound)?;
+    let user = find_user(&state, &claims, id).await?;
     Ok(user.into())
 }
 
 #[axum::debug_handler]
+#[utoipa::path(
+    get,
+    path = "/users/me",
+    tag = "users",
+    responses(
+        (status = 200, description = "The calling user", body = GetUserResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
 pub async fn get_me(
     State(state): State<AppState>,
     Extension(claims): Extension<Claims>,
 ) -> AppResult<ServerGetUserResponse> {
-    let id = Uuid::parse_str(&claims.sub).unwrap();
+    let id = claims.user_id()?;
     let user = state
         .user_service
         .find_user_by_id(id)
@@ -42,29 +73,146 @@ pub async fn get_me(
 }
 
 #[axum::debug_handler]
+#[utoipa::path(
+    post,
+    path = "/users/me/password",
+    tag = "users",
+    request_body = PostPasswordRequest,
+    responses(
+        (status = 204, description = "Password changed"),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 422, description = "Invalid request body", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn post_password(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    ValidatedJson(password): ValidatedJson<ServerPostPasswordRequest>,
+) -> AppResult<ServerPostPasswordResponse> {
+    let id = claims.user_id()?;
+    state
+        .auth_service
+        .change_password(id, &password.0.current_password, &password.0.new_password)
+        .await?
+        .ok_or(ClientError::Unauthorized)?;
+    Ok(ServerPostPasswordResponse)
+}
+
+#[axum::debug_handler]
+#[utoipa::path(
+    post,
+    path = "/users",
+    tag = "users",
+    request_body = PostUserRequest,
+    responses(
+        (status = 201, description = "User created", body = PostUserResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 422, description = "Invalid request body", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
 pub async fn post_user(
     State(state): State<AppState>,
-    Json(user): Json<ServerPostUserRequest>,
+    Extension(claims): Extension<Claims>,
+    ValidatedJson(user): ValidatedJson<ServerPostUserRequest>,
 ) -> AppResult<ServerPostUserResponse> {
-    let user = state.user_service.create_user(user).await?;
+    let user = state.user_service.create_user(claims.org, user).await?;
     Ok(user.into())
 }
 
 #[axum::debug_handler]
+#[utoipa::path(
+    patch,
+    path = "/users/{uuid}",
+    tag = "users",
+    params(
+        ("uuid" = Uuid, Path, description = "Id of the user"),
+    ),
+    request_body = PatchUserRequest,
+    responses(
+        (status = 200, description = "User updated", body = PatchUserResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+        (status = 422, description = "Invalid request body", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
 pub async fn patch_user(
     State(state): State<AppState>,
-    Path(id): Path<Uuid>,
-    Json(user): Json<ServerPatchUserRequest>,
+    Extension(claims): Extension<Claims>,
+    ValidatedPath(id): ValidatedPath<Uuid>,
+    ValidatedJson(user_patch): ValidatedJson<ServerPatchUserRequest>,
 ) -> AppResult<ServerPatchUserResponse> {
-    let user = state.user_service.update_user(id, user).await?;
+    let user = find_user(&state, &claims, id).await?;
+    let user = state.user_service.update_user(user.id, user_patch).await?;
     Ok(user.into())
 }
 
 #[axum::debug_handler]
+#[utoipa::path(
+    delete,
+    path = "/users/{uuid}",
+    tag = "users",
+    params(
+        ("uuid" = Uuid, Path, description = "Id of the user"),
+    ),
+    responses(
+        (status = 204, description = "User deleted"),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
 pub async fn delete_user(
     State(state): State<AppState>,
-    Path(id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+    ValidatedPath(id): ValidatedPath<Uuid>,
 ) -> AppResult<ServerDeleteUserResponse> {
-    let user = state.user_service.delete_user(id).await?;
+    let user = find_user(&state, &claims, id).await?;
+    let user = state.user_service.delete_user(user.id).await?;
     Ok(user.into())
 }
+
+/// Lifts a login lockout of the account before it expires on its own.
+#[axum::debug_handler]
+#[utoipa::path(
+    post,
+    path = "/users/{uuid}/unlock",
+    tag = "users",
+    params(
+        ("uuid" = Uuid, Path, description = "Id of the user"),
+    ),
+    responses(
+        (status = 204, description = "Lockout lifted"),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Credentials lack the required permission", body = ErrorResponse),
+        (status = 404, description = "Resource not found", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn post_unlock_user(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    ValidatedPath(id): ValidatedPath<Uuid>,
+) -> AppResult<ServerPostUnlockUserResponse> {
+    let user = find_user(&state, &claims, id).await?;
+    state
+        .login_throttle_service
+        .unlock_account(&user.email)
+        .await?;
+    Ok(ServerPostUnlockUserResponse)
+}
+
+pub(crate) async fn find_user(state: &AppState, claims: &Claims, id: Uuid) -> AppResult<User> {
+    let user = state
+        .user_service
+        .find_member_by_id(claims.org, id)
+        .await?
+        .ok_or(ClientError::NotFound)?;
+    Ok(user)
+}
@@ -0,0 +1,162 @@
+//! Just enough of ISO 9660 to find files inside an uploaded image without mounting it.
+//! Names come from Rock Ridge when present, as xorriso writes them for archiso, and
+//! otherwise from the plain ISO 9660 identifiers without their `;1` version.
+
+use std::io::{self, SeekFrom};
+
+use tokio::{
+    fs::File,
+    io::{AsyncReadExt, AsyncSeekExt},
+};
+
+const SECTOR_SIZE: u64 = 2048;
+/// Volume descriptors start after the 32 KiB system area.
+const FIRST_VOLUME_DESCRIPTOR: u64 = 16;
+const PRIMARY_VOLUME_DESCRIPTOR: u8 = 1;
+const VOLUME_DESCRIPTOR_TERMINATOR: u8 = 255;
+const STANDARD_IDENTIFIER: &[u8] = b"CD001";
+const ROOT_DIRECTORY_RECORD: usize = 156;
+const DIRECTORY_FLAG: u8 = 0x02;
+/// Directories of a sane image are a few sectors, this bounds what a corrupt one reads.
+const MAX_DIRECTORY_SIZE: u32 = 1024 * 1024;
+
+/// Location of a file inside the image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Extent {
+    pub offset: u64,
+    pub size: u64,
+}
+
+#[derive(Debug)]
+struct DirectoryRecord {
+    extent: Extent,
+    is_directory: bool,
+    name: String,
+}
+
+/// Finds the file at `path`, e.g. `arch/boot/x86_64/vmlinuz-linux`, in the image.
+/// Files that span several extents, which only happens above 4 GiB, are not supported.
+pub async fn find(file: &mut File, path: &str) -> io::Result<Option<Extent>> {
+    let mut directory = root_directory(file).await?;
+    let mut components = path.split('/').filter(|component| !component.is_empty());
+    let Some(mut component) = components.next() else {
+        return Ok(None);
+    };
+    loop {
+        let Some(record) = read_directory(file, directory)
+            .await?
+            .into_iter()
+            .find(|record| record.name == component)
+        else {
+            return Ok(None);
+        };
+        match components.next() {
+            Some(next) if record.is_directory => {
+                directory = record.extent;
+                component = next;
+            }
+            Some(_) => return Ok(None),
+            None if record.is_directory => return Ok(None),
+            None => return Ok(Some(record.extent)),
+        }
+    }
+}
+
+async fn root_directory(file: &mut File) -> io::Result<Extent> {
+    let mut sector = [0; SECTOR_SIZE as usize];
+    for index in FIRST_VOLUME_DESCRIPTOR.. {
+        file.seek(SeekFrom::Start(index * SECTOR_SIZE)).await?;
+        file.read_exact(&mut sector).await?;
+        if &sector[1..6] != STANDARD_IDENTIFIER || sector[0] == VOLUME_DESCRIPTOR_TERMINATOR {
+            break;
+        }
+        if sector[0] == PRIMARY_VOLUME_DESCRIPTOR {
+            let record = parse_record(&sector[ROOT_DIRECTORY_RECORD..ROOT_DIRECTORY_RECORD + 34])
+                .ok_or_else(|| invalid("malformed root directory record"))?;
+            return Ok(record.extent);
+        }
+    }
+    Err(invalid("no primary volume descriptor"))
+}
+
+async fn read_directory(file: &mut File, directory: Extent) -> io::Result<Vec<DirectoryRecord>> {
+    if directory.size > MAX_DIRECTORY_SIZE as u64 {
+        return Err(invalid("directory too large"));
+    }
+    let mut data = vec![0; directory.size as usize];
+    file.seek(SeekFrom::Start(directory.offset)).await?;
+    file.read_exact(&mut data).await?;
+    let mut records = Vec::new();
+    let mut position = 0;
+    while position < data.len() {
+        let length = data[position] as usize;
+        if length == 0 {
+            // records never cross sectors, the rest of this one is padding
+            position = (position / SECTOR_SIZE as usize + 1) * SECTOR_SIZE as usize;
+            continue;
+        }
+        let record = data
+            .get(position..position + length)
+            .and_then(parse_record)
+            .ok_or_else(|| invalid("malformed directory record"))?;
+        // skip the `.` and `..` entries
+        if !record.name.is_empty() {
+            records.push(record);
+        }
+        position += length;
+    }
+    Ok(records)
+}
+
+fn parse_record(record: &[u8]) -> Option<DirectoryRecord> {
+    let sector = u32::from_le_bytes(record.get(2..6)?.try_into().ok()?);
+    let size = u32::from_le_bytes(record.get(10..14)?.try_into().ok()?);
+    let flags = *record.get(25)?;
+    let name_length = *record.get(32)? as usize;
+    let identifier = record.get(33..33 + name_length)?;
+    let name = if identifier == [0] || identifier == [1] {
+        String::new()
+    } else {
+        // the system use area starts after the identifier, padded to an even offset
+        let system_use = record.get(33 + name_length + (1 - name_length % 2)..)?;
+        rock_ridge_name(system_use).unwrap_or_else(|| iso_name(identifier))
+    };
+    Some(DirectoryRecord {
+        extent: Extent {
+            offset: sector as u64 * SECTOR_SIZE,
+            size: size as u64,
+        },
+        is_directory: flags & DIRECTORY_FLAG != 0,
+        name,
+    })
+}
+
+/// The alternate name from the `NM` entries of the SUSP area.
+fn rock_ridge_name(mut system_use: &[u8]) -> Option<String> {
+    let mut name = Vec::new();
+    while system_use.len() >= 4 {
+        let length = system_use[2] as usize;
+        if length < 4 || length > system_use.len() {
+            break;
+        }
+        if &system_use[..2] == b"NM" && length >= 5 {
+            name.extend_from_slice(&system_use[5..length]);
+        }
+        system_use = &system_use[length..];
+    }
+    if name.is_empty() {
+        None
+    } else {
+        String::from_utf8(name).ok()
+    }
+}
+
+fn iso_name(identifier: &[u8]) -> String {
+    let name = String::from_utf8_lossy(identifier);
+    let name = name.split(';').next().unwrap_or_default();
+    name.strip_suffix('.').unwrap_or(name).to_lowercase()
+}
+
+fn invalid(message: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
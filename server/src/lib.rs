@@ -1,5 +1,9 @@
+pub mod auth_provider;
+pub mod config;
 pub mod error;
 pub mod handlers;
+pub mod iso9660;
+pub mod mailer;
 pub mod middleware;
 pub mod models;
 pub mod repositories;
@@ -7,33 +11,109 @@ pub mod routes;
 pub mod schemas;
 pub mod services;
 pub mod state;
+pub mod tasks;
+pub mod validation;
 
 #[cfg(test)]
 pub mod test_helpers;
 
 pub async fn bootstrap() -> Result<(), Box<dyn std::error::Error>> {
     tracing_subscriber::fmt().compact().init();
-    let state = crate::state::AppState::postgres().await?;
+    let config = crate::config::Config::load()?;
+    let state = crate::state::AppState::postgres(config).await?;
+    let (shutdown_sender, shutdown_receiver) = tokio::sync::watch::channel(());
+    let cleanup = tasks::cleanup::spawn(
+        state.session_service.clone(),
+        state.login_throttle_service.clone(),
+        state.config.cleanup.clone(),
+        shutdown_receiver.clone(),
+    );
+    let tftp = if state.config.boot.tftp_enabled {
+        let socket = tokio::net::UdpSocket::bind(state.config.boot.tftp_bind_address).await?;
+        Some(tasks::tftp::spawn(
+            socket,
+            state.boot_service.clone(),
+            shutdown_receiver,
+        ))
+    } else {
+        None
+    };
+    let listener = tokio::net::TcpListener::bind(state.config.server.bind_address).await?;
     let app = routes::app(state);
-    let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await?;
     tracing::info!("server successfully started");
     axum::serve(
         listener,
         app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
     )
+    .with_graceful_shutdown(shutdown_signal())
     .await?;
+    let _ = shutdown_sender.send(());
+    cleanup.await?;
+    if let Some(tftp) = tftp {
+        tftp.await?;
+    }
     Ok(())
 }
 
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        if let Ok(mut signal) =
+            tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+        {
+            signal.recv().await;
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+    tracing::info!("shutting down server");
+}
+
 #[cfg(test)]
 mod tests {
-    use axum::{body::Body, extract::Request, http::StatusCode};
+    use axum::{
+        body::{Body, to_bytes},
+        extract::Request,
+        http::StatusCode,
+    };
     use base64::{Engine, prelude::BASE64_STANDARD};
-    use open_erase_lib::schemas::user::PostUserRequest;
+    use chrono::Utc;
+    use open_erase_lib::schemas::{
+        api_key::{ApiKeyScope, GetApiKeysResponse, PostApiKeyRequest, PostApiKeyResponse},
+        batch::{
+            GetBatchCertificateResponse, GetBatchSummaryResponse, GetBatchesResponse,
+            PostBatchRequest,
+        },
+        device::{PostDeviceRequest, PostDeviceResponse},
+        error::{ErrorCode, ErrorResponse},
+        image::{Architecture, GetImagesResponse, PatchImageRequest, PostImageResponse},
+        mfa::{MfaChallengeResponse, PostTotpConfirmResponse, PostTotpResponse},
+        report::{ErasureResult, PostReportRequest},
+        session::GetSessionsResponse,
+        signing_key::{GetJwksResponse, PostSigningKeyRequest, SigningAlgorithm},
+        station::{
+            PatchStationRequest, PostEnrollRequest, PostEnrollResponse,
+            PostStationEnrollmentCodeResponse, PostStationRequest, PostStationResponse,
+        },
+        token::{LoginResponse, RefreshResponse},
+        user::{PostPasswordRequest, PostUserRequest, Role},
+    };
     use tower::ServiceExt;
     use uuid::Uuid;
 
-    use crate::{models::User, routes, state::AppState, test_helpers::test_request};
+    use crate::{
+        models::{Batch, Organization, RefreshToken, SigningKey, User},
+        routes,
+        state::AppState,
+        test_helpers::test_request,
+    };
 
     #[tokio::test]
     async fn valid_login() {
@@ -72,9 +152,10 @@ mod tests {
         let app = routes::app(state.clone());
         let token = state
             .auth_service
-            .generate_access_token(User::mock().id)
+            .generate_access_token(&User::mock(), Organization::mock().id, false)
+            .await
             .unwrap();
-        let uri = format!("/api/user/{}", Uuid::default());
+        let uri = format!("/api/users/{}", Uuid::default());
         let auth_header = format!("Bearer {}", token);
         let response = app
             .oneshot(
@@ -95,17 +176,20 @@ mod tests {
         let app = routes::app(state.clone());
         let token = state
             .auth_service
-            .generate_access_token(User::mock().id)
+            .generate_access_token(&User::mock(), Organization::mock().id, false)
+            .await
             .unwrap();
         let auth_header = format!("Bearer {}", token);
         let body = PostUserRequest {
             email: String::from("some@mail.com"),
-            password: String::from("abc123"),
+            password: String::from("abc12345"),
+            role: Role::Operator,
         };
         let response = app
+            .clone()
             .oneshot(
                 Request::builder()
-                    .uri("/api/user")
+                    .uri("/api/users")
                     .method("POST")
                     .header("Authorization", auth_header)
                     .header("Content-Type", "application/json")
@@ -115,6 +199,334 @@ mod tests {
             .await
             .unwrap();
         assert_eq!(response.status(), StatusCode::CREATED);
+
+        // the new user joined the organization of the creator and can log in
+        let credentials = format!("{}:{}", body.email, body.password);
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/auth/login")
+                    .method("POST")
+                    .header(
+                        "Authorization",
+                        format!("Basic {}", BASE64_STANDARD.encode(credentials)),
+                    )
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn users_are_scoped_to_organization() {
+        let state = AppState::mock();
+        let app = routes::app(state.clone());
+        let token = state
+            .auth_service
+            .generate_access_token(&User::mock(), Uuid::now_v7(), false)
+            .await
+            .unwrap();
+        let auth_header = format!("Bearer {}", token);
+
+        // the mock user belongs to another organization than the caller
+        let uri = format!("/api/users/{}", User::mock().id);
+        for (method, uri, body) in [
+            ("GET", uri.clone(), ""),
+            ("PATCH", uri.clone(), r#"{"role":"admin"}"#),
+            ("POST", format!("{uri}/unlock"), ""),
+            ("GET", format!("{uri}/sessions"), ""),
+            ("DELETE", format!("{uri}/sessions"), ""),
+        ] {
+            let response = app
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .uri(&uri)
+                        .method(method)
+                        .header("Authorization", auth_header.clone())
+                        .header("Content-Type", "application/json")
+                        .body(Body::from(body))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::NOT_FOUND, "{method} {uri}");
+        }
+        assert!(
+            state
+                .user_service
+                .find_user_by_id(User::mock().id)
+                .await
+                .unwrap()
+                .is_some()
+        );
+    }
+
+    #[tokio::test]
+    async fn organization_members_join_by_invitation() {
+        use open_erase_lib::schemas::organization::{
+            GetOrganizationInvitationsResponse, PostOrganizationRequest, PostOrganizationResponse,
+        };
+
+        let state = AppState::mock();
+        let app = routes::app(state.clone());
+        let token = state
+            .auth_service
+            .generate_access_token(&User::mock(), Organization::mock().id, false)
+            .await
+            .unwrap();
+        let auth_header = format!("Bearer {}", token);
+        // a user who only belongs to another tenant
+        let outsider = state
+            .user_service
+            .create_user(
+                Organization::mock().id,
+                crate::schemas::user::ServerPostUserRequest(PostUserRequest {
+                    email: String::from("outsider@example.com"),
+                    password: String::from("password123"),
+                    role: Role::Admin,
+                }),
+            )
+            .await
+            .unwrap();
+        let tenant = state
+            .organization_service
+            .create_organization(
+                crate::schemas::organization::ServerPostOrganizationRequest(
+                    PostOrganizationRequest {
+                        name: String::from("tenant"),
+                    },
+                ),
+                outsider.id,
+            )
+            .await
+            .unwrap();
+        state
+            .organization_service
+            .remove_member(&Organization::mock(), outsider.id)
+            .await
+            .unwrap()
+            .unwrap();
+        let send = async |auth_header: &str, method: &str, uri: String, body: String| {
+            app.clone()
+                .oneshot(
+                    Request::builder()
+                        .uri(uri)
+                        .method(method)
+                        .header("Authorization", auth_header)
+                        .header("Content-Type", "application/json")
+                        .body(Body::from(body))
+                        .unwrap(),
+                )
+                .await
+                .unwrap()
+        };
+        let default_id = Organization::mock().id;
+        let user_body = serde_json::json!({ "user_id": outsider.id }).to_string();
+
+        let response = send(
+            &auth_header,
+            "POST",
+            format!("/api/organizations/{default_id}/members"),
+            user_body.clone(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let response = send(
+            &auth_header,
+            "POST",
+            format!("/api/organizations/{default_id}/invitations"),
+            user_body.clone(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let response = send(
+            &auth_header,
+            "POST",
+            format!("/api/organizations/{default_id}/invitations"),
+            user_body.clone(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        let credentials = BASE64_STANDARD.encode("outsider@example.com:password123");
+        let response = send(
+            &format!("Basic {credentials}"),
+            "POST",
+            String::from("/api/auth/login"),
+            String::new(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let login: LoginResponse = serde_json::from_slice(&body).unwrap();
+        let claims = state
+            .auth_service
+            .get_valid_access_token_claims(&login.access_token)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(claims.org, tenant.id);
+        let outsider_header = format!("Bearer {}", login.access_token);
+        let response = send(
+            &outsider_header,
+            "GET",
+            String::from("/api/organizations/invitations"),
+            String::new(),
+        )
+        .await;
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let invitations: GetOrganizationInvitationsResponse =
+            serde_json::from_slice(&body).unwrap();
+        assert_eq!(invitations.0.len(), 1);
+        assert_eq!(invitations.0[0].organization_id, default_id);
+        let accept_uri = format!("/api/organizations/invitations/{default_id}/accept");
+        let response = send(&outsider_header, "POST", accept_uri.clone(), String::new()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = send(&outsider_header, "POST", accept_uri, String::new()).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        // members of a shared organization can be added directly, but only once
+        let response = send(
+            &auth_header,
+            "POST",
+            String::from("/api/organizations"),
+            serde_json::json!({ "name": "second" }).to_string(),
+        )
+        .await;
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let second: PostOrganizationResponse = serde_json::from_slice(&body).unwrap();
+        let members_uri = format!("/api/organizations/{}/members", second.id);
+        let response = send(&auth_header, "POST", members_uri.clone(), user_body.clone()).await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let response = send(&auth_header, "POST", members_uri.clone(), user_body.clone()).await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let member_uri = format!("{members_uri}/{}", outsider.id);
+        let response = send(&auth_header, "DELETE", member_uri.clone(), String::new()).await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let response = send(&auth_header, "DELETE", member_uri, String::new()).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        // the session acts for the organization it was switched to, refreshes included
+        let switch = |refresh_token: &str, organization_id: Uuid| {
+            Request::builder()
+                .uri("/api/auth/switch-organization")
+                .method("POST")
+                .header("Cookie", format!("refresh_token={refresh_token}"))
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    serde_json::json!({ "organization_id": organization_id }).to_string(),
+                ))
+                .unwrap()
+        };
+        let response = app
+            .clone()
+            .oneshot(switch(&login.refresh_token, second.id))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let response = app
+            .clone()
+            .oneshot(switch(&login.refresh_token, default_id))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let switched: RefreshResponse = serde_json::from_slice(&body).unwrap();
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/auth/refresh")
+                    .method("POST")
+                    .header(
+                        "Cookie",
+                        format!("refresh_token={}", switched.refresh_token),
+                    )
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let refreshed: RefreshResponse = serde_json::from_slice(&body).unwrap();
+        for access_token in [switched.access_token, refreshed.access_token] {
+            let claims = state
+                .auth_service
+                .get_valid_access_token_claims(&access_token)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(claims.org, default_id);
+        }
+    }
+
+    #[tokio::test]
+    async fn change_password() {
+        let state = AppState::mock();
+        let app = routes::app(state.clone());
+        let token = state
+            .auth_service
+            .generate_access_token(&User::mock(), Organization::mock().id, false)
+            .await
+            .unwrap();
+        let auth_header = format!("Bearer {}", token);
+        let change_password = |current_password: &str| {
+            let body = PostPasswordRequest {
+                current_password: String::from(current_password),
+                new_password: String::from("password456"),
+            };
+            Request::builder()
+                .uri("/api/users/me/password")
+                .method("POST")
+                .header("Authorization", auth_header.clone())
+                .header("Content-Type", "application/json")
+                .body(Body::from(serde_json::to_string(&body).unwrap()))
+                .unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(change_password("wrong-password"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = app
+            .clone()
+            .oneshot(change_password("password123"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        let refresh_token = format!("{}.{}", RefreshToken::mock().id, "password123");
+        assert!(
+            state
+                .auth_service
+                .get_valid_refresh_token(&refresh_token)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        let email_password = format!("{}:{}", User::mock().email, "password456");
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/auth/login")
+                    .header(
+                        "Authorization",
+                        format!("Basic {}", BASE64_STANDARD.encode(email_password)),
+                    )
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
     }
 
     #[tokio::test]
@@ -123,10 +535,11 @@ mod tests {
         let app = routes::app(state.clone());
         let token = state
             .auth_service
-            .generate_access_token(User::mock().id)
+            .generate_access_token(&User::mock(), Organization::mock().id, true)
+            .await
             .unwrap();
         let auth_header = format!("Bearer {}", token);
-        let uri = format!("/api/user/{}", User::mock().id);
+        let uri = format!("/api/users/{}", User::mock().id);
 
         let response = app
             .clone()
@@ -170,4 +583,2890 @@ mod tests {
             .unwrap();
         assert_eq!(response.status(), StatusCode::NOT_FOUND);
     }
+
+    #[tokio::test]
+    async fn batch_summary() {
+        let state = AppState::mock();
+        let app = routes::app(state.clone());
+        let token = state
+            .auth_service
+            .generate_access_token(&User::mock(), Organization::mock().id, false)
+            .await
+            .unwrap();
+        let auth_header = format!("Bearer {}", token);
+        let uri = format!("/api/batches/{}/summary", Batch::mock().id);
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri(&uri)
+                    .header("Authorization", auth_header)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let summary: GetBatchSummaryResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(summary.expected_device_count, 2);
+        assert_eq!(summary.device_count, 1);
+        assert_eq!(summary.erased_device_count, 1);
+        assert_eq!(summary.outstanding_device_count, 1);
+    }
+
+    #[tokio::test]
+    async fn batch_certificate() {
+        let state = AppState::mock();
+        let app = routes::app(state.clone());
+        let token = state
+            .auth_service
+            .generate_access_token(&User::mock(), Organization::mock().id, false)
+            .await
+            .unwrap();
+        let auth_header = format!("Bearer {}", token);
+
+        let body = PostBatchRequest {
+            customer_reference: String::from("PALLET-0002"),
+            expected_device_count: 1,
+        };
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/batches")
+                    .method("POST")
+                    .header("Authorization", auth_header.clone())
+                    .header("Content-Type", "application/json")
+                    .body(Body::from(serde_json::to_string(&body).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let batch_id = serde_json::from_slice::<serde_json::Value>(&body).unwrap()["id"]
+            .as_str()
+            .unwrap()
+            .to_owned();
+
+        let body = PostDeviceRequest {
+            serial_number: String::from("SN-0002"),
+            manufacturer: None,
+            model: None,
+        };
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/api/batches/{}/devices", batch_id))
+                    .method("POST")
+                    .header("Authorization", auth_header.clone())
+                    .header("Content-Type", "application/json")
+                    .body(Body::from(serde_json::to_string(&body).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let device: PostDeviceResponse = serde_json::from_slice(&body).unwrap();
+
+        let body = PostReportRequest {
+            device_id: device.id,
+            method: String::from("nist-800-88-clear"),
+            result: ErasureResult::Passed,
+            started_at: Utc::now(),
+            finished_at: Utc::now(),
+        };
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/reports")
+                    .method("POST")
+                    .header("Authorization", auth_header.clone())
+                    .header("Content-Type", "application/json")
+                    .body(Body::from(serde_json::to_string(&body).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/api/batches/{}/certificate", batch_id))
+                    .header("Authorization", auth_header.clone())
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let certificate: GetBatchCertificateResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(certificate.summary.erased_device_count, 1);
+        assert_eq!(certificate.summary.outstanding_device_count, 0);
+        assert_eq!(certificate.entries.len(), 1);
+        assert_eq!(certificate.entries[0].device.id, device.id);
+        assert!(certificate.entries[0].report.is_some());
+    }
+
+    #[tokio::test]
+    async fn batches_are_scoped_to_organization() {
+        let state = AppState::mock();
+        let app = routes::app(state.clone());
+        let token = state
+            .auth_service
+            .generate_access_token(&User::mock(), Uuid::now_v7(), false)
+            .await
+            .unwrap();
+        let auth_header = format!("Bearer {}", token);
+
+        let uri = format!("/api/batches/{}", Batch::mock().id);
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(&uri)
+                    .header("Authorization", auth_header.clone())
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/batches")
+                    .header("Authorization", auth_header.clone())
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let batches: GetBatchesResponse = serde_json::from_slice(&body).unwrap();
+        assert!(batches.0.is_empty());
+    }
+
+    #[tokio::test]
+    async fn forbidden_without_permission() {
+        let state = AppState::mock();
+        let app = routes::app(state.clone());
+        let mut user = User::mock();
+        user.role = crate::models::Role::ReadOnly;
+        let token = state
+            .auth_service
+            .generate_access_token(&user, Organization::mock().id, false)
+            .await
+            .unwrap();
+        let auth_header = format!("Bearer {}", token);
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/api/batches/{}", Batch::mock().id))
+                    .header("Authorization", auth_header.clone())
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/api/users/{}", User::mock().id))
+                    .method("DELETE")
+                    .header("Authorization", auth_header.clone())
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn signing_key_rotation() {
+        let state = AppState::mock();
+        let app = routes::app(state.clone());
+        let get_me = |token: &str| {
+            Request::builder()
+                .uri("/api/users/me")
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap()
+        };
+        let get_jwks = || {
+            Request::builder()
+                .uri("/api/auth/jwks")
+                .body(Body::empty())
+                .unwrap()
+        };
+        let token = state
+            .auth_service
+            .generate_access_token(&User::mock(), Organization::mock().id, false)
+            .await
+            .unwrap();
+        let header = jsonwebtoken::decode_header(&token).unwrap();
+        assert_eq!(header.alg, jsonwebtoken::Algorithm::EdDSA);
+
+        let response = app.clone().oneshot(get_jwks()).await.unwrap();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let jwks: GetJwksResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(jwks.keys.len(), 1);
+        assert_eq!(Some(&jwks.keys[0].kid), header.kid.as_ref());
+
+        let body = PostSigningKeyRequest {
+            algorithm: SigningAlgorithm::Hs256,
+        };
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/signing-keys")
+                    .method("POST")
+                    .header("Authorization", format!("Bearer {}", token))
+                    .header("Content-Type", "application/json")
+                    .body(Body::from(serde_json::to_string(&body).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        // tokens signed by the previous key stay valid until it retires
+        let response = app.clone().oneshot(get_me(&token)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let rotated_token = state
+            .auth_service
+            .generate_access_token(&User::mock(), Organization::mock().id, false)
+            .await
+            .unwrap();
+        let rotated_header = jsonwebtoken::decode_header(&rotated_token).unwrap();
+        assert_eq!(rotated_header.alg, jsonwebtoken::Algorithm::HS256);
+        assert_ne!(rotated_header.kid, header.kid);
+        let response = app.clone().oneshot(get_me(&rotated_token)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app.clone().oneshot(get_jwks()).await.unwrap();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let jwks: GetJwksResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(jwks.keys.len(), 1);
+
+        let signing_key = SigningKey::mock();
+        let mut unknown_header = jsonwebtoken::Header::new(signing_key.jwt_algorithm());
+        unknown_header.kid = Some(Uuid::now_v7().to_string());
+        let claims = crate::services::auth::Claims::new(
+            &User::mock(),
+            Organization::mock().id,
+            state.config.auth.access_token_lifetime(),
+            false,
+        );
+        let unknown_token =
+            jsonwebtoken::encode(&unknown_header, &claims, &signing_key.encoding_key()).unwrap();
+        let response = app.oneshot(get_me(&unknown_token)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn configured_signing_key_is_used_and_not_rotated() {
+        use ed25519_dalek::pkcs8::{EncodePrivateKey, spki::der::pem::LineEnding};
+
+        let key_file = std::env::temp_dir().join(format!("signing-key-{}.pem", Uuid::now_v7()));
+        let pem = ed25519_dalek::SigningKey::from_bytes(&[7; 32])
+            .to_pkcs8_pem(LineEnding::LF)
+            .unwrap();
+        std::fs::write(&key_file, pem.as_bytes()).unwrap();
+        let mut config = crate::config::Config::default();
+        config.auth.signing_key_file = Some(key_file.clone());
+        let state = AppState::mock_with_config(config);
+        let app = routes::app(state.clone());
+
+        let token = state
+            .auth_service
+            .generate_access_token(&User::mock(), Organization::mock().id, false)
+            .await
+            .unwrap();
+        let header = jsonwebtoken::decode_header(&token).unwrap();
+        assert_eq!(header.alg, jsonwebtoken::Algorithm::EdDSA);
+        // the key id is stable across restarts and replicas
+        let restarted = AppState::mock_with_config((*state.config).clone());
+        let restarted_token = restarted
+            .auth_service
+            .generate_access_token(&User::mock(), Organization::mock().id, false)
+            .await
+            .unwrap();
+        assert_eq!(
+            jsonwebtoken::decode_header(&restarted_token).unwrap().kid,
+            header.kid
+        );
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/auth/jwks")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let jwks: GetJwksResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(jwks.keys.len(), 1);
+        assert_eq!(Some(&jwks.keys[0].kid), header.kid.as_ref());
+
+        let body = PostSigningKeyRequest {
+            algorithm: SigningAlgorithm::EdDsa,
+        };
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/signing-keys")
+                    .method("POST")
+                    .header("Authorization", format!("Bearer {}", token))
+                    .header("Content-Type", "application/json")
+                    .body(Body::from(serde_json::to_string(&body).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        std::fs::remove_file(key_file).unwrap();
+    }
+
+    #[tokio::test]
+    async fn server_wide_resources_need_a_server_admin() {
+        let state = AppState::mock();
+        let app = routes::app(state.clone());
+        // an admin of an organization, but not of the server
+        let mut tenant_admin = User::mock();
+        tenant_admin.is_server_admin = false;
+        let token = state
+            .auth_service
+            .generate_access_token(&tenant_admin, Organization::mock().id, false)
+            .await
+            .unwrap();
+        let mut upload = state.image_service.start_upload().await.unwrap();
+        assert!(upload.write(b"iso").await.unwrap());
+        let image = state
+            .image_service
+            .finish_upload(
+                upload,
+                User::mock().id,
+                String::from("1.0.0"),
+                crate::models::Architecture::X86_64,
+                None,
+            )
+            .await
+            .unwrap()
+            .unwrap();
+
+        for (method, uri, content_type, body) in [
+            (
+                "POST",
+                String::from("/api/signing-keys"),
+                "application/json",
+                r#"{"algorithm":"EdDSA"}"#,
+            ),
+            (
+                "GET",
+                String::from("/api/signing-keys"),
+                "application/json",
+                "",
+            ),
+            (
+                "PATCH",
+                format!("/api/images/{}", image.id),
+                "application/json",
+                r#"{"is_active":true}"#,
+            ),
+            (
+                "POST",
+                String::from("/api/images"),
+                "multipart/form-data; boundary=x",
+                "--x--\r\n",
+            ),
+        ] {
+            let response = app
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .uri(&uri)
+                        .method(method)
+                        .header("Authorization", format!("Bearer {token}"))
+                        .header("Content-Type", content_type)
+                        .body(Body::from(body))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::FORBIDDEN, "{method} {uri}");
+        }
+        let image = state
+            .image_service
+            .find_by_id(image.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!image.is_active);
+    }
+
+    #[tokio::test]
+    async fn signing_keys_are_encrypted_at_rest() {
+        use crate::{
+            repositories::{mocks::MockSigningKeyRepository, signing_key::SigningKeyRepository},
+            services::signing_key::SigningKeyService,
+        };
+
+        let signing_key_repository = std::sync::Arc::new(MockSigningKeyRepository::new());
+        let plaintext_key = signing_key_repository
+            .create_first(
+                crate::models::SigningAlgorithm::Hs256,
+                vec![1; 32],
+                false,
+                None,
+            )
+            .await
+            .unwrap();
+        let mut auth_config = crate::config::Config::default().auth;
+        auth_config.key_encryption_key = BASE64_STANDARD.encode([3; 32]);
+        let signing_key_service =
+            SigningKeyService::new(signing_key_repository.clone(), &auth_config).unwrap();
+
+        // keys stored before the key encryption key was set are encrypted on load
+        let signing_key = signing_key_service.current_signing_key().await.unwrap();
+        assert_eq!(signing_key.id, plaintext_key.id);
+        assert_eq!(signing_key.private_key, vec![1; 32]);
+        let stored = signing_key_repository.find_active().await.unwrap();
+        assert!(stored[0].private_key_encrypted);
+        assert_ne!(stored[0].private_key, vec![1; 32]);
+
+        let rotated = signing_key_service
+            .rotate(crate::models::SigningAlgorithm::EdDsa)
+            .await
+            .unwrap()
+            .unwrap();
+        let stored = signing_key_repository.find_active().await.unwrap();
+        let stored_rotated = stored.iter().find(|key| key.id == rotated.id).unwrap();
+        assert!(stored_rotated.private_key_encrypted);
+        assert_ne!(stored_rotated.private_key, rotated.private_key);
+
+        // without the right key encryption key the stored keys cannot be used
+        auth_config.key_encryption_key = BASE64_STANDARD.encode([4; 32]);
+        let signing_key_service =
+            SigningKeyService::new(signing_key_repository, &auth_config).unwrap();
+        assert!(signing_key_service.current_signing_key().await.is_err());
+    }
+    #[tokio::test]
+    async fn refresh_token_reuse_revokes_family() {
+        let state = AppState::mock();
+        let app = routes::app(state.clone());
+        let refresh = |refresh_token: &str| {
+            Request::builder()
+                .uri("/api/auth/refresh")
+                .method("POST")
+                .header("Cookie", format!("refresh_token={}", refresh_token))
+                .body(Body::empty())
+                .unwrap()
+        };
+        let refresh_token = format!("{}.{}", RefreshToken::mock().id, "password123");
+
+        let response = app.clone().oneshot(refresh(&refresh_token)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let child: RefreshResponse = serde_json::from_slice(&body).unwrap();
+
+        let response = app
+            .clone()
+            .oneshot(refresh(&child.refresh_token))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let grandchild: RefreshResponse = serde_json::from_slice(&body).unwrap();
+
+        // the first token has been cycled already, presenting it again counts as theft
+        let response = app.clone().oneshot(refresh(&refresh_token)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = app
+            .oneshot(refresh(&grandchild.refresh_token))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert!(
+            state
+                .auth_service
+                .get_valid_refresh_token(&child.refresh_token)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn sessions_can_be_listed_and_revoked() {
+        let state = AppState::mock();
+        let app = routes::app(state.clone());
+        let email_password = format!("{}:{}", User::mock().email, "password123");
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/auth/login")
+                    .header(
+                        "Authorization",
+                        format!("Basic {}", BASE64_STANDARD.encode(email_password)),
+                    )
+                    .header("User-Agent", "open-erase-station")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let login: LoginResponse = serde_json::from_slice(&body).unwrap();
+        let auth_header = format!("Bearer {}", login.access_token);
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/users/me/sessions")
+                    .header("Authorization", auth_header.clone())
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let sessions: GetSessionsResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(sessions.0.len(), 1);
+        assert_eq!(
+            sessions.0[0].user_agent.as_deref(),
+            Some("open-erase-station")
+        );
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(format!(
+                        "/api/users/{}/sessions/{}",
+                        User::mock().id,
+                        sessions.0[0].id
+                    ))
+                    .method("DELETE")
+                    .header("Authorization", auth_header.clone())
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/auth/refresh")
+                    .method("POST")
+                    .header("Cookie", format!("refresh_token={}", login.refresh_token))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/users/me/sessions")
+                    .header("Authorization", auth_header)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let sessions: GetSessionsResponse = serde_json::from_slice(&body).unwrap();
+        assert!(sessions.0.is_empty());
+    }
+
+    #[tokio::test]
+    async fn cleanup_task_stops_on_shutdown() {
+        use crate::{
+            repositories::{
+                mocks::{MockRefreshTokenRepository, MockSessionRepository},
+                refresh_token::RefreshTokenRepository,
+            },
+            services::session::SessionService,
+        };
+
+        let refresh_token_repository = MockRefreshTokenRepository::new();
+        let expired = refresh_token_repository
+            .create(
+                User::mock().id,
+                None,
+                None,
+                String::new(),
+                Utc::now() - chrono::Duration::hours(1),
+            )
+            .await
+            .unwrap();
+        let used_and_expired = refresh_token_repository
+            .create(
+                User::mock().id,
+                Some(expired.id),
+                None,
+                String::new(),
+                Utc::now() - chrono::Duration::hours(1),
+            )
+            .await
+            .unwrap();
+        refresh_token_repository
+            .mark_as_used(used_and_expired.id)
+            .await
+            .unwrap();
+        let session_service = SessionService::new(
+            std::sync::Arc::new(MockSessionRepository::new(refresh_token_repository.clone())),
+            std::sync::Arc::new(refresh_token_repository.clone()),
+        );
+        let (shutdown_sender, shutdown_receiver) = tokio::sync::watch::channel(());
+        let cleanup = crate::tasks::cleanup::spawn(
+            session_service,
+            AppState::mock().login_throttle_service,
+            crate::config::CleanupConfig {
+                interval_secs: 1,
+                retention_secs: 0,
+            },
+            shutdown_receiver,
+        );
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        shutdown_sender.send(()).unwrap();
+        tokio::time::timeout(std::time::Duration::from_secs(1), cleanup)
+            .await
+            .unwrap()
+            .unwrap();
+
+        for id in [expired.id, used_and_expired.id] {
+            assert!(
+                refresh_token_repository
+                    .find_by_id(id)
+                    .await
+                    .unwrap()
+                    .is_none()
+            );
+        }
+        // the mock refresh token has not expired yet and must survive the purge
+        assert!(
+            refresh_token_repository
+                .find_by_id(RefreshToken::mock().id)
+                .await
+                .unwrap()
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn config_file_overrides_defaults() {
+        let config: crate::config::Config = toml::from_str(
+            r#"
+            [server]
+            bind_address = "127.0.0.1:8000"
+
+            [auth]
+            access_token_lifetime_secs = 60
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.server.bind_address.port(), 8000);
+        assert_eq!(config.server.request_timeout_secs, 5);
+        assert_eq!(config.auth.access_token_lifetime_secs, 60);
+        assert!(config.validate().is_ok());
+
+        let config: crate::config::Config = toml::from_str(
+            r#"
+            [auth]
+            access_token_lifetime_secs = 60
+            refresh_token_lifetime_secs = 30
+            "#,
+        )
+        .unwrap();
+        assert!(config.validate().is_err());
+
+        let mut config = crate::config::Config::default();
+        config.cleanup.retention_secs = u64::MAX;
+        assert!(config.validate().is_err());
+        let mut config = crate::config::Config::default();
+        config.auth.refresh_token_lifetime_secs = u64::MAX;
+        assert!(config.validate().is_err());
+        let mut config = crate::config::Config::default();
+        config.auth.enrollment_code_lifetime_secs = u64::MAX;
+        assert!(config.validate().is_err());
+        let mut config = crate::config::Config::default();
+        config.lockout.max_lockout_secs = u64::MAX;
+        assert!(config.validate().is_err());
+        assert!(toml::from_str::<crate::config::Config>("[server]\nport = 1").is_err());
+    }
+
+    #[tokio::test]
+    async fn repeated_failed_logins_lock_the_account() {
+        let state = AppState::mock();
+        let app = routes::app(state.clone());
+        let login = |password: &str| {
+            let email_password = format!("{}:{}", User::mock().email, password);
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/login")
+                .header(
+                    "Authorization",
+                    format!("Basic {}", BASE64_STANDARD.encode(email_password)),
+                )
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        for _ in 0..state.config.lockout.account_free_attempts {
+            let response = app.clone().oneshot(login("wrong-password")).await.unwrap();
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        }
+        let response = app.clone().oneshot(login("wrong-password")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        let retry_after = response.headers().get("Retry-After").unwrap();
+        assert_eq!(
+            retry_after.to_str().unwrap(),
+            state.config.lockout.base_lockout_secs.to_string()
+        );
+
+        // the right password does not help while the account is locked
+        let response = app.clone().oneshot(login("password123")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        let token = state
+            .auth_service
+            .generate_access_token(&User::mock(), Organization::mock().id, false)
+            .await
+            .unwrap();
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/api/users/{}/unlock", User::mock().id))
+                    .method("POST")
+                    .header("Authorization", format!("Bearer {}", token))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        let response = app.oneshot(login("password123")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn failing_auth_provider_counts_as_no_match() {
+        use std::sync::Arc;
+
+        use async_trait::async_trait;
+
+        use crate::{
+            auth_provider::{AuthProvider, DatabaseAuthProvider},
+            error::{RepositoryError, ServiceError, ServiceResult},
+            repositories::mocks::{
+                MockOrganizationRepository, MockRefreshTokenRepository, MockSessionRepository,
+                MockUserRepository,
+            },
+            services::auth::AuthService,
+        };
+
+        struct UnreachableAuthProvider;
+
+        #[async_trait]
+        impl AuthProvider for UnreachableAuthProvider {
+            async fn authenticate(&self, _: &str, _: &str) -> ServiceResult<Option<User>> {
+                Err(ServiceError::Repository(RepositoryError::Test))
+            }
+        }
+
+        let mut state = AppState::mock();
+        let user_repository = Arc::new(MockUserRepository::new());
+        let refresh_token_repository = MockRefreshTokenRepository::new();
+        state.auth_service = AuthService::new(
+            user_repository.clone(),
+            Arc::new(refresh_token_repository.clone()),
+            Arc::new(MockOrganizationRepository::new()),
+            Arc::new(MockSessionRepository::new(refresh_token_repository)),
+            vec![
+                Arc::new(UnreachableAuthProvider),
+                Arc::new(DatabaseAuthProvider::new(user_repository)),
+            ],
+            state.signing_key_service.clone(),
+            state.config.auth.clone(),
+        );
+        let app = routes::app(state.clone());
+        let login = |password: &str| {
+            let email_password = format!("{}:{}", User::mock().email, password);
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/login")
+                .header(
+                    "Authorization",
+                    format!("Basic {}", BASE64_STANDARD.encode(email_password)),
+                )
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        // the providers after the failing one are still asked
+        let response = app.clone().oneshot(login("password123")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        for _ in 0..state.config.lockout.account_free_attempts {
+            let response = app.clone().oneshot(login("wrong-password")).await.unwrap();
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        }
+        let response = app.oneshot(login("wrong-password")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[tokio::test]
+    async fn failed_logins_outside_the_window_do_not_count() {
+        use crate::{
+            config::LockoutConfig, repositories::mocks::MockLoginThrottleRepository,
+            services::login_throttle::LoginThrottleService,
+        };
+
+        let login_throttle_service = LoginThrottleService::new(
+            std::sync::Arc::new(MockLoginThrottleRepository::new()),
+            LockoutConfig {
+                account_free_attempts: 1,
+                ip_address_free_attempts: 1,
+                base_lockout_secs: 1,
+                max_lockout_secs: 1,
+                failure_window_secs: 1,
+            },
+        );
+        let email = User::mock().email;
+
+        login_throttle_service
+            .record_failure(&email, None)
+            .await
+            .unwrap();
+        login_throttle_service
+            .record_failure(&email, None)
+            .await
+            .unwrap();
+        assert!(
+            login_throttle_service
+                .find_lockout(&email, None)
+                .await
+                .unwrap()
+                .is_some()
+        );
+
+        tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+        login_throttle_service
+            .record_failure(&email, None)
+            .await
+            .unwrap();
+        assert!(
+            login_throttle_service
+                .find_lockout(&email, None)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+        assert_eq!(login_throttle_service.purge_stale().await.unwrap(), 1);
+
+        // emails longer than the column still count instead of failing the login
+        let long_email = format!("{}@example.com", "a".repeat(300));
+        login_throttle_service
+            .record_failure(&long_email, None)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn malformed_requests_get_the_error_envelope() {
+        let state = AppState::mock();
+        let app = routes::app(state.clone());
+        let token = state
+            .auth_service
+            .generate_access_token(&User::mock(), Organization::mock().id, false)
+            .await
+            .unwrap();
+        let get = |uri: &str, header: Option<(&str, &str)>| {
+            let mut request = Request::builder()
+                .uri(uri)
+                .header("Authorization", format!("Bearer {}", token));
+            if let Some((name, value)) = header {
+                request = request.header(name, value);
+            }
+            request.body(Body::empty()).unwrap()
+        };
+
+        for (request, status, code) in [
+            (
+                get("/api/users/not-a-uuid", None),
+                StatusCode::NOT_FOUND,
+                ErrorCode::NotFound,
+            ),
+            (
+                get("/api/no-such-route", None),
+                StatusCode::NOT_FOUND,
+                ErrorCode::NotFound,
+            ),
+            (
+                get(
+                    &format!("/api/images/{}/download", Uuid::now_v7()),
+                    Some(("If-Range", "neither a date nor an etag")),
+                ),
+                StatusCode::BAD_REQUEST,
+                ErrorCode::MalformedRequest,
+            ),
+        ] {
+            let response = app.clone().oneshot(request).await.unwrap();
+            assert_eq!(response.status(), status);
+            let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            let error_response: ErrorResponse = serde_json::from_slice(&body).unwrap();
+            assert_eq!(error_response.code, code);
+        }
+    }
+
+    #[tokio::test]
+    async fn rate_limit_per_route_group() {
+        let mut state = AppState::mock();
+        let mut rate_limit = state.config.rate_limit.clone();
+        rate_limit.auth = crate::config::BucketConfig {
+            capacity: 2,
+            refill_per_minute: 1,
+        };
+        state.rate_limit_service = crate::services::rate_limit::RateLimitService::new(rate_limit);
+        let app = routes::app(state);
+        let jwks = || {
+            Request::builder()
+                .uri("/api/auth/jwks")
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        for remaining in ["1", "0"] {
+            let response = app.clone().oneshot(jwks()).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(response.headers().get("RateLimit-Limit").unwrap(), "2");
+            assert_eq!(
+                response.headers().get("RateLimit-Remaining").unwrap(),
+                remaining
+            );
+        }
+        let response = app.clone().oneshot(jwks()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers().get("Retry-After").unwrap(), "60");
+        assert_eq!(response.headers().get("RateLimit-Reset").unwrap(), "120");
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let error_response: ErrorResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(error_response.message, "too many requests, try again later");
+
+        // credentials get a bucket of their own without being checked first
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/auth/jwks")
+                    .header("x-api-key", "oe_made_up_key")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers().get("RateLimit-Remaining").unwrap(), "1");
+
+        // other route groups keep their own bucket
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/batches")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.headers().get("RateLimit-Limit").unwrap(), "300");
+    }
+
+    #[tokio::test]
+    async fn mfa_enrollment_and_login() {
+        let state = AppState::mock();
+        let app = routes::app(state.clone());
+        let token = state
+            .auth_service
+            .generate_access_token(&User::mock(), Organization::mock().id, false)
+            .await
+            .unwrap();
+        let post_json = |uri: &str, token: Option<&str>, body: serde_json::Value| {
+            let mut request = Request::builder()
+                .uri(uri)
+                .method("POST")
+                .header("Content-Type", "application/json");
+            if let Some(token) = token {
+                request = request.header("Authorization", format!("Bearer {}", token));
+            }
+            request.body(Body::from(body.to_string())).unwrap()
+        };
+        let login = || {
+            let email_password = format!("{}:{}", User::mock().email, "password123");
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/login")
+                .header(
+                    "Authorization",
+                    format!("Basic {}", BASE64_STANDARD.encode(email_password)),
+                )
+                .body(Body::empty())
+                .unwrap()
+        };
+        let delete_user = |token: &str| {
+            Request::builder()
+                .uri(format!("/api/users/{}", User::mock().id))
+                .method("DELETE")
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        // deleting needs a second factor even for admins
+        let response = app.clone().oneshot(delete_user(&token)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let response = app
+            .clone()
+            .oneshot(post_json(
+                "/api/users/me/mfa/totp",
+                Some(&token),
+                serde_json::Value::Null,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let enrollment: PostTotpResponse = serde_json::from_slice(&body).unwrap();
+        assert!(enrollment.otpauth_uri.starts_with("otpauth://totp/"));
+        let secret = data_encoding::BASE32_NOPAD
+            .decode(enrollment.secret.as_bytes())
+            .unwrap();
+        let code = crate::services::mfa::totp_code(&secret, Utc::now().timestamp() / 30);
+
+        let response = app
+            .clone()
+            .oneshot(post_json(
+                "/api/users/me/mfa/totp/confirm",
+                Some(&token),
+                serde_json::json!({ "code": code }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let confirm: PostTotpConfirmResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(confirm.recovery_codes.len(), 10);
+
+        let verify = |challenge_token: &str, code: &str| {
+            post_json(
+                "/api/auth/mfa/verify",
+                None,
+                serde_json::json!({ "challenge_token": challenge_token, "code": code }),
+            )
+        };
+        let response = app.clone().oneshot(login()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let challenge: MfaChallengeResponse = serde_json::from_slice(&body).unwrap();
+
+        // the challenge token is no access token
+        let response = app
+            .clone()
+            .oneshot(delete_user(&challenge.challenge_token))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        // the code used for confirmation can't be replayed
+        let response = app
+            .clone()
+            .oneshot(verify(&challenge.challenge_token, &code))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let recovery_code = &confirm.recovery_codes[0];
+        let response = app
+            .clone()
+            .oneshot(verify(&challenge.challenge_token, recovery_code))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let login_response: LoginResponse = serde_json::from_slice(&body).unwrap();
+
+        let response = app
+            .clone()
+            .oneshot(verify(&challenge.challenge_token, recovery_code))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = app
+            .oneshot(delete_user(&login_response.access_token))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn password_reset_flow() {
+        let state = AppState::mock();
+        let app = routes::app(state.clone());
+        let post_json = |uri: &str, body: serde_json::Value| {
+            Request::builder()
+                .uri(uri)
+                .method("POST")
+                .header("Content-Type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+        let login = |password: &str| {
+            let email_password = format!("{}:{}", User::mock().email, password);
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/login")
+                .header(
+                    "Authorization",
+                    format!("Basic {}", BASE64_STANDARD.encode(email_password)),
+                )
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        // unknown addresses get the same answer but no email
+        for email in ["nobody@example.com", User::mock().email.as_str()] {
+            let response = app
+                .clone()
+                .oneshot(post_json(
+                    "/api/auth/password-reset",
+                    serde_json::json!({ "email": email }),
+                ))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::ACCEPTED);
+        }
+        // the email is sent in the background
+        let mail_directory = &state.config.mail.directory;
+        let read_emails = || -> Vec<String> {
+            std::fs::read_dir(mail_directory)
+                .into_iter()
+                .flatten()
+                .map(|entry| std::fs::read_to_string(entry.unwrap().path()).unwrap())
+                .collect()
+        };
+        let mut emails = read_emails();
+        for _ in 0..50 {
+            if !emails.is_empty() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            emails = read_emails();
+        }
+        assert_eq!(emails.len(), 1);
+        // undo the quoted-printable soft line breaks around the link
+        let email = emails[0].replace("=\r\n", "").replace("=3D", "=");
+        let token = email
+            .split("token=")
+            .nth(1)
+            .unwrap()
+            .split_whitespace()
+            .next()
+            .unwrap()
+            .to_string();
+        std::fs::remove_dir_all(mail_directory).unwrap();
+
+        let confirm = || {
+            post_json(
+                "/api/auth/password-reset/confirm",
+                serde_json::json!({ "token": token, "new_password": "password456" }),
+            )
+        };
+        let response = app.clone().oneshot(confirm()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let response = app.clone().oneshot(confirm()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = app.clone().oneshot(login("password123")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let response = app.oneshot(login("password456")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // without a way to send the link there is no reset
+        let app = routes::app(AppState::mock_with_config(crate::config::Config::default()));
+        let response = app
+            .oneshot(post_json(
+                "/api/auth/password-reset",
+                serde_json::json!({ "email": User::mock().email }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn password_reset_skips_external_accounts() {
+        use std::sync::Arc;
+
+        use crate::{
+            repositories::{
+                mocks::{
+                    MockPasswordResetTokenRepository, MockRefreshTokenRepository,
+                    MockUserIdentityRepository, MockUserRepository,
+                },
+                user_identity::UserIdentityRepository,
+            },
+            services::password_reset::PasswordResetService,
+        };
+
+        let state = AppState::mock();
+        let user_identity_repository = MockUserIdentityRepository::new();
+        user_identity_repository
+            .create(
+                User::mock().id,
+                String::from("https://idp.example.com"),
+                String::from("subject"),
+            )
+            .await
+            .unwrap();
+        let password_reset_service = PasswordResetService::new(
+            Arc::new(MockUserRepository::new()),
+            Arc::new(MockPasswordResetTokenRepository::new()),
+            Arc::new(MockRefreshTokenRepository::new()),
+            Arc::new(user_identity_repository),
+            crate::mailer::from_config(&state.config.mail).unwrap(),
+            state.config.auth.clone(),
+            &state.config.server,
+        );
+
+        password_reset_service
+            .request_reset(&User::mock().email)
+            .await
+            .unwrap();
+        assert!(!state.config.mail.directory.exists());
+    }
+
+    #[tokio::test]
+    async fn oidc_login_with_mock_provider() {
+        use std::{
+            collections::HashMap,
+            sync::{Arc, Mutex},
+        };
+
+        use axum::{Form, Json, routing::get, routing::post};
+        use ed25519_dalek::pkcs8::EncodePrivateKey;
+        use jsonwebtoken::{Algorithm, EncodingKey, Header};
+        use sha2::{Digest, Sha256};
+
+        use crate::models::Role as ModelRole;
+
+        #[derive(Default)]
+        struct MockProvider {
+            nonce: String,
+            code_challenge: String,
+            subject: &'static str,
+            email: String,
+            groups: Vec<&'static str>,
+        }
+
+        let signing_key = ed25519_dalek::SigningKey::from_bytes(&[7; 32]);
+        let encoding_key = EncodingKey::from_ed_der(signing_key.to_pkcs8_der().unwrap().as_bytes());
+        let public_key =
+            base64::prelude::BASE64_URL_SAFE_NO_PAD.encode(signing_key.verifying_key().to_bytes());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let issuer = format!("http://{}", listener.local_addr().unwrap());
+        let provider = Arc::new(Mutex::new(MockProvider::default()));
+        let provider_router = axum::Router::new()
+            .route(
+                "/.well-known/openid-configuration",
+                get({
+                    let issuer = issuer.clone();
+                    move || async move {
+                        Json(serde_json::json!({
+                            "issuer": issuer,
+                            "authorization_endpoint": format!("{issuer}/authorize"),
+                            "token_endpoint": format!("{issuer}/token"),
+                            "jwks_uri": format!("{issuer}/jwks"),
+                        }))
+                    }
+                }),
+            )
+            .route(
+                "/jwks",
+                get(move || async move {
+                    Json(serde_json::json!({ "keys": [{
+                        "kty": "OKP",
+                        "crv": "Ed25519",
+                        "alg": "EdDSA",
+                        "use": "sig",
+                        "kid": "provider-key",
+                        "x": public_key,
+                    }]}))
+                }),
+            )
+            .route(
+                "/token",
+                post({
+                    let issuer = issuer.clone();
+                    let provider = provider.clone();
+                    move |Form(form): Form<HashMap<String, String>>| async move {
+                        let provider = provider.lock().unwrap();
+                        let code_challenge = base64::prelude::BASE64_URL_SAFE_NO_PAD
+                            .encode(Sha256::digest(form["code_verifier"].as_bytes()));
+                        if form["code"] != "valid-code" || code_challenge != provider.code_challenge
+                        {
+                            return Err(StatusCode::BAD_REQUEST);
+                        }
+                        let now = Utc::now().timestamp();
+                        let claims = serde_json::json!({
+                            "iss": issuer,
+                            "aud": form["client_id"],
+                            "sub": provider.subject,
+                            "email": provider.email,
+                            "email_verified": true,
+                            "nonce": provider.nonce,
+                            "groups": provider.groups,
+                            "amr": ["pwd", "mfa"],
+                            "iat": now,
+                            "exp": now + 300,
+                        });
+                        let mut header = Header::new(Algorithm::EdDSA);
+                        header.kid = Some(String::from("provider-key"));
+                        let id_token =
+                            jsonwebtoken::encode(&header, &claims, &encoding_key).unwrap();
+                        Ok(Json(serde_json::json!({
+                            "access_token": "provider-access-token",
+                            "token_type": "Bearer",
+                            "id_token": id_token,
+                        })))
+                    }
+                }),
+            );
+        tokio::spawn(async move { axum::serve(listener, provider_router).await });
+
+        let mut config = crate::config::Config::default();
+        config.oidc.enabled = true;
+        config.oidc.issuer_url = issuer;
+        config.oidc.client_id = String::from("open-erase");
+        config.oidc.client_secret = String::from("secret");
+        config.oidc.role_mappings = HashMap::from([
+            (String::from("erasure-admins"), ModelRole::Admin),
+            (String::from("erasure-operators"), ModelRole::Operator),
+        ]);
+        config.oidc.jit_provisioning = true;
+        config.oidc.jit_organization_id = Some(Organization::mock().id);
+        let state = AppState::mock_with_config(config);
+        let app = routes::app(state.clone());
+        let cookie_value = |response: &axum::response::Response, name: &str| {
+            response
+                .headers()
+                .get_all("set-cookie")
+                .iter()
+                .filter_map(|cookie| cookie.to_str().unwrap().strip_prefix(&format!("{name}=")))
+                .map(|cookie| cookie.split(';').next().unwrap().to_string())
+                .next()
+                .unwrap()
+        };
+
+        // the last identity uses the email of a local account, which it may not take over
+        let mut user_ids = Vec::new();
+        for (subject, email, groups, expected_role) in [
+            (
+                "provider-user",
+                String::from("sso@example.com"),
+                vec!["erasure-operators"],
+                Some(ModelRole::Operator),
+            ),
+            (
+                "provider-user",
+                String::from("sso@example.com"),
+                vec!["erasure-admins", "erasure-operators"],
+                Some(ModelRole::Admin),
+            ),
+            (
+                "other-provider-user",
+                User::mock().email,
+                vec!["erasure-admins"],
+                None,
+            ),
+        ] {
+            let response = app
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .uri("/api/auth/oidc/login")
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::SEE_OTHER);
+            let location = response.headers()["location"].to_str().unwrap();
+            let query: HashMap<_, _> = location
+                .split_once('?')
+                .unwrap()
+                .1
+                .split('&')
+                .filter_map(|pair| pair.split_once('='))
+                .collect();
+            assert_eq!(query["code_challenge_method"], "S256");
+            let login_cookie = cookie_value(&response, "oidc_login");
+            *provider.lock().unwrap() = MockProvider {
+                nonce: query["nonce"].to_string(),
+                code_challenge: query["code_challenge"].to_string(),
+                subject,
+                email,
+                groups,
+            };
+            let callback = |code: &str, oidc_state: &str| {
+                Request::builder()
+                    .uri(format!(
+                        "/api/auth/oidc/callback?code={code}&state={oidc_state}"
+                    ))
+                    .header("Cookie", format!("oidc_login={login_cookie}"))
+                    .body(Body::empty())
+                    .unwrap()
+            };
+
+            // the state has to round trip and the code has to be accepted by the provider
+            let response = app
+                .clone()
+                .oneshot(callback("valid-code", "forged-state"))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+            let response = app
+                .clone()
+                .oneshot(callback("invalid-code", query["state"]))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+            let response = app
+                .clone()
+                .oneshot(callback("valid-code", query["state"]))
+                .await
+                .unwrap();
+            let Some(expected_role) = expected_role else {
+                assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+                continue;
+            };
+            assert_eq!(response.status(), StatusCode::SEE_OTHER);
+            let refresh_token = cookie_value(&response, "refresh_token");
+            let response = app
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .method("POST")
+                        .uri("/api/auth/refresh")
+                        .header("Cookie", format!("refresh_token={refresh_token}"))
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            let refresh_response: RefreshResponse = serde_json::from_slice(&body).unwrap();
+            let claims = state
+                .auth_service
+                .get_valid_access_token_claims(&refresh_response.access_token)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(claims.role, expected_role);
+            assert_eq!(claims.org, Organization::mock().id);
+            assert!(claims.mfa);
+            user_ids.push(claims.sub);
+        }
+        // the second login found the user through the linked subject
+        assert_eq!(user_ids[0], user_ids[1]);
+    }
+
+    #[tokio::test]
+    async fn api_keys_are_limited_to_their_scopes() {
+        let state = AppState::mock();
+        let app = routes::app(state.clone());
+        let token = state
+            .auth_service
+            .generate_access_token(&User::mock(), Organization::mock().id, false)
+            .await
+            .unwrap();
+        let auth_header = format!("Bearer {}", token);
+        let body = PostApiKeyRequest {
+            name: String::from("station 1"),
+            scopes: vec![ApiKeyScope::UploadReports],
+            expires_at: None,
+        };
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/api-keys")
+                    .method("POST")
+                    .header("Authorization", auth_header.clone())
+                    .header("Content-Type", "application/json")
+                    .body(Body::from(serde_json::to_string(&body).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let api_key: PostApiKeyResponse = serde_json::from_slice(&body).unwrap();
+        assert!(api_key.key.starts_with(&format!("oe_{}_", api_key.prefix)));
+
+        let with_key = |method: &str, uri: &str, key: &str| {
+            let report = PostReportRequest {
+                device_id: Uuid::now_v7(),
+                method: String::from("nist-800-88-clear"),
+                result: ErasureResult::Passed,
+                started_at: Utc::now(),
+                finished_at: Utc::now(),
+            };
+            Request::builder()
+                .uri(uri)
+                .method(method)
+                .header("x-api-key", key)
+                .header("Content-Type", "application/json")
+                .body(Body::from(serde_json::to_string(&report).unwrap()))
+                .unwrap()
+        };
+        // the unknown device is only looked up once the key passed authorization
+        let response = app
+            .clone()
+            .oneshot(with_key("POST", "/api/reports", &api_key.key))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        for (method, uri) in [
+            ("GET", "/api/images"),
+            ("GET", "/api/batches"),
+            ("GET", "/api/users/me"),
+            ("GET", "/api/api-keys"),
+        ] {
+            let response = app
+                .clone()
+                .oneshot(with_key(method, uri, &api_key.key))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::FORBIDDEN, "{method} {uri}");
+        }
+        let forged_key = format!("{}x", api_key.key);
+        let response = app
+            .clone()
+            .oneshot(with_key("POST", "/api/reports", &forged_key))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/api-keys")
+                    .header("Authorization", auth_header.clone())
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let api_keys: GetApiKeysResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(api_keys.0.len(), 1);
+        assert!(api_keys.0[0].last_used_at.is_some());
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/api/api-keys/{}", api_key.id))
+                    .method("DELETE")
+                    .header("Authorization", auth_header)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let response = app
+            .oneshot(with_key("POST", "/api/reports", &api_key.key))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn stations_enroll_with_a_one_time_code() {
+        let state = AppState::mock();
+        let app = routes::app(state.clone());
+        let token = state
+            .auth_service
+            .generate_access_token(&User::mock(), Organization::mock().id, false)
+            .await
+            .unwrap();
+        let auth_header = format!("Bearer {}", token);
+        let admin_request = |method: &str, uri: &str, body: String| {
+            Request::builder()
+                .uri(uri)
+                .method(method)
+                .header("Authorization", auth_header.clone())
+                .header("Content-Type", "application/json")
+                .body(Body::from(body))
+                .unwrap()
+        };
+        let body = PostStationRequest {
+            name: String::from("bench 1"),
+        };
+        let response = app
+            .clone()
+            .oneshot(admin_request(
+                "POST",
+                "/api/stations",
+                serde_json::to_string(&body).unwrap(),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let station: PostStationResponse = serde_json::from_slice(&body).unwrap();
+
+        let enroll = |enrollment_code: &str| {
+            let body = PostEnrollRequest {
+                enrollment_code: enrollment_code.to_string(),
+                hardware_fingerprint: String::from("fingerprint-1"),
+            };
+            Request::builder()
+                .uri("/api/auth/enroll")
+                .method("POST")
+                .header("Content-Type", "application/json")
+                .body(Body::from(serde_json::to_string(&body).unwrap()))
+                .unwrap()
+        };
+        // codes may be typed without grouping and in lower case
+        let typed_code = station.enrollment_code.replace('-', "").to_lowercase();
+        let response = app.clone().oneshot(enroll(&typed_code)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let enrollment: PostEnrollResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(enrollment.station_id, station.id);
+        let response = app
+            .clone()
+            .oneshot(enroll(&station.enrollment_code))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let upload_report = |key: &str, fingerprint: &str| {
+            let report = PostReportRequest {
+                device_id: Uuid::now_v7(),
+                method: String::from("nist-800-88-clear"),
+                result: ErasureResult::Passed,
+                started_at: Utc::now(),
+                finished_at: Utc::now(),
+            };
+            Request::builder()
+                .uri("/api/reports")
+                .method("POST")
+                .header("x-api-key", key)
+                .header("x-station-fingerprint", fingerprint)
+                .header("Content-Type", "application/json")
+                .body(Body::from(serde_json::to_string(&report).unwrap()))
+                .unwrap()
+        };
+        // the unknown device is only looked up once the key passed authorization
+        let response = app
+            .clone()
+            .oneshot(upload_report(&enrollment.api_key, "fingerprint-1"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let response = app
+            .clone()
+            .oneshot(upload_report(&enrollment.api_key, "fingerprint-2"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let station_uri = format!("/api/stations/{}", station.id);
+        let disable = |is_disabled: bool| PatchStationRequest {
+            name: None,
+            is_disabled: Some(is_disabled),
+        };
+        let response = app
+            .clone()
+            .oneshot(admin_request(
+                "PATCH",
+                &station_uri,
+                serde_json::to_string(&disable(true)).unwrap(),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = app
+            .clone()
+            .oneshot(upload_report(&enrollment.api_key, "fingerprint-1"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        app.clone()
+            .oneshot(admin_request(
+                "PATCH",
+                &station_uri,
+                serde_json::to_string(&disable(false)).unwrap(),
+            ))
+            .await
+            .unwrap();
+        let response = app
+            .clone()
+            .oneshot(upload_report(&enrollment.api_key, "fingerprint-1"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = app
+            .clone()
+            .oneshot(admin_request(
+                "POST",
+                &format!("{station_uri}/enrollment-code"),
+                String::new(),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let reenrollment: PostStationEnrollmentCodeResponse =
+            serde_json::from_slice(&body).unwrap();
+        let response = app
+            .clone()
+            .oneshot(upload_report(&enrollment.api_key, "fingerprint-1"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let response = app
+            .oneshot(enroll(&reenrollment.enrollment_code))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    #[tokio::test]
+    async fn errors_are_json_envelopes_with_request_id() {
+        let response = test_request(
+            Request::builder()
+                .uri("/api/users/me")
+                .header("Authorization", "Bearer invalid")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let request_id = response.headers()["x-request-id"]
+            .to_str()
+            .unwrap()
+            .to_string();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let error: ErrorResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(error.code, ErrorCode::Unauthorized);
+        assert_eq!(error.request_id, Some(request_id));
+        assert!(error.details.is_empty());
+
+        // an id set by a proxy in front of the server is kept
+        let response = test_request(
+            Request::builder()
+                .uri("/api/users/me")
+                .method("PUT")
+                .header("x-request-id", "proxy-42")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(response.headers()["x-request-id"], "proxy-42");
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let error: ErrorResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(error.code, ErrorCode::MethodNotAllowed);
+        assert_eq!(error.request_id.as_deref(), Some("proxy-42"));
+    }
+
+    #[tokio::test]
+    async fn invalid_request_bodies_are_rejected() {
+        let state = AppState::mock();
+        let app = routes::app(state.clone());
+        let token = state
+            .auth_service
+            .generate_access_token(&User::mock(), Organization::mock().id, false)
+            .await
+            .unwrap();
+        let auth_header = format!("Bearer {}", token);
+        let post_user = |content_type: &str, body: String| {
+            Request::builder()
+                .uri("/api/users")
+                .method("POST")
+                .header("Authorization", auth_header.clone())
+                .header("Content-Type", content_type)
+                .body(Body::from(body))
+                .unwrap()
+        };
+        let error_of = async |response: axum::response::Response| {
+            let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            serde_json::from_slice::<ErrorResponse>(&body).unwrap()
+        };
+
+        let body = PostUserRequest {
+            email: String::from("not an email"),
+            password: String::from("short"),
+            role: Role::Operator,
+        };
+        let response = app
+            .clone()
+            .oneshot(post_user(
+                "application/json",
+                serde_json::to_string(&body).unwrap(),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let error = error_of(response).await;
+        assert_eq!(error.code, ErrorCode::ValidationFailed);
+        let fields: Vec<&str> = error
+            .details
+            .iter()
+            .map(|detail| detail.field.as_str())
+            .collect();
+        assert_eq!(fields, ["email", "password"]);
+
+        let response = app
+            .clone()
+            .oneshot(post_user(
+                "application/json",
+                String::from(r#"{"email": "some@mail.com"}"#),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let error = error_of(response).await;
+        assert_eq!(error.details[0].field, "password");
+        assert_eq!(error.details[0].message, "is required");
+
+        let response = app
+            .clone()
+            .oneshot(post_user("application/json", String::from(r#"{"email": "#)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(error_of(response).await.code, ErrorCode::MalformedBody);
+
+        let response = app
+            .oneshot(post_user(
+                "text/plain",
+                serde_json::to_string(&body).unwrap(),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(
+            error_of(response).await.code,
+            ErrorCode::UnsupportedMediaType
+        );
+    }
+
+    /// Every route of the API, kept by hand since axum can't list the routes of a router.
+    const API_ROUTES: &[&str] = &[
+        "DELETE /api/api-keys/{uuid}",
+        "DELETE /api/batches/{uuid}",
+        "DELETE /api/organizations/invitations/{uuid}",
+        "DELETE /api/organizations/{uuid}/members/{user_uuid}",
+        "DELETE /api/users/me/mfa",
+        "DELETE /api/users/me/sessions",
+        "DELETE /api/users/me/sessions/{session_uuid}",
+        "DELETE /api/users/{uuid}",
+        "DELETE /api/users/{uuid}/sessions",
+        "DELETE /api/users/{uuid}/sessions/{session_uuid}",
+        "GET /api/api-keys",
+        "GET /api/auth/jwks",
+        "GET /api/auth/oidc",
+        "GET /api/auth/oidc/callback",
+        "GET /api/auth/oidc/login",
+        "GET /api/batches",
+        "GET /api/batches/{uuid}",
+        "GET /api/batches/{uuid}/certificate",
+        "GET /api/batches/{uuid}/devices",
+        "GET /api/batches/{uuid}/reports",
+        "GET /api/batches/{uuid}/summary",
+        "GET /api/boot/images/{uuid}/{path}",
+        "GET /api/boot/{architecture}/boot.ipxe",
+        "GET /api/images",
+        "GET /api/images/SHA256SUMS",
+        "GET /api/images/{uuid}",
+        "GET /api/images/{uuid}/download",
+        "GET /api/images/{uuid}/variants",
+        "GET /api/organizations",
+        "GET /api/organizations/invitations",
+        "GET /api/organizations/{uuid}",
+        "GET /api/organizations/{uuid}/members",
+        "GET /api/reports/{uuid}",
+        "GET /api/signing-keys",
+        "GET /api/stations",
+        "GET /api/stations/{uuid}",
+        "GET /api/users/me",
+        "GET /api/users/me/mfa",
+        "GET /api/users/me/sessions",
+        "GET /api/users/{uuid}",
+        "GET /api/users/{uuid}/sessions",
+        "PATCH /api/batches/{uuid}",
+        "PATCH /api/images/{uuid}",
+        "PATCH /api/stations/{uuid}",
+        "PATCH /api/users/{uuid}",
+        "POST /api/api-keys",
+        "POST /api/auth/enroll",
+        "POST /api/auth/login",
+        "POST /api/auth/logout",
+        "POST /api/auth/mfa/verify",
+        "POST /api/auth/password-reset",
+        "POST /api/auth/password-reset/confirm",
+        "POST /api/auth/refresh",
+        "POST /api/auth/switch-organization",
+        "POST /api/batches",
+        "POST /api/batches/{uuid}/devices",
+        "POST /api/images",
+        "POST /api/images/{uuid}/variants",
+        "POST /api/organizations",
+        "POST /api/organizations/invitations/{uuid}/accept",
+        "POST /api/organizations/{uuid}/invitations",
+        "POST /api/organizations/{uuid}/members",
+        "POST /api/reports",
+        "POST /api/signing-keys",
+        "POST /api/stations",
+        "POST /api/stations/{uuid}/enrollment-code",
+        "POST /api/users",
+        "POST /api/users/me/mfa/recovery-codes",
+        "POST /api/users/me/mfa/totp",
+        "POST /api/users/me/mfa/totp/confirm",
+        "POST /api/users/me/password",
+        "POST /api/users/{uuid}/unlock",
+    ];
+
+    #[test]
+    fn openapi_documents_every_route() {
+        use std::collections::BTreeSet;
+
+        use utoipa::OpenApi;
+
+        let openapi = routes::docs::Doc::openapi();
+        let documented: BTreeSet<String> = openapi
+            .paths
+            .paths
+            .iter()
+            .flat_map(|(path, item)| {
+                [
+                    ("GET", &item.get),
+                    ("POST", &item.post),
+                    ("PUT", &item.put),
+                    ("PATCH", &item.patch),
+                    ("DELETE", &item.delete),
+                ]
+                .into_iter()
+                .filter(|(_, operation)| operation.is_some())
+                .map(move |(method, _)| format!("{method} /api{path}"))
+            })
+            .collect();
+        let listed: BTreeSet<String> = API_ROUTES.iter().map(|route| route.to_string()).collect();
+
+        let undocumented: Vec<_> = listed.difference(&documented).collect();
+        assert!(
+            undocumented.is_empty(),
+            "undocumented routes: {undocumented:?}"
+        );
+        let unlisted: Vec<_> = documented.difference(&listed).collect();
+        assert!(unlisted.is_empty(), "unlisted routes: {unlisted:?}");
+    }
+
+    #[tokio::test]
+    async fn api_router_serves_every_listed_route() {
+        let state = AppState::mock();
+        let app = routes::app(state.clone());
+        let token = state
+            .auth_service
+            .generate_access_token(&User::mock(), Organization::mock().id, false)
+            .await
+            .unwrap();
+
+        for route in API_ROUTES {
+            let (method, path) = route.split_once(' ').unwrap();
+            // any value reaches the handler, which may then not find the resource
+            let uri = path
+                .split('/')
+                .map(|segment| {
+                    if segment.starts_with('{') {
+                        Uuid::now_v7().to_string()
+                    } else {
+                        segment.to_string()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("/");
+            let response = app
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .method(method)
+                        .uri(uri)
+                        .header("Authorization", format!("Bearer {}", token))
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_ne!(
+                response.status(),
+                StatusCode::METHOD_NOT_ALLOWED,
+                "{route} is not routed"
+            );
+            if response.status() == StatusCode::NOT_FOUND {
+                let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+                let error_response: ErrorResponse = serde_json::from_slice(&body).unwrap();
+                assert_ne!(
+                    error_response.message, "no api route matches the requested path",
+                    "{route} is not routed"
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn api_client_refreshes_expired_access_token() {
+        use std::sync::{
+            Arc,
+            atomic::{AtomicUsize, Ordering},
+        };
+
+        use open_erase_lib::client::{
+            self, ApiClient, HttpBackend, HttpRequest, HttpResponse, LoginOutcome, Method,
+        };
+
+        /// Hands the requests of the client straight to the router and counts refreshes.
+        #[derive(Clone)]
+        struct RouterBackend(axum::Router, Arc<AtomicUsize>);
+
+        impl HttpBackend for RouterBackend {
+            async fn send(&self, request: HttpRequest) -> Result<HttpResponse, client::Error> {
+                if request.url.ends_with("/auth/refresh") {
+                    self.1.fetch_add(1, Ordering::SeqCst);
+                }
+                // lets concurrent requests of the client interleave
+                tokio::task::yield_now().await;
+                let method = match request.method {
+                    Method::Get => "GET",
+                    Method::Post => "POST",
+                    Method::Patch => "PATCH",
+                    Method::Delete => "DELETE",
+                };
+                let mut builder = Request::builder().method(method).uri(request.url);
+                for (name, value) in request.headers {
+                    builder = builder.header(name, value);
+                }
+                let body = request.body.map(Body::from).unwrap_or_default();
+                let response = self
+                    .0
+                    .clone()
+                    .oneshot(builder.body(body).unwrap())
+                    .await
+                    .unwrap();
+                let status = response.status().as_u16();
+                let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+                Ok(HttpResponse {
+                    status,
+                    body: body.to_vec(),
+                })
+            }
+        }
+
+        let state = AppState::mock();
+        let refreshes = Arc::new(AtomicUsize::new(0));
+        let client = ApiClient::new(RouterBackend(routes::app(state), refreshes.clone()), "");
+        assert!(matches!(
+            client.login(&User::mock().email, "password123").await,
+            Ok(LoginOutcome::LoggedIn)
+        ));
+        assert_eq!(client.get_me().await.unwrap().email, User::mock().email);
+
+        let refresh_token = client.refresh_token();
+        assert!(refresh_token.is_some());
+        client.set_tokens(String::from("expired"), refresh_token.clone());
+        assert_eq!(client.get_me().await.unwrap().email, User::mock().email);
+        assert_ne!(client.access_token().as_deref(), Some("expired"));
+        assert_ne!(client.refresh_token(), refresh_token);
+        assert_eq!(refreshes.load(Ordering::SeqCst), 1);
+
+        // requests that find the token expired together share one refresh, as spending
+        // the refresh token twice would end the session
+        client.set_tokens(String::from("expired"), client.refresh_token());
+        let (first, second) = tokio::join!(client.get_me(), client.get_me());
+        assert_eq!(first.unwrap().email, User::mock().email);
+        assert_eq!(second.unwrap().email, User::mock().email);
+        assert_eq!(refreshes.load(Ordering::SeqCst), 2);
+
+        // once the refresh token is gone as well the client is logged out
+        client.set_tokens(String::from("expired"), Some(String::from("invalid")));
+        assert!(matches!(
+            client.get_me().await,
+            Err(client::Error::Api {
+                status: 401,
+                error: Some(ErrorResponse {
+                    code: ErrorCode::Unauthorized,
+                    ..
+                }),
+            })
+        ));
+        assert!(client.access_token().is_none());
+    }
+
+    #[tokio::test]
+    async fn images_are_uploaded_with_metadata() {
+        use sha2::{Digest, Sha256};
+
+        let mut config = crate::config::Config::default();
+        config.images.max_upload_size_bytes = 1024;
+        let state = AppState::mock_with_config(config);
+        let app = routes::app(state.clone());
+        let token = state
+            .auth_service
+            .generate_access_token(&User::mock(), Organization::mock().id, false)
+            .await
+            .unwrap();
+        let auth_header = format!("Bearer {}", token);
+        let upload = |fields: &[(&str, &str)], iso: &[u8]| {
+            let boundary = "open-erase-boundary";
+            let mut body = Vec::new();
+            for (name, value) in fields {
+                body.extend_from_slice(
+                    format!(
+                        "--{boundary}\r\nContent-Disposition: form-data; name=\"{name}\"\r\n\r\n{value}\r\n"
+                    )
+                    .as_bytes(),
+                );
+            }
+            body.extend_from_slice(
+                format!(
+                    "--{boundary}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"open-erase.iso\"\r\nContent-Type: application/octet-stream\r\n\r\n"
+                )
+                .as_bytes(),
+            );
+            body.extend_from_slice(iso);
+            body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+            Request::builder()
+                .uri("/api/images")
+                .method("POST")
+                .header("Authorization", auth_header.clone())
+                .header(
+                    "Content-Type",
+                    format!("multipart/form-data; boundary={boundary}"),
+                )
+                .body(Body::from(body))
+                .unwrap()
+        };
+        let iso = [0x42; 512];
+        let fields = [
+            ("version", "1.0.0"),
+            ("architecture", "x86_64"),
+            ("release_notes", "first release"),
+        ];
+
+        let response = app.clone().oneshot(upload(&fields, &iso)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let image: PostImageResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(image.architecture, Architecture::X86_64);
+        assert_eq!(image.size_bytes, 512);
+        assert_eq!(image.sha256, format!("{:x}", Sha256::digest(iso)));
+        assert!(!image.is_active);
+        let path = state
+            .config
+            .images
+            .directory
+            .join(format!("{}.iso", image.id));
+        assert_eq!(std::fs::read(path).unwrap(), iso);
+
+        let response = app.clone().oneshot(upload(&fields, &iso)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let response = app
+            .clone()
+            .oneshot(upload(&[("version", "1.0.1")], &iso))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let error: ErrorResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(error.details[0].field, "architecture");
+        let response = app
+            .clone()
+            .oneshot(upload(
+                &[("version", "2.0.0"), ("architecture", "x86_64")],
+                &[0; 2048],
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        // neither rejected upload is left behind
+        let files = std::fs::read_dir(&state.config.images.directory)
+            .unwrap()
+            .count();
+        assert_eq!(files, 1);
+
+        let response = app
+            .clone()
+            .oneshot(upload(
+                &[("version", "1.1.0"), ("architecture", "x86_64")],
+                &iso,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let newer: PostImageResponse = serde_json::from_slice(&body).unwrap();
+        for id in [image.id, newer.id] {
+            let body = PatchImageRequest {
+                release_notes: None,
+                is_active: Some(true),
+            };
+            let response = app
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .uri(format!("/api/images/{id}"))
+                        .method("PATCH")
+                        .header("Authorization", auth_header.clone())
+                        .header("Content-Type", "application/json")
+                        .body(Body::from(serde_json::to_string(&body).unwrap()))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+
+        // only the image activated last stays active for the architecture
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/images")
+                    .header("Authorization", auth_header.clone())
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let images: GetImagesResponse = serde_json::from_slice(&body).unwrap();
+        let active: Vec<(&str, bool)> = images
+            .0
+            .iter()
+            .map(|image| (image.version.as_str(), image.is_active))
+            .collect();
+        assert_eq!(active, [("1.1.0", true), ("1.0.0", false)]);
+        assert_eq!(images.0[1].release_notes.as_deref(), Some("first release"));
+    }
+
+    #[tokio::test]
+    async fn images_are_downloaded_in_ranges() {
+        use sha2::{Digest, Sha256};
+
+        let state = AppState::mock();
+        let app = routes::app(state.clone());
+        let token = state
+            .auth_service
+            .generate_access_token(&User::mock(), Organization::mock().id, false)
+            .await
+            .unwrap();
+        let auth_header = format!("Bearer {}", token);
+        let iso: Vec<u8> = (0..=255).collect();
+        let mut upload = state.image_service.start_upload().await.unwrap();
+        assert!(upload.write(&iso).await.unwrap());
+        let image = state
+            .image_service
+            .finish_upload(
+                upload,
+                User::mock().id,
+                "1.0.0".to_string(),
+                crate::models::Architecture::X86_64,
+                None,
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        let etag = format!("\"{:x}\"", Sha256::digest(&iso));
+        let download = |headers: &[(&str, &str)]| {
+            let mut request = Request::builder()
+                .uri(format!("/api/images/{}/download", image.id))
+                .header("Authorization", auth_header.clone());
+            for (name, value) in headers {
+                request = request.header(*name, *value);
+            }
+            request.body(Body::empty()).unwrap()
+        };
+
+        let response = app.clone().oneshot(download(&[])).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["content-length"], "256");
+        assert_eq!(response.headers()["accept-ranges"], "bytes");
+        assert_eq!(response.headers()["etag"], etag.as_str());
+        assert_eq!(
+            response.headers()["content-disposition"],
+            "attachment; filename=\"open-erase-1.0.0-x86_64.iso\""
+        );
+        let digest = format!("sha-256=:{}:", BASE64_STANDARD.encode(Sha256::digest(&iso)));
+        assert_eq!(response.headers()["repr-digest"], digest.as_str());
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(body, iso);
+
+        let response = app
+            .clone()
+            .oneshot(download(&[("Range", "bytes=16-31")]))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(response.headers()["content-range"], "bytes 16-31/256");
+        assert_eq!(response.headers()["content-length"], "16");
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(body, iso[16..32]);
+
+        let response = app
+            .clone()
+            .oneshot(download(&[("Range", "bytes=-8")]))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(response.headers()["content-range"], "bytes 248-255/256");
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(body, iso[248..]);
+
+        let response = app
+            .clone()
+            .oneshot(download(&[
+                ("Range", "bytes=100-"),
+                ("If-Range", "\"stale\""),
+            ]))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app
+            .clone()
+            .oneshot(download(&[("Range", "bytes=256-")]))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+        assert_eq!(response.headers()["content-range"], "bytes */256");
+
+        let response = app
+            .clone()
+            .oneshot(download(&[("If-None-Match", etag.as_str())]))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+        let request = Request::builder()
+            .uri("/api/images/SHA256SUMS")
+            .header("Authorization", auth_header.clone())
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(
+            body,
+            format!("{:x}  open-erase-1.0.0-x86_64.iso\n", Sha256::digest(&iso))
+        );
+    }
+
+    #[tokio::test]
+    async fn network_boot_serves_active_image() {
+        use tokio::net::UdpSocket;
+
+        let ipxe_directory =
+            std::env::temp_dir().join(format!("open-erase-ipxe-{}", Uuid::now_v7()));
+        std::fs::create_dir_all(&ipxe_directory).unwrap();
+        let undionly: Vec<u8> = (0..1500).map(|byte| byte as u8).collect();
+        std::fs::write(ipxe_directory.join("undionly.kpxe"), &undionly).unwrap();
+        let mut config = crate::config::Config::default();
+        config.server.public_url = String::from("http://erase.test/");
+        config.boot.enabled = true;
+        config.boot.ipxe_directory = ipxe_directory;
+        config.boot.kernel_parameters = String::from("console=ttyS0");
+        let state = AppState::mock_with_config(config);
+        let app = routes::app(state.clone());
+
+        let airootfs = vec![0x5a; 5000];
+        let iso = crate::test_helpers::iso9660_image(&[
+            ("arch/boot/x86_64/vmlinuz-linux", b"kernel"),
+            ("arch/boot/x86_64/initramfs-linux.img", b"initramfs"),
+            ("arch/boot/intel-ucode.img", b"microcode"),
+            ("arch/x86_64/airootfs.sfs", &airootfs),
+            ("EFI/BOOT/BOOTx64.EFI", b"bootloader"),
+        ]);
+        let mut upload = state.image_service.start_upload().await.unwrap();
+        assert!(upload.write(&iso).await.unwrap());
+        let image = state
+            .image_service
+            .finish_upload(
+                upload,
+                User::mock().id,
+                "1.0.0".to_string(),
+                crate::models::Architecture::X86_64,
+                None,
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        let get = |uri: String| Request::builder().uri(uri).body(Body::empty()).unwrap();
+
+        let script_uri = "/api/boot/x86_64/boot.ipxe".to_string();
+        let response = app.clone().oneshot(get(script_uri.clone())).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        state
+            .image_service
+            .update(Organization::mock().id, image.id, None, Some(true))
+            .await
+            .unwrap();
+
+        let response = app.clone().oneshot(get(script_uri)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let base_url = format!("http://erase.test/api/boot/images/{}", image.id);
+        assert_eq!(
+            std::str::from_utf8(&body).unwrap(),
+            format!(
+                "#!ipxe\n\
+                 # open-erase-1.0.0-x86_64.iso\n\
+                 kernel {base_url}/arch/boot/x86_64/vmlinuz-linux initrd=intel-ucode.img \
+                 initrd=initramfs-linux.img archisobasedir=arch archiso_http_srv={base_url}/ \
+                 ip=dhcp console=ttyS0\n\
+                 initrd {base_url}/arch/boot/intel-ucode.img\n\
+                 initrd {base_url}/arch/boot/x86_64/initramfs-linux.img\n\
+                 boot\n"
+            )
+        );
+        let response = app
+            .clone()
+            .oneshot(get("/api/boot/aarch64/boot.ipxe".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let artifact_uri = |path: &str| format!("/api/boot/images/{}/{path}", image.id);
+        let response = app
+            .clone()
+            .oneshot(get(artifact_uri("arch/x86_64/airootfs.sfs")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["content-length"], "5000");
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(body, airootfs);
+        for path in [
+            "EFI/BOOT/BOOTx64.EFI",
+            "arch/x86_64/missing.sfs",
+            "arch/boot",
+        ] {
+            let response = app.clone().oneshot(get(artifact_uri(path))).await.unwrap();
+            assert_eq!(response.status(), StatusCode::NOT_FOUND, "{path}");
+        }
+
+        let (shutdown_sender, shutdown_receiver) = tokio::sync::watch::channel(());
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let server_address = socket.local_addr().unwrap();
+        let tftp = crate::tasks::tftp::spawn(socket, state.boot_service.clone(), shutdown_receiver);
+        let client = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let read_request = |fields: &[&str]| {
+            let mut packet = vec![0, 1];
+            for field in fields {
+                packet.extend_from_slice(field.as_bytes());
+                packet.push(0);
+            }
+            packet
+        };
+        let mut buffer = [0; 2048];
+        let mut receive = async || {
+            let (length, peer) = client.recv_from(&mut buffer).await.unwrap();
+            (buffer[..length].to_vec(), peer)
+        };
+
+        client
+            .send_to(&read_request(&["boot.ipxe", "octet"]), server_address)
+            .await
+            .unwrap();
+        let (packet, peer) = receive().await;
+        assert_ne!(peer, server_address);
+        assert_eq!(packet[..4], [0, 3, 0, 1]);
+        assert_eq!(
+            std::str::from_utf8(&packet[4..]).unwrap(),
+            "#!ipxe\niseq ${buildarch} arm64 && set arch aarch64 || set arch x86_64\n\
+             chain --autofree http://erase.test/api/boot/${arch}/boot.ipxe\n"
+        );
+        client.send_to(&[0, 4, 0, 1], peer).await.unwrap();
+
+        client
+            .send_to(
+                &read_request(&["undionly.kpxe", "octet", "blksize", "1024", "tsize", "0"]),
+                server_address,
+            )
+            .await
+            .unwrap();
+        let (packet, peer) = receive().await;
+        assert_eq!(packet, b"\0\x06blksize\x001024\0tsize\x001500\0");
+        let mut received = Vec::new();
+        let mut block = 0u16;
+        loop {
+            client.send_to(&[0, 4, 0, block as u8], peer).await.unwrap();
+            let (packet, _) = receive().await;
+            block += 1;
+            assert_eq!(packet[..4], [0, 3, 0, block as u8]);
+            received.extend_from_slice(&packet[4..]);
+            if packet.len() < 4 + 1024 {
+                client.send_to(&[0, 4, 0, block as u8], peer).await.unwrap();
+                break;
+            }
+        }
+        assert_eq!(block, 2);
+        assert_eq!(received, undionly);
+
+        for (request, code) in [
+            (read_request(&["../undionly.kpxe", "octet"]), 1),
+            ([&[0, 2][..], b"upload.bin\0octet\0"].concat(), 2),
+        ] {
+            client.send_to(&request, server_address).await.unwrap();
+            let (packet, _) = receive().await;
+            assert_eq!(packet[..4], [0, 5, 0, code]);
+        }
+
+        shutdown_sender.send(()).unwrap();
+        tftp.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn image_variants_carry_client_config() {
+        use open_erase_lib::schemas::image::{ClientConfig, PostImageVariantResponse};
+        use sha2::{Digest, Sha256};
+
+        let mut config = crate::config::Config::default();
+        config.server.public_url = String::from("https://erase.example.com/");
+        let state = AppState::mock_with_config(config);
+        let app = routes::app(state.clone());
+        let token = state
+            .auth_service
+            .generate_access_token(&User::mock(), Organization::mock().id, false)
+            .await
+            .unwrap();
+        let auth_header = format!("Bearer {}", token);
+        let upload = async |version: &str, files: &[(&str, &[u8])]| {
+            let mut upload = state.image_service.start_upload().await.unwrap();
+            assert!(
+                upload
+                    .write(&crate::test_helpers::iso9660_image(files))
+                    .await
+                    .unwrap()
+            );
+            state
+                .image_service
+                .finish_upload(
+                    upload,
+                    User::mock().id,
+                    version.to_string(),
+                    crate::models::Architecture::X86_64,
+                    None,
+                )
+                .await
+                .unwrap()
+                .unwrap()
+        };
+        let placeholder = vec![b' '; 2048];
+        let base_image = upload(
+            "1.0.0",
+            &[
+                ("arch/x86_64/airootfs.sfs", &[0x5a; 4096]),
+                ("open-erase/config.toml", &placeholder),
+            ],
+        )
+        .await;
+        let post_variant = |id: Uuid, body: serde_json::Value| {
+            Request::builder()
+                .uri(format!("/api/images/{id}/variants"))
+                .method("POST")
+                .header("Authorization", auth_header.clone())
+                .header("Content-Type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+        let certificate = "-----BEGIN CERTIFICATE-----\nMIIB\n-----END CERTIFICATE-----\n";
+
+        let response = app
+            .clone()
+            .oneshot(post_variant(
+                base_image.id,
+                serde_json::json!({
+                    "name": "berlin",
+                    "ca_certificate": certificate,
+                    "station_name": "berlin-1",
+                    "default_erase_method": "nist-800-88-purge",
+                    "locale": "de_DE.UTF-8",
+                }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let variant: PostImageVariantResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(variant.base_image_id, Some(base_image.id));
+        assert_eq!(variant.variant_name.as_deref(), Some("berlin"));
+        assert_eq!(variant.version, "1.0.0");
+        assert_eq!(variant.size_bytes, base_image.size_bytes as u64);
+        let station = state
+            .station_service
+            .find_by_id(Organization::mock().id, variant.station_id.unwrap())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(station.name, "berlin-1");
+
+        let path = |id: Uuid| state.config.images.directory.join(format!("{id}.iso"));
+        let base_iso = std::fs::read(path(base_image.id)).unwrap();
+        let variant_iso = std::fs::read(path(variant.id)).unwrap();
+        assert_eq!(
+            variant.sha256,
+            format!("{:x}", Sha256::digest(&variant_iso))
+        );
+        let mut file = tokio::fs::File::open(path(variant.id)).await.unwrap();
+        let extent = crate::iso9660::find(&mut file, "open-erase/config.toml")
+            .await
+            .unwrap()
+            .unwrap();
+        let range = extent.offset as usize..(extent.offset + extent.size) as usize;
+        let client_config: ClientConfig =
+            toml::from_str(std::str::from_utf8(&variant_iso[range.clone()]).unwrap()).unwrap();
+        let enrollment_code = client_config.enrollment_code.clone().unwrap();
+        assert_eq!(
+            client_config,
+            ClientConfig {
+                server_url: String::from("https://erase.example.com"),
+                ca_certificate: Some(certificate.to_string()),
+                enrollment_code: Some(enrollment_code.clone()),
+                default_erase_method: Some(String::from("nist-800-88-purge")),
+                locale: Some(String::from("de_DE.UTF-8")),
+            }
+        );
+        // the code written into the ISO enrolls the station registered for it
+        let (enrolled, _) = state
+            .station_service
+            .enroll(&enrollment_code, "fingerprint")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(enrolled.id, station.id);
+        // everything around the config is left as it was
+        assert_eq!(variant_iso[..range.start], base_iso[..range.start]);
+        assert_eq!(variant_iso[range.end..], base_iso[range.end..]);
+
+        let request = Request::builder()
+            .uri(format!("/api/images/{}/variants", base_image.id))
+            .header("Authorization", auth_header.clone())
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let variants: GetImagesResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(variants.0.len(), 1);
+        assert_eq!(variants.0[0].id, variant.id);
+
+        // variants are private to the organization that made them
+        let other_token = state
+            .auth_service
+            .generate_access_token(&User::mock(), Uuid::now_v7(), false)
+            .await
+            .unwrap();
+        for (uri, status) in [
+            (format!("/api/images/{}", variant.id), StatusCode::NOT_FOUND),
+            (
+                format!("/api/images/{}/download", variant.id),
+                StatusCode::NOT_FOUND,
+            ),
+            (
+                format!("/api/images/{}/variants", variant.id),
+                StatusCode::NOT_FOUND,
+            ),
+            (format!("/api/images/{}", base_image.id), StatusCode::OK),
+        ] {
+            let request = Request::builder()
+                .uri(uri)
+                .header("Authorization", format!("Bearer {other_token}"))
+                .body(Body::empty())
+                .unwrap();
+            let response = app.clone().oneshot(request).await.unwrap();
+            assert_eq!(response.status(), status);
+        }
+        let request = Request::builder()
+            .uri(format!("/api/images/{}/variants", base_image.id))
+            .header("Authorization", format!("Bearer {other_token}"))
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let variants: GetImagesResponse = serde_json::from_slice(&body).unwrap();
+        assert!(variants.0.is_empty());
+
+        // the enrollment code makes the ISO as good as a station of its own
+        let mut read_only_user = User::mock();
+        read_only_user.role = crate::models::Role::ReadOnly;
+        let read_only_token = state
+            .auth_service
+            .generate_access_token(&read_only_user, Organization::mock().id, false)
+            .await
+            .unwrap();
+        for (token, status) in [
+            (&read_only_token, StatusCode::FORBIDDEN),
+            (&token, StatusCode::OK),
+        ] {
+            let request = Request::builder()
+                .uri(format!("/api/images/{}/download", variant.id))
+                .header("Authorization", format!("Bearer {token}"))
+                .body(Body::empty())
+                .unwrap();
+            let response = app.clone().oneshot(request).await.unwrap();
+            assert_eq!(response.status(), status);
+        }
+
+        // only base images are activated and booted
+        let request = Request::builder()
+            .uri(format!("/api/images/{}", variant.id))
+            .method("PATCH")
+            .header("Authorization", auth_header.clone())
+            .header("Content-Type", "application/json")
+            .body(Body::from(r#"{"is_active":true}"#))
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let response = app
+            .clone()
+            .oneshot(post_variant(
+                base_image.id,
+                serde_json::json!({ "name": "berlin" }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let large_certificate = format!(
+            "-----BEGIN CERTIFICATE-----\n{}\n-----END CERTIFICATE-----\n",
+            "A".repeat(4096)
+        );
+        let response = app
+            .clone()
+            .oneshot(post_variant(
+                base_image.id,
+                serde_json::json!({ "name": "hamburg", "ca_certificate": large_certificate }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let error: ErrorResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(error.details[0].field, "ca_certificate");
+        let response = app
+            .clone()
+            .oneshot(post_variant(
+                base_image.id,
+                serde_json::json!({ "name": "munich", "server_url": "ftp://erase", "locale": "de DE" }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let error: ErrorResponse = serde_json::from_slice(&body).unwrap();
+        let fields: Vec<_> = error
+            .details
+            .iter()
+            .map(|detail| detail.field.as_str())
+            .collect();
+        assert_eq!(fields, ["server_url", "locale"]);
+
+        let bare_image = upload("0.9.0", &[("arch/x86_64/airootfs.sfs", &[0x5a; 4096])]).await;
+        let response = app
+            .clone()
+            .oneshot(post_variant(
+                bare_image.id,
+                serde_json::json!({ "name": "berlin", "station_name": "berlin-2" }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        // the station minted for the failed variant is gone again
+        let stations = state
+            .station_service
+            .find_by_organization_id(Organization::mock().id)
+            .await
+            .unwrap();
+        assert!(!stations.iter().any(|station| station.name == "berlin-2"));
+
+        let request = Request::builder()
+            .uri("/api/images/SHA256SUMS")
+            .header("Authorization", auth_header.clone())
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let checksums = std::str::from_utf8(&body).unwrap();
+        assert!(checksums.contains("open-erase-1.0.0-x86_64.iso"));
+        assert!(!checksums.contains("open-erase-1.0.0-berlin-x86_64.iso"));
+
+        // building a variant copies the whole ISO, like an upload does
+        let variant_path = format!("/api/images/{}/variants", base_image.id);
+        assert_eq!(
+            routes::request_timeout(&state.config, &axum::http::Method::POST, &variant_path),
+            state.config.images.upload_timeout()
+        );
+        assert_eq!(
+            routes::request_timeout(&state.config, &axum::http::Method::GET, &variant_path),
+            state.config.server.request_timeout()
+        );
+    }
 }
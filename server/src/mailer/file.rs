@@ -0,0 +1,46 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use lettre::{AsyncFileTransport, AsyncTransport, Tokio1Executor, message::Mailbox};
+
+use crate::{
+    error::MailResult,
+    mailer::{Email, Mailer, build_message},
+};
+
+/// Development sink that stores every email as an `.eml` file in `directory` and logs
+/// where it went instead of delivering it.
+pub struct FileMailer {
+    from: Mailbox,
+    directory: PathBuf,
+    transport: AsyncFileTransport<Tokio1Executor>,
+}
+
+impl FileMailer {
+    pub fn new(from: Mailbox, directory: PathBuf) -> Self {
+        let transport = AsyncFileTransport::new(&directory);
+        Self {
+            from,
+            directory,
+            transport,
+        }
+    }
+}
+
+#[async_trait]
+impl Mailer for FileMailer {
+    async fn send(&self, email: Email) -> MailResult<()> {
+        let to = email.to.clone();
+        let subject = email.subject.clone();
+        let message = build_message(&self.from, email)?;
+        tokio::fs::create_dir_all(&self.directory).await?;
+        let id = self.transport.send(message).await?;
+        tracing::info!(
+            to,
+            subject,
+            path = %self.directory.join(format!("{id}.eml")).display(),
+            "email written to file"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use lettre::{
+    Message,
+    message::{Mailbox, header::ContentType},
+};
+
+use crate::{
+    config::{MailConfig, MailTransport},
+    error::MailResult,
+};
+
+mod file;
+mod smtp;
+
+pub use file::FileMailer;
+pub use smtp::SmtpMailer;
+
+/// Plain text email to a single recipient.
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, email: Email) -> MailResult<()>;
+}
+
+/// `None` if sending email is disabled.
+pub fn from_config(config: &MailConfig) -> MailResult<Option<Arc<dyn Mailer>>> {
+    let from = config.from.parse()?;
+    Ok(match config.transport {
+        MailTransport::Disabled => None,
+        MailTransport::Log => Some(Arc::new(FileMailer::new(from, config.directory.clone()))),
+        MailTransport::Smtp => Some(Arc::new(SmtpMailer::new(from, config)?)),
+    })
+}
+
+fn build_message(from: &Mailbox, email: Email) -> MailResult<Message> {
+    Ok(Message::builder()
+        .from(from.clone())
+        .to(email.to.parse()?)
+        .subject(email.subject)
+        .header(ContentType::TEXT_PLAIN)
+        .body(email.body)?)
+}
@@ -1,4 +1,5 @@
 use axum::{
+    Extension,
     extract::{Request, State},
     middleware::Next,
     response::IntoResponse,
@@ -15,11 +16,17 @@ use axum_extra::{
 
 use crate::{
     error::{AppResult, ClientError},
+    middleware::rate_limit::API_KEY_HEADER,
+    models::Permission,
+    schemas::session::ClientInfo,
+    services::auth::Claims,
     state::AppState,
 };
 
 pub const REFRESH_TOKEN_COOKIE: &str = "refresh_token";
+pub const STATION_FINGERPRINT_HEADER: &str = "x-station-fingerprint";
 
+/// Lets requests through that `validate_api_key` already authenticated.
 #[axum::debug_middleware]
 pub async fn validate_access_token(
     State(state): State<AppState>,
@@ -27,16 +34,47 @@ pub async fn validate_access_token(
     mut request: Request,
     next: Next,
 ) -> AppResult<impl IntoResponse> {
+    if request.extensions().get::<Claims>().is_some() {
+        return Ok(next.run(request).await);
+    }
     let authorization_header = header_result.map_err(|_| ClientError::Unauthorized)?;
     let access_token = authorization_header.token();
     let claims = state
         .auth_service
         .get_valid_access_token_claims(access_token)
+        .await?
         .ok_or(ClientError::Unauthorized)?;
     request.extensions_mut().insert(claims);
     Ok(next.run(request).await)
 }
 
+/// Machine clients send an API key in `x-api-key` instead of an access token. Enrolled
+/// stations also send their hardware fingerprint in `x-station-fingerprint`. Must be
+/// layered outside `validate_access_token`.
+#[axum::debug_middleware]
+pub async fn validate_api_key(
+    State(state): State<AppState>,
+    mut request: Request,
+    next: Next,
+) -> AppResult<impl IntoResponse> {
+    if let Some(api_key) = request.headers().get(API_KEY_HEADER) {
+        let api_key = api_key.to_str().map_err(|_| ClientError::Unauthorized)?;
+        let hardware_fingerprint = request
+            .headers()
+            .get(STATION_FINGERPRINT_HEADER)
+            .map(|fingerprint| fingerprint.to_str())
+            .transpose()
+            .map_err(|_| ClientError::Unauthorized)?;
+        let claims = state
+            .api_key_service
+            .get_valid_api_key_claims(api_key, hardware_fingerprint)
+            .await?
+            .ok_or(ClientError::Unauthorized)?;
+        request.extensions_mut().insert(claims);
+    }
+    Ok(next.run(request).await)
+}
+
 #[axum::debug_middleware]
 pub async fn validate_refresh_token(
     State(state): State<AppState>,
@@ -56,22 +94,56 @@ pub async fn validate_refresh_token(
     Ok(next.run(request).await)
 }
 
+/// Failed attempts are counted per account and per client address. Once either is
+/// locked out every attempt is answered with 429, even one with the right password.
 #[axum::debug_middleware]
 pub async fn validate_basic_auth(
     State(state): State<AppState>,
+    client_info: ClientInfo,
     header_result: Result<TypedHeader<Authorization<Basic>>, TypedHeaderRejection>,
     mut request: Request,
     next: Next,
 ) -> AppResult<impl IntoResponse> {
     let authorization_header = header_result.map_err(|_| ClientError::Unauthorized)?;
-    let user = state
-        .auth_service
-        .get_user_from_basic_auth(
-            authorization_header.username(),
-            authorization_header.password(),
-        )
+    let email = authorization_header.username();
+    let ip_address = client_info.ip_address.as_deref();
+    if let Some(retry_after) = state
+        .login_throttle_service
+        .find_lockout(email, ip_address)
         .await?
-        .ok_or(ClientError::Unauthorized)?;
+    {
+        return Err(ClientError::TooManyRequests(retry_after).into());
+    }
+    let Some(user) = state
+        .auth_service
+        .get_user_from_basic_auth(email, authorization_header.password())
+        .await
+    else {
+        if let Some(retry_after) = state
+            .login_throttle_service
+            .record_failure(email, ip_address)
+            .await?
+        {
+            return Err(ClientError::TooManyRequests(retry_after).into());
+        }
+        return Err(ClientError::Unauthorized.into());
+    };
+    state.login_throttle_service.record_success(email).await?;
     request.extensions_mut().insert(user);
     Ok(next.run(request).await)
 }
+
+/// Rejects the request unless the claims of the caller grant `permission`, see
+/// `Claims::has_permission`. Must be layered inside `validate_access_token`.
+#[axum::debug_middleware]
+pub async fn authorize(
+    State(permission): State<Permission>,
+    Extension(claims): Extension<Claims>,
+    request: Request,
+    next: Next,
+) -> AppResult<impl IntoResponse> {
+    if !claims.has_permission(permission) {
+        return Err(ClientError::Forbidden.into());
+    }
+    Ok(next.run(request).await)
+}
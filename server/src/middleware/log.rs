@@ -2,13 +2,21 @@ use std::sync::Arc;
 
 use axum::{extract::Request, middleware::Next, response::IntoResponse};
 
-use crate::error::{AppResult, ServiceError};
+use crate::{
+    error::{AppResult, ServiceError},
+    middleware::request_id::current_request_id,
+};
 
+/// Logs the cause of internal errors, which the response only reports by request id.
 #[axum::debug_middleware]
 pub async fn log(request: Request, next: Next) -> AppResult<impl IntoResponse> {
-    let extensions = request.extensions();
-    if let Some(service_error) = extensions.get::<Arc<ServiceError>>() {
-        tracing::error!("{:#?}", service_error);
+    let response = next.run(request).await;
+    if let Some(service_error) = response.extensions().get::<Arc<ServiceError>>() {
+        tracing::error!(
+            request_id = current_request_id().as_deref(),
+            "{:#?}",
+            service_error
+        );
     }
-    Ok(next.run(request).await)
+    Ok(response)
 }
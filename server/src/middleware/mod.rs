@@ -1,2 +1,5 @@
 pub mod auth;
 pub mod log;
+pub mod rate_limit;
+pub mod request_id;
+pub mod timeout;
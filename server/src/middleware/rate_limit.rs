@@ -0,0 +1,85 @@
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    time::Duration,
+};
+
+use axum::{
+    extract::{Request, State},
+    http::{HeaderMap, HeaderValue},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use axum_extra::headers::{Authorization, Header, authorization::Bearer};
+
+use crate::{
+    error::{AppError, ClientError},
+    routes::rate_limit_group,
+    schemas::session::ClientInfo,
+    services::rate_limit::RateLimitDecision,
+    state::AppState,
+};
+
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Limits API requests per client with a token bucket for each route group. Clients are
+/// told apart by the access token or API key they present, then by address. Credentials
+/// are not checked here, so limiting costs no database access and authentication still
+/// runs once; made up credentials only open buckets the service evicts again.
+#[axum::debug_middleware]
+pub async fn rate_limit(
+    State(state): State<AppState>,
+    client_info: ClientInfo,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(group) =
+        rate_limit_group(request.uri().path()).filter(|_| state.rate_limit_service.is_enabled())
+    else {
+        return next.run(request).await;
+    };
+    let key = client_key(&client_info, request.headers());
+    let decision = state.rate_limit_service.check(group, key);
+    let mut response = if decision.is_allowed {
+        next.run(request).await
+    } else {
+        AppError::from(ClientError::RateLimited(decision.retry_after)).into_response()
+    };
+    insert_rate_limit_headers(response.headers_mut(), &decision);
+    response
+}
+
+fn client_key(client_info: &ClientInfo, headers: &HeaderMap) -> String {
+    // decoded by hand because basic credentials must fall through rather than be rejected
+    if let Ok(Authorization(bearer)) = Authorization::<Bearer>::decode(
+        &mut headers.get_all(Authorization::<Bearer>::name()).iter(),
+    ) {
+        return format!("bearer:{:x}", digest(bearer.token().as_bytes()));
+    }
+    if let Some(api_key) = headers.get(API_KEY_HEADER) {
+        return format!("api_key:{:x}", digest(api_key.as_bytes()));
+    }
+    match &client_info.ip_address {
+        Some(ip_address) => format!("ip:{ip_address}"),
+        None => String::from("ip:unknown"),
+    }
+}
+
+/// Only a digest is kept so the bucket map never holds usable secrets.
+fn digest(credential: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    credential.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn insert_rate_limit_headers(headers: &mut HeaderMap, decision: &RateLimitDecision) {
+    headers.insert("ratelimit-limit", HeaderValue::from(decision.limit));
+    headers.insert("ratelimit-remaining", HeaderValue::from(decision.remaining));
+    headers.insert(
+        "ratelimit-reset",
+        HeaderValue::from(ceil_secs(decision.reset)),
+    );
+}
+
+fn ceil_secs(duration: Duration) -> u64 {
+    duration.as_secs() + u64::from(duration.subsec_nanos() > 0)
+}
@@ -0,0 +1,17 @@
+use axum::{
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::{routes::request_timeout, state::AppState};
+
+/// Answers with 408 once a request takes longer than the timeout of its route.
+#[axum::debug_middleware]
+pub async fn timeout(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let timeout = request_timeout(&state.config, request.method(), request.uri().path());
+    tokio::time::timeout(timeout, next.run(request))
+        .await
+        .unwrap_or_else(|_| StatusCode::REQUEST_TIMEOUT.into_response())
+}
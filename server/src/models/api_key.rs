@@ -0,0 +1,67 @@
+use chrono::{DateTime, Utc};
+use open_erase_lib::schemas::api_key::ApiKeyScope as SchemaApiKeyScope;
+use sqlx::prelude::FromRow;
+use uuid::Uuid;
+
+use crate::models::Permission;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "api_key_scope", rename_all = "snake_case")]
+pub enum ApiKeyScope {
+    UploadReports,
+    DownloadImages,
+}
+
+impl ApiKeyScope {
+    pub fn grants(&self, permission: Permission) -> bool {
+        match self {
+            ApiKeyScope::UploadReports => permission == Permission::WriteReports,
+            ApiKeyScope::DownloadImages => permission == Permission::ReadImages,
+        }
+    }
+}
+
+impl From<ApiKeyScope> for SchemaApiKeyScope {
+    fn from(value: ApiKeyScope) -> Self {
+        match value {
+            ApiKeyScope::UploadReports => Self::UploadReports,
+            ApiKeyScope::DownloadImages => Self::DownloadImages,
+        }
+    }
+}
+
+impl From<SchemaApiKeyScope> for ApiKeyScope {
+    fn from(value: SchemaApiKeyScope) -> Self {
+        match value {
+            SchemaApiKeyScope::UploadReports => Self::UploadReports,
+            SchemaApiKeyScope::DownloadImages => Self::DownloadImages,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ApiKey {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub user_id: Uuid,
+    /// Set for the credential of an enrolled station.
+    pub station_id: Option<Uuid>,
+    pub name: String,
+    pub prefix: String,
+    pub key_hash: String,
+    pub scopes: Vec<ApiKeyScope>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ApiKey {
+    pub fn is_valid(&self) -> bool {
+        self.revoked_at.is_none()
+            && self
+                .expires_at
+                .is_none_or(|expires_at| expires_at > Utc::now())
+    }
+}
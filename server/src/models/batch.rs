@@ -0,0 +1,46 @@
+use chrono::{DateTime, Utc};
+use open_erase_lib::schemas::batch::BatchStatus as SchemaBatchStatus;
+use sqlx::prelude::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "batch_status", rename_all = "snake_case")]
+pub enum BatchStatus {
+    Open,
+    InProgress,
+    Completed,
+    Cancelled,
+}
+
+impl From<BatchStatus> for SchemaBatchStatus {
+    fn from(value: BatchStatus) -> Self {
+        match value {
+            BatchStatus::Open => Self::Open,
+            BatchStatus::InProgress => Self::InProgress,
+            BatchStatus::Completed => Self::Completed,
+            BatchStatus::Cancelled => Self::Cancelled,
+        }
+    }
+}
+
+impl From<SchemaBatchStatus> for BatchStatus {
+    fn from(value: SchemaBatchStatus) -> Self {
+        match value {
+            SchemaBatchStatus::Open => Self::Open,
+            SchemaBatchStatus::InProgress => Self::InProgress,
+            SchemaBatchStatus::Completed => Self::Completed,
+            SchemaBatchStatus::Cancelled => Self::Cancelled,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct Batch {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub customer_reference: String,
+    pub expected_device_count: i32,
+    pub status: BatchStatus,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
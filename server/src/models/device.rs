@@ -0,0 +1,15 @@
+use chrono::{DateTime, Utc};
+use sqlx::prelude::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct Device {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub batch_id: Option<Uuid>,
+    pub serial_number: String,
+    pub manufacturer: Option<String>,
+    pub model: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
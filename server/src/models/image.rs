@@ -1,3 +1,106 @@
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use chrono::{DateTime, Utc};
+use open_erase_lib::schemas::image::Architecture as SchemaArchitecture;
+use sqlx::prelude::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "image_architecture")]
+pub enum Architecture {
+    #[sqlx(rename = "x86_64")]
+    X86_64,
+    #[sqlx(rename = "aarch64")]
+    Aarch64,
+}
+
+impl Architecture {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Architecture::X86_64 => "x86_64",
+            Architecture::Aarch64 => "aarch64",
+        }
+    }
+}
+
+impl FromStr for Architecture {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        [Self::X86_64, Self::Aarch64]
+            .into_iter()
+            .find(|architecture| architecture.as_str() == value)
+            .ok_or(())
+    }
+}
+
+impl From<Architecture> for SchemaArchitecture {
+    fn from(value: Architecture) -> Self {
+        match value {
+            Architecture::X86_64 => Self::X86_64,
+            Architecture::Aarch64 => Self::Aarch64,
+        }
+    }
+}
+
+/// Bootable ISO that stations download, the file lives in the images directory.
+#[derive(Debug, Clone, FromRow)]
 pub struct Image {
-    pub name: String,
+    pub id: Uuid,
+    pub version: String,
+    pub architecture: Architecture,
+    pub size_bytes: i64,
+    pub sha256: String,
+    pub release_notes: Option<String>,
+    pub is_active: bool,
+    /// Set together with `organization_id` and `variant_name` on site variants of the
+    /// base image, which only the organization that made them can see.
+    pub base_image_id: Option<Uuid>,
+    pub organization_id: Option<Uuid>,
+    pub variant_name: Option<String>,
+    /// The station whose enrollment code the variant carries.
+    pub station_id: Option<Uuid>,
+    pub uploaded_by: Option<Uuid>,
+    pub uploaded_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Image {
+    /// Base images are shared, site variants belong to one organization.
+    pub fn is_visible_to(&self, organization_id: Uuid) -> bool {
+        self.organization_id
+            .is_none_or(|variant_organization_id| variant_organization_id == organization_id)
+    }
+
+    pub fn path(&self, directory: &Path) -> PathBuf {
+        directory.join(format!("{}.iso", self.id))
+    }
+
+    /// Name the image is downloaded as and listed under in `SHA256SUMS`, e.g.
+    /// `open-erase-1.2.0-x86_64.iso` or `open-erase-1.2.0-berlin-x86_64.iso` for a variant.
+    pub fn download_name(&self) -> String {
+        let mut name = format!("open-erase-{}", file_name_safe(&self.version));
+        if let Some(variant_name) = &self.variant_name {
+            name.push('-');
+            name.push_str(&file_name_safe(variant_name));
+        }
+        format!("{name}-{}.iso", self.architecture.as_str())
+    }
+}
+
+fn file_name_safe(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
 }
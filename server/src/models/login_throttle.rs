@@ -0,0 +1,29 @@
+use chrono::{DateTime, Utc};
+use sqlx::prelude::FromRow;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "login_throttle_kind", rename_all = "snake_case")]
+pub enum LoginThrottleKind {
+    Account,
+    IpAddress,
+}
+
+/// Failed login attempts against one account (keyed by email) or from one address,
+/// counted since `window_started_at`.
+#[derive(Debug, Clone, FromRow)]
+pub struct LoginThrottle {
+    pub kind: LoginThrottleKind,
+    pub subject: String,
+    pub failed_attempts: i32,
+    pub locked_until: Option<DateTime<Utc>>,
+    pub window_started_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl LoginThrottle {
+    pub fn is_locked(&self) -> bool {
+        self.locked_until
+            .is_some_and(|locked_until| locked_until > Utc::now())
+    }
+}
@@ -1,9 +1,35 @@
+mod api_key;
+mod batch;
+mod device;
 mod image;
+mod login_throttle;
+mod organization;
+mod password_reset_token;
+mod recovery_code;
 mod refresh_token;
 mod report;
+mod role;
+mod session;
+mod signing_key;
+mod station;
+mod totp_credential;
 mod user;
+mod user_identity;
 
-pub use image::Image;
+pub use api_key::{ApiKey, ApiKeyScope};
+pub use batch::{Batch, BatchStatus};
+pub use device::Device;
+pub use image::{Architecture, Image};
+pub use login_throttle::{LoginThrottle, LoginThrottleKind};
+pub use organization::{Organization, OrganizationInvitation, OrganizationMember};
+pub use password_reset_token::PasswordResetToken;
+pub use recovery_code::RecoveryCode;
 pub use refresh_token::RefreshToken;
-pub use report::Report;
+pub use report::{ErasureResult, Report};
+pub use role::{Permission, Role};
+pub use session::Session;
+pub use signing_key::{SigningAlgorithm, SigningKey};
+pub use station::Station;
+pub use totp_credential::TotpCredential;
 pub use user::User;
+pub use user_identity::UserIdentity;
@@ -7,8 +7,10 @@ pub struct RefreshToken {
     pub id: Uuid,
     pub user_id: Uuid,
     pub parent_id: Option<Uuid>,
+    pub session_id: Option<Uuid>,
     pub opaque_token_hash: String,
     pub is_used: bool,
+    pub is_revoked: bool,
     pub expires_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -20,6 +22,6 @@ impl RefreshToken {
     }
 
     pub fn is_valid(&self) -> bool {
-        !self.is_expired() && !self.is_used
+        !self.is_expired() && !self.is_used && !self.is_revoked
     }
 }
@@ -1,10 +1,42 @@
 use chrono::{DateTime, Utc};
+use open_erase_lib::schemas::report::ErasureResult as SchemaErasureResult;
 use sqlx::prelude::FromRow;
 use uuid::Uuid;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "erasure_result", rename_all = "snake_case")]
+pub enum ErasureResult {
+    Passed,
+    Failed,
+}
+
+impl From<ErasureResult> for SchemaErasureResult {
+    fn from(value: ErasureResult) -> Self {
+        match value {
+            ErasureResult::Passed => Self::Passed,
+            ErasureResult::Failed => Self::Failed,
+        }
+    }
+}
+
+impl From<SchemaErasureResult> for ErasureResult {
+    fn from(value: SchemaErasureResult) -> Self {
+        match value {
+            SchemaErasureResult::Passed => Self::Passed,
+            SchemaErasureResult::Failed => Self::Failed,
+        }
+    }
+}
+
 #[derive(Debug, Clone, FromRow)]
 pub struct Report {
-    id: Uuid,
-    created_at: DateTime<Utc>,
-    updated_at: DateTime<Utc>,
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub device_id: Uuid,
+    pub method: String,
+    pub result: ErasureResult,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
 }
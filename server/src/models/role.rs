@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+use open_erase_lib::schemas::user::Role as SchemaRole;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "user_role", rename_all = "snake_case")]
+pub enum Role {
+    Admin,
+    Operator,
+    Auditor,
+    ReadOnly,
+}
+
+/// Every route behind an access token requires one, so API keys are denied wherever
+/// their scopes don't grant it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    /// Managing the own password, second factors and sessions.
+    ManageAccount,
+    ReadUsers,
+    WriteUsers,
+    DeleteUsers,
+    ReadBatches,
+    WriteBatches,
+    DeleteBatches,
+    ReadReports,
+    WriteReports,
+    ReadImages,
+    WriteImages,
+    ReadOrganizations,
+    ManageOrganizations,
+    ManageSigningKeys,
+    /// Uploading and changing the base images that every organization sees and boots.
+    ManageBaseImages,
+    ManageApiKeys,
+    ManageStations,
+}
+
+impl Permission {
+    /// Destructive permissions are only granted to tokens whose login passed a second
+    /// factor.
+    pub fn requires_mfa(&self) -> bool {
+        matches!(self, Permission::DeleteUsers | Permission::DeleteBatches)
+    }
+
+    /// Permissions over resources that all organizations share, which roles alone don't
+    /// grant since they apply within every organization of a user.
+    pub fn is_server_wide(&self) -> bool {
+        matches!(
+            self,
+            Permission::ManageSigningKeys | Permission::ManageBaseImages
+        )
+    }
+}
+
+impl Role {
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        use Permission::*;
+        if matches!(permission, ManageAccount | ReadOrganizations) {
+            return true;
+        }
+        match self {
+            Role::Admin => true,
+            Role::Operator => matches!(
+                permission,
+                ReadBatches | WriteBatches | ReadReports | WriteReports | ReadImages
+            ),
+            Role::Auditor => matches!(
+                permission,
+                ReadUsers | ReadBatches | ReadReports | ReadImages
+            ),
+            Role::ReadOnly => matches!(permission, ReadBatches | ReadReports | ReadImages),
+        }
+    }
+
+    /// Most privileged role any of `groups` is mapped to by an identity provider.
+    pub fn highest_mapped<'a>(
+        role_mappings: &HashMap<String, Role>,
+        groups: impl IntoIterator<Item = &'a str>,
+    ) -> Option<Role> {
+        groups
+            .into_iter()
+            .filter_map(|group| role_mappings.get(group).copied())
+            .max_by_key(Role::privilege)
+    }
+
+    fn privilege(&self) -> u8 {
+        match self {
+            Role::Admin => 3,
+            Role::Operator => 2,
+            Role::Auditor => 1,
+            Role::ReadOnly => 0,
+        }
+    }
+}
+
+impl From<Role> for SchemaRole {
+    fn from(value: Role) -> Self {
+        match value {
+            Role::Admin => Self::Admin,
+            Role::Operator => Self::Operator,
+            Role::Auditor => Self::Auditor,
+            Role::ReadOnly => Self::ReadOnly,
+        }
+    }
+}
+
+impl From<SchemaRole> for Role {
+    fn from(value: SchemaRole) -> Self {
+        match value {
+            SchemaRole::Admin => Self::Admin,
+            SchemaRole::Operator => Self::Operator,
+            SchemaRole::Auditor => Self::Auditor,
+            SchemaRole::ReadOnly => Self::ReadOnly,
+        }
+    }
+}
@@ -0,0 +1,20 @@
+use chrono::{DateTime, Utc};
+use sqlx::prelude::FromRow;
+use uuid::Uuid;
+
+/// A login on one client. Every refresh token cycled from that login shares its session.
+#[derive(Debug, Clone, FromRow)]
+pub struct Session {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    /// Whether the login that opened the session passed a second factor.
+    pub is_mfa_verified: bool,
+    /// The organization the session acts for, switched by its user. `None` stands for
+    /// the organization the user joined first.
+    pub organization_id: Option<Uuid>,
+    pub last_used_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
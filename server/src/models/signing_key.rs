@@ -0,0 +1,46 @@
+use chrono::{DateTime, Utc};
+use open_erase_lib::schemas::signing_key::SigningAlgorithm as SchemaSigningAlgorithm;
+use sqlx::prelude::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "signing_algorithm", rename_all = "lowercase")]
+pub enum SigningAlgorithm {
+    Hs256,
+    EdDsa,
+}
+
+impl From<SigningAlgorithm> for SchemaSigningAlgorithm {
+    fn from(value: SigningAlgorithm) -> Self {
+        match value {
+            SigningAlgorithm::Hs256 => Self::Hs256,
+            SigningAlgorithm::EdDsa => Self::EdDsa,
+        }
+    }
+}
+
+impl From<SchemaSigningAlgorithm> for SigningAlgorithm {
+    fn from(value: SchemaSigningAlgorithm) -> Self {
+        match value {
+            SchemaSigningAlgorithm::Hs256 => Self::Hs256,
+            SchemaSigningAlgorithm::EdDsa => Self::EdDsa,
+        }
+    }
+}
+
+/// Key material used to sign and verify access tokens. Its `id` is published as the
+/// `kid` header of every token it signs. `private_key` holds the HMAC secret for
+/// HS256 and a PKCS#8 document for EdDSA, `public_key` the raw Ed25519 public key.
+/// While `private_key_encrypted` is set, `private_key` is sealed with the configured
+/// key encryption key, as the nonce followed by the AES-256-GCM ciphertext.
+#[derive(Debug, Clone, FromRow)]
+pub struct SigningKey {
+    pub id: Uuid,
+    pub algorithm: SigningAlgorithm,
+    pub private_key: Vec<u8>,
+    pub private_key_encrypted: bool,
+    pub public_key: Option<Vec<u8>>,
+    pub retires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
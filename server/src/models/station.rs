@@ -0,0 +1,32 @@
+use chrono::{DateTime, Utc};
+use sqlx::prelude::FromRow;
+use uuid::Uuid;
+
+/// Bench client that uploads reports with its own credential once enrolled.
+#[derive(Debug, Clone, FromRow)]
+pub struct Station {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub hardware_fingerprint: Option<String>,
+    pub enrollment_code_hash: Option<String>,
+    pub enrollment_code_expires_at: Option<DateTime<Utc>>,
+    pub enrolled_at: Option<DateTime<Utc>>,
+    pub disabled_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Station {
+    pub fn is_disabled(&self) -> bool {
+        self.disabled_at.is_some()
+    }
+
+    pub fn is_enrollable(&self) -> bool {
+        !self.is_disabled()
+            && self
+                .enrollment_code_expires_at
+                .is_some_and(|expires_at| expires_at > Utc::now())
+    }
+}
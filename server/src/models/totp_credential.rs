@@ -0,0 +1,21 @@
+use chrono::{DateTime, Utc};
+use sqlx::prelude::FromRow;
+use uuid::Uuid;
+
+/// TOTP secret of a user. It only counts as a second factor once `confirmed_at` is set.
+#[derive(Debug, Clone, FromRow)]
+pub struct TotpCredential {
+    pub user_id: Uuid,
+    pub secret: Vec<u8>,
+    pub confirmed_at: Option<DateTime<Utc>>,
+    /// Time step of the last accepted code, codes from it or earlier steps are replays.
+    pub last_used_step: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TotpCredential {
+    pub fn is_confirmed(&self) -> bool {
+        self.confirmed_at.is_some()
+    }
+}
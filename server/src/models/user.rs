@@ -2,11 +2,17 @@ use chrono::{DateTime, Utc};
 use sqlx::prelude::FromRow;
 use uuid::Uuid;
 
+use crate::models::Role;
+
 #[derive(Debug, Clone, FromRow)]
 pub struct User {
     pub id: Uuid,
     pub email: String,
     pub password_hash: String,
+    pub role: Role,
+    /// Allowed to manage what all organizations share, such as signing keys and which
+    /// images boot. Only set in the database.
+    pub is_server_admin: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -0,0 +1,142 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    error::RepositoryResult,
+    models::{ApiKey, ApiKeyScope},
+};
+
+#[async_trait]
+pub trait ApiKeyRepository: Send + Sync {
+    async fn find_by_prefix(&self, prefix: &str) -> RepositoryResult<Option<ApiKey>>;
+    async fn find_by_organization_id(&self, organization_id: Uuid)
+    -> RepositoryResult<Vec<ApiKey>>;
+    #[allow(clippy::too_many_arguments)]
+    async fn create(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+        station_id: Option<Uuid>,
+        name: String,
+        prefix: String,
+        key_hash: String,
+        scopes: Vec<ApiKeyScope>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> RepositoryResult<ApiKey>;
+    /// Returns `None` if the key doesn't belong to the organization or was already revoked.
+    async fn revoke(&self, organization_id: Uuid, id: Uuid) -> RepositoryResult<Option<ApiKey>>;
+    async fn revoke_all_by_station_id(&self, station_id: Uuid) -> RepositoryResult<()>;
+    async fn touch(&self, id: Uuid) -> RepositoryResult<()>;
+}
+
+#[derive(Clone)]
+pub struct PostgresApiKeyRepository {
+    pool: PgPool,
+}
+
+impl PostgresApiKeyRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ApiKeyRepository for PostgresApiKeyRepository {
+    async fn find_by_prefix(&self, prefix: &str) -> RepositoryResult<Option<ApiKey>> {
+        let query = "
+            SELECT * FROM api_keys
+            WHERE prefix = $1;
+        ";
+        let api_key = sqlx::query_as::<_, ApiKey>(query)
+            .bind(prefix)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(api_key)
+    }
+
+    async fn find_by_organization_id(
+        &self,
+        organization_id: Uuid,
+    ) -> RepositoryResult<Vec<ApiKey>> {
+        let query = "
+            SELECT * FROM api_keys
+            WHERE organization_id = $1
+            ORDER BY created_at DESC;
+        ";
+        let api_keys = sqlx::query_as::<_, ApiKey>(query)
+            .bind(organization_id)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(api_keys)
+    }
+
+    async fn create(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+        station_id: Option<Uuid>,
+        name: String,
+        prefix: String,
+        key_hash: String,
+        scopes: Vec<ApiKeyScope>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> RepositoryResult<ApiKey> {
+        let query = "
+            INSERT INTO api_keys (organization_id, user_id, station_id, name, prefix, key_hash, scopes, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING *;
+        ";
+        let api_key = sqlx::query_as::<_, ApiKey>(query)
+            .bind(organization_id)
+            .bind(user_id)
+            .bind(station_id)
+            .bind(&name)
+            .bind(&prefix)
+            .bind(&key_hash)
+            .bind(&scopes)
+            .bind(expires_at)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(api_key)
+    }
+
+    async fn revoke(&self, organization_id: Uuid, id: Uuid) -> RepositoryResult<Option<ApiKey>> {
+        let query = "
+            UPDATE api_keys
+            SET revoked_at = NOW()
+            WHERE id = $1 AND organization_id = $2 AND revoked_at IS NULL
+            RETURNING *;
+        ";
+        let api_key = sqlx::query_as::<_, ApiKey>(query)
+            .bind(id)
+            .bind(organization_id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(api_key)
+    }
+
+    async fn revoke_all_by_station_id(&self, station_id: Uuid) -> RepositoryResult<()> {
+        let query = "
+            UPDATE api_keys
+            SET revoked_at = NOW()
+            WHERE station_id = $1 AND revoked_at IS NULL;
+        ";
+        sqlx::query(query)
+            .bind(station_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn touch(&self, id: Uuid) -> RepositoryResult<()> {
+        let query = "
+            UPDATE api_keys
+            SET last_used_at = NOW()
+            WHERE id = $1;
+        ";
+        sqlx::query(query).bind(id).execute(&self.pool).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,130 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    error::RepositoryResult,
+    models::{Batch, BatchStatus},
+};
+
+#[async_trait]
+pub trait BatchRepository: Send + Sync {
+    async fn find_all(&self, organization_id: Uuid) -> RepositoryResult<Vec<Batch>>;
+    async fn find_by_id(&self, organization_id: Uuid, id: Uuid) -> RepositoryResult<Option<Batch>>;
+    async fn create(
+        &self,
+        organization_id: Uuid,
+        customer_reference: String,
+        expected_device_count: i32,
+    ) -> RepositoryResult<Batch>;
+    async fn update(
+        &self,
+        organization_id: Uuid,
+        id: Uuid,
+        customer_reference: Option<String>,
+        expected_device_count: Option<i32>,
+        status: Option<BatchStatus>,
+    ) -> RepositoryResult<Batch>;
+    async fn delete(&self, organization_id: Uuid, id: Uuid) -> RepositoryResult<Batch>;
+}
+
+#[derive(Clone)]
+pub struct PostgresBatchRepository {
+    pool: PgPool,
+}
+
+impl PostgresBatchRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl BatchRepository for PostgresBatchRepository {
+    async fn find_all(&self, organization_id: Uuid) -> RepositoryResult<Vec<Batch>> {
+        let query = "
+            SELECT * FROM batches
+            WHERE organization_id = $1
+            ORDER BY created_at DESC;
+        ";
+        let batches = sqlx::query_as::<_, Batch>(query)
+            .bind(organization_id)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(batches)
+    }
+
+    async fn find_by_id(&self, organization_id: Uuid, id: Uuid) -> RepositoryResult<Option<Batch>> {
+        let query = "
+            SELECT * FROM batches
+            WHERE organization_id = $1 AND id = $2;
+        ";
+        let batch = sqlx::query_as::<_, Batch>(query)
+            .bind(organization_id)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(batch)
+    }
+
+    async fn create(
+        &self,
+        organization_id: Uuid,
+        customer_reference: String,
+        expected_device_count: i32,
+    ) -> RepositoryResult<Batch> {
+        let query = "
+            INSERT INTO batches (organization_id, customer_reference, expected_device_count)
+            VALUES ($1, $2, $3)
+            RETURNING *;
+        ";
+        let batch = sqlx::query_as::<_, Batch>(query)
+            .bind(organization_id)
+            .bind(&customer_reference)
+            .bind(expected_device_count)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(batch)
+    }
+
+    async fn update(
+        &self,
+        organization_id: Uuid,
+        id: Uuid,
+        customer_reference: Option<String>,
+        expected_device_count: Option<i32>,
+        status: Option<BatchStatus>,
+    ) -> RepositoryResult<Batch> {
+        let query = "
+            UPDATE batches
+            SET customer_reference = COALESCE($3, customer_reference),
+                expected_device_count = COALESCE($4, expected_device_count),
+                status = COALESCE($5, status)
+            WHERE organization_id = $1 AND id = $2
+            RETURNING *;
+        ";
+        let batch = sqlx::query_as::<_, Batch>(query)
+            .bind(organization_id)
+            .bind(id)
+            .bind(&customer_reference)
+            .bind(expected_device_count)
+            .bind(status)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(batch)
+    }
+
+    async fn delete(&self, organization_id: Uuid, id: Uuid) -> RepositoryResult<Batch> {
+        let query = "
+            DELETE FROM batches
+            WHERE organization_id = $1 AND id = $2
+            RETURNING *;
+        ";
+        let batch = sqlx::query_as::<_, Batch>(query)
+            .bind(organization_id)
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(batch)
+    }
+}
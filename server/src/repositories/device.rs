@@ -0,0 +1,97 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{error::RepositoryResult, models::Device};
+
+#[async_trait]
+pub trait DeviceRepository: Send + Sync {
+    async fn find_by_id(&self, organization_id: Uuid, id: Uuid)
+    -> RepositoryResult<Option<Device>>;
+    async fn find_by_batch_id(
+        &self,
+        organization_id: Uuid,
+        batch_id: Uuid,
+    ) -> RepositoryResult<Vec<Device>>;
+    async fn create(
+        &self,
+        organization_id: Uuid,
+        batch_id: Option<Uuid>,
+        serial_number: String,
+        manufacturer: Option<String>,
+        model: Option<String>,
+    ) -> RepositoryResult<Device>;
+}
+
+#[derive(Clone)]
+pub struct PostgresDeviceRepository {
+    pool: PgPool,
+}
+
+impl PostgresDeviceRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl DeviceRepository for PostgresDeviceRepository {
+    async fn find_by_id(
+        &self,
+        organization_id: Uuid,
+        id: Uuid,
+    ) -> RepositoryResult<Option<Device>> {
+        let query = "
+            SELECT * FROM devices
+            WHERE organization_id = $1 AND id = $2;
+        ";
+        let device = sqlx::query_as::<_, Device>(query)
+            .bind(organization_id)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(device)
+    }
+
+    async fn find_by_batch_id(
+        &self,
+        organization_id: Uuid,
+        batch_id: Uuid,
+    ) -> RepositoryResult<Vec<Device>> {
+        let query = "
+            SELECT * FROM devices
+            WHERE organization_id = $1 AND batch_id = $2
+            ORDER BY created_at;
+        ";
+        let devices = sqlx::query_as::<_, Device>(query)
+            .bind(organization_id)
+            .bind(batch_id)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(devices)
+    }
+
+    async fn create(
+        &self,
+        organization_id: Uuid,
+        batch_id: Option<Uuid>,
+        serial_number: String,
+        manufacturer: Option<String>,
+        model: Option<String>,
+    ) -> RepositoryResult<Device> {
+        let query = "
+            INSERT INTO devices (organization_id, batch_id, serial_number, manufacturer, model)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *;
+        ";
+        let device = sqlx::query_as::<_, Device>(query)
+            .bind(organization_id)
+            .bind(batch_id)
+            .bind(&serial_number)
+            .bind(&manufacturer)
+            .bind(&model)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(device)
+    }
+}
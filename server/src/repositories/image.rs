@@ -1,26 +1,235 @@
 use async_trait::async_trait;
-use std::fs;
+use sqlx::PgPool;
+use uuid::Uuid;
 
-use crate::{error::RepositoryResult, models::Image};
+use crate::{
+    error::RepositoryResult,
+    models::{Architecture, Image},
+};
 
 #[async_trait]
 pub trait ImageRepository: Send + Sync {
-    async fn get_all(&self) -> RepositoryResult<Vec<Image>>;
+    /// Base images, newest first.
+    async fn find_all(&self) -> RepositoryResult<Vec<Image>>;
+    async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<Image>>;
+    async fn find_by_version(
+        &self,
+        version: &str,
+        architecture: Architecture,
+    ) -> RepositoryResult<Option<Image>>;
+    async fn find_active(&self, architecture: Architecture) -> RepositoryResult<Option<Image>>;
+    /// Variants the organization made of the base image, newest first.
+    async fn find_variants(
+        &self,
+        base_image_id: Uuid,
+        organization_id: Uuid,
+    ) -> RepositoryResult<Vec<Image>>;
+    async fn find_variant_by_name(
+        &self,
+        base_image_id: Uuid,
+        organization_id: Uuid,
+        variant_name: &str,
+    ) -> RepositoryResult<Option<Image>>;
+    #[allow(clippy::too_many_arguments)]
+    async fn create(
+        &self,
+        id: Uuid,
+        version: String,
+        architecture: Architecture,
+        size_bytes: i64,
+        sha256: String,
+        release_notes: Option<String>,
+        uploaded_by: Uuid,
+        base_image_id: Option<Uuid>,
+        organization_id: Option<Uuid>,
+        variant_name: Option<String>,
+        station_id: Option<Uuid>,
+    ) -> RepositoryResult<Image>;
+    /// Activating an image deactivates the active one of the same architecture.
+    async fn update(
+        &self,
+        id: Uuid,
+        release_notes: Option<String>,
+        is_active: Option<bool>,
+    ) -> RepositoryResult<Image>;
 }
 
-pub struct FsImageRepository;
+#[derive(Clone)]
+pub struct PostgresImageRepository {
+    pool: PgPool,
+}
+
+impl PostgresImageRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
 
 #[async_trait]
-impl ImageRepository for FsImageRepository {
-    async fn get_all(&self) -> RepositoryResult<Vec<Image>> {
-        let mut images = Vec::new();
-        for entry in fs::read_dir("/dist/iso")? {
-            let entry = entry?;
-            let path = entry.path();
-            images.push(Image {
-                name: String::from(path.to_str().unwrap()),
-            });
-        }
+impl ImageRepository for PostgresImageRepository {
+    async fn find_all(&self) -> RepositoryResult<Vec<Image>> {
+        let query = "
+            SELECT * FROM images
+            WHERE base_image_id IS NULL
+            ORDER BY uploaded_at DESC;
+        ";
+        let images = sqlx::query_as::<_, Image>(query)
+            .fetch_all(&self.pool)
+            .await?;
         Ok(images)
     }
+
+    async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<Image>> {
+        let query = "
+            SELECT * FROM images
+            WHERE id = $1;
+        ";
+        let image = sqlx::query_as::<_, Image>(query)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(image)
+    }
+
+    async fn find_by_version(
+        &self,
+        version: &str,
+        architecture: Architecture,
+    ) -> RepositoryResult<Option<Image>> {
+        let query = "
+            SELECT * FROM images
+            WHERE version = $1 AND architecture = $2 AND base_image_id IS NULL;
+        ";
+        let image = sqlx::query_as::<_, Image>(query)
+            .bind(version)
+            .bind(architecture)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(image)
+    }
+
+    async fn find_active(&self, architecture: Architecture) -> RepositoryResult<Option<Image>> {
+        let query = "
+            SELECT * FROM images
+            WHERE architecture = $1 AND is_active;
+        ";
+        let image = sqlx::query_as::<_, Image>(query)
+            .bind(architecture)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(image)
+    }
+
+    async fn find_variants(
+        &self,
+        base_image_id: Uuid,
+        organization_id: Uuid,
+    ) -> RepositoryResult<Vec<Image>> {
+        let query = "
+            SELECT * FROM images
+            WHERE base_image_id = $1 AND organization_id = $2
+            ORDER BY uploaded_at DESC;
+        ";
+        let images = sqlx::query_as::<_, Image>(query)
+            .bind(base_image_id)
+            .bind(organization_id)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(images)
+    }
+
+    async fn find_variant_by_name(
+        &self,
+        base_image_id: Uuid,
+        organization_id: Uuid,
+        variant_name: &str,
+    ) -> RepositoryResult<Option<Image>> {
+        let query = "
+            SELECT * FROM images
+            WHERE base_image_id = $1 AND organization_id = $2 AND variant_name = $3;
+        ";
+        let image = sqlx::query_as::<_, Image>(query)
+            .bind(base_image_id)
+            .bind(organization_id)
+            .bind(variant_name)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(image)
+    }
+
+    async fn create(
+        &self,
+        id: Uuid,
+        version: String,
+        architecture: Architecture,
+        size_bytes: i64,
+        sha256: String,
+        release_notes: Option<String>,
+        uploaded_by: Uuid,
+        base_image_id: Option<Uuid>,
+        organization_id: Option<Uuid>,
+        variant_name: Option<String>,
+        station_id: Option<Uuid>,
+    ) -> RepositoryResult<Image> {
+        let query = "
+            INSERT INTO images (
+                id, version, architecture, size_bytes, sha256, release_notes, uploaded_by,
+                base_image_id, organization_id, variant_name, station_id
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            RETURNING *;
+        ";
+        let image = sqlx::query_as::<_, Image>(query)
+            .bind(id)
+            .bind(&version)
+            .bind(architecture)
+            .bind(size_bytes)
+            .bind(&sha256)
+            .bind(&release_notes)
+            .bind(uploaded_by)
+            .bind(base_image_id)
+            .bind(organization_id)
+            .bind(&variant_name)
+            .bind(station_id)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(image)
+    }
+
+    async fn update(
+        &self,
+        id: Uuid,
+        release_notes: Option<String>,
+        is_active: Option<bool>,
+    ) -> RepositoryResult<Image> {
+        let mut transaction = self.pool.begin().await?;
+        if is_active == Some(true) {
+            let deactivate_query = "
+                UPDATE images
+                SET is_active = FALSE
+                WHERE is_active AND id <> $1
+                    AND architecture = (SELECT architecture FROM images WHERE id = $1);
+            ";
+            sqlx::query(deactivate_query)
+                .bind(id)
+                .execute(&mut *transaction)
+                .await?;
+        }
+        let update_query = "
+            UPDATE images
+            SET
+                release_notes = COALESCE($2, release_notes),
+                is_active = COALESCE($3, is_active)
+            WHERE id = $1
+            RETURNING *;
+        ";
+        let image = sqlx::query_as::<_, Image>(update_query)
+            .bind(id)
+            .bind(release_notes)
+            .bind(is_active)
+            .fetch_one(&mut *transaction)
+            .await?;
+        transaction.commit().await?;
+        Ok(image)
+    }
 }
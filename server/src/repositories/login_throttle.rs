@@ -0,0 +1,152 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use crate::{
+    error::RepositoryResult,
+    models::{LoginThrottle, LoginThrottleKind},
+};
+
+#[async_trait]
+pub trait LoginThrottleRepository: Send + Sync {
+    async fn find(
+        &self,
+        kind: LoginThrottleKind,
+        subject: &str,
+    ) -> RepositoryResult<Option<LoginThrottle>>;
+    /// Counts one more failed attempt, creating the throttle on the first failure. A
+    /// window that started before `window_started_before` is replaced by a new one.
+    async fn increment(
+        &self,
+        kind: LoginThrottleKind,
+        subject: &str,
+        window_started_before: DateTime<Utc>,
+    ) -> RepositoryResult<LoginThrottle>;
+    async fn lock(
+        &self,
+        kind: LoginThrottleKind,
+        subject: &str,
+        locked_until: DateTime<Utc>,
+    ) -> RepositoryResult<LoginThrottle>;
+    async fn delete(
+        &self,
+        kind: LoginThrottleKind,
+        subject: &str,
+    ) -> RepositoryResult<Option<LoginThrottle>>;
+    /// Deletes every unlocked throttle whose window started before `window_started_before`
+    /// and returns how many.
+    async fn delete_stale(&self, window_started_before: DateTime<Utc>) -> RepositoryResult<u64>;
+}
+
+#[derive(Clone)]
+pub struct PostgresLoginThrottleRepository {
+    pool: PgPool,
+}
+
+impl PostgresLoginThrottleRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl LoginThrottleRepository for PostgresLoginThrottleRepository {
+    async fn find(
+        &self,
+        kind: LoginThrottleKind,
+        subject: &str,
+    ) -> RepositoryResult<Option<LoginThrottle>> {
+        let query = "
+            SELECT * FROM login_throttles
+            WHERE kind = $1 AND subject = $2;
+        ";
+        let login_throttle = sqlx::query_as::<_, LoginThrottle>(query)
+            .bind(kind)
+            .bind(subject)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(login_throttle)
+    }
+
+    async fn increment(
+        &self,
+        kind: LoginThrottleKind,
+        subject: &str,
+        window_started_before: DateTime<Utc>,
+    ) -> RepositoryResult<LoginThrottle> {
+        let query = "
+            INSERT INTO login_throttles (kind, subject, failed_attempts)
+            VALUES ($1, $2, 1)
+            ON CONFLICT (kind, subject)
+            DO UPDATE SET
+                failed_attempts = CASE
+                    WHEN login_throttles.window_started_at < $3 THEN 1
+                    ELSE login_throttles.failed_attempts + 1
+                END,
+                window_started_at = CASE
+                    WHEN login_throttles.window_started_at < $3 THEN NOW()
+                    ELSE login_throttles.window_started_at
+                END
+            RETURNING *;
+        ";
+        let login_throttle = sqlx::query_as::<_, LoginThrottle>(query)
+            .bind(kind)
+            .bind(subject)
+            .bind(window_started_before)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(login_throttle)
+    }
+
+    async fn lock(
+        &self,
+        kind: LoginThrottleKind,
+        subject: &str,
+        locked_until: DateTime<Utc>,
+    ) -> RepositoryResult<LoginThrottle> {
+        let query = "
+            UPDATE login_throttles
+            SET locked_until = $3
+            WHERE kind = $1 AND subject = $2
+            RETURNING *;
+        ";
+        let login_throttle = sqlx::query_as::<_, LoginThrottle>(query)
+            .bind(kind)
+            .bind(subject)
+            .bind(locked_until)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(login_throttle)
+    }
+
+    async fn delete(
+        &self,
+        kind: LoginThrottleKind,
+        subject: &str,
+    ) -> RepositoryResult<Option<LoginThrottle>> {
+        let query = "
+            DELETE FROM login_throttles
+            WHERE kind = $1 AND subject = $2
+            RETURNING *;
+        ";
+        let login_throttle = sqlx::query_as::<_, LoginThrottle>(query)
+            .bind(kind)
+            .bind(subject)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(login_throttle)
+    }
+
+    async fn delete_stale(&self, window_started_before: DateTime<Utc>) -> RepositoryResult<u64> {
+        let query = "
+            DELETE FROM login_throttles
+            WHERE window_started_at < $1
+                AND (locked_until IS NULL OR locked_until < NOW());
+        ";
+        let result = sqlx::query(query)
+            .bind(window_started_before)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
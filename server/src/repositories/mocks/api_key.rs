@@ -0,0 +1,130 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{
+    error::RepositoryResult,
+    models::{ApiKey, ApiKeyScope},
+    repositories::api_key::ApiKeyRepository,
+};
+
+#[derive(Clone)]
+pub struct MockApiKeyRepository {
+    data: Arc<Mutex<Vec<ApiKey>>>,
+}
+
+impl MockApiKeyRepository {
+    pub fn new() -> Self {
+        Self {
+            data: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+}
+
+impl Default for MockApiKeyRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ApiKeyRepository for MockApiKeyRepository {
+    async fn find_by_prefix(&self, prefix: &str) -> RepositoryResult<Option<ApiKey>> {
+        Ok(self
+            .data
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|api_key| api_key.prefix == prefix)
+            .cloned())
+    }
+
+    async fn find_by_organization_id(
+        &self,
+        organization_id: Uuid,
+    ) -> RepositoryResult<Vec<ApiKey>> {
+        Ok(self
+            .data
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .filter(|api_key| api_key.organization_id == organization_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn create(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+        station_id: Option<Uuid>,
+        name: String,
+        prefix: String,
+        key_hash: String,
+        scopes: Vec<ApiKeyScope>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> RepositoryResult<ApiKey> {
+        let now = Utc::now();
+        let api_key = ApiKey {
+            id: Uuid::now_v7(),
+            organization_id,
+            user_id,
+            station_id,
+            name,
+            prefix,
+            key_hash,
+            scopes,
+            expires_at,
+            last_used_at: None,
+            revoked_at: None,
+            created_at: now,
+            updated_at: now,
+        };
+        self.data.lock().unwrap().push(api_key.clone());
+        Ok(api_key)
+    }
+
+    async fn revoke(&self, organization_id: Uuid, id: Uuid) -> RepositoryResult<Option<ApiKey>> {
+        let mut data = self.data.lock().unwrap();
+        Ok(data
+            .iter_mut()
+            .find(|api_key| {
+                api_key.id == id
+                    && api_key.organization_id == organization_id
+                    && api_key.revoked_at.is_none()
+            })
+            .map(|api_key| {
+                api_key.revoked_at = Some(Utc::now());
+                api_key.clone()
+            }))
+    }
+
+    async fn revoke_all_by_station_id(&self, station_id: Uuid) -> RepositoryResult<()> {
+        let now = Utc::now();
+        self.data
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .filter(|api_key| {
+                api_key.station_id == Some(station_id) && api_key.revoked_at.is_none()
+            })
+            .for_each(|api_key| api_key.revoked_at = Some(now));
+        Ok(())
+    }
+
+    async fn touch(&self, id: Uuid) -> RepositoryResult<()> {
+        if let Some(api_key) = self
+            .data
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .find(|api_key| api_key.id == id)
+        {
+            api_key.last_used_at = Some(Utc::now());
+        }
+        Ok(())
+    }
+}
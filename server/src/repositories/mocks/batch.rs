@@ -0,0 +1,121 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::DateTime;
+use uuid::Uuid;
+
+use crate::{
+    error::{RepositoryError, RepositoryResult},
+    models::{Batch, BatchStatus, Organization},
+    repositories::batch::BatchRepository,
+};
+
+impl Batch {
+    pub fn mock() -> Self {
+        Self {
+            id: Uuid::default(),
+            organization_id: Organization::mock().id,
+            customer_reference: String::from("PALLET-0001"),
+            expected_device_count: 2,
+            status: BatchStatus::Open,
+            created_at: DateTime::default(),
+            updated_at: DateTime::default(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct MockBatchRepository {
+    data: Arc<Mutex<Vec<Batch>>>,
+}
+
+impl MockBatchRepository {
+    pub fn new() -> Self {
+        Self {
+            data: Arc::new(Mutex::new(vec![Batch::mock()])),
+        }
+    }
+}
+
+impl Default for MockBatchRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl BatchRepository for MockBatchRepository {
+    async fn find_all(&self, organization_id: Uuid) -> RepositoryResult<Vec<Batch>> {
+        Ok(self
+            .data
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|batch| batch.organization_id == organization_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn find_by_id(&self, organization_id: Uuid, id: Uuid) -> RepositoryResult<Option<Batch>> {
+        Ok(self
+            .data
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|batch| batch.organization_id == organization_id && batch.id == id)
+            .cloned())
+    }
+
+    async fn create(
+        &self,
+        organization_id: Uuid,
+        customer_reference: String,
+        expected_device_count: i32,
+    ) -> RepositoryResult<Batch> {
+        let mut batch = Batch::mock();
+        batch.id = Uuid::now_v7();
+        batch.organization_id = organization_id;
+        batch.customer_reference = customer_reference;
+        batch.expected_device_count = expected_device_count;
+        let mut data = self.data.lock().unwrap();
+        data.push(batch.clone());
+        Ok(batch)
+    }
+
+    async fn update(
+        &self,
+        organization_id: Uuid,
+        id: Uuid,
+        customer_reference: Option<String>,
+        expected_device_count: Option<i32>,
+        status: Option<BatchStatus>,
+    ) -> RepositoryResult<Batch> {
+        let mut data = self.data.lock().unwrap();
+        let batch = data
+            .iter_mut()
+            .find(|batch| batch.organization_id == organization_id && batch.id == id)
+            .ok_or(RepositoryError::Test)?;
+        if let Some(customer_reference) = customer_reference {
+            batch.customer_reference = customer_reference;
+        }
+        if let Some(expected_device_count) = expected_device_count {
+            batch.expected_device_count = expected_device_count;
+        }
+        if let Some(status) = status {
+            batch.status = status;
+        }
+        Ok(batch.clone())
+    }
+
+    async fn delete(&self, organization_id: Uuid, id: Uuid) -> RepositoryResult<Batch> {
+        let mut data = self.data.lock().unwrap();
+        let batch = data
+            .extract_if(.., |batch| {
+                batch.organization_id == organization_id && batch.id == id
+            })
+            .collect::<Vec<Batch>>()
+            .first()
+            .cloned();
+        batch.ok_or(RepositoryError::Test)
+    }
+}
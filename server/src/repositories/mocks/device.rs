@@ -0,0 +1,99 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::DateTime;
+use uuid::Uuid;
+
+use crate::{
+    error::RepositoryResult,
+    models::{Device, Organization},
+    repositories::device::DeviceRepository,
+};
+
+impl Device {
+    pub fn mock() -> Self {
+        Self {
+            id: Uuid::default(),
+            organization_id: Organization::mock().id,
+            batch_id: Some(Uuid::default()),
+            serial_number: String::from("SN-0001"),
+            manufacturer: Some(String::from("Lenovo")),
+            model: Some(String::from("ThinkPad T14")),
+            created_at: DateTime::default(),
+            updated_at: DateTime::default(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct MockDeviceRepository {
+    data: Arc<Mutex<Vec<Device>>>,
+}
+
+impl MockDeviceRepository {
+    pub fn new() -> Self {
+        Self {
+            data: Arc::new(Mutex::new(vec![Device::mock()])),
+        }
+    }
+}
+
+impl Default for MockDeviceRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl DeviceRepository for MockDeviceRepository {
+    async fn find_by_id(
+        &self,
+        organization_id: Uuid,
+        id: Uuid,
+    ) -> RepositoryResult<Option<Device>> {
+        Ok(self
+            .data
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|device| device.organization_id == organization_id && device.id == id)
+            .cloned())
+    }
+
+    async fn find_by_batch_id(
+        &self,
+        organization_id: Uuid,
+        batch_id: Uuid,
+    ) -> RepositoryResult<Vec<Device>> {
+        Ok(self
+            .data
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|device| {
+                device.organization_id == organization_id && device.batch_id == Some(batch_id)
+            })
+            .cloned()
+            .collect())
+    }
+
+    async fn create(
+        &self,
+        organization_id: Uuid,
+        batch_id: Option<Uuid>,
+        serial_number: String,
+        manufacturer: Option<String>,
+        model: Option<String>,
+    ) -> RepositoryResult<Device> {
+        let mut device = Device::mock();
+        device.id = Uuid::now_v7();
+        device.organization_id = organization_id;
+        device.batch_id = batch_id;
+        device.serial_number = serial_number;
+        device.manufacturer = manufacturer;
+        device.model = model;
+        let mut data = self.data.lock().unwrap();
+        data.push(device.clone());
+        Ok(device)
+    }
+}
@@ -0,0 +1,192 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::{
+    error::{RepositoryError, RepositoryResult},
+    models::{Architecture, Image},
+    repositories::image::ImageRepository,
+};
+
+#[derive(Clone)]
+pub struct MockImageRepository {
+    data: Arc<Mutex<Vec<Image>>>,
+}
+
+impl MockImageRepository {
+    pub fn new() -> Self {
+        Self {
+            data: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+}
+
+impl Default for MockImageRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ImageRepository for MockImageRepository {
+    async fn find_all(&self) -> RepositoryResult<Vec<Image>> {
+        let mut images: Vec<Image> = self
+            .data
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|image| image.base_image_id.is_none())
+            .cloned()
+            .collect();
+        images.sort_by_key(|image| std::cmp::Reverse(image.uploaded_at));
+        Ok(images)
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<Image>> {
+        Ok(self
+            .data
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|image| image.id == id)
+            .cloned())
+    }
+
+    async fn find_by_version(
+        &self,
+        version: &str,
+        architecture: Architecture,
+    ) -> RepositoryResult<Option<Image>> {
+        Ok(self
+            .data
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|image| {
+                image.version == version
+                    && image.architecture == architecture
+                    && image.base_image_id.is_none()
+            })
+            .cloned())
+    }
+
+    async fn find_active(&self, architecture: Architecture) -> RepositoryResult<Option<Image>> {
+        Ok(self
+            .data
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|image| image.is_active && image.architecture == architecture)
+            .cloned())
+    }
+
+    async fn find_variants(
+        &self,
+        base_image_id: Uuid,
+        organization_id: Uuid,
+    ) -> RepositoryResult<Vec<Image>> {
+        let mut images: Vec<Image> = self
+            .data
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|image| {
+                image.base_image_id == Some(base_image_id)
+                    && image.organization_id == Some(organization_id)
+            })
+            .cloned()
+            .collect();
+        images.sort_by_key(|image| std::cmp::Reverse(image.uploaded_at));
+        Ok(images)
+    }
+
+    async fn find_variant_by_name(
+        &self,
+        base_image_id: Uuid,
+        organization_id: Uuid,
+        variant_name: &str,
+    ) -> RepositoryResult<Option<Image>> {
+        Ok(self
+            .data
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|image| {
+                image.base_image_id == Some(base_image_id)
+                    && image.organization_id == Some(organization_id)
+                    && image.variant_name.as_deref() == Some(variant_name)
+            })
+            .cloned())
+    }
+
+    async fn create(
+        &self,
+        id: Uuid,
+        version: String,
+        architecture: Architecture,
+        size_bytes: i64,
+        sha256: String,
+        release_notes: Option<String>,
+        uploaded_by: Uuid,
+        base_image_id: Option<Uuid>,
+        organization_id: Option<Uuid>,
+        variant_name: Option<String>,
+        station_id: Option<Uuid>,
+    ) -> RepositoryResult<Image> {
+        let now = Utc::now();
+        let image = Image {
+            id,
+            version,
+            architecture,
+            size_bytes,
+            sha256,
+            release_notes,
+            is_active: false,
+            base_image_id,
+            organization_id,
+            variant_name,
+            station_id,
+            uploaded_by: Some(uploaded_by),
+            uploaded_at: now,
+            created_at: now,
+            updated_at: now,
+        };
+        self.data.lock().unwrap().push(image.clone());
+        Ok(image)
+    }
+
+    async fn update(
+        &self,
+        id: Uuid,
+        release_notes: Option<String>,
+        is_active: Option<bool>,
+    ) -> RepositoryResult<Image> {
+        let mut data = self.data.lock().unwrap();
+        let architecture = data
+            .iter()
+            .find(|image| image.id == id)
+            .ok_or(RepositoryError::Test)?
+            .architecture;
+        if is_active == Some(true) {
+            for image in data.iter_mut() {
+                if image.architecture == architecture && image.id != id {
+                    image.is_active = false;
+                }
+            }
+        }
+        let image = data
+            .iter_mut()
+            .find(|image| image.id == id)
+            .ok_or(RepositoryError::Test)?;
+        if let Some(release_notes) = release_notes {
+            image.release_notes = Some(release_notes);
+        }
+        if let Some(is_active) = is_active {
+            image.is_active = is_active;
+        }
+        image.updated_at = Utc::now();
+        Ok(image.clone())
+    }
+}
@@ -0,0 +1,123 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use crate::{
+    error::{RepositoryError, RepositoryResult},
+    models::{LoginThrottle, LoginThrottleKind},
+    repositories::login_throttle::LoginThrottleRepository,
+    validation::MAX_TEXT_LENGTH,
+};
+
+#[derive(Clone)]
+pub struct MockLoginThrottleRepository {
+    data: Arc<Mutex<Vec<LoginThrottle>>>,
+}
+
+impl MockLoginThrottleRepository {
+    pub fn new() -> Self {
+        Self {
+            data: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+}
+
+impl Default for MockLoginThrottleRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl LoginThrottleRepository for MockLoginThrottleRepository {
+    async fn find(
+        &self,
+        kind: LoginThrottleKind,
+        subject: &str,
+    ) -> RepositoryResult<Option<LoginThrottle>> {
+        Ok(self
+            .data
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|login_throttle| login_throttle.kind == kind && login_throttle.subject == subject)
+            .cloned())
+    }
+
+    async fn increment(
+        &self,
+        kind: LoginThrottleKind,
+        subject: &str,
+        window_started_before: DateTime<Utc>,
+    ) -> RepositoryResult<LoginThrottle> {
+        // mirrors the VARCHAR(255) column
+        if subject.chars().count() > MAX_TEXT_LENGTH {
+            return Err(RepositoryError::Test);
+        }
+        let mut data = self.data.lock().unwrap();
+        let now = Utc::now();
+        if let Some(login_throttle) = data
+            .iter_mut()
+            .find(|login_throttle| login_throttle.kind == kind && login_throttle.subject == subject)
+        {
+            if login_throttle.window_started_at < window_started_before {
+                login_throttle.failed_attempts = 1;
+                login_throttle.window_started_at = now;
+            } else {
+                login_throttle.failed_attempts += 1;
+            }
+            return Ok(login_throttle.clone());
+        }
+        let login_throttle = LoginThrottle {
+            kind,
+            subject: String::from(subject),
+            failed_attempts: 1,
+            locked_until: None,
+            window_started_at: now,
+            created_at: now,
+            updated_at: now,
+        };
+        data.push(login_throttle.clone());
+        Ok(login_throttle)
+    }
+
+    async fn lock(
+        &self,
+        kind: LoginThrottleKind,
+        subject: &str,
+        locked_until: DateTime<Utc>,
+    ) -> RepositoryResult<LoginThrottle> {
+        let mut data = self.data.lock().unwrap();
+        let login_throttle = data
+            .iter_mut()
+            .find(|login_throttle| login_throttle.kind == kind && login_throttle.subject == subject)
+            .ok_or(RepositoryError::Test)?;
+        login_throttle.locked_until = Some(locked_until);
+        Ok(login_throttle.clone())
+    }
+
+    async fn delete(
+        &self,
+        kind: LoginThrottleKind,
+        subject: &str,
+    ) -> RepositoryResult<Option<LoginThrottle>> {
+        let mut data = self.data.lock().unwrap();
+        Ok(data
+            .extract_if(.., |login_throttle| {
+                login_throttle.kind == kind && login_throttle.subject == subject
+            })
+            .next())
+    }
+
+    async fn delete_stale(&self, window_started_before: DateTime<Utc>) -> RepositoryResult<u64> {
+        let mut data = self.data.lock().unwrap();
+        let deleted = data
+            .extract_if(.., |login_throttle| {
+                login_throttle.window_started_at < window_started_before
+                    && !login_throttle.is_locked()
+            })
+            .count();
+        Ok(deleted as u64)
+    }
+}
@@ -1,5 +1,33 @@
+mod api_key;
+mod batch;
+mod device;
+mod image;
+mod login_throttle;
+mod organization;
+mod password_reset_token;
+mod recovery_code;
 mod refresh_token;
+mod report;
+mod session;
+mod signing_key;
+mod station;
+mod totp_credential;
 mod user;
+mod user_identity;
 
+pub use api_key::MockApiKeyRepository;
+pub use batch::MockBatchRepository;
+pub use device::MockDeviceRepository;
+pub use image::MockImageRepository;
+pub use login_throttle::MockLoginThrottleRepository;
+pub use organization::MockOrganizationRepository;
+pub use password_reset_token::MockPasswordResetTokenRepository;
+pub use recovery_code::MockRecoveryCodeRepository;
 pub use refresh_token::MockRefreshTokenRepository;
+pub use report::MockReportRepository;
+pub use session::MockSessionRepository;
+pub use signing_key::MockSigningKeyRepository;
+pub use station::MockStationRepository;
+pub use totp_credential::MockTotpCredentialRepository;
 pub use user::MockUserRepository;
+pub use user_identity::MockUserIdentityRepository;
@@ -0,0 +1,237 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{
+    error::RepositoryResult,
+    models::{Organization, OrganizationInvitation, OrganizationMember, User},
+    repositories::organization::OrganizationRepository,
+};
+
+impl Organization {
+    pub fn mock() -> Self {
+        Self {
+            id: Uuid::default(),
+            name: String::from("default"),
+            created_at: DateTime::default(),
+            updated_at: DateTime::default(),
+        }
+    }
+}
+
+impl OrganizationMember {
+    pub fn mock() -> Self {
+        Self {
+            organization_id: Organization::mock().id,
+            user_id: User::mock().id,
+            created_at: DateTime::default(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct MockOrganizationRepository {
+    data: Arc<Mutex<Vec<Organization>>>,
+    members: Arc<Mutex<Vec<OrganizationMember>>>,
+    invitations: Arc<Mutex<Vec<OrganizationInvitation>>>,
+}
+
+impl MockOrganizationRepository {
+    pub fn new() -> Self {
+        Self {
+            data: Arc::new(Mutex::new(vec![Organization::mock()])),
+            members: Arc::new(Mutex::new(vec![OrganizationMember::mock()])),
+            invitations: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+}
+
+impl Default for MockOrganizationRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl OrganizationRepository for MockOrganizationRepository {
+    async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<Organization>> {
+        Ok(self
+            .data
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|organization| organization.id == id)
+            .cloned())
+    }
+
+    async fn find_by_user_id(&self, user_id: Uuid) -> RepositoryResult<Vec<Organization>> {
+        let organization_ids = self
+            .members
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|member| member.user_id == user_id)
+            .map(|member| member.organization_id)
+            .collect::<Vec<Uuid>>();
+        Ok(self
+            .data
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|organization| organization_ids.contains(&organization.id))
+            .cloned()
+            .collect())
+    }
+
+    async fn create(&self, name: String) -> RepositoryResult<Organization> {
+        let mut organization = Organization::mock();
+        organization.id = Uuid::now_v7();
+        organization.name = name;
+        let mut data = self.data.lock().unwrap();
+        data.push(organization.clone());
+        Ok(organization)
+    }
+
+    async fn find_member(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+    ) -> RepositoryResult<Option<OrganizationMember>> {
+        Ok(self
+            .members
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|member| member.organization_id == organization_id && member.user_id == user_id)
+            .cloned())
+    }
+
+    async fn find_members(
+        &self,
+        organization_id: Uuid,
+    ) -> RepositoryResult<Vec<OrganizationMember>> {
+        Ok(self
+            .members
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|member| member.organization_id == organization_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn share_organization(
+        &self,
+        user_id: Uuid,
+        other_user_id: Uuid,
+    ) -> RepositoryResult<bool> {
+        let members = self.members.lock().unwrap();
+        Ok(members.iter().any(|member| {
+            member.user_id == user_id
+                && members.iter().any(|other_member| {
+                    other_member.user_id == other_user_id
+                        && other_member.organization_id == member.organization_id
+                })
+        }))
+    }
+
+    async fn add_member(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+    ) -> RepositoryResult<Option<OrganizationMember>> {
+        let mut members = self.members.lock().unwrap();
+        if members
+            .iter()
+            .any(|member| member.organization_id == organization_id && member.user_id == user_id)
+        {
+            return Ok(None);
+        }
+        let member = OrganizationMember {
+            organization_id,
+            user_id,
+            created_at: Utc::now(),
+        };
+        members.push(member.clone());
+        Ok(Some(member))
+    }
+
+    async fn remove_member(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+    ) -> RepositoryResult<Option<OrganizationMember>> {
+        let mut members = self.members.lock().unwrap();
+        Ok(members
+            .extract_if(.., |member| {
+                member.organization_id == organization_id && member.user_id == user_id
+            })
+            .next())
+    }
+
+    async fn find_invitations_by_user_id(
+        &self,
+        user_id: Uuid,
+    ) -> RepositoryResult<Vec<OrganizationInvitation>> {
+        Ok(self
+            .invitations
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|invitation| invitation.user_id == user_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn create_invitation(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+        invited_by: Uuid,
+    ) -> RepositoryResult<Option<OrganizationInvitation>> {
+        let mut invitations = self.invitations.lock().unwrap();
+        if invitations.iter().any(|invitation| {
+            invitation.organization_id == organization_id && invitation.user_id == user_id
+        }) {
+            return Ok(None);
+        }
+        let invitation = OrganizationInvitation {
+            organization_id,
+            user_id,
+            invited_by: Some(invited_by),
+            created_at: Utc::now(),
+        };
+        invitations.push(invitation.clone());
+        Ok(Some(invitation))
+    }
+
+    async fn accept_invitation(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+    ) -> RepositoryResult<Option<OrganizationMember>> {
+        if self
+            .delete_invitation(organization_id, user_id)
+            .await?
+            .is_none()
+        {
+            return Ok(None);
+        }
+        self.add_member(organization_id, user_id).await
+    }
+
+    async fn delete_invitation(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+    ) -> RepositoryResult<Option<OrganizationInvitation>> {
+        let mut invitations = self.invitations.lock().unwrap();
+        Ok(invitations
+            .extract_if(.., |invitation| {
+                invitation.organization_id == organization_id && invitation.user_id == user_id
+            })
+            .next())
+    }
+}
@@ -0,0 +1,85 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{
+    error::RepositoryResult, models::PasswordResetToken,
+    repositories::password_reset_token::PasswordResetTokenRepository,
+};
+
+#[derive(Clone)]
+pub struct MockPasswordResetTokenRepository {
+    data: Arc<Mutex<Vec<PasswordResetToken>>>,
+}
+
+impl MockPasswordResetTokenRepository {
+    pub fn new() -> Self {
+        Self {
+            data: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+}
+
+impl Default for MockPasswordResetTokenRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl PasswordResetTokenRepository for MockPasswordResetTokenRepository {
+    async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<PasswordResetToken>> {
+        Ok(self
+            .data
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|password_reset_token| password_reset_token.id == id)
+            .cloned())
+    }
+
+    async fn create(
+        &self,
+        user_id: Uuid,
+        token_hash: String,
+        expires_at: DateTime<Utc>,
+    ) -> RepositoryResult<PasswordResetToken> {
+        let now = Utc::now();
+        let password_reset_token = PasswordResetToken {
+            id: Uuid::now_v7(),
+            user_id,
+            token_hash,
+            expires_at,
+            used_at: None,
+            created_at: now,
+            updated_at: now,
+        };
+        self.data.lock().unwrap().push(password_reset_token.clone());
+        Ok(password_reset_token)
+    }
+
+    async fn mark_as_used(&self, id: Uuid) -> RepositoryResult<Option<PasswordResetToken>> {
+        let mut data = self.data.lock().unwrap();
+        Ok(data
+            .iter_mut()
+            .find(|password_reset_token| {
+                password_reset_token.id == id && password_reset_token.used_at.is_none()
+            })
+            .map(|password_reset_token| {
+                password_reset_token.used_at = Some(Utc::now());
+                password_reset_token.clone()
+            }))
+    }
+
+    async fn delete_all_by_user_id(&self, user_id: Uuid) -> RepositoryResult<u64> {
+        let mut data = self.data.lock().unwrap();
+        let deleted = data
+            .extract_if(.., |password_reset_token| {
+                password_reset_token.user_id == user_id
+            })
+            .count();
+        Ok(deleted as u64)
+    }
+}
@@ -0,0 +1,87 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::{
+    error::RepositoryResult, models::RecoveryCode,
+    repositories::recovery_code::RecoveryCodeRepository,
+};
+
+#[derive(Clone)]
+pub struct MockRecoveryCodeRepository {
+    data: Arc<Mutex<Vec<RecoveryCode>>>,
+}
+
+impl MockRecoveryCodeRepository {
+    pub fn new() -> Self {
+        Self {
+            data: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+}
+
+impl Default for MockRecoveryCodeRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl RecoveryCodeRepository for MockRecoveryCodeRepository {
+    async fn find_unused_by_user_id(&self, user_id: Uuid) -> RepositoryResult<Vec<RecoveryCode>> {
+        Ok(self
+            .data
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|recovery_code| {
+                recovery_code.user_id == user_id && recovery_code.used_at.is_none()
+            })
+            .cloned()
+            .collect())
+    }
+
+    async fn replace_all(
+        &self,
+        user_id: Uuid,
+        code_hashes: Vec<String>,
+    ) -> RepositoryResult<Vec<RecoveryCode>> {
+        let mut data = self.data.lock().unwrap();
+        data.retain(|recovery_code| recovery_code.user_id != user_id);
+        let now = Utc::now();
+        let recovery_codes: Vec<RecoveryCode> = code_hashes
+            .into_iter()
+            .map(|code_hash| RecoveryCode {
+                id: Uuid::now_v7(),
+                user_id,
+                code_hash,
+                used_at: None,
+                created_at: now,
+                updated_at: now,
+            })
+            .collect();
+        data.extend(recovery_codes.iter().cloned());
+        Ok(recovery_codes)
+    }
+
+    async fn mark_as_used(&self, id: Uuid) -> RepositoryResult<Option<RecoveryCode>> {
+        let mut data = self.data.lock().unwrap();
+        Ok(data
+            .iter_mut()
+            .find(|recovery_code| recovery_code.id == id && recovery_code.used_at.is_none())
+            .map(|recovery_code| {
+                recovery_code.used_at = Some(Utc::now());
+                recovery_code.clone()
+            }))
+    }
+
+    async fn delete_all_by_user_id(&self, user_id: Uuid) -> RepositoryResult<u64> {
+        let mut data = self.data.lock().unwrap();
+        let deleted = data
+            .extract_if(.., |recovery_code| recovery_code.user_id == user_id)
+            .count();
+        Ok(deleted as u64)
+    }
+}
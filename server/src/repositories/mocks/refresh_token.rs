@@ -1,7 +1,7 @@
 use std::sync::{Arc, Mutex};
 
 use async_trait::async_trait;
-use chrono::{DateTime, Duration};
+use chrono::{DateTime, Duration, Utc};
 use uuid::Uuid;
 
 use crate::{
@@ -16,12 +16,14 @@ impl RefreshToken {
             id: Uuid::default(),
             user_id: Uuid::default(),
             parent_id: None,
+            session_id: None,
             // password123
             opaque_token_hash: String::from(
                 "$argon2id$v=19$m=16,t=2,p=1$NjFWcEMwUEQ0dmZXcDMwSg$TfJtuSrudRp6hhV2mFSt3g",
             ),
             is_used: false,
-            expires_at: DateTime::default() + Duration::days(60),
+            is_revoked: false,
+            expires_at: Utc::now() + Duration::days(60),
             created_at: DateTime::default(),
             updated_at: DateTime::default(),
         }
@@ -41,6 +43,28 @@ impl MockRefreshTokenRepository {
     }
 }
 
+impl MockRefreshTokenRepository {
+    pub fn has_token_in_session(&self, session_id: Uuid) -> bool {
+        self.data
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|refresh_token| refresh_token.session_id == Some(session_id))
+    }
+
+    pub fn has_valid_token_in_session(&self, session_id: Uuid) -> bool {
+        self.data.lock().unwrap().iter().any(|refresh_token| {
+            refresh_token.session_id == Some(session_id) && refresh_token.is_valid()
+        })
+    }
+}
+
+impl Default for MockRefreshTokenRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[async_trait]
 impl RefreshTokenRepository for MockRefreshTokenRepository {
     async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<RefreshToken>> {
@@ -48,39 +72,101 @@ impl RefreshTokenRepository for MockRefreshTokenRepository {
             .data
             .lock()
             .unwrap()
-            .clone()
-            .into_iter()
-            .find(|refresh_token| refresh_token.id == id && refresh_token.is_valid()))
+            .iter()
+            .find(|refresh_token| refresh_token.id == id)
+            .cloned())
     }
 
     async fn create(
         &self,
         user_id: Uuid,
         parent_id: Option<Uuid>,
+        session_id: Option<Uuid>,
         opaque_token_hash: String,
+        expires_at: DateTime<Utc>,
     ) -> RepositoryResult<RefreshToken> {
         let mut refresh_token = RefreshToken::mock();
+        refresh_token.id = Uuid::now_v7();
         refresh_token.user_id = user_id;
         refresh_token.parent_id = parent_id;
+        refresh_token.session_id = session_id;
         refresh_token.opaque_token_hash = opaque_token_hash;
+        refresh_token.expires_at = expires_at;
         let mut data = self.data.lock().unwrap();
         data.push(refresh_token.clone());
         Ok(refresh_token)
     }
 
     async fn mark_as_used(&self, id: Uuid) -> RepositoryResult<RefreshToken> {
-        if let Some(mut refresh_token) = self
-            .data
-            .lock()
-            .unwrap()
+        let mut data = self.data.lock().unwrap();
+        let refresh_token = data
             .iter_mut()
             .find(|refresh_token| refresh_token.id == id)
-            .cloned()
+            .ok_or(RepositoryError::Test)?;
+        refresh_token.is_used = true;
+        Ok(refresh_token.clone())
+    }
+
+    async fn revoke_all_by_user_id(&self, user_id: Uuid) -> RepositoryResult<Vec<RefreshToken>> {
+        let mut data = self.data.lock().unwrap();
+        let refresh_tokens = data
+            .iter_mut()
+            .filter(|refresh_token| refresh_token.user_id == user_id && !refresh_token.is_revoked)
+            .map(|refresh_token| {
+                refresh_token.is_revoked = true;
+                refresh_token.clone()
+            })
+            .collect();
+        Ok(refresh_tokens)
+    }
+
+    async fn revoke_all_by_session_id(
+        &self,
+        session_id: Uuid,
+    ) -> RepositoryResult<Vec<RefreshToken>> {
+        let mut data = self.data.lock().unwrap();
+        let refresh_tokens = data
+            .iter_mut()
+            .filter(|refresh_token| {
+                refresh_token.session_id == Some(session_id) && !refresh_token.is_revoked
+            })
+            .map(|refresh_token| {
+                refresh_token.is_revoked = true;
+                refresh_token.clone()
+            })
+            .collect();
+        Ok(refresh_tokens)
+    }
+
+    async fn revoke_family(&self, id: Uuid) -> RepositoryResult<Vec<RefreshToken>> {
+        let mut data = self.data.lock().unwrap();
+        let mut root_id = id;
+        while let Some(parent_id) = data
+            .iter()
+            .find(|refresh_token| refresh_token.id == root_id)
+            .and_then(|refresh_token| refresh_token.parent_id)
         {
-            refresh_token.is_used = true;
-            return Ok(refresh_token);
+            root_id = parent_id;
+        }
+        let mut family = vec![root_id];
+        let mut index = 0;
+        while let Some(&family_id) = family.get(index) {
+            family.extend(
+                data.iter()
+                    .filter(|refresh_token| refresh_token.parent_id == Some(family_id))
+                    .map(|refresh_token| refresh_token.id),
+            );
+            index += 1;
         }
-        Err(RepositoryError::Test)
+        let refresh_tokens = data
+            .iter_mut()
+            .filter(|refresh_token| family.contains(&refresh_token.id) && !refresh_token.is_revoked)
+            .map(|refresh_token| {
+                refresh_token.is_revoked = true;
+                refresh_token.clone()
+            })
+            .collect();
+        Ok(refresh_tokens)
     }
 
     async fn delete(&self, id: Uuid) -> RepositoryResult<RefreshToken> {
@@ -92,4 +178,14 @@ impl RefreshTokenRepository for MockRefreshTokenRepository {
             .cloned();
         user.ok_or(RepositoryError::Test)
     }
+
+    async fn delete_expired(&self, expired_before: DateTime<Utc>) -> RepositoryResult<u64> {
+        let mut data = self.data.lock().unwrap();
+        let deleted = data
+            .extract_if(.., |refresh_token| {
+                refresh_token.expires_at < expired_before
+            })
+            .count();
+        Ok(deleted as u64)
+    }
 }
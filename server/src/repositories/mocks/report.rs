@@ -0,0 +1,136 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{
+    error::{RepositoryError, RepositoryResult},
+    models::{Device, ErasureResult, Organization, Report},
+    repositories::{
+        device::DeviceRepository, mocks::MockDeviceRepository, report::ReportRepository,
+    },
+};
+
+impl Report {
+    pub fn mock() -> Self {
+        Self {
+            id: Uuid::default(),
+            organization_id: Organization::mock().id,
+            device_id: Device::mock().id,
+            method: String::from("nist-800-88-purge"),
+            result: ErasureResult::Passed,
+            started_at: DateTime::default(),
+            finished_at: DateTime::default(),
+            created_at: DateTime::default(),
+            updated_at: DateTime::default(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct MockReportRepository {
+    data: Arc<Mutex<Vec<Report>>>,
+    device_repository: MockDeviceRepository,
+}
+
+impl MockReportRepository {
+    pub fn new(device_repository: MockDeviceRepository) -> Self {
+        Self {
+            data: Arc::new(Mutex::new(vec![Report::mock()])),
+            device_repository,
+        }
+    }
+}
+
+#[async_trait]
+impl ReportRepository for MockReportRepository {
+    async fn find_by_id(
+        &self,
+        organization_id: Uuid,
+        id: Uuid,
+    ) -> RepositoryResult<Option<Report>> {
+        Ok(self
+            .data
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|report| report.organization_id == organization_id && report.id == id)
+            .cloned())
+    }
+
+    async fn find_by_device_id(
+        &self,
+        organization_id: Uuid,
+        device_id: Uuid,
+    ) -> RepositoryResult<Vec<Report>> {
+        Ok(self
+            .data
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|report| {
+                report.organization_id == organization_id && report.device_id == device_id
+            })
+            .cloned()
+            .collect())
+    }
+
+    async fn find_by_batch_id(
+        &self,
+        organization_id: Uuid,
+        batch_id: Uuid,
+    ) -> RepositoryResult<Vec<Report>> {
+        let device_ids = self
+            .device_repository
+            .find_by_batch_id(organization_id, batch_id)
+            .await?
+            .into_iter()
+            .map(|device| device.id)
+            .collect::<Vec<Uuid>>();
+        Ok(self
+            .data
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|report| {
+                report.organization_id == organization_id && device_ids.contains(&report.device_id)
+            })
+            .cloned()
+            .collect())
+    }
+
+    async fn create(
+        &self,
+        organization_id: Uuid,
+        device_id: Uuid,
+        method: String,
+        result: ErasureResult,
+        started_at: DateTime<Utc>,
+        finished_at: DateTime<Utc>,
+    ) -> RepositoryResult<Report> {
+        let mut report = Report::mock();
+        report.id = Uuid::now_v7();
+        report.organization_id = organization_id;
+        report.device_id = device_id;
+        report.method = method;
+        report.result = result;
+        report.started_at = started_at;
+        report.finished_at = finished_at;
+        let mut data = self.data.lock().unwrap();
+        data.push(report.clone());
+        Ok(report)
+    }
+
+    async fn delete(&self, organization_id: Uuid, id: Uuid) -> RepositoryResult<Report> {
+        let mut data = self.data.lock().unwrap();
+        let report = data
+            .extract_if(.., |report| {
+                report.organization_id == organization_id && report.id == id
+            })
+            .collect::<Vec<Report>>()
+            .first()
+            .cloned();
+        report.ok_or(RepositoryError::Test)
+    }
+}
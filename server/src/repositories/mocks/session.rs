@@ -0,0 +1,133 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{
+    error::{RepositoryError, RepositoryResult},
+    models::{Session, User},
+    repositories::{mocks::MockRefreshTokenRepository, session::SessionRepository},
+};
+
+impl Session {
+    pub fn mock() -> Self {
+        Self {
+            id: Uuid::default(),
+            user_id: User::mock().id,
+            user_agent: None,
+            ip_address: None,
+            is_mfa_verified: false,
+            organization_id: None,
+            last_used_at: DateTime::default(),
+            created_at: DateTime::default(),
+            updated_at: DateTime::default(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct MockSessionRepository {
+    data: Arc<Mutex<Vec<Session>>>,
+    refresh_token_repository: MockRefreshTokenRepository,
+}
+
+impl MockSessionRepository {
+    pub fn new(refresh_token_repository: MockRefreshTokenRepository) -> Self {
+        Self {
+            data: Arc::new(Mutex::new(Vec::new())),
+            refresh_token_repository,
+        }
+    }
+}
+
+#[async_trait]
+impl SessionRepository for MockSessionRepository {
+    async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<Session>> {
+        Ok(self
+            .data
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|session| session.id == id)
+            .cloned())
+    }
+
+    async fn find_active_by_user_id(&self, user_id: Uuid) -> RepositoryResult<Vec<Session>> {
+        Ok(self
+            .data
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|session| {
+                session.user_id == user_id
+                    && self
+                        .refresh_token_repository
+                        .has_valid_token_in_session(session.id)
+            })
+            .cloned()
+            .collect())
+    }
+
+    async fn create(
+        &self,
+        user_id: Uuid,
+        user_agent: Option<String>,
+        ip_address: Option<String>,
+        is_mfa_verified: bool,
+    ) -> RepositoryResult<Session> {
+        let now = Utc::now();
+        let session = Session {
+            id: Uuid::now_v7(),
+            user_id,
+            user_agent,
+            ip_address,
+            is_mfa_verified,
+            organization_id: None,
+            last_used_at: now,
+            created_at: now,
+            updated_at: now,
+        };
+        self.data.lock().unwrap().push(session.clone());
+        Ok(session)
+    }
+
+    async fn touch(
+        &self,
+        id: Uuid,
+        user_agent: Option<String>,
+        ip_address: Option<String>,
+    ) -> RepositoryResult<Session> {
+        let mut data = self.data.lock().unwrap();
+        let session = data
+            .iter_mut()
+            .find(|session| session.id == id)
+            .ok_or(RepositoryError::Test)?;
+        session.user_agent = user_agent.or(session.user_agent.take());
+        session.ip_address = ip_address.or(session.ip_address.take());
+        session.last_used_at = Utc::now();
+        Ok(session.clone())
+    }
+
+    async fn set_organization(&self, id: Uuid, organization_id: Uuid) -> RepositoryResult<Session> {
+        let mut data = self.data.lock().unwrap();
+        let session = data
+            .iter_mut()
+            .find(|session| session.id == id)
+            .ok_or(RepositoryError::Test)?;
+        session.organization_id = Some(organization_id);
+        Ok(session.clone())
+    }
+
+    async fn delete_without_refresh_tokens(&self) -> RepositoryResult<u64> {
+        let mut data = self.data.lock().unwrap();
+        let deleted = data
+            .extract_if(.., |session| {
+                !self
+                    .refresh_token_repository
+                    .has_token_in_session(session.id)
+            })
+            .count();
+        Ok(deleted as u64)
+    }
+}
@@ -0,0 +1,143 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{
+    error::{RepositoryError, RepositoryResult},
+    models::{SigningAlgorithm, SigningKey},
+    repositories::signing_key::SigningKeyRepository,
+};
+
+impl SigningKey {
+    pub fn mock() -> Self {
+        Self {
+            id: Uuid::default(),
+            algorithm: SigningAlgorithm::Hs256,
+            private_key: vec![0; 32],
+            private_key_encrypted: false,
+            public_key: None,
+            retires_at: None,
+            created_at: DateTime::default(),
+            updated_at: DateTime::default(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct MockSigningKeyRepository {
+    data: Arc<Mutex<Vec<SigningKey>>>,
+}
+
+impl MockSigningKeyRepository {
+    pub fn new() -> Self {
+        Self {
+            data: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+}
+
+impl Default for MockSigningKeyRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl SigningKeyRepository for MockSigningKeyRepository {
+    async fn find_active(&self) -> RepositoryResult<Vec<SigningKey>> {
+        let now = Utc::now();
+        Ok(self
+            .data
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .filter(|signing_key| {
+                signing_key
+                    .retires_at
+                    .is_none_or(|retires_at| retires_at > now)
+            })
+            .cloned()
+            .collect())
+    }
+
+    async fn create_first(
+        &self,
+        algorithm: SigningAlgorithm,
+        private_key: Vec<u8>,
+        private_key_encrypted: bool,
+        public_key: Option<Vec<u8>>,
+    ) -> RepositoryResult<SigningKey> {
+        let mut data = self.data.lock().unwrap();
+        if let Some(signing_key) = data
+            .iter()
+            .rev()
+            .find(|signing_key| signing_key.retires_at.is_none())
+        {
+            return Ok(signing_key.clone());
+        }
+        let signing_key =
+            new_signing_key(algorithm, private_key, private_key_encrypted, public_key);
+        data.push(signing_key.clone());
+        Ok(signing_key)
+    }
+
+    async fn update_private_key(
+        &self,
+        id: Uuid,
+        private_key: Vec<u8>,
+        private_key_encrypted: bool,
+    ) -> RepositoryResult<SigningKey> {
+        let mut data = self.data.lock().unwrap();
+        let signing_key = data
+            .iter_mut()
+            .find(|signing_key| signing_key.id == id)
+            .ok_or(RepositoryError::Test)?;
+        signing_key.private_key = private_key;
+        signing_key.private_key_encrypted = private_key_encrypted;
+        signing_key.updated_at = Utc::now();
+        Ok(signing_key.clone())
+    }
+
+    async fn rotate(
+        &self,
+        algorithm: SigningAlgorithm,
+        private_key: Vec<u8>,
+        private_key_encrypted: bool,
+        public_key: Option<Vec<u8>>,
+        retires_at: DateTime<Utc>,
+    ) -> RepositoryResult<SigningKey> {
+        let mut data = self.data.lock().unwrap();
+        for signing_key in data
+            .iter_mut()
+            .filter(|signing_key| signing_key.retires_at.is_none())
+        {
+            signing_key.retires_at = Some(retires_at);
+        }
+        let signing_key =
+            new_signing_key(algorithm, private_key, private_key_encrypted, public_key);
+        data.push(signing_key.clone());
+        Ok(signing_key)
+    }
+}
+
+fn new_signing_key(
+    algorithm: SigningAlgorithm,
+    private_key: Vec<u8>,
+    private_key_encrypted: bool,
+    public_key: Option<Vec<u8>>,
+) -> SigningKey {
+    let now = Utc::now();
+    SigningKey {
+        id: Uuid::now_v7(),
+        algorithm,
+        private_key,
+        private_key_encrypted,
+        public_key,
+        retires_at: None,
+        created_at: now,
+        updated_at: now,
+    }
+}
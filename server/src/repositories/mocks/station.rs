@@ -0,0 +1,168 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{
+    error::{RepositoryError, RepositoryResult},
+    models::Station,
+    repositories::station::StationRepository,
+};
+
+#[derive(Clone)]
+pub struct MockStationRepository {
+    data: Arc<Mutex<Vec<Station>>>,
+}
+
+impl MockStationRepository {
+    pub fn new() -> Self {
+        Self {
+            data: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    fn modify(&self, id: Uuid, modify: impl FnOnce(&mut Station)) -> RepositoryResult<Station> {
+        let mut data = self.data.lock().unwrap();
+        let station = data
+            .iter_mut()
+            .find(|station| station.id == id)
+            .ok_or(RepositoryError::Test)?;
+        modify(station);
+        station.updated_at = Utc::now();
+        Ok(station.clone())
+    }
+}
+
+impl Default for MockStationRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl StationRepository for MockStationRepository {
+    async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<Station>> {
+        Ok(self
+            .data
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|station| station.id == id)
+            .cloned())
+    }
+
+    async fn find_by_organization_id(
+        &self,
+        organization_id: Uuid,
+    ) -> RepositoryResult<Vec<Station>> {
+        let mut stations: Vec<Station> = self
+            .data
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|station| station.organization_id == organization_id)
+            .cloned()
+            .collect();
+        stations.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(stations)
+    }
+
+    async fn find_by_enrollment_code_hash(
+        &self,
+        enrollment_code_hash: &str,
+    ) -> RepositoryResult<Option<Station>> {
+        Ok(self
+            .data
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|station| station.enrollment_code_hash.as_deref() == Some(enrollment_code_hash))
+            .cloned())
+    }
+
+    async fn create(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+        name: String,
+        enrollment_code_hash: String,
+        enrollment_code_expires_at: DateTime<Utc>,
+    ) -> RepositoryResult<Station> {
+        let now = Utc::now();
+        let station = Station {
+            id: Uuid::now_v7(),
+            organization_id,
+            user_id,
+            name,
+            hardware_fingerprint: None,
+            enrollment_code_hash: Some(enrollment_code_hash),
+            enrollment_code_expires_at: Some(enrollment_code_expires_at),
+            enrolled_at: None,
+            disabled_at: None,
+            created_at: now,
+            updated_at: now,
+        };
+        self.data.lock().unwrap().push(station.clone());
+        Ok(station)
+    }
+
+    async fn update(
+        &self,
+        id: Uuid,
+        name: Option<String>,
+        is_disabled: Option<bool>,
+    ) -> RepositoryResult<Station> {
+        self.modify(id, |station| {
+            if let Some(name) = name {
+                station.name = name;
+            }
+            match is_disabled {
+                Some(true) if station.disabled_at.is_none() => {
+                    station.disabled_at = Some(Utc::now())
+                }
+                Some(false) => station.disabled_at = None,
+                _ => {}
+            }
+        })
+    }
+
+    async fn reset_enrollment(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        enrollment_code_hash: String,
+        enrollment_code_expires_at: DateTime<Utc>,
+    ) -> RepositoryResult<Station> {
+        self.modify(id, |station| {
+            station.user_id = user_id;
+            station.enrollment_code_hash = Some(enrollment_code_hash);
+            station.enrollment_code_expires_at = Some(enrollment_code_expires_at);
+            station.hardware_fingerprint = None;
+            station.enrolled_at = None;
+        })
+    }
+
+    async fn enroll(
+        &self,
+        id: Uuid,
+        hardware_fingerprint: String,
+    ) -> RepositoryResult<Option<Station>> {
+        let mut data = self.data.lock().unwrap();
+        Ok(data
+            .iter_mut()
+            .find(|station| station.id == id && station.enrollment_code_hash.is_some())
+            .map(|station| {
+                station.hardware_fingerprint = Some(hardware_fingerprint);
+                station.enrolled_at = Some(Utc::now());
+                station.enrollment_code_hash = None;
+                station.enrollment_code_expires_at = None;
+                station.clone()
+            }))
+    }
+
+    async fn delete(&self, id: Uuid) -> RepositoryResult<Option<Station>> {
+        let mut data = self.data.lock().unwrap();
+        Ok(data.extract_if(.., |station| station.id == id).next())
+    }
+}
@@ -0,0 +1,92 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::{
+    error::{RepositoryError, RepositoryResult},
+    models::TotpCredential,
+    repositories::totp_credential::TotpCredentialRepository,
+};
+
+#[derive(Clone)]
+pub struct MockTotpCredentialRepository {
+    data: Arc<Mutex<Vec<TotpCredential>>>,
+}
+
+impl MockTotpCredentialRepository {
+    pub fn new() -> Self {
+        Self {
+            data: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+}
+
+impl Default for MockTotpCredentialRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl TotpCredentialRepository for MockTotpCredentialRepository {
+    async fn find_by_user_id(&self, user_id: Uuid) -> RepositoryResult<Option<TotpCredential>> {
+        Ok(self
+            .data
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|totp_credential| totp_credential.user_id == user_id)
+            .cloned())
+    }
+
+    async fn upsert(&self, user_id: Uuid, secret: Vec<u8>) -> RepositoryResult<TotpCredential> {
+        let mut data = self.data.lock().unwrap();
+        data.retain(|totp_credential| totp_credential.user_id != user_id);
+        let now = Utc::now();
+        let totp_credential = TotpCredential {
+            user_id,
+            secret,
+            confirmed_at: None,
+            last_used_step: None,
+            created_at: now,
+            updated_at: now,
+        };
+        data.push(totp_credential.clone());
+        Ok(totp_credential)
+    }
+
+    async fn confirm(&self, user_id: Uuid) -> RepositoryResult<TotpCredential> {
+        let mut data = self.data.lock().unwrap();
+        let totp_credential = data
+            .iter_mut()
+            .find(|totp_credential| totp_credential.user_id == user_id)
+            .ok_or(RepositoryError::Test)?;
+        totp_credential.confirmed_at = Some(Utc::now());
+        Ok(totp_credential.clone())
+    }
+
+    async fn use_step(&self, user_id: Uuid, step: i64) -> RepositoryResult<Option<TotpCredential>> {
+        let mut data = self.data.lock().unwrap();
+        Ok(data
+            .iter_mut()
+            .find(|totp_credential| {
+                totp_credential.user_id == user_id
+                    && totp_credential
+                        .last_used_step
+                        .is_none_or(|last_used_step| last_used_step < step)
+            })
+            .map(|totp_credential| {
+                totp_credential.last_used_step = Some(step);
+                totp_credential.clone()
+            }))
+    }
+
+    async fn delete(&self, user_id: Uuid) -> RepositoryResult<Option<TotpCredential>> {
+        let mut data = self.data.lock().unwrap();
+        Ok(data
+            .extract_if(.., |totp_credential| totp_credential.user_id == user_id)
+            .next())
+    }
+}
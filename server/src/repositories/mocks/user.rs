@@ -6,7 +6,7 @@ use uuid::Uuid;
 
 use crate::{
     error::{RepositoryError, RepositoryResult},
-    models::User,
+    models::{Role, User},
     repositories::user::UserRepository,
 };
 
@@ -18,6 +18,8 @@ impl User {
             password_hash: String::from(
                 "$argon2id$v=19$m=16,t=2,p=1$NjFWcEMwUEQ0dmZXcDMwSg$TfJtuSrudRp6hhV2mFSt3g",
             ),
+            role: Role::Admin,
+            is_server_admin: true,
             created_at: DateTime::default(),
             updated_at: DateTime::default(),
         }
@@ -37,6 +39,12 @@ impl MockUserRepository {
     }
 }
 
+impl Default for MockUserRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[async_trait]
 impl UserRepository for MockUserRepository {
     async fn find_by_id(&self, uuid: Uuid) -> RepositoryResult<Option<User>> {
@@ -59,16 +67,29 @@ impl UserRepository for MockUserRepository {
             .cloned())
     }
 
-    async fn create(&self, email: String, password_hash: String) -> RepositoryResult<User> {
+    async fn create(
+        &self,
+        email: String,
+        password_hash: String,
+        role: Role,
+    ) -> RepositoryResult<User> {
         let mut user = User::mock();
+        user.id = Uuid::now_v7();
         user.email = email;
         user.password_hash = password_hash;
+        user.role = role;
+        user.is_server_admin = false;
         let mut data = self.data.lock().unwrap();
         data.push(user.clone());
         Ok(user)
     }
 
-    async fn update(&self, id: Uuid, email: Option<String>) -> RepositoryResult<User> {
+    async fn update(
+        &self,
+        id: Uuid,
+        email: Option<String>,
+        role: Option<Role>,
+    ) -> RepositoryResult<User> {
         if let Some(email) = email {
             self.data
                 .lock()
@@ -77,6 +98,14 @@ impl UserRepository for MockUserRepository {
                 .filter(|user| user.id == id)
                 .for_each(|user| user.email = email.clone());
         };
+        if let Some(role) = role {
+            self.data
+                .lock()
+                .unwrap()
+                .iter_mut()
+                .filter(|user| user.id == id)
+                .for_each(|user| user.role = role);
+        };
         let user = self
             .data
             .lock()
@@ -87,6 +116,20 @@ impl UserRepository for MockUserRepository {
         user.ok_or(RepositoryError::Test)
     }
 
+    async fn update_password_hash(
+        &self,
+        id: Uuid,
+        password_hash: String,
+    ) -> RepositoryResult<User> {
+        let mut data = self.data.lock().unwrap();
+        let user = data
+            .iter_mut()
+            .find(|user| user.id == id)
+            .ok_or(RepositoryError::Test)?;
+        user.password_hash = password_hash;
+        Ok(user.clone())
+    }
+
     async fn delete(&self, id: Uuid) -> RepositoryResult<User> {
         let mut data = self.data.lock().unwrap();
         let user = data
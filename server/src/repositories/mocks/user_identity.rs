@@ -0,0 +1,78 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::{
+    error::RepositoryResult, models::UserIdentity,
+    repositories::user_identity::UserIdentityRepository,
+};
+
+#[derive(Clone)]
+pub struct MockUserIdentityRepository {
+    data: Arc<Mutex<Vec<UserIdentity>>>,
+}
+
+impl MockUserIdentityRepository {
+    pub fn new() -> Self {
+        Self {
+            data: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+}
+
+impl Default for MockUserIdentityRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl UserIdentityRepository for MockUserIdentityRepository {
+    async fn find_by_subject(
+        &self,
+        issuer: &str,
+        subject: &str,
+    ) -> RepositoryResult<Option<UserIdentity>> {
+        Ok(self
+            .data
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|user_identity| {
+                user_identity.issuer == issuer && user_identity.subject == subject
+            })
+            .cloned())
+    }
+
+    async fn find_all_by_user_id(&self, user_id: Uuid) -> RepositoryResult<Vec<UserIdentity>> {
+        Ok(self
+            .data
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|user_identity| user_identity.user_id == user_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn create(
+        &self,
+        user_id: Uuid,
+        issuer: String,
+        subject: String,
+    ) -> RepositoryResult<UserIdentity> {
+        let now = Utc::now();
+        let user_identity = UserIdentity {
+            id: Uuid::now_v7(),
+            user_id,
+            issuer,
+            subject,
+            created_at: now,
+            updated_at: now,
+        };
+        self.data.lock().unwrap().push(user_identity.clone());
+        Ok(user_identity)
+    }
+}
@@ -1,7 +1,19 @@
 #[cfg(test)]
 pub mod mocks;
 
+pub mod api_key;
+pub mod batch;
+pub mod device;
 pub mod image;
+pub mod login_throttle;
+pub mod organization;
+pub mod password_reset_token;
+pub mod recovery_code;
 pub mod refresh_token;
 pub mod report;
+pub mod session;
+pub mod signing_key;
+pub mod station;
+pub mod totp_credential;
 pub mod user;
+pub mod user_identity;
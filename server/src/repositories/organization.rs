@@ -0,0 +1,288 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    error::RepositoryResult,
+    models::{Organization, OrganizationInvitation, OrganizationMember},
+};
+
+#[async_trait]
+pub trait OrganizationRepository: Send + Sync {
+    async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<Organization>>;
+    async fn find_by_user_id(&self, user_id: Uuid) -> RepositoryResult<Vec<Organization>>;
+    async fn create(&self, name: String) -> RepositoryResult<Organization>;
+    async fn find_member(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+    ) -> RepositoryResult<Option<OrganizationMember>>;
+    async fn find_members(
+        &self,
+        organization_id: Uuid,
+    ) -> RepositoryResult<Vec<OrganizationMember>>;
+    /// Whether the users are members of at least one organization together.
+    async fn share_organization(
+        &self,
+        user_id: Uuid,
+        other_user_id: Uuid,
+    ) -> RepositoryResult<bool>;
+    /// `None` if the user is a member already.
+    async fn add_member(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+    ) -> RepositoryResult<Option<OrganizationMember>>;
+    async fn remove_member(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+    ) -> RepositoryResult<Option<OrganizationMember>>;
+    /// Invitations to `user_id`, oldest first.
+    async fn find_invitations_by_user_id(
+        &self,
+        user_id: Uuid,
+    ) -> RepositoryResult<Vec<OrganizationInvitation>>;
+    /// `None` if the user is invited already.
+    async fn create_invitation(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+        invited_by: Uuid,
+    ) -> RepositoryResult<Option<OrganizationInvitation>>;
+    /// Turns the invitation into a membership. `None` if there is no such invitation.
+    async fn accept_invitation(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+    ) -> RepositoryResult<Option<OrganizationMember>>;
+    async fn delete_invitation(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+    ) -> RepositoryResult<Option<OrganizationInvitation>>;
+}
+
+#[derive(Clone)]
+pub struct PostgresOrganizationRepository {
+    pool: PgPool,
+}
+
+impl PostgresOrganizationRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl OrganizationRepository for PostgresOrganizationRepository {
+    async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<Organization>> {
+        let query = "
+            SELECT * FROM organizations
+            WHERE id = $1;
+        ";
+        let organization = sqlx::query_as::<_, Organization>(query)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(organization)
+    }
+
+    async fn find_by_user_id(&self, user_id: Uuid) -> RepositoryResult<Vec<Organization>> {
+        let query = "
+            SELECT organizations.* FROM organizations
+            JOIN organization_members ON organization_members.organization_id = organizations.id
+            WHERE organization_members.user_id = $1
+            ORDER BY organization_members.created_at;
+        ";
+        let organizations = sqlx::query_as::<_, Organization>(query)
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(organizations)
+    }
+
+    async fn create(&self, name: String) -> RepositoryResult<Organization> {
+        let query = "
+            INSERT INTO organizations (name)
+            VALUES ($1)
+            RETURNING *;
+        ";
+        let organization = sqlx::query_as::<_, Organization>(query)
+            .bind(&name)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(organization)
+    }
+
+    async fn find_member(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+    ) -> RepositoryResult<Option<OrganizationMember>> {
+        let query = "
+            SELECT * FROM organization_members
+            WHERE organization_id = $1 AND user_id = $2;
+        ";
+        let member = sqlx::query_as::<_, OrganizationMember>(query)
+            .bind(organization_id)
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(member)
+    }
+
+    async fn find_members(
+        &self,
+        organization_id: Uuid,
+    ) -> RepositoryResult<Vec<OrganizationMember>> {
+        let query = "
+            SELECT * FROM organization_members
+            WHERE organization_id = $1
+            ORDER BY created_at;
+        ";
+        let members = sqlx::query_as::<_, OrganizationMember>(query)
+            .bind(organization_id)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(members)
+    }
+
+    async fn share_organization(
+        &self,
+        user_id: Uuid,
+        other_user_id: Uuid,
+    ) -> RepositoryResult<bool> {
+        let query = "
+            SELECT EXISTS (
+                SELECT 1 FROM organization_members
+                JOIN organization_members AS other_members
+                    ON other_members.organization_id = organization_members.organization_id
+                WHERE organization_members.user_id = $1 AND other_members.user_id = $2
+            );
+        ";
+        let shared = sqlx::query_scalar::<_, bool>(query)
+            .bind(user_id)
+            .bind(other_user_id)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(shared)
+    }
+
+    async fn add_member(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+    ) -> RepositoryResult<Option<OrganizationMember>> {
+        let query = "
+            INSERT INTO organization_members (organization_id, user_id)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING
+            RETURNING *;
+        ";
+        let member = sqlx::query_as::<_, OrganizationMember>(query)
+            .bind(organization_id)
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(member)
+    }
+
+    async fn remove_member(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+    ) -> RepositoryResult<Option<OrganizationMember>> {
+        let query = "
+            DELETE FROM organization_members
+            WHERE organization_id = $1 AND user_id = $2
+            RETURNING *;
+        ";
+        let member = sqlx::query_as::<_, OrganizationMember>(query)
+            .bind(organization_id)
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(member)
+    }
+
+    async fn find_invitations_by_user_id(
+        &self,
+        user_id: Uuid,
+    ) -> RepositoryResult<Vec<OrganizationInvitation>> {
+        let query = "
+            SELECT * FROM organization_invitations
+            WHERE user_id = $1
+            ORDER BY created_at;
+        ";
+        let invitations = sqlx::query_as::<_, OrganizationInvitation>(query)
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(invitations)
+    }
+
+    async fn create_invitation(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+        invited_by: Uuid,
+    ) -> RepositoryResult<Option<OrganizationInvitation>> {
+        let query = "
+            INSERT INTO organization_invitations (organization_id, user_id, invited_by)
+            VALUES ($1, $2, $3)
+            ON CONFLICT DO NOTHING
+            RETURNING *;
+        ";
+        let invitation = sqlx::query_as::<_, OrganizationInvitation>(query)
+            .bind(organization_id)
+            .bind(user_id)
+            .bind(invited_by)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(invitation)
+    }
+
+    async fn accept_invitation(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+    ) -> RepositoryResult<Option<OrganizationMember>> {
+        // one statement, so the invitation can't be accepted twice
+        let query = "
+            WITH invitation AS (
+                DELETE FROM organization_invitations
+                WHERE organization_id = $1 AND user_id = $2
+                RETURNING organization_id, user_id
+            )
+            INSERT INTO organization_members (organization_id, user_id)
+            SELECT organization_id, user_id FROM invitation
+            ON CONFLICT DO NOTHING
+            RETURNING *;
+        ";
+        let member = sqlx::query_as::<_, OrganizationMember>(query)
+            .bind(organization_id)
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(member)
+    }
+
+    async fn delete_invitation(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+    ) -> RepositoryResult<Option<OrganizationInvitation>> {
+        let query = "
+            DELETE FROM organization_invitations
+            WHERE organization_id = $1 AND user_id = $2
+            RETURNING *;
+        ";
+        let invitation = sqlx::query_as::<_, OrganizationInvitation>(query)
+            .bind(organization_id)
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(invitation)
+    }
+}
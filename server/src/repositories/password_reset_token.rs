@@ -0,0 +1,89 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{error::RepositoryResult, models::PasswordResetToken};
+
+#[async_trait]
+pub trait PasswordResetTokenRepository: Send + Sync {
+    async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<PasswordResetToken>>;
+    async fn create(
+        &self,
+        user_id: Uuid,
+        token_hash: String,
+        expires_at: DateTime<Utc>,
+    ) -> RepositoryResult<PasswordResetToken>;
+    /// Returns `None` if the token was used in the meantime.
+    async fn mark_as_used(&self, id: Uuid) -> RepositoryResult<Option<PasswordResetToken>>;
+    async fn delete_all_by_user_id(&self, user_id: Uuid) -> RepositoryResult<u64>;
+}
+
+#[derive(Clone)]
+pub struct PostgresPasswordResetTokenRepository {
+    pool: PgPool,
+}
+
+impl PostgresPasswordResetTokenRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl PasswordResetTokenRepository for PostgresPasswordResetTokenRepository {
+    async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<PasswordResetToken>> {
+        let query = "
+            SELECT * FROM password_reset_tokens
+            WHERE id = $1;
+        ";
+        let password_reset_token = sqlx::query_as::<_, PasswordResetToken>(query)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(password_reset_token)
+    }
+
+    async fn create(
+        &self,
+        user_id: Uuid,
+        token_hash: String,
+        expires_at: DateTime<Utc>,
+    ) -> RepositoryResult<PasswordResetToken> {
+        let query = "
+            INSERT INTO password_reset_tokens (user_id, token_hash, expires_at)
+            VALUES ($1, $2, $3)
+            RETURNING *;
+        ";
+        let password_reset_token = sqlx::query_as::<_, PasswordResetToken>(query)
+            .bind(user_id)
+            .bind(&token_hash)
+            .bind(expires_at)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(password_reset_token)
+    }
+
+    async fn mark_as_used(&self, id: Uuid) -> RepositoryResult<Option<PasswordResetToken>> {
+        let query = "
+            UPDATE password_reset_tokens
+            SET used_at = NOW()
+            WHERE id = $1 AND used_at IS NULL
+            RETURNING *;
+        ";
+        let password_reset_token = sqlx::query_as::<_, PasswordResetToken>(query)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(password_reset_token)
+    }
+
+    async fn delete_all_by_user_id(&self, user_id: Uuid) -> RepositoryResult<u64> {
+        let query = "
+            DELETE FROM password_reset_tokens
+            WHERE user_id = $1;
+        ";
+        let result = sqlx::query(query).bind(user_id).execute(&self.pool).await?;
+        Ok(result.rows_affected())
+    }
+}
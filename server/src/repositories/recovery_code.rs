@@ -0,0 +1,96 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{error::RepositoryResult, models::RecoveryCode};
+
+#[async_trait]
+pub trait RecoveryCodeRepository: Send + Sync {
+    async fn find_unused_by_user_id(&self, user_id: Uuid) -> RepositoryResult<Vec<RecoveryCode>>;
+    /// Deletes every recovery code of `user_id` and stores `code_hashes` in their place.
+    async fn replace_all(
+        &self,
+        user_id: Uuid,
+        code_hashes: Vec<String>,
+    ) -> RepositoryResult<Vec<RecoveryCode>>;
+    /// Returns `None` if the code was used in the meantime.
+    async fn mark_as_used(&self, id: Uuid) -> RepositoryResult<Option<RecoveryCode>>;
+    async fn delete_all_by_user_id(&self, user_id: Uuid) -> RepositoryResult<u64>;
+}
+
+#[derive(Clone)]
+pub struct PostgresRecoveryCodeRepository {
+    pool: PgPool,
+}
+
+impl PostgresRecoveryCodeRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl RecoveryCodeRepository for PostgresRecoveryCodeRepository {
+    async fn find_unused_by_user_id(&self, user_id: Uuid) -> RepositoryResult<Vec<RecoveryCode>> {
+        let query = "
+            SELECT * FROM recovery_codes
+            WHERE user_id = $1 AND used_at IS NULL;
+        ";
+        let recovery_codes = sqlx::query_as::<_, RecoveryCode>(query)
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(recovery_codes)
+    }
+
+    async fn replace_all(
+        &self,
+        user_id: Uuid,
+        code_hashes: Vec<String>,
+    ) -> RepositoryResult<Vec<RecoveryCode>> {
+        let mut transaction = self.pool.begin().await?;
+        let delete_query = "
+            DELETE FROM recovery_codes
+            WHERE user_id = $1;
+        ";
+        sqlx::query(delete_query)
+            .bind(user_id)
+            .execute(&mut *transaction)
+            .await?;
+        let insert_query = "
+            INSERT INTO recovery_codes (user_id, code_hash)
+            SELECT $1, UNNEST($2::VARCHAR[])
+            RETURNING *;
+        ";
+        let recovery_codes = sqlx::query_as::<_, RecoveryCode>(insert_query)
+            .bind(user_id)
+            .bind(&code_hashes)
+            .fetch_all(&mut *transaction)
+            .await?;
+        transaction.commit().await?;
+        Ok(recovery_codes)
+    }
+
+    async fn mark_as_used(&self, id: Uuid) -> RepositoryResult<Option<RecoveryCode>> {
+        let query = "
+            UPDATE recovery_codes
+            SET used_at = NOW()
+            WHERE id = $1 AND used_at IS NULL
+            RETURNING *;
+        ";
+        let recovery_code = sqlx::query_as::<_, RecoveryCode>(query)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(recovery_code)
+    }
+
+    async fn delete_all_by_user_id(&self, user_id: Uuid) -> RepositoryResult<u64> {
+        let query = "
+            DELETE FROM recovery_codes
+            WHERE user_id = $1;
+        ";
+        let result = sqlx::query(query).bind(user_id).execute(&self.pool).await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 use uuid::Uuid;
 
@@ -11,10 +12,21 @@ pub trait RefreshTokenRepository: Send + Sync {
         &self,
         user_id: Uuid,
         parent_id: Option<Uuid>,
+        session_id: Option<Uuid>,
         opaque_token_hash: String,
+        expires_at: DateTime<Utc>,
     ) -> RepositoryResult<RefreshToken>;
     async fn mark_as_used(&self, id: Uuid) -> RepositoryResult<RefreshToken>;
+    async fn revoke_all_by_user_id(&self, user_id: Uuid) -> RepositoryResult<Vec<RefreshToken>>;
+    async fn revoke_all_by_session_id(
+        &self,
+        session_id: Uuid,
+    ) -> RepositoryResult<Vec<RefreshToken>>;
+    /// Revokes every token descending from the same login as the token `id`.
+    async fn revoke_family(&self, id: Uuid) -> RepositoryResult<Vec<RefreshToken>>;
     async fn delete(&self, id: Uuid) -> RepositoryResult<RefreshToken>;
+    /// Deletes every token that expired before `expired_before` and returns how many.
+    async fn delete_expired(&self, expired_before: DateTime<Utc>) -> RepositoryResult<u64>;
 }
 
 #[derive(Clone)]
@@ -46,17 +58,23 @@ impl RefreshTokenRepository for PostgresRefreshTokenRepository {
         &self,
         user_id: Uuid,
         parent_id: Option<Uuid>,
+        session_id: Option<Uuid>,
         opaque_token_hash: String,
+        expires_at: DateTime<Utc>,
     ) -> RepositoryResult<RefreshToken> {
         let query = "
-            INSERT INTO refresh_tokens (user_id, parent_id, opaque_token_hash)
-            VALUES ($1, $2, $3)
+            INSERT INTO refresh_tokens (
+                user_id, parent_id, session_id, opaque_token_hash, expires_at
+            )
+            VALUES ($1, $2, $3, $4, $5)
             RETURNING *;
         ";
         let refresh_token = sqlx::query_as::<_, RefreshToken>(query)
             .bind(user_id)
             .bind(parent_id)
+            .bind(session_id)
             .bind(&opaque_token_hash)
+            .bind(expires_at)
             .fetch_one(&self.pool)
             .await?;
         Ok(refresh_token)
@@ -76,6 +94,64 @@ impl RefreshTokenRepository for PostgresRefreshTokenRepository {
         Ok(refresh_token)
     }
 
+    async fn revoke_all_by_user_id(&self, user_id: Uuid) -> RepositoryResult<Vec<RefreshToken>> {
+        let query = "
+            UPDATE refresh_tokens
+            SET is_revoked = true
+            WHERE user_id = $1 AND is_revoked = false
+            RETURNING *;
+        ";
+        let refresh_tokens = sqlx::query_as::<_, RefreshToken>(query)
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(refresh_tokens)
+    }
+
+    async fn revoke_all_by_session_id(
+        &self,
+        session_id: Uuid,
+    ) -> RepositoryResult<Vec<RefreshToken>> {
+        let query = "
+            UPDATE refresh_tokens
+            SET is_revoked = true
+            WHERE session_id = $1 AND is_revoked = false
+            RETURNING *;
+        ";
+        let refresh_tokens = sqlx::query_as::<_, RefreshToken>(query)
+            .bind(session_id)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(refresh_tokens)
+    }
+
+    async fn revoke_family(&self, id: Uuid) -> RepositoryResult<Vec<RefreshToken>> {
+        let query = "
+            WITH RECURSIVE ancestors AS (
+                SELECT id, parent_id FROM refresh_tokens
+                WHERE id = $1
+                UNION ALL
+                SELECT refresh_tokens.id, refresh_tokens.parent_id FROM refresh_tokens
+                JOIN ancestors ON refresh_tokens.id = ancestors.parent_id
+            ), family AS (
+                SELECT id FROM ancestors
+                WHERE parent_id IS NULL
+                UNION ALL
+                SELECT refresh_tokens.id FROM refresh_tokens
+                JOIN family ON refresh_tokens.parent_id = family.id
+            )
+            UPDATE refresh_tokens
+            SET is_revoked = true
+            WHERE id IN (SELECT id FROM family) AND is_revoked = false
+            RETURNING *;
+        ";
+        let refresh_tokens = sqlx::query_as::<_, RefreshToken>(query)
+            .bind(id)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(refresh_tokens)
+    }
+
     async fn delete(&self, id: Uuid) -> RepositoryResult<RefreshToken> {
         let query = "
             DELETE FROM refresh_tokens
@@ -88,4 +164,16 @@ impl RefreshTokenRepository for PostgresRefreshTokenRepository {
             .await?;
         Ok(refresh_token)
     }
+
+    async fn delete_expired(&self, expired_before: DateTime<Utc>) -> RepositoryResult<u64> {
+        let query = "
+            DELETE FROM refresh_tokens
+            WHERE expires_at < $1;
+        ";
+        let result = sqlx::query(query)
+            .bind(expired_before)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
 }
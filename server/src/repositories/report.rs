@@ -1,14 +1,37 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 use uuid::Uuid;
 
-use crate::{error::RepositoryResult, models::Report};
+use crate::{
+    error::RepositoryResult,
+    models::{ErasureResult, Report},
+};
 
 #[async_trait]
 pub trait ReportRepository: Send + Sync {
-    async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<Report>>;
-    async fn create(&self) -> RepositoryResult<Report>;
-    async fn delete(&self, id: Uuid) -> RepositoryResult<Report>;
+    async fn find_by_id(&self, organization_id: Uuid, id: Uuid)
+    -> RepositoryResult<Option<Report>>;
+    async fn find_by_device_id(
+        &self,
+        organization_id: Uuid,
+        device_id: Uuid,
+    ) -> RepositoryResult<Vec<Report>>;
+    async fn find_by_batch_id(
+        &self,
+        organization_id: Uuid,
+        batch_id: Uuid,
+    ) -> RepositoryResult<Vec<Report>>;
+    async fn create(
+        &self,
+        organization_id: Uuid,
+        device_id: Uuid,
+        method: String,
+        result: ErasureResult,
+        started_at: DateTime<Utc>,
+        finished_at: DateTime<Utc>,
+    ) -> RepositoryResult<Report>;
+    async fn delete(&self, organization_id: Uuid, id: Uuid) -> RepositoryResult<Report>;
 }
 
 #[derive(Clone)]
@@ -24,39 +47,94 @@ impl PostgresReportRepository {
 
 #[async_trait]
 impl ReportRepository for PostgresReportRepository {
-    async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<Report>> {
+    async fn find_by_id(
+        &self,
+        organization_id: Uuid,
+        id: Uuid,
+    ) -> RepositoryResult<Option<Report>> {
         let query = "
-            SELECT *
-            FROM reports
-            WHERE id = $1
-            RETURNING *;  
+            SELECT * FROM reports
+            WHERE organization_id = $1 AND id = $2;
         ";
         let report = sqlx::query_as::<_, Report>(query)
+            .bind(organization_id)
             .bind(id)
             .fetch_optional(&self.pool)
             .await?;
         Ok(report)
     }
 
-    async fn create(&self) -> RepositoryResult<Report> {
+    async fn find_by_device_id(
+        &self,
+        organization_id: Uuid,
+        device_id: Uuid,
+    ) -> RepositoryResult<Vec<Report>> {
+        let query = "
+            SELECT * FROM reports
+            WHERE organization_id = $1 AND device_id = $2
+            ORDER BY finished_at;
+        ";
+        let reports = sqlx::query_as::<_, Report>(query)
+            .bind(organization_id)
+            .bind(device_id)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(reports)
+    }
+
+    async fn find_by_batch_id(
+        &self,
+        organization_id: Uuid,
+        batch_id: Uuid,
+    ) -> RepositoryResult<Vec<Report>> {
+        let query = "
+            SELECT reports.* FROM reports
+            JOIN devices ON devices.id = reports.device_id
+            WHERE reports.organization_id = $1 AND devices.batch_id = $2
+            ORDER BY reports.finished_at;
+        ";
+        let reports = sqlx::query_as::<_, Report>(query)
+            .bind(organization_id)
+            .bind(batch_id)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(reports)
+    }
+
+    async fn create(
+        &self,
+        organization_id: Uuid,
+        device_id: Uuid,
+        method: String,
+        result: ErasureResult,
+        started_at: DateTime<Utc>,
+        finished_at: DateTime<Utc>,
+    ) -> RepositoryResult<Report> {
         let query = "
-            INSERT INTO reports
-            DEFAULT VALUES
+            INSERT INTO reports (organization_id, device_id, method, result, started_at, finished_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
             RETURNING *;
         ";
         let report = sqlx::query_as::<_, Report>(query)
+            .bind(organization_id)
+            .bind(device_id)
+            .bind(&method)
+            .bind(result)
+            .bind(started_at)
+            .bind(finished_at)
             .fetch_one(&self.pool)
             .await?;
         Ok(report)
     }
 
-    async fn delete(&self, id: Uuid) -> RepositoryResult<Report> {
+    async fn delete(&self, organization_id: Uuid, id: Uuid) -> RepositoryResult<Report> {
         let query = "
             DELETE FROM reports
-            WHERE id = $1
+            WHERE organization_id = $1 AND id = $2
             RETURNING *;  
         ";
         let report = sqlx::query_as::<_, Report>(query)
+            .bind(organization_id)
             .bind(id)
             .fetch_one(&self.pool)
             .await?;
@@ -0,0 +1,146 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{error::RepositoryResult, models::Session};
+
+#[async_trait]
+pub trait SessionRepository: Send + Sync {
+    async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<Session>>;
+    /// Sessions of `user_id` that still hold a refresh token which can be cycled.
+    async fn find_active_by_user_id(&self, user_id: Uuid) -> RepositoryResult<Vec<Session>>;
+    async fn create(
+        &self,
+        user_id: Uuid,
+        user_agent: Option<String>,
+        ip_address: Option<String>,
+        is_mfa_verified: bool,
+    ) -> RepositoryResult<Session>;
+    async fn touch(
+        &self,
+        id: Uuid,
+        user_agent: Option<String>,
+        ip_address: Option<String>,
+    ) -> RepositoryResult<Session>;
+    async fn set_organization(&self, id: Uuid, organization_id: Uuid) -> RepositoryResult<Session>;
+    /// Deletes sessions whose refresh tokens have all been purged and returns how many.
+    async fn delete_without_refresh_tokens(&self) -> RepositoryResult<u64>;
+}
+
+#[derive(Clone)]
+pub struct PostgresSessionRepository {
+    pool: PgPool,
+}
+
+impl PostgresSessionRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl SessionRepository for PostgresSessionRepository {
+    async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<Session>> {
+        let query = "
+            SELECT * FROM sessions
+            WHERE id = $1;
+        ";
+        let session = sqlx::query_as::<_, Session>(query)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(session)
+    }
+
+    async fn find_active_by_user_id(&self, user_id: Uuid) -> RepositoryResult<Vec<Session>> {
+        let query = "
+            SELECT * FROM sessions
+            WHERE user_id = $1 AND EXISTS (
+                SELECT 1 FROM refresh_tokens
+                WHERE refresh_tokens.session_id = sessions.id
+                    AND refresh_tokens.is_used = false
+                    AND refresh_tokens.is_revoked = false
+                    AND refresh_tokens.expires_at > NOW()
+            )
+            ORDER BY last_used_at DESC;
+        ";
+        let sessions = sqlx::query_as::<_, Session>(query)
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(sessions)
+    }
+
+    async fn create(
+        &self,
+        user_id: Uuid,
+        user_agent: Option<String>,
+        ip_address: Option<String>,
+        is_mfa_verified: bool,
+    ) -> RepositoryResult<Session> {
+        let query = "
+            INSERT INTO sessions (user_id, user_agent, ip_address, is_mfa_verified)
+            VALUES ($1, $2, $3, $4)
+            RETURNING *;
+        ";
+        let session = sqlx::query_as::<_, Session>(query)
+            .bind(user_id)
+            .bind(&user_agent)
+            .bind(&ip_address)
+            .bind(is_mfa_verified)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(session)
+    }
+
+    async fn touch(
+        &self,
+        id: Uuid,
+        user_agent: Option<String>,
+        ip_address: Option<String>,
+    ) -> RepositoryResult<Session> {
+        let query = "
+            UPDATE sessions
+            SET
+                user_agent = COALESCE($2, user_agent),
+                ip_address = COALESCE($3, ip_address),
+                last_used_at = NOW()
+            WHERE id = $1
+            RETURNING *;
+        ";
+        let session = sqlx::query_as::<_, Session>(query)
+            .bind(id)
+            .bind(&user_agent)
+            .bind(&ip_address)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(session)
+    }
+
+    async fn set_organization(&self, id: Uuid, organization_id: Uuid) -> RepositoryResult<Session> {
+        let query = "
+            UPDATE sessions
+            SET organization_id = $2
+            WHERE id = $1
+            RETURNING *;
+        ";
+        let session = sqlx::query_as::<_, Session>(query)
+            .bind(id)
+            .bind(organization_id)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(session)
+    }
+
+    async fn delete_without_refresh_tokens(&self) -> RepositoryResult<u64> {
+        let query = "
+            DELETE FROM sessions
+            WHERE NOT EXISTS (
+                SELECT 1 FROM refresh_tokens
+                WHERE refresh_tokens.session_id = sessions.id
+            );
+        ";
+        let result = sqlx::query(query).execute(&self.pool).await?;
+        Ok(result.rows_affected())
+    }
+}
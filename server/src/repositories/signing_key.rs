@@ -0,0 +1,180 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{
+    error::RepositoryResult,
+    models::{SigningAlgorithm, SigningKey},
+};
+
+#[async_trait]
+pub trait SigningKeyRepository: Send + Sync {
+    async fn find_active(&self) -> RepositoryResult<Vec<SigningKey>>;
+    /// Returns the current key instead if another replica created one first.
+    async fn create_first(
+        &self,
+        algorithm: SigningAlgorithm,
+        private_key: Vec<u8>,
+        private_key_encrypted: bool,
+        public_key: Option<Vec<u8>>,
+    ) -> RepositoryResult<SigningKey>;
+    async fn update_private_key(
+        &self,
+        id: Uuid,
+        private_key: Vec<u8>,
+        private_key_encrypted: bool,
+    ) -> RepositoryResult<SigningKey>;
+    /// Retires the current keys at `retires_at` in favour of a new one.
+    async fn rotate(
+        &self,
+        algorithm: SigningAlgorithm,
+        private_key: Vec<u8>,
+        private_key_encrypted: bool,
+        public_key: Option<Vec<u8>>,
+        retires_at: DateTime<Utc>,
+    ) -> RepositoryResult<SigningKey>;
+}
+
+#[derive(Clone)]
+pub struct PostgresSigningKeyRepository {
+    pool: PgPool,
+}
+
+impl PostgresSigningKeyRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl SigningKeyRepository for PostgresSigningKeyRepository {
+    async fn find_active(&self) -> RepositoryResult<Vec<SigningKey>> {
+        let query = "
+            SELECT * FROM signing_keys
+            WHERE retires_at IS NULL OR retires_at > NOW()
+            ORDER BY created_at DESC;
+        ";
+        let signing_keys = sqlx::query_as::<_, SigningKey>(query)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(signing_keys)
+    }
+
+    async fn create_first(
+        &self,
+        algorithm: SigningAlgorithm,
+        private_key: Vec<u8>,
+        private_key_encrypted: bool,
+        public_key: Option<Vec<u8>>,
+    ) -> RepositoryResult<SigningKey> {
+        let mut transaction = self.pool.begin().await?;
+        lock_signing_keys(&mut transaction).await?;
+        let current_query = "
+            SELECT * FROM signing_keys
+            WHERE retires_at IS NULL
+            ORDER BY created_at DESC
+            LIMIT 1;
+        ";
+        let current_key = sqlx::query_as::<_, SigningKey>(current_query)
+            .fetch_optional(&mut *transaction)
+            .await?;
+        let signing_key = match current_key {
+            Some(signing_key) => signing_key,
+            None => {
+                insert(
+                    &mut transaction,
+                    algorithm,
+                    private_key,
+                    private_key_encrypted,
+                    public_key,
+                )
+                .await?
+            }
+        };
+        transaction.commit().await?;
+        Ok(signing_key)
+    }
+
+    async fn update_private_key(
+        &self,
+        id: Uuid,
+        private_key: Vec<u8>,
+        private_key_encrypted: bool,
+    ) -> RepositoryResult<SigningKey> {
+        let query = "
+            UPDATE signing_keys
+            SET private_key = $2, private_key_encrypted = $3
+            WHERE id = $1
+            RETURNING *;
+        ";
+        let signing_key = sqlx::query_as::<_, SigningKey>(query)
+            .bind(id)
+            .bind(&private_key)
+            .bind(private_key_encrypted)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(signing_key)
+    }
+
+    async fn rotate(
+        &self,
+        algorithm: SigningAlgorithm,
+        private_key: Vec<u8>,
+        private_key_encrypted: bool,
+        public_key: Option<Vec<u8>>,
+        retires_at: DateTime<Utc>,
+    ) -> RepositoryResult<SigningKey> {
+        let mut transaction = self.pool.begin().await?;
+        lock_signing_keys(&mut transaction).await?;
+        let retire_query = "
+            UPDATE signing_keys
+            SET retires_at = $1
+            WHERE retires_at IS NULL;
+        ";
+        sqlx::query(retire_query)
+            .bind(retires_at)
+            .execute(&mut *transaction)
+            .await?;
+        let signing_key = insert(
+            &mut transaction,
+            algorithm,
+            private_key,
+            private_key_encrypted,
+            public_key,
+        )
+        .await?;
+        transaction.commit().await?;
+        Ok(signing_key)
+    }
+}
+
+/// Serializes key creation across replicas, so there is only ever one current key.
+async fn lock_signing_keys(transaction: &mut Transaction<'_, Postgres>) -> RepositoryResult<()> {
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtext('signing_keys'));")
+        .execute(&mut **transaction)
+        .await?;
+    Ok(())
+}
+
+async fn insert(
+    transaction: &mut Transaction<'_, Postgres>,
+    algorithm: SigningAlgorithm,
+    private_key: Vec<u8>,
+    private_key_encrypted: bool,
+    public_key: Option<Vec<u8>>,
+) -> RepositoryResult<SigningKey> {
+    let query = "
+        INSERT INTO signing_keys (algorithm, private_key, private_key_encrypted, public_key)
+        VALUES ($1, $2, $3, $4)
+        RETURNING *;
+    ";
+    let signing_key = sqlx::query_as::<_, SigningKey>(query)
+        .bind(algorithm)
+        .bind(&private_key)
+        .bind(private_key_encrypted)
+        .bind(&public_key)
+        .fetch_one(&mut **transaction)
+        .await?;
+    Ok(signing_key)
+}
@@ -0,0 +1,221 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{error::RepositoryResult, models::Station};
+
+#[async_trait]
+pub trait StationRepository: Send + Sync {
+    async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<Station>>;
+    async fn find_by_organization_id(
+        &self,
+        organization_id: Uuid,
+    ) -> RepositoryResult<Vec<Station>>;
+    async fn find_by_enrollment_code_hash(
+        &self,
+        enrollment_code_hash: &str,
+    ) -> RepositoryResult<Option<Station>>;
+    async fn create(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+        name: String,
+        enrollment_code_hash: String,
+        enrollment_code_expires_at: DateTime<Utc>,
+    ) -> RepositoryResult<Station>;
+    async fn update(
+        &self,
+        id: Uuid,
+        name: Option<String>,
+        is_disabled: Option<bool>,
+    ) -> RepositoryResult<Station>;
+    /// Forgets the hardware the station was enrolled on and hands out a new code.
+    async fn reset_enrollment(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        enrollment_code_hash: String,
+        enrollment_code_expires_at: DateTime<Utc>,
+    ) -> RepositoryResult<Station>;
+    /// Binds the station to its hardware and uses up the enrollment code. Returns
+    /// `None` if the code was used in the meantime.
+    async fn enroll(
+        &self,
+        id: Uuid,
+        hardware_fingerprint: String,
+    ) -> RepositoryResult<Option<Station>>;
+    async fn delete(&self, id: Uuid) -> RepositoryResult<Option<Station>>;
+}
+
+#[derive(Clone)]
+pub struct PostgresStationRepository {
+    pool: PgPool,
+}
+
+impl PostgresStationRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl StationRepository for PostgresStationRepository {
+    async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<Station>> {
+        let query = "
+            SELECT * FROM stations
+            WHERE id = $1;
+        ";
+        let station = sqlx::query_as::<_, Station>(query)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(station)
+    }
+
+    async fn find_by_organization_id(
+        &self,
+        organization_id: Uuid,
+    ) -> RepositoryResult<Vec<Station>> {
+        let query = "
+            SELECT * FROM stations
+            WHERE organization_id = $1
+            ORDER BY name;
+        ";
+        let stations = sqlx::query_as::<_, Station>(query)
+            .bind(organization_id)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(stations)
+    }
+
+    async fn find_by_enrollment_code_hash(
+        &self,
+        enrollment_code_hash: &str,
+    ) -> RepositoryResult<Option<Station>> {
+        let query = "
+            SELECT * FROM stations
+            WHERE enrollment_code_hash = $1;
+        ";
+        let station = sqlx::query_as::<_, Station>(query)
+            .bind(enrollment_code_hash)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(station)
+    }
+
+    async fn create(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+        name: String,
+        enrollment_code_hash: String,
+        enrollment_code_expires_at: DateTime<Utc>,
+    ) -> RepositoryResult<Station> {
+        let query = "
+            INSERT INTO stations (organization_id, user_id, name, enrollment_code_hash, enrollment_code_expires_at)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *;
+        ";
+        let station = sqlx::query_as::<_, Station>(query)
+            .bind(organization_id)
+            .bind(user_id)
+            .bind(&name)
+            .bind(&enrollment_code_hash)
+            .bind(enrollment_code_expires_at)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(station)
+    }
+
+    async fn update(
+        &self,
+        id: Uuid,
+        name: Option<String>,
+        is_disabled: Option<bool>,
+    ) -> RepositoryResult<Station> {
+        let query = "
+            UPDATE stations
+            SET
+                name = COALESCE($2, name),
+                disabled_at = CASE
+                    WHEN $3 IS NULL THEN disabled_at
+                    WHEN $3 THEN COALESCE(disabled_at, NOW())
+                    ELSE NULL
+                END
+            WHERE id = $1
+            RETURNING *;
+        ";
+        let station = sqlx::query_as::<_, Station>(query)
+            .bind(id)
+            .bind(name)
+            .bind(is_disabled)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(station)
+    }
+
+    async fn reset_enrollment(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        enrollment_code_hash: String,
+        enrollment_code_expires_at: DateTime<Utc>,
+    ) -> RepositoryResult<Station> {
+        let query = "
+            UPDATE stations
+            SET
+                user_id = $2,
+                enrollment_code_hash = $3,
+                enrollment_code_expires_at = $4,
+                hardware_fingerprint = NULL,
+                enrolled_at = NULL
+            WHERE id = $1
+            RETURNING *;
+        ";
+        let station = sqlx::query_as::<_, Station>(query)
+            .bind(id)
+            .bind(user_id)
+            .bind(&enrollment_code_hash)
+            .bind(enrollment_code_expires_at)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(station)
+    }
+
+    async fn enroll(
+        &self,
+        id: Uuid,
+        hardware_fingerprint: String,
+    ) -> RepositoryResult<Option<Station>> {
+        let query = "
+            UPDATE stations
+            SET
+                hardware_fingerprint = $2,
+                enrolled_at = NOW(),
+                enrollment_code_hash = NULL,
+                enrollment_code_expires_at = NULL
+            WHERE id = $1 AND enrollment_code_hash IS NOT NULL
+            RETURNING *;
+        ";
+        let station = sqlx::query_as::<_, Station>(query)
+            .bind(id)
+            .bind(&hardware_fingerprint)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(station)
+    }
+
+    async fn delete(&self, id: Uuid) -> RepositoryResult<Option<Station>> {
+        let query = "
+            DELETE FROM stations
+            WHERE id = $1
+            RETURNING *;
+        ";
+        let station = sqlx::query_as::<_, Station>(query)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(station)
+    }
+}
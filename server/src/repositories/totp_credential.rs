@@ -0,0 +1,104 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{error::RepositoryResult, models::TotpCredential};
+
+#[async_trait]
+pub trait TotpCredentialRepository: Send + Sync {
+    async fn find_by_user_id(&self, user_id: Uuid) -> RepositoryResult<Option<TotpCredential>>;
+    /// Stores a new unconfirmed secret for `user_id`, replacing any previous one.
+    async fn upsert(&self, user_id: Uuid, secret: Vec<u8>) -> RepositoryResult<TotpCredential>;
+    async fn confirm(&self, user_id: Uuid) -> RepositoryResult<TotpCredential>;
+    /// Records `step` as used unless it is not newer than the last used step, in which
+    /// case `None` is returned and the code has to be rejected as a replay.
+    async fn use_step(&self, user_id: Uuid, step: i64) -> RepositoryResult<Option<TotpCredential>>;
+    async fn delete(&self, user_id: Uuid) -> RepositoryResult<Option<TotpCredential>>;
+}
+
+#[derive(Clone)]
+pub struct PostgresTotpCredentialRepository {
+    pool: PgPool,
+}
+
+impl PostgresTotpCredentialRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl TotpCredentialRepository for PostgresTotpCredentialRepository {
+    async fn find_by_user_id(&self, user_id: Uuid) -> RepositoryResult<Option<TotpCredential>> {
+        let query = "
+            SELECT * FROM totp_credentials
+            WHERE user_id = $1;
+        ";
+        let totp_credential = sqlx::query_as::<_, TotpCredential>(query)
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(totp_credential)
+    }
+
+    async fn upsert(&self, user_id: Uuid, secret: Vec<u8>) -> RepositoryResult<TotpCredential> {
+        let query = "
+            INSERT INTO totp_credentials (user_id, secret)
+            VALUES ($1, $2)
+            ON CONFLICT (user_id) DO UPDATE
+            SET
+                secret = EXCLUDED.secret,
+                confirmed_at = NULL,
+                last_used_step = NULL
+            RETURNING *;
+        ";
+        let totp_credential = sqlx::query_as::<_, TotpCredential>(query)
+            .bind(user_id)
+            .bind(&secret)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(totp_credential)
+    }
+
+    async fn confirm(&self, user_id: Uuid) -> RepositoryResult<TotpCredential> {
+        let query = "
+            UPDATE totp_credentials
+            SET confirmed_at = NOW()
+            WHERE user_id = $1
+            RETURNING *;
+        ";
+        let totp_credential = sqlx::query_as::<_, TotpCredential>(query)
+            .bind(user_id)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(totp_credential)
+    }
+
+    async fn use_step(&self, user_id: Uuid, step: i64) -> RepositoryResult<Option<TotpCredential>> {
+        let query = "
+            UPDATE totp_credentials
+            SET last_used_step = $2
+            WHERE user_id = $1 AND (last_used_step IS NULL OR last_used_step < $2)
+            RETURNING *;
+        ";
+        let totp_credential = sqlx::query_as::<_, TotpCredential>(query)
+            .bind(user_id)
+            .bind(step)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(totp_credential)
+    }
+
+    async fn delete(&self, user_id: Uuid) -> RepositoryResult<Option<TotpCredential>> {
+        let query = "
+            DELETE FROM totp_credentials
+            WHERE user_id = $1
+            RETURNING *;
+        ";
+        let totp_credential = sqlx::query_as::<_, TotpCredential>(query)
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(totp_credential)
+    }
+}
@@ -1,4 +1,7 @@
-use crate::{error::RepositoryResult, models::User};
+use crate::{
+    error::RepositoryResult,
+    models::{Role, User},
+};
 use async_trait::async_trait;
 use sqlx::PgPool;
 use uuid::Uuid;
@@ -7,8 +10,20 @@ use uuid::Uuid;
 pub trait UserRepository: Send + Sync {
     async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<User>>;
     async fn find_by_email(&self, email: &str) -> RepositoryResult<Option<User>>;
-    async fn create(&self, email: String, password_hash: String) -> RepositoryResult<User>;
-    async fn update(&self, id: Uuid, email: Option<String>) -> RepositoryResult<User>;
+    async fn create(
+        &self,
+        email: String,
+        password_hash: String,
+        role: Role,
+    ) -> RepositoryResult<User>;
+    async fn update(
+        &self,
+        id: Uuid,
+        email: Option<String>,
+        role: Option<Role>,
+    ) -> RepositoryResult<User>;
+    async fn update_password_hash(&self, id: Uuid, password_hash: String)
+    -> RepositoryResult<User>;
     async fn delete(&self, id: Uuid) -> RepositoryResult<User>;
 }
 
@@ -49,30 +64,62 @@ impl UserRepository for PostgresUserRepository {
         Ok(user)
     }
 
-    async fn create(&self, email: String, password_hash: String) -> RepositoryResult<User> {
+    async fn create(
+        &self,
+        email: String,
+        password_hash: String,
+        role: Role,
+    ) -> RepositoryResult<User> {
         let query = "
-            INSERT INTO users (email, password_hash)
-            VALUES ($1, $2)
+            INSERT INTO users (email, password_hash, role)
+            VALUES ($1, $2, $3)
             RETURNING *;
         ";
         let user = sqlx::query_as::<_, User>(query)
             .bind(&email)
             .bind(&password_hash)
+            .bind(role)
             .fetch_one(&self.pool)
             .await?;
         Ok(user)
     }
 
-    async fn update(&self, id: Uuid, email: Option<String>) -> RepositoryResult<User> {
+    async fn update(
+        &self,
+        id: Uuid,
+        email: Option<String>,
+        role: Option<Role>,
+    ) -> RepositoryResult<User> {
         let query = "
             UPDATE users
-            SET email = $1
-            WHERE id = $2
+            SET email = COALESCE($2, email),
+                role = COALESCE($3, role)
+            WHERE id = $1
             RETURNING *;
         ";
         let user = sqlx::query_as::<_, User>(query)
             .bind(id)
             .bind(&email)
+            .bind(role)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(user)
+    }
+
+    async fn update_password_hash(
+        &self,
+        id: Uuid,
+        password_hash: String,
+    ) -> RepositoryResult<User> {
+        let query = "
+            UPDATE users
+            SET password_hash = $2
+            WHERE id = $1
+            RETURNING *;
+        ";
+        let user = sqlx::query_as::<_, User>(query)
+            .bind(id)
+            .bind(&password_hash)
             .fetch_one(&self.pool)
             .await?;
         Ok(user)
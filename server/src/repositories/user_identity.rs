@@ -0,0 +1,84 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{error::RepositoryResult, models::UserIdentity};
+
+#[async_trait]
+pub trait UserIdentityRepository: Send + Sync {
+    async fn find_by_subject(
+        &self,
+        issuer: &str,
+        subject: &str,
+    ) -> RepositoryResult<Option<UserIdentity>>;
+    async fn find_all_by_user_id(&self, user_id: Uuid) -> RepositoryResult<Vec<UserIdentity>>;
+    async fn create(
+        &self,
+        user_id: Uuid,
+        issuer: String,
+        subject: String,
+    ) -> RepositoryResult<UserIdentity>;
+}
+
+#[derive(Clone)]
+pub struct PostgresUserIdentityRepository {
+    pool: PgPool,
+}
+
+impl PostgresUserIdentityRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl UserIdentityRepository for PostgresUserIdentityRepository {
+    async fn find_by_subject(
+        &self,
+        issuer: &str,
+        subject: &str,
+    ) -> RepositoryResult<Option<UserIdentity>> {
+        let query = "
+            SELECT * FROM user_identities
+            WHERE issuer = $1 AND subject = $2;
+        ";
+        let user_identity = sqlx::query_as::<_, UserIdentity>(query)
+            .bind(issuer)
+            .bind(subject)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(user_identity)
+    }
+
+    async fn find_all_by_user_id(&self, user_id: Uuid) -> RepositoryResult<Vec<UserIdentity>> {
+        let query = "
+            SELECT * FROM user_identities
+            WHERE user_id = $1;
+        ";
+        let user_identities = sqlx::query_as::<_, UserIdentity>(query)
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(user_identities)
+    }
+
+    async fn create(
+        &self,
+        user_id: Uuid,
+        issuer: String,
+        subject: String,
+    ) -> RepositoryResult<UserIdentity> {
+        let query = "
+            INSERT INTO user_identities (user_id, issuer, subject)
+            VALUES ($1, $2, $3)
+            RETURNING *;
+        ";
+        let user_identity = sqlx::query_as::<_, UserIdentity>(query)
+            .bind(user_id)
+            .bind(&issuer)
+            .bind(&subject)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(user_identity)
+    }
+}
@@ -0,0 +1,24 @@
+use axum::{
+    Router,
+    routing::{delete, get, post},
+};
+
+use crate::{
+    handlers::api_keys::{delete_api_key, get_api_keys, post_api_key},
+    models::Permission,
+    routes::require,
+    state::AppState,
+};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/",
+            require(Permission::ManageApiKeys, get(get_api_keys))
+                .merge(require(Permission::ManageApiKeys, post(post_api_key))),
+        )
+        .route(
+            "/{uuid}",
+            require(Permission::ManageApiKeys, delete(delete_api_key)),
+        )
+}
@@ -0,0 +1,46 @@
+use axum::{
+    Router,
+    routing::{delete, get, patch, post},
+};
+
+use crate::{
+    handlers::batches::{
+        delete_batch, get_batch, get_batch_certificate, get_batch_devices, get_batch_reports,
+        get_batch_summary, get_batches, patch_batch, post_batch, post_batch_device,
+    },
+    models::Permission,
+    routes::require,
+    state::AppState,
+};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/",
+            require(Permission::ReadBatches, get(get_batches))
+                .merge(require(Permission::WriteBatches, post(post_batch))),
+        )
+        .route(
+            "/{uuid}",
+            require(Permission::ReadBatches, get(get_batch))
+                .merge(require(Permission::WriteBatches, patch(patch_batch)))
+                .merge(require(Permission::DeleteBatches, delete(delete_batch))),
+        )
+        .route(
+            "/{uuid}/devices",
+            require(Permission::ReadBatches, get(get_batch_devices))
+                .merge(require(Permission::WriteBatches, post(post_batch_device))),
+        )
+        .route(
+            "/{uuid}/reports",
+            require(Permission::ReadReports, get(get_batch_reports)),
+        )
+        .route(
+            "/{uuid}/summary",
+            require(Permission::ReadBatches, get(get_batch_summary)),
+        )
+        .route(
+            "/{uuid}/certificate",
+            require(Permission::ReadReports, get(get_batch_certificate)),
+        )
+}
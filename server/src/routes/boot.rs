@@ -0,0 +1,13 @@
+use axum::{Router, routing::get};
+
+use crate::{
+    handlers::boot::{get_boot_artifact, get_ipxe_script},
+    state::AppState,
+};
+
+/// Public, as neither iPXE nor the initramfs can authenticate.
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/{architecture}/boot.ipxe", get(get_ipxe_script))
+        .route("/images/{uuid}/{*path}", get(get_boot_artifact))
+}
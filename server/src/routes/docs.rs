@@ -1,12 +1,140 @@
-use utoipa::OpenApi;
+use open_erase_lib::schemas::error::{ErrorCode, ErrorResponse, FieldError};
+use utoipa::{
+    Modify, OpenApi,
+    openapi::{
+        OpenApi as OpenApiDocument,
+        security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme},
+    },
+};
 use utoipa_swagger_ui::SwaggerUi;
 
+use crate::{
+    handlers::{
+        api_keys, auth, batches, boot, images, mfa, organizations, reports, sessions, signing_keys,
+        stations, users,
+    },
+    middleware::{auth::REFRESH_TOKEN_COOKIE, rate_limit::API_KEY_HEADER},
+};
+
 const DOCS_PATH: &str = "/docs";
 const OPENAPI_PATH: &str = "/api-docs/openapi.json";
 
 #[derive(OpenApi)]
+#[openapi(
+    servers((url = "/api")),
+    paths(
+        auth::login,
+        auth::verify_mfa,
+        auth::get_oidc,
+        auth::oidc_login,
+        auth::oidc_callback,
+        auth::refresh,
+        auth::switch_organization,
+        auth::logout,
+        auth::jwks,
+        auth::request_password_reset,
+        auth::confirm_password_reset,
+        stations::enroll,
+        users::get_user,
+        users::get_me,
+        users::post_password,
+        users::post_user,
+        users::patch_user,
+        users::delete_user,
+        users::post_unlock_user,
+        sessions::get_my_sessions,
+        sessions::delete_my_sessions,
+        sessions::delete_my_session,
+        sessions::get_user_sessions,
+        sessions::delete_user_sessions,
+        sessions::delete_user_session,
+        mfa::get_my_mfa,
+        mfa::post_my_totp,
+        mfa::post_my_totp_confirm,
+        mfa::post_my_recovery_codes,
+        mfa::delete_my_mfa,
+        batches::get_batches,
+        batches::get_batch,
+        batches::post_batch,
+        batches::patch_batch,
+        batches::delete_batch,
+        batches::get_batch_devices,
+        batches::post_batch_device,
+        batches::get_batch_reports,
+        batches::get_batch_summary,
+        batches::get_batch_certificate,
+        reports::get_report,
+        reports::post_report,
+        images::get_images,
+        images::get_image,
+        images::post_image,
+        images::patch_image,
+        images::get_image_variants,
+        images::post_image_variant,
+        images::download_image,
+        images::get_image_checksums,
+        boot::get_ipxe_script,
+        boot::get_boot_artifact,
+        organizations::get_organizations,
+        organizations::get_organization,
+        organizations::post_organization,
+        organizations::get_organization_members,
+        organizations::post_organization_member,
+        organizations::delete_organization_member,
+        organizations::post_organization_invitation,
+        organizations::get_my_organization_invitations,
+        organizations::accept_organization_invitation,
+        organizations::decline_organization_invitation,
+        signing_keys::get_signing_keys,
+        signing_keys::post_signing_key,
+        api_keys::get_api_keys,
+        api_keys::post_api_key,
+        api_keys::delete_api_key,
+        stations::get_stations,
+        stations::get_station,
+        stations::post_station,
+        stations::patch_station,
+        stations::post_station_enrollment_code,
+    ),
+    components(schemas(ErrorResponse, ErrorCode, FieldError)),
+    modifiers(&SecuritySchemes),
+)]
 pub struct Doc;
 
+/// Ways to authenticate that the `security` of the paths refer to.
+struct SecuritySchemes;
+
+impl Modify for SecuritySchemes {
+    fn modify(&self, openapi: &mut OpenApiDocument) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "basic",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Basic).build()),
+        );
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .build(),
+            ),
+        );
+        components.add_security_scheme(
+            "refresh_token",
+            SecurityScheme::ApiKey(ApiKey::Cookie(ApiKeyValue::new(REFRESH_TOKEN_COOKIE))),
+        );
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::with_description(
+                API_KEY_HEADER,
+                "Keys of enrolled stations also need the hardware fingerprint in \
+                 `x-station-fingerprint`.",
+            ))),
+        );
+    }
+}
+
 pub fn router() -> SwaggerUi {
     SwaggerUi::new(DOCS_PATH).url(OPENAPI_PATH, Doc::openapi())
 }
@@ -1,7 +1,45 @@
-use axum::{Router, routing::get};
+use axum::{
+    Router,
+    extract::DefaultBodyLimit,
+    routing::{get, patch, post},
+};
 
-use crate::{handlers::images, state::AppState};
+use crate::{
+    handlers::images::{
+        download_image, get_image, get_image_checksums, get_image_variants, get_images,
+        patch_image, post_image, post_image_variant,
+    },
+    models::Permission,
+    routes::require,
+    state::AppState,
+};
 
 pub fn router() -> Router<AppState> {
-    Router::new().route("/", get(images::images))
+    Router::new()
+        .route(
+            "/",
+            require(Permission::ReadImages, get(get_images)).merge(require(
+                Permission::ManageBaseImages,
+                // the upload limit is enforced while the image is streamed to disk
+                post(post_image).layer(DefaultBodyLimit::disable()),
+            )),
+        )
+        .route(
+            "/{uuid}",
+            require(Permission::ReadImages, get(get_image))
+                .merge(require(Permission::WriteImages, patch(patch_image))),
+        )
+        .route(
+            "/{uuid}/variants",
+            require(Permission::ReadImages, get(get_image_variants))
+                .merge(require(Permission::WriteImages, post(post_image_variant))),
+        )
+        .route(
+            "/{uuid}/download",
+            require(Permission::ReadImages, get(download_image)),
+        )
+        .route(
+            "/SHA256SUMS",
+            require(Permission::ReadImages, get(get_image_checksums)),
+        )
 }
@@ -1,49 +1,83 @@
 use std::time::Duration;
 
-use axum::{Router, middleware, routing::post};
+use axum::{
+    Router,
+    http::Method,
+    middleware,
+    routing::{MethodRouter, get, post},
+};
 use tower::ServiceBuilder;
 use tower_http::{
     CompressionLevel,
     compression::CompressionLayer,
     services::{ServeDir, ServeFile},
-    timeout::TimeoutLayer,
     trace::{DefaultMakeSpan, DefaultOnRequest, DefaultOnResponse, TraceLayer},
 };
 use tracing::Level;
 
 use crate::{
+    config::{Config, ServerConfig},
     error::{AppResult, ClientError},
-    handlers::auth::refresh,
-    middleware::auth::validate_refresh_token,
+    handlers::auth::{
+        confirm_password_reset, get_oidc, jwks, oidc_callback, oidc_login, refresh,
+        request_password_reset, switch_organization, verify_mfa,
+    },
+    handlers::stations::enroll,
+    middleware::auth::{authorize, validate_refresh_token},
+    models::Permission,
 };
 use crate::{
     handlers::auth::login,
     middleware::{
-        auth::{validate_access_token, validate_basic_auth},
+        auth::{validate_access_token, validate_api_key, validate_basic_auth},
         log::log,
+        rate_limit::rate_limit,
+        request_id::request_id,
+        timeout::timeout,
     },
+    services::rate_limit::RateLimitGroup,
 };
 use crate::{handlers::auth::logout, state::AppState};
 
-mod docs;
+mod api_keys;
+mod batches;
+mod boot;
+pub(crate) mod docs;
 mod images;
+mod organizations;
+mod reports;
+mod signing_keys;
+mod stations;
 mod users;
 
 const API_PATH: &str = "/api";
+const API_KEYS_PATH: &str = "/api-keys";
 const AUTH_PATH: &str = "/auth";
+const BATCHES_PATH: &str = "/batches";
+const BOOT_PATH: &str = "/boot";
+const ENROLL_PATH: &str = "/enroll";
 const IMAGES_PATH: &str = "/images";
+const JWKS_PATH: &str = "/jwks";
 const LOGIN_PATH: &str = "/login";
 const LOGOUT_PATH: &str = "/logout";
+const MFA_VERIFY_PATH: &str = "/mfa/verify";
+const OIDC_PATH: &str = "/oidc";
+const OIDC_CALLBACK_PATH: &str = "/oidc/callback";
+const OIDC_LOGIN_PATH: &str = "/oidc/login";
+const ORGANIZATIONS_PATH: &str = "/organizations";
+const PASSWORD_RESET_PATH: &str = "/password-reset";
+const PASSWORD_RESET_CONFIRM_PATH: &str = "/password-reset/confirm";
 const REFRESH_PATH: &str = "/refresh";
+const REPORTS_PATH: &str = "/reports";
+const SIGNING_KEYS_PATH: &str = "/signing-keys";
+const STATIONS_PATH: &str = "/stations";
+const SWITCH_ORGANIZATION_PATH: &str = "/switch-organization";
 const USERS_PATH: &str = "/users";
 
-const STATIC_ASSETS_PATH: &str = "/web/dist";
-const INDEX_HTML_PATH: &str = "/web/dist/index.html";
-
 pub fn app(state: AppState) -> Router {
     Router::new()
         .merge(api_router(state.clone()))
-        .fallback_service(web_service())
+        .fallback_service(web_service(&state.config.server))
         .method_not_allowed_fallback(method_not_allowed_fallback)
         .layer(
             ServiceBuilder::new()
@@ -53,6 +87,7 @@ pub fn app(state: AppState) -> Router {
                         .on_request(DefaultOnRequest::new().level(Level::INFO))
                         .on_response(DefaultOnResponse::new().level(Level::INFO)),
                 )
+                .layer(middleware::from_fn(request_id))
                 // so far from testing brotli + default quality is best performer
                 .layer(
                     CompressionLayer::new()
@@ -62,7 +97,8 @@ pub fn app(state: AppState) -> Router {
                         .no_deflate()
                         .no_zstd(),
                 )
-                .layer(TimeoutLayer::new(Duration::from_secs(5)))
+                .layer(middleware::from_fn_with_state(state.clone(), timeout))
+                .layer(middleware::from_fn_with_state(state.clone(), rate_limit))
                 .layer(middleware::from_fn(log)),
         )
         .with_state(state)
@@ -75,11 +111,21 @@ pub fn api_router(state: AppState) -> Router<AppState> {
             .nest(
                 AUTH_PATH,
                 Router::new()
+                    .route(ENROLL_PATH, post(enroll))
+                    .route(JWKS_PATH, get(jwks))
+                    .route(MFA_VERIFY_PATH, post(verify_mfa))
+                    .route(OIDC_PATH, get(get_oidc))
+                    .route(OIDC_LOGIN_PATH, get(oidc_login))
+                    .route(OIDC_CALLBACK_PATH, get(oidc_callback))
+                    .route(PASSWORD_RESET_PATH, post(request_password_reset))
+                    .route(PASSWORD_RESET_CONFIRM_PATH, post(confirm_password_reset))
                     .merge(basic_auth_router(state.clone()))
                     .merge(refresh_token_auth_router(state.clone())),
             )
+            .nest(BOOT_PATH, boot::router())
             .merge(access_token_auth_router(state.clone()))
-            .merge(docs::router()),
+            .merge(docs::router())
+            .fallback(api_fallback),
     )
 }
 
@@ -92,6 +138,7 @@ fn basic_auth_router(state: AppState) -> Router<AppState> {
 fn refresh_token_auth_router(state: AppState) -> Router<AppState> {
     Router::new()
         .route(REFRESH_PATH, post(refresh))
+        .route(SWITCH_ORGANIZATION_PATH, post(switch_organization))
         .route(LOGOUT_PATH, post(logout))
         .layer(middleware::from_fn_with_state(
             state,
@@ -103,11 +150,69 @@ fn access_token_auth_router(state: AppState) -> Router<AppState> {
     Router::new()
         .nest(USERS_PATH, users::router())
         .nest(IMAGES_PATH, images::router())
-        .layer(middleware::from_fn_with_state(state, validate_access_token))
+        .nest(BATCHES_PATH, batches::router())
+        .nest(REPORTS_PATH, reports::router())
+        .nest(ORGANIZATIONS_PATH, organizations::router())
+        .nest(SIGNING_KEYS_PATH, signing_keys::router())
+        .nest(API_KEYS_PATH, api_keys::router())
+        .nest(STATIONS_PATH, stations::router())
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            validate_access_token,
+        ))
+        .layer(middleware::from_fn_with_state(state, validate_api_key))
+}
+
+/// Route group whose rate limit applies to `path`, `None` for requests outside the API.
+pub(crate) fn rate_limit_group(path: &str) -> Option<RateLimitGroup> {
+    let api_path = path.strip_prefix(API_PATH)?;
+    if !api_path.is_empty() && !api_path.starts_with('/') {
+        return None;
+    }
+    if api_path.starts_with(AUTH_PATH) {
+        Some(RateLimitGroup::Auth)
+    } else if api_path.starts_with(REPORTS_PATH) {
+        Some(RateLimitGroup::Reports)
+    } else {
+        Some(RateLimitGroup::Default)
+    }
+}
+
+/// Time a request may take. Image uploads and site variants write a whole ISO and get
+/// their own timeout.
+pub(crate) fn request_timeout(config: &Config, method: &Method, path: &str) -> Duration {
+    let Some(images_path) = path
+        .strip_prefix(API_PATH)
+        .and_then(|api_path| api_path.strip_prefix(IMAGES_PATH))
+    else {
+        return config.server.request_timeout();
+    };
+    let is_variant = images_path
+        .strip_prefix('/')
+        .and_then(|image_path| image_path.strip_suffix("/variants"))
+        .is_some_and(|id| !id.is_empty() && !id.contains('/'));
+    if method == Method::POST && (images_path.is_empty() || is_variant) {
+        config.images.upload_timeout()
+    } else {
+        config.server.request_timeout()
+    }
+}
+
+/// Restricts `method_router` to callers whose role grants `permission`.
+fn require(
+    permission: Permission,
+    method_router: MethodRouter<AppState>,
+) -> MethodRouter<AppState> {
+    method_router.route_layer(middleware::from_fn_with_state(permission, authorize))
+}
+
+fn web_service(config: &ServerConfig) -> ServeDir<ServeFile> {
+    ServeDir::new(&config.static_assets_dir).fallback(ServeFile::new(config.index_html_path()))
 }
 
-fn web_service() -> ServeDir<ServeFile> {
-    ServeDir::new(STATIC_ASSETS_PATH).fallback(ServeFile::new(INDEX_HTML_PATH))
+/// Keeps unknown API paths from falling through to the web app's `index.html`.
+async fn api_fallback() -> AppResult<()> {
+    Err(ClientError::UnknownPath.into())
 }
 
 async fn method_not_allowed_fallback() -> AppResult<()> {
@@ -0,0 +1,73 @@
+use axum::{
+    Router,
+    routing::{delete, get, post},
+};
+
+use crate::{
+    handlers::organizations::{
+        accept_organization_invitation, decline_organization_invitation,
+        delete_organization_member, get_my_organization_invitations, get_organization,
+        get_organization_members, get_organizations, post_organization,
+        post_organization_invitation, post_organization_member,
+    },
+    models::Permission,
+    routes::require,
+    state::AppState,
+};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/",
+            require(Permission::ReadOrganizations, get(get_organizations)).merge(require(
+                Permission::ManageOrganizations,
+                post(post_organization),
+            )),
+        )
+        .route(
+            "/invitations",
+            require(
+                Permission::ManageAccount,
+                get(get_my_organization_invitations),
+            ),
+        )
+        .route(
+            "/invitations/{uuid}",
+            require(
+                Permission::ManageAccount,
+                delete(decline_organization_invitation),
+            ),
+        )
+        .route(
+            "/invitations/{uuid}/accept",
+            require(
+                Permission::ManageAccount,
+                post(accept_organization_invitation),
+            ),
+        )
+        .route(
+            "/{uuid}",
+            require(Permission::ReadOrganizations, get(get_organization)),
+        )
+        .route(
+            "/{uuid}/members",
+            require(Permission::ReadOrganizations, get(get_organization_members)).merge(require(
+                Permission::ManageOrganizations,
+                post(post_organization_member),
+            )),
+        )
+        .route(
+            "/{uuid}/invitations",
+            require(
+                Permission::ManageOrganizations,
+                post(post_organization_invitation),
+            ),
+        )
+        .route(
+            "/{uuid}/members/{user_uuid}",
+            require(
+                Permission::ManageOrganizations,
+                delete(delete_organization_member),
+            ),
+        )
+}
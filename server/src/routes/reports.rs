@@ -0,0 +1,17 @@
+use axum::{
+    Router,
+    routing::{get, post},
+};
+
+use crate::{
+    handlers::reports::{get_report, post_report},
+    models::Permission,
+    routes::require,
+    state::AppState,
+};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", require(Permission::WriteReports, post(post_report)))
+        .route("/{uuid}", require(Permission::ReadReports, get(get_report)))
+}
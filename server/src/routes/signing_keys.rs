@@ -0,0 +1,21 @@
+use axum::{
+    Router,
+    routing::{get, post},
+};
+
+use crate::{
+    handlers::signing_keys::{get_signing_keys, post_signing_key},
+    models::Permission,
+    routes::require,
+    state::AppState,
+};
+
+pub fn router() -> Router<AppState> {
+    Router::new().route(
+        "/",
+        require(
+            Permission::ManageSigningKeys,
+            get(get_signing_keys).merge(post(post_signing_key)),
+        ),
+    )
+}
@@ -0,0 +1,34 @@
+use axum::{
+    Router,
+    routing::{get, patch, post},
+};
+
+use crate::{
+    handlers::stations::{
+        get_station, get_stations, patch_station, post_station, post_station_enrollment_code,
+    },
+    models::Permission,
+    routes::require,
+    state::AppState,
+};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/",
+            require(Permission::ManageStations, get(get_stations))
+                .merge(require(Permission::ManageStations, post(post_station))),
+        )
+        .route(
+            "/{uuid}",
+            require(Permission::ManageStations, get(get_station))
+                .merge(require(Permission::ManageStations, patch(patch_station))),
+        )
+        .route(
+            "/{uuid}/enrollment-code",
+            require(
+                Permission::ManageStations,
+                post(post_station_enrollment_code),
+            ),
+        )
+}
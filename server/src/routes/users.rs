@@ -1,18 +1,82 @@
 use crate::{
-    handlers::users::{delete_user, get_me, get_user, patch_user, post_user},
+    handlers::{
+        mfa::{
+            delete_my_mfa, get_my_mfa, post_my_recovery_codes, post_my_totp, post_my_totp_confirm,
+        },
+        sessions::{
+            delete_my_session, delete_my_sessions, delete_user_session, delete_user_sessions,
+            get_my_sessions, get_user_sessions,
+        },
+        users::{
+            delete_user, get_me, get_user, patch_user, post_password, post_unlock_user, post_user,
+        },
+    },
+    models::Permission,
+    routes::require,
     state::AppState,
 };
 use axum::{
     Router,
-    routing::{get, post},
+    routing::{delete, get, patch, post},
 };
 
 pub fn router() -> Router<AppState> {
     Router::new()
-        .route("/", post(post_user))
-        .route("/me", get(get_me))
+        .route("/", require(Permission::WriteUsers, post(post_user)))
+        .route("/me", require(Permission::ManageAccount, get(get_me)))
+        .route(
+            "/me/password",
+            require(Permission::ManageAccount, post(post_password)),
+        )
+        .route(
+            "/me/mfa",
+            require(
+                Permission::ManageAccount,
+                get(get_my_mfa).merge(delete(delete_my_mfa)),
+            ),
+        )
+        .route(
+            "/me/mfa/totp",
+            require(Permission::ManageAccount, post(post_my_totp)),
+        )
+        .route(
+            "/me/mfa/totp/confirm",
+            require(Permission::ManageAccount, post(post_my_totp_confirm)),
+        )
+        .route(
+            "/me/mfa/recovery-codes",
+            require(Permission::ManageAccount, post(post_my_recovery_codes)),
+        )
+        .route(
+            "/me/sessions",
+            require(
+                Permission::ManageAccount,
+                get(get_my_sessions).merge(delete(delete_my_sessions)),
+            ),
+        )
+        .route(
+            "/me/sessions/{session_uuid}",
+            require(Permission::ManageAccount, delete(delete_my_session)),
+        )
         .route(
             "/{uuid}",
-            get(get_user).patch(patch_user).delete(delete_user),
+            require(Permission::ReadUsers, get(get_user))
+                .merge(require(Permission::WriteUsers, patch(patch_user)))
+                .merge(require(Permission::DeleteUsers, delete(delete_user))),
+        )
+        .route(
+            "/{uuid}/sessions",
+            require(Permission::ReadUsers, get(get_user_sessions)).merge(require(
+                Permission::WriteUsers,
+                delete(delete_user_sessions),
+            )),
+        )
+        .route(
+            "/{uuid}/sessions/{session_uuid}",
+            require(Permission::WriteUsers, delete(delete_user_session)),
+        )
+        .route(
+            "/{uuid}/unlock",
+            require(Permission::WriteUsers, post(post_unlock_user)),
         )
 }
@@ -0,0 +1,119 @@
+use axum::{
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use open_erase_lib::schemas::api_key::{
+    DeleteApiKeyResponse, GetApiKeyResponse, GetApiKeysResponse, PostApiKeyRequest,
+    PostApiKeyResponse,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    models::ApiKey,
+    schemas::json,
+    validation::{MAX_TEXT_LENGTH, Validate, Validator},
+};
+
+impl From<ApiKey> for GetApiKeyResponse {
+    fn from(value: ApiKey) -> Self {
+        Self {
+            id: value.id,
+            user_id: value.user_id,
+            station_id: value.station_id,
+            name: value.name,
+            prefix: value.prefix,
+            scopes: value.scopes.into_iter().map(Into::into).collect(),
+            expires_at: value.expires_at,
+            last_used_at: value.last_used_at,
+            revoked_at: value.revoked_at,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerGetApiKeysResponse(pub GetApiKeysResponse);
+
+impl IntoResponse for ServerGetApiKeysResponse {
+    fn into_response(self) -> Response {
+        (StatusCode::OK, json(self.0)).into_response()
+    }
+}
+
+impl From<Vec<ApiKey>> for ServerGetApiKeysResponse {
+    fn from(value: Vec<ApiKey>) -> Self {
+        let api_keys = value
+            .into_iter()
+            .map(GetApiKeyResponse::from)
+            .collect::<Vec<GetApiKeyResponse>>();
+        Self(GetApiKeysResponse(api_keys))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerPostApiKeyRequest(pub PostApiKeyRequest);
+
+impl Validate for ServerPostApiKeyRequest {
+    fn validate(&self, validator: &mut Validator) {
+        validator.text("name", &self.0.name, MAX_TEXT_LENGTH);
+        if self.0.scopes.is_empty() {
+            validator.error("scopes", "must grant at least one scope");
+        }
+        if self
+            .0
+            .expires_at
+            .is_some_and(|expires_at| expires_at <= Utc::now())
+        {
+            validator.error("expires_at", "must be in the future");
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerPostApiKeyResponse(pub PostApiKeyResponse);
+
+impl ServerPostApiKeyResponse {
+    pub fn new(api_key: ApiKey, key: String) -> Self {
+        Self(PostApiKeyResponse {
+            id: api_key.id,
+            name: api_key.name,
+            prefix: api_key.prefix,
+            key,
+            scopes: api_key.scopes.into_iter().map(Into::into).collect(),
+            expires_at: api_key.expires_at,
+            created_at: api_key.created_at,
+        })
+    }
+}
+
+impl IntoResponse for ServerPostApiKeyResponse {
+    fn into_response(self) -> Response {
+        (
+            StatusCode::CREATED,
+            [(header::CACHE_CONTROL, "no-store")],
+            json(self.0),
+        )
+            .into_response()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerDeleteApiKeyResponse(pub DeleteApiKeyResponse);
+
+impl IntoResponse for ServerDeleteApiKeyResponse {
+    fn into_response(self) -> Response {
+        StatusCode::NO_CONTENT.into_response()
+    }
+}
+
+impl From<ApiKey> for ServerDeleteApiKeyResponse {
+    fn from(value: ApiKey) -> Self {
+        Self(DeleteApiKeyResponse { id: value.id })
+    }
+}
@@ -0,0 +1,242 @@
+use axum::{
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use open_erase_lib::schemas::batch::{
+    BatchCertificateEntry, DeleteBatchResponse, GetBatchCertificateResponse, GetBatchResponse,
+    GetBatchSummaryResponse, GetBatchesResponse, PatchBatchRequest, PatchBatchResponse,
+    PostBatchRequest, PostBatchResponse,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    models::Batch,
+    schemas::json,
+    services::batch::{BatchCertificate, BatchSummary},
+    validation::{MAX_TEXT_LENGTH, Validate, Validator},
+};
+
+impl From<Batch> for GetBatchResponse {
+    fn from(value: Batch) -> Self {
+        Self {
+            id: value.id,
+            customer_reference: value.customer_reference,
+            expected_device_count: value.expected_device_count.max(0) as u32,
+            status: value.status.into(),
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerGetBatchResponse(pub GetBatchResponse);
+
+impl IntoResponse for ServerGetBatchResponse {
+    fn into_response(self) -> Response {
+        (StatusCode::OK, json(self.0)).into_response()
+    }
+}
+
+impl From<Batch> for ServerGetBatchResponse {
+    fn from(value: Batch) -> Self {
+        Self(value.into())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerGetBatchesResponse(pub GetBatchesResponse);
+
+impl IntoResponse for ServerGetBatchesResponse {
+    fn into_response(self) -> Response {
+        (StatusCode::OK, json(self.0)).into_response()
+    }
+}
+
+impl From<Vec<Batch>> for ServerGetBatchesResponse {
+    fn from(value: Vec<Batch>) -> Self {
+        let batches = value
+            .into_iter()
+            .map(GetBatchResponse::from)
+            .collect::<Vec<GetBatchResponse>>();
+        Self(GetBatchesResponse(batches))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerPostBatchRequest(pub PostBatchRequest);
+
+impl Validate for ServerPostBatchRequest {
+    fn validate(&self, validator: &mut Validator) {
+        validator.text(
+            "customer_reference",
+            &self.0.customer_reference,
+            MAX_TEXT_LENGTH,
+        );
+        validate_device_count(validator, self.0.expected_device_count);
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerPostBatchResponse(pub PostBatchResponse);
+
+impl IntoResponse for ServerPostBatchResponse {
+    fn into_response(self) -> Response {
+        (
+            StatusCode::CREATED,
+            [(header::LOCATION, format!("/{}", self.0.id))],
+            json(self.0),
+        )
+            .into_response()
+    }
+}
+
+impl From<Batch> for ServerPostBatchResponse {
+    fn from(value: Batch) -> Self {
+        Self(PostBatchResponse {
+            id: value.id,
+            customer_reference: value.customer_reference,
+            expected_device_count: value.expected_device_count.max(0) as u32,
+            status: value.status.into(),
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerPatchBatchRequest(pub PatchBatchRequest);
+
+impl Validate for ServerPatchBatchRequest {
+    fn validate(&self, validator: &mut Validator) {
+        if let Some(customer_reference) = &self.0.customer_reference {
+            validator.text("customer_reference", customer_reference, MAX_TEXT_LENGTH);
+        }
+        if let Some(expected_device_count) = self.0.expected_device_count {
+            validate_device_count(validator, expected_device_count);
+        }
+    }
+}
+
+/// Counts are stored as `INTEGER`.
+fn validate_device_count(validator: &mut Validator, expected_device_count: u32) {
+    if i32::try_from(expected_device_count).is_err() {
+        validator.error(
+            "expected_device_count",
+            format!("must be at most {}", i32::MAX),
+        );
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerPatchBatchResponse(pub PatchBatchResponse);
+
+impl IntoResponse for ServerPatchBatchResponse {
+    fn into_response(self) -> Response {
+        (StatusCode::OK, json(self.0)).into_response()
+    }
+}
+
+impl From<Batch> for ServerPatchBatchResponse {
+    fn from(value: Batch) -> Self {
+        Self(PatchBatchResponse {
+            id: value.id,
+            customer_reference: value.customer_reference,
+            expected_device_count: value.expected_device_count.max(0) as u32,
+            status: value.status.into(),
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerDeleteBatchResponse(pub DeleteBatchResponse);
+
+impl IntoResponse for ServerDeleteBatchResponse {
+    fn into_response(self) -> Response {
+        StatusCode::NO_CONTENT.into_response()
+    }
+}
+
+impl From<Batch> for ServerDeleteBatchResponse {
+    fn from(value: Batch) -> Self {
+        Self(DeleteBatchResponse { id: value.id })
+    }
+}
+
+impl From<BatchSummary> for GetBatchSummaryResponse {
+    fn from(value: BatchSummary) -> Self {
+        Self {
+            pending_device_count: value.pending_device_count(),
+            outstanding_device_count: value.outstanding_device_count(),
+            id: value.batch.id,
+            customer_reference: value.batch.customer_reference,
+            status: value.batch.status.into(),
+            expected_device_count: value.batch.expected_device_count.max(0) as u32,
+            device_count: value.device_count,
+            erased_device_count: value.erased_device_count,
+            failed_device_count: value.failed_device_count,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerGetBatchSummaryResponse(pub GetBatchSummaryResponse);
+
+impl IntoResponse for ServerGetBatchSummaryResponse {
+    fn into_response(self) -> Response {
+        (StatusCode::OK, json(self.0)).into_response()
+    }
+}
+
+impl From<BatchSummary> for ServerGetBatchSummaryResponse {
+    fn from(value: BatchSummary) -> Self {
+        Self(value.into())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerGetBatchCertificateResponse(pub GetBatchCertificateResponse);
+
+impl IntoResponse for ServerGetBatchCertificateResponse {
+    fn into_response(self) -> Response {
+        let content_disposition = format!(
+            "attachment; filename=\"batch-{}-certificate.json\"",
+            self.0.summary.id
+        );
+        (
+            StatusCode::OK,
+            [(header::CONTENT_DISPOSITION, content_disposition)],
+            json(self.0),
+        )
+            .into_response()
+    }
+}
+
+impl From<BatchCertificate> for ServerGetBatchCertificateResponse {
+    fn from(value: BatchCertificate) -> Self {
+        let entries = value
+            .entries
+            .into_iter()
+            .map(|(device, report)| BatchCertificateEntry {
+                device: device.into(),
+                report: report.map(Into::into),
+            })
+            .collect();
+        Self(GetBatchCertificateResponse {
+            issued_at: value.issued_at,
+            summary: value.summary.into(),
+            entries,
+        })
+    }
+}
@@ -0,0 +1,39 @@
+use axum::{
+    body::Body,
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+
+/// iPXE script, which iPXE recognizes by its `#!ipxe` line rather than the content type.
+pub struct ServerIpxeScript(pub String);
+
+impl IntoResponse for ServerIpxeScript {
+    fn into_response(self) -> Response {
+        (
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+            self.0,
+        )
+            .into_response()
+    }
+}
+
+/// Kernel, initrd or squashfs streamed out of an image.
+pub struct ServerBootArtifact {
+    pub size: u64,
+    pub body: Body,
+}
+
+impl IntoResponse for ServerBootArtifact {
+    fn into_response(self) -> Response {
+        (
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, "application/octet-stream".to_string()),
+                (header::CONTENT_LENGTH, self.size.to_string()),
+            ],
+            self.body,
+        )
+            .into_response()
+    }
+}
@@ -0,0 +1,93 @@
+use axum::{
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use open_erase_lib::schemas::device::{
+    GetDeviceResponse, GetDevicesResponse, PostDeviceRequest, PostDeviceResponse,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    models::Device,
+    schemas::json,
+    validation::{MAX_TEXT_LENGTH, Validate, Validator},
+};
+
+impl From<Device> for GetDeviceResponse {
+    fn from(value: Device) -> Self {
+        Self {
+            id: value.id,
+            batch_id: value.batch_id,
+            serial_number: value.serial_number,
+            manufacturer: value.manufacturer,
+            model: value.model,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerGetDevicesResponse(pub GetDevicesResponse);
+
+impl IntoResponse for ServerGetDevicesResponse {
+    fn into_response(self) -> Response {
+        (StatusCode::OK, json(self.0)).into_response()
+    }
+}
+
+impl From<Vec<Device>> for ServerGetDevicesResponse {
+    fn from(value: Vec<Device>) -> Self {
+        let devices = value
+            .into_iter()
+            .map(GetDeviceResponse::from)
+            .collect::<Vec<GetDeviceResponse>>();
+        Self(GetDevicesResponse(devices))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerPostDeviceRequest(pub PostDeviceRequest);
+
+impl Validate for ServerPostDeviceRequest {
+    fn validate(&self, validator: &mut Validator) {
+        validator.text("serial_number", &self.0.serial_number, MAX_TEXT_LENGTH);
+        if let Some(manufacturer) = &self.0.manufacturer {
+            validator.max_length("manufacturer", manufacturer, MAX_TEXT_LENGTH);
+        }
+        if let Some(model) = &self.0.model {
+            validator.max_length("model", model, MAX_TEXT_LENGTH);
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerPostDeviceResponse(pub PostDeviceResponse);
+
+impl IntoResponse for ServerPostDeviceResponse {
+    fn into_response(self) -> Response {
+        (
+            StatusCode::CREATED,
+            [(header::LOCATION, format!("/{}", self.0.id))],
+            json(self.0),
+        )
+            .into_response()
+    }
+}
+
+impl From<Device> for ServerPostDeviceResponse {
+    fn from(value: Device) -> Self {
+        Self(PostDeviceResponse {
+            id: value.id,
+            batch_id: value.batch_id,
+            serial_number: value.serial_number,
+            manufacturer: value.manufacturer,
+            model: value.model,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        })
+    }
+}
@@ -1,11 +1,64 @@
+use std::ops::Bound;
+
 use axum::{
-    http::StatusCode,
+    body::Body,
+    http::{HeaderMap, HeaderValue, StatusCode, header},
     response::{IntoResponse, Response},
 };
-use open_erase_lib::schemas::image::{GetImageResponse, GetImagesResponse};
+use axum_extra::headers::{ETag, HeaderMapExt, Range};
+use base64::{Engine, prelude::BASE64_STANDARD};
+use data_encoding::HEXLOWER;
+use open_erase_lib::schemas::image::{
+    ClientConfig, GetImageResponse, GetImagesResponse, PatchImageRequest, PatchImageResponse,
+    PostImageResponse, PostImageVariantRequest, PostImageVariantResponse,
+};
 use serde::{Deserialize, Serialize};
 
-use crate::{models::Image, schemas::json};
+use crate::{
+    models::{Architecture, Image, Station},
+    schemas::json,
+    validation::{MAX_TEXT_LENGTH, Validate, Validator},
+};
+
+pub const MAX_VERSION_LENGTH: usize = 64;
+pub const MAX_RELEASE_NOTES_LENGTH: usize = 10_000;
+const MAX_VARIANT_NAME_LENGTH: usize = 64;
+const MAX_SERVER_URL_LENGTH: usize = 2048;
+const MAX_CA_CERTIFICATE_LENGTH: usize = 32 * 1024;
+const MAX_LOCALE_LENGTH: usize = 32;
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerGetImageResponse(pub GetImageResponse);
+
+impl IntoResponse for ServerGetImageResponse {
+    fn into_response(self) -> Response {
+        (StatusCode::OK, json(self.0)).into_response()
+    }
+}
+
+impl From<Image> for GetImageResponse {
+    fn from(value: Image) -> Self {
+        Self {
+            id: value.id,
+            version: value.version,
+            architecture: value.architecture.into(),
+            size_bytes: value.size_bytes as u64,
+            sha256: value.sha256,
+            release_notes: value.release_notes,
+            is_active: value.is_active,
+            base_image_id: value.base_image_id,
+            variant_name: value.variant_name,
+            uploaded_at: value.uploaded_at,
+        }
+    }
+}
+
+impl From<Image> for ServerGetImageResponse {
+    fn from(value: Image) -> Self {
+        Self(value.into())
+    }
+}
 
 #[derive(Serialize, Deserialize)]
 #[serde(transparent)]
@@ -21,8 +74,337 @@ impl From<Vec<Image>> for ServerGetImagesResponse {
     fn from(value: Vec<Image>) -> Self {
         let images = value
             .into_iter()
-            .map(|image| GetImageResponse { name: image.name })
+            .map(GetImageResponse::from)
             .collect::<Vec<GetImageResponse>>();
         Self(GetImagesResponse(images))
     }
 }
+
+/// Text fields of the upload form, collected while the file part is streamed to disk.
+#[derive(Default)]
+pub struct ServerPostImageForm {
+    pub version: Option<String>,
+    pub architecture: Option<String>,
+    pub release_notes: Option<String>,
+    pub has_file: bool,
+}
+
+impl ServerPostImageForm {
+    /// Only meaningful once the form passed validation.
+    pub fn architecture(&self) -> Option<Architecture> {
+        self.architecture.as_deref()?.parse().ok()
+    }
+}
+
+impl Validate for ServerPostImageForm {
+    fn validate(&self, validator: &mut Validator) {
+        match &self.version {
+            Some(version) => validator.text("version", version, MAX_VERSION_LENGTH),
+            None => validator.error("version", "is required"),
+        }
+        match &self.architecture {
+            Some(_) if self.architecture().is_none() => {
+                validator.error("architecture", "must be one of x86_64, aarch64")
+            }
+            Some(_) => {}
+            None => validator.error("architecture", "is required"),
+        }
+        if let Some(release_notes) = &self.release_notes {
+            validator.max_length("release_notes", release_notes, MAX_RELEASE_NOTES_LENGTH);
+        }
+        if !self.has_file {
+            validator.error("file", "is required");
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerPostImageResponse(pub PostImageResponse);
+
+impl IntoResponse for ServerPostImageResponse {
+    fn into_response(self) -> Response {
+        (StatusCode::CREATED, json(self.0)).into_response()
+    }
+}
+
+impl From<Image> for ServerPostImageResponse {
+    fn from(value: Image) -> Self {
+        Self(PostImageResponse {
+            id: value.id,
+            version: value.version,
+            architecture: value.architecture.into(),
+            size_bytes: value.size_bytes as u64,
+            sha256: value.sha256,
+            release_notes: value.release_notes,
+            is_active: value.is_active,
+            base_image_id: value.base_image_id,
+            variant_name: value.variant_name,
+            uploaded_at: value.uploaded_at,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerPatchImageRequest(pub PatchImageRequest);
+
+impl Validate for ServerPatchImageRequest {
+    fn validate(&self, validator: &mut Validator) {
+        if let Some(release_notes) = &self.0.release_notes {
+            validator.max_length("release_notes", release_notes, MAX_RELEASE_NOTES_LENGTH);
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerPatchImageResponse(pub PatchImageResponse);
+
+impl IntoResponse for ServerPatchImageResponse {
+    fn into_response(self) -> Response {
+        (StatusCode::OK, json(self.0)).into_response()
+    }
+}
+
+impl From<Image> for ServerPatchImageResponse {
+    fn from(value: Image) -> Self {
+        Self(PatchImageResponse {
+            id: value.id,
+            version: value.version,
+            architecture: value.architecture.into(),
+            size_bytes: value.size_bytes as u64,
+            sha256: value.sha256,
+            release_notes: value.release_notes,
+            is_active: value.is_active,
+            base_image_id: value.base_image_id,
+            variant_name: value.variant_name,
+            uploaded_at: value.uploaded_at,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerPostImageVariantRequest(pub PostImageVariantRequest);
+
+impl ServerPostImageVariantRequest {
+    /// The config written into the variant, pointing at `public_url` unless the request
+    /// names another server.
+    pub fn client_config(&self, public_url: &str, enrollment_code: Option<String>) -> ClientConfig {
+        ClientConfig {
+            server_url: self
+                .0
+                .server_url
+                .clone()
+                .unwrap_or_else(|| public_url.trim_end_matches('/').to_string()),
+            ca_certificate: self.0.ca_certificate.clone(),
+            enrollment_code,
+            default_erase_method: self.0.default_erase_method.clone(),
+            locale: self.0.locale.clone(),
+        }
+    }
+}
+
+impl Validate for ServerPostImageVariantRequest {
+    fn validate(&self, validator: &mut Validator) {
+        validator.text("name", &self.0.name, MAX_VARIANT_NAME_LENGTH);
+        if let Some(server_url) = &self.0.server_url {
+            if !server_url.starts_with("https://") && !server_url.starts_with("http://") {
+                validator.error("server_url", "must be an http or https URL");
+            } else {
+                validator.max_length("server_url", server_url, MAX_SERVER_URL_LENGTH);
+            }
+        }
+        if let Some(ca_certificate) = &self.0.ca_certificate {
+            if !ca_certificate.contains("-----BEGIN CERTIFICATE-----") {
+                validator.error("ca_certificate", "must be a PEM encoded certificate");
+            } else {
+                validator.max_length("ca_certificate", ca_certificate, MAX_CA_CERTIFICATE_LENGTH);
+            }
+        }
+        if let Some(station_name) = &self.0.station_name {
+            validator.text("station_name", station_name, MAX_TEXT_LENGTH);
+        }
+        if let Some(default_erase_method) = &self.0.default_erase_method {
+            validator.text(
+                "default_erase_method",
+                default_erase_method,
+                MAX_TEXT_LENGTH,
+            );
+        }
+        if let Some(locale) = &self.0.locale {
+            let is_locale = !locale.is_empty()
+                && locale.len() <= MAX_LOCALE_LENGTH
+                && locale
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-' | '@'));
+            if !is_locale {
+                validator.error("locale", "must be a locale like en_US.UTF-8");
+            }
+        }
+        if let Some(release_notes) = &self.0.release_notes {
+            validator.max_length("release_notes", release_notes, MAX_RELEASE_NOTES_LENGTH);
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerPostImageVariantResponse(pub PostImageVariantResponse);
+
+impl IntoResponse for ServerPostImageVariantResponse {
+    fn into_response(self) -> Response {
+        (StatusCode::CREATED, json(self.0)).into_response()
+    }
+}
+
+impl ServerPostImageVariantResponse {
+    pub fn new(value: Image, station: Option<Station>) -> Self {
+        Self(PostImageVariantResponse {
+            id: value.id,
+            version: value.version,
+            architecture: value.architecture.into(),
+            size_bytes: value.size_bytes as u64,
+            sha256: value.sha256,
+            release_notes: value.release_notes,
+            is_active: value.is_active,
+            base_image_id: value.base_image_id,
+            variant_name: value.variant_name,
+            station_id: station.map(|station| station.id),
+            uploaded_at: value.uploaded_at,
+        })
+    }
+}
+
+/// Part of an image a download asks for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteRange {
+    Full,
+    /// Inclusive first and last byte.
+    Partial(u64, u64),
+    Unsatisfiable,
+}
+
+impl ByteRange {
+    /// Only a single range is served partially, several ranges get the whole image
+    /// like a request without any.
+    pub fn resolve(range: Option<&Range>, size: u64) -> Self {
+        let Some(range) = range else {
+            return Self::Full;
+        };
+        let mut ranges = range.satisfiable_ranges(size);
+        let (Some((start, end)), None) = (ranges.next(), ranges.next()) else {
+            return Self::Full;
+        };
+        let start = match start {
+            Bound::Included(start) => start,
+            Bound::Excluded(start) => start + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match end {
+            Bound::Included(end) => end.min(size.saturating_sub(1)),
+            Bound::Excluded(end) => end.min(size).saturating_sub(1),
+            Bound::Unbounded => size.saturating_sub(1),
+        };
+        if start >= size || start > end {
+            Self::Unsatisfiable
+        } else {
+            Self::Partial(start, end)
+        }
+    }
+}
+
+/// Strong validator of the image, which never changes once uploaded.
+pub fn image_etag(image: &Image) -> ETag {
+    format!("\"{}\"", image.sha256).parse().unwrap()
+}
+
+/// The ISO streamed from disk, or only its headers when the client has it already.
+pub struct ServerImageDownload {
+    image: Image,
+    content: Option<(ByteRange, Body)>,
+}
+
+impl ServerImageDownload {
+    pub fn new(image: Image, range: ByteRange, body: Body) -> Self {
+        Self {
+            image,
+            content: Some((range, body)),
+        }
+    }
+
+    pub fn not_modified(image: Image) -> Self {
+        Self {
+            image,
+            content: None,
+        }
+    }
+
+    fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.typed_insert(image_etag(&self.image));
+        headers.insert(header::ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+        // RFC 9530 digest of the whole image, also on partial responses
+        if let Ok(sha256) = HEXLOWER.decode(self.image.sha256.as_bytes()) {
+            let digest = format!("sha-256=:{}:", BASE64_STANDARD.encode(sha256));
+            headers.insert("repr-digest", HeaderValue::from_str(&digest).unwrap());
+        }
+        headers
+    }
+}
+
+impl IntoResponse for ServerImageDownload {
+    fn into_response(self) -> Response {
+        let mut headers = self.headers();
+        let size = self.image.size_bytes as u64;
+        let Some((range, body)) = self.content else {
+            return (StatusCode::NOT_MODIFIED, headers).into_response();
+        };
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/x-iso9660-image"),
+        );
+        let content_disposition =
+            format!("attachment; filename=\"{}\"", self.image.download_name());
+        headers.insert(
+            header::CONTENT_DISPOSITION,
+            HeaderValue::from_str(&content_disposition).unwrap(),
+        );
+        let status_code = match range {
+            ByteRange::Partial(start, end) => {
+                headers.insert(
+                    header::CONTENT_RANGE,
+                    HeaderValue::from_str(&format!("bytes {start}-{end}/{size}")).unwrap(),
+                );
+                headers.insert(header::CONTENT_LENGTH, HeaderValue::from(end - start + 1));
+                StatusCode::PARTIAL_CONTENT
+            }
+            ByteRange::Full | ByteRange::Unsatisfiable => {
+                headers.insert(header::CONTENT_LENGTH, HeaderValue::from(size));
+                StatusCode::OK
+            }
+        };
+        (status_code, headers, body).into_response()
+    }
+}
+
+/// `SHA256SUMS` of every image in the format of `sha256sum`, so that
+/// `sha256sum --check --ignore-missing SHA256SUMS` verifies downloaded images.
+pub struct ServerImageChecksums(pub Vec<Image>);
+
+impl IntoResponse for ServerImageChecksums {
+    fn into_response(self) -> Response {
+        let checksums: String = self
+            .0
+            .iter()
+            .map(|image| format!("{}  {}\n", image.sha256, image.download_name()))
+            .collect();
+        (
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+            checksums,
+        )
+            .into_response()
+    }
+}
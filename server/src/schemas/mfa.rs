@@ -0,0 +1,160 @@
+use axum::{
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use open_erase_lib::schemas::mfa::{
+    GetMfaResponse, MfaChallengeResponse, PostMfaVerifyRequest, PostRecoveryCodesResponse,
+    PostTotpConfirmRequest, PostTotpConfirmResponse, PostTotpResponse,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    schemas::json,
+    services::mfa::{MfaStatus, TotpEnrollment},
+    validation::{Validate, Validator},
+};
+
+// fits TOTP codes and recovery codes with some room for separators
+const MAX_CODE_LENGTH: usize = 32;
+
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct ServerMfaChallengeResponse(pub MfaChallengeResponse);
+
+impl ServerMfaChallengeResponse {
+    pub fn new(challenge_token: String) -> Self {
+        Self(MfaChallengeResponse { challenge_token })
+    }
+}
+
+impl IntoResponse for ServerMfaChallengeResponse {
+    fn into_response(self) -> Response {
+        (
+            StatusCode::ACCEPTED,
+            [
+                (header::CACHE_CONTROL, "no-store"),
+                (header::PRAGMA, "no-cache"),
+            ],
+            json(self.0),
+        )
+            .into_response()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerPostMfaVerifyRequest(pub PostMfaVerifyRequest);
+
+impl Validate for ServerPostMfaVerifyRequest {
+    fn validate(&self, validator: &mut Validator) {
+        validator.text("challenge_token", &self.0.challenge_token, usize::MAX);
+        validator.text("code", &self.0.code, MAX_CODE_LENGTH);
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerGetMfaResponse(pub GetMfaResponse);
+
+impl IntoResponse for ServerGetMfaResponse {
+    fn into_response(self) -> Response {
+        (StatusCode::OK, json(self.0)).into_response()
+    }
+}
+
+impl From<MfaStatus> for ServerGetMfaResponse {
+    fn from(value: MfaStatus) -> Self {
+        Self(GetMfaResponse {
+            totp_enabled: value.is_totp_enabled,
+            recovery_codes_remaining: value.recovery_codes_remaining as u32,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerPostTotpResponse(pub PostTotpResponse);
+
+impl IntoResponse for ServerPostTotpResponse {
+    fn into_response(self) -> Response {
+        (
+            StatusCode::CREATED,
+            [(header::CACHE_CONTROL, "no-store")],
+            json(self.0),
+        )
+            .into_response()
+    }
+}
+
+impl From<TotpEnrollment> for ServerPostTotpResponse {
+    fn from(value: TotpEnrollment) -> Self {
+        Self(PostTotpResponse {
+            secret: value.secret,
+            otpauth_uri: value.otpauth_uri,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerPostTotpConfirmRequest(pub PostTotpConfirmRequest);
+
+impl Validate for ServerPostTotpConfirmRequest {
+    fn validate(&self, validator: &mut Validator) {
+        validator.text("code", &self.0.code, MAX_CODE_LENGTH);
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerPostTotpConfirmResponse(pub PostTotpConfirmResponse);
+
+impl IntoResponse for ServerPostTotpConfirmResponse {
+    fn into_response(self) -> Response {
+        (
+            StatusCode::OK,
+            [(header::CACHE_CONTROL, "no-store")],
+            json(self.0),
+        )
+            .into_response()
+    }
+}
+
+impl From<Vec<String>> for ServerPostTotpConfirmResponse {
+    fn from(value: Vec<String>) -> Self {
+        Self(PostTotpConfirmResponse {
+            recovery_codes: value,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerPostRecoveryCodesResponse(pub PostRecoveryCodesResponse);
+
+impl IntoResponse for ServerPostRecoveryCodesResponse {
+    fn into_response(self) -> Response {
+        (
+            StatusCode::OK,
+            [(header::CACHE_CONTROL, "no-store")],
+            json(self.0),
+        )
+            .into_response()
+    }
+}
+
+impl From<Vec<String>> for ServerPostRecoveryCodesResponse {
+    fn from(value: Vec<String>) -> Self {
+        Self(PostRecoveryCodesResponse {
+            recovery_codes: value,
+        })
+    }
+}
+
+pub struct ServerDeleteMfaResponse;
+
+impl IntoResponse for ServerDeleteMfaResponse {
+    fn into_response(self) -> Response {
+        StatusCode::NO_CONTENT.into_response()
+    }
+}
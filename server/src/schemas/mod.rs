@@ -2,7 +2,19 @@ use serde::Serialize;
 
 use crate::error::ServiceResult;
 
+pub mod api_key;
+pub mod batch;
+pub mod boot;
+pub mod device;
 pub mod image;
+pub mod mfa;
+pub mod oidc;
+pub mod organization;
+pub mod password_reset;
+pub mod report;
+pub mod session;
+pub mod signing_key;
+pub mod station;
 pub mod token;
 pub mod user;
 
@@ -0,0 +1,96 @@
+use axum::{
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use open_erase_lib::schemas::oidc::GetOidcResponse;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    schemas::{json, token::set_refresh_token_cookie},
+    services::oidc::OidcAuthorization,
+};
+
+pub const OIDC_LOGIN_COOKIE: &str = "oidc_login";
+// the provider redirects back cross-site, which SameSite=Strict cookies don't survive
+const OIDC_LOGIN_COOKIE_ATTRIBUTES: &str = "HttpOnly; Secure; SameSite=Lax; Path=/api/auth/oidc";
+// page the browser lands on after signing in, the web app picks up the session from there
+const WEB_APP_PATH: &str = "/";
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerGetOidcResponse(pub GetOidcResponse);
+
+impl ServerGetOidcResponse {
+    pub fn new(enabled: bool) -> Self {
+        Self(GetOidcResponse { enabled })
+    }
+}
+
+impl IntoResponse for ServerGetOidcResponse {
+    fn into_response(self) -> Response {
+        (StatusCode::OK, json(self.0)).into_response()
+    }
+}
+
+/// Redirects the browser to the provider, remembering the login state in a cookie.
+pub struct ServerOidcLoginResponse(pub OidcAuthorization);
+
+impl IntoResponse for ServerOidcLoginResponse {
+    fn into_response(self) -> Response {
+        (
+            StatusCode::SEE_OTHER,
+            [
+                (header::CACHE_CONTROL, "no-store"),
+                (header::LOCATION, &self.0.url),
+                (
+                    header::SET_COOKIE,
+                    &format!(
+                        "{}={}; {}",
+                        OIDC_LOGIN_COOKIE, self.0.state_token, OIDC_LOGIN_COOKIE_ATTRIBUTES
+                    ),
+                ),
+            ],
+        )
+            .into_response()
+    }
+}
+
+/// Providers report failed logins through `error` instead of `code`.
+#[derive(Deserialize)]
+pub struct OidcCallbackQuery {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Redirects the browser back to the web app with a refresh token, which it exchanges
+/// for an access token on load.
+pub struct ServerOidcCallbackResponse {
+    pub refresh_token: String,
+}
+
+impl IntoResponse for ServerOidcCallbackResponse {
+    fn into_response(self) -> Response {
+        let mut response = (
+            StatusCode::SEE_OTHER,
+            [
+                (header::CACHE_CONTROL, "no-store"),
+                (header::LOCATION, WEB_APP_PATH),
+            ],
+        )
+            .into_response();
+        let headers = response.headers_mut();
+        for cookie in [
+            set_refresh_token_cookie(&self.refresh_token),
+            format!(
+                "{}=; {}; Max-Age=0",
+                OIDC_LOGIN_COOKIE, OIDC_LOGIN_COOKIE_ATTRIBUTES
+            ),
+        ] {
+            if let Ok(cookie) = cookie.parse() {
+                headers.append(header::SET_COOKIE, cookie);
+            }
+        }
+        response
+    }
+}
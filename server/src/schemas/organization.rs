@@ -0,0 +1,246 @@
+use axum::{
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use open_erase_lib::schemas::organization::{
+    DeleteOrganizationInvitationResponse, DeleteOrganizationMemberResponse,
+    GetOrganizationInvitationResponse, GetOrganizationInvitationsResponse,
+    GetOrganizationMemberResponse, GetOrganizationMembersResponse, GetOrganizationResponse,
+    GetOrganizationsResponse, PostOrganizationInvitationRequest,
+    PostOrganizationInvitationResponse, PostOrganizationMemberRequest,
+    PostOrganizationMemberResponse, PostOrganizationRequest, PostOrganizationResponse,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    models::{Organization, OrganizationInvitation, OrganizationMember, User},
+    schemas::json,
+    validation::{MAX_TEXT_LENGTH, Validate, Validator},
+};
+
+impl From<Organization> for GetOrganizationResponse {
+    fn from(value: Organization) -> Self {
+        Self {
+            id: value.id,
+            name: value.name,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerGetOrganizationResponse(pub GetOrganizationResponse);
+
+impl IntoResponse for ServerGetOrganizationResponse {
+    fn into_response(self) -> Response {
+        (StatusCode::OK, json(self.0)).into_response()
+    }
+}
+
+impl From<Organization> for ServerGetOrganizationResponse {
+    fn from(value: Organization) -> Self {
+        Self(value.into())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerGetOrganizationsResponse(pub GetOrganizationsResponse);
+
+impl IntoResponse for ServerGetOrganizationsResponse {
+    fn into_response(self) -> Response {
+        (StatusCode::OK, json(self.0)).into_response()
+    }
+}
+
+impl From<Vec<Organization>> for ServerGetOrganizationsResponse {
+    fn from(value: Vec<Organization>) -> Self {
+        let organizations = value
+            .into_iter()
+            .map(GetOrganizationResponse::from)
+            .collect::<Vec<GetOrganizationResponse>>();
+        Self(GetOrganizationsResponse(organizations))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerPostOrganizationRequest(pub PostOrganizationRequest);
+
+impl Validate for ServerPostOrganizationRequest {
+    fn validate(&self, validator: &mut Validator) {
+        validator.text("name", &self.0.name, MAX_TEXT_LENGTH);
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerPostOrganizationResponse(pub PostOrganizationResponse);
+
+impl IntoResponse for ServerPostOrganizationResponse {
+    fn into_response(self) -> Response {
+        (
+            StatusCode::CREATED,
+            [(header::LOCATION, format!("/{}", self.0.id))],
+            json(self.0),
+        )
+            .into_response()
+    }
+}
+
+impl From<Organization> for ServerPostOrganizationResponse {
+    fn from(value: Organization) -> Self {
+        Self(PostOrganizationResponse {
+            id: value.id,
+            name: value.name,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerGetOrganizationMembersResponse(pub GetOrganizationMembersResponse);
+
+impl IntoResponse for ServerGetOrganizationMembersResponse {
+    fn into_response(self) -> Response {
+        (StatusCode::OK, json(self.0)).into_response()
+    }
+}
+
+impl From<Vec<(OrganizationMember, User)>> for ServerGetOrganizationMembersResponse {
+    fn from(value: Vec<(OrganizationMember, User)>) -> Self {
+        let members = value
+            .into_iter()
+            .map(|(member, user)| member_response(member, user))
+            .collect::<Vec<GetOrganizationMemberResponse>>();
+        Self(GetOrganizationMembersResponse(members))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerPostOrganizationMemberRequest(pub PostOrganizationMemberRequest);
+
+impl Validate for ServerPostOrganizationMemberRequest {}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerPostOrganizationMemberResponse(pub PostOrganizationMemberResponse);
+
+impl IntoResponse for ServerPostOrganizationMemberResponse {
+    fn into_response(self) -> Response {
+        (StatusCode::CREATED, json(self.0)).into_response()
+    }
+}
+
+impl From<(OrganizationMember, User)> for ServerPostOrganizationMemberResponse {
+    fn from((member, user): (OrganizationMember, User)) -> Self {
+        Self(PostOrganizationMemberResponse {
+            user_id: user.id,
+            email: user.email,
+            joined_at: member.created_at,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerDeleteOrganizationMemberResponse(pub DeleteOrganizationMemberResponse);
+
+impl IntoResponse for ServerDeleteOrganizationMemberResponse {
+    fn into_response(self) -> Response {
+        StatusCode::NO_CONTENT.into_response()
+    }
+}
+
+impl From<OrganizationMember> for ServerDeleteOrganizationMemberResponse {
+    fn from(value: OrganizationMember) -> Self {
+        Self(DeleteOrganizationMemberResponse {
+            user_id: value.user_id,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerGetOrganizationInvitationsResponse(pub GetOrganizationInvitationsResponse);
+
+impl IntoResponse for ServerGetOrganizationInvitationsResponse {
+    fn into_response(self) -> Response {
+        (StatusCode::OK, json(self.0)).into_response()
+    }
+}
+
+impl From<Vec<(OrganizationInvitation, Organization)>>
+    for ServerGetOrganizationInvitationsResponse
+{
+    fn from(value: Vec<(OrganizationInvitation, Organization)>) -> Self {
+        let invitations = value
+            .into_iter()
+            .map(
+                |(invitation, organization)| GetOrganizationInvitationResponse {
+                    organization_id: organization.id,
+                    organization_name: organization.name,
+                    invited_at: invitation.created_at,
+                },
+            )
+            .collect::<Vec<GetOrganizationInvitationResponse>>();
+        Self(GetOrganizationInvitationsResponse(invitations))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerPostOrganizationInvitationRequest(pub PostOrganizationInvitationRequest);
+
+impl Validate for ServerPostOrganizationInvitationRequest {}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerPostOrganizationInvitationResponse(pub PostOrganizationInvitationResponse);
+
+impl IntoResponse for ServerPostOrganizationInvitationResponse {
+    fn into_response(self) -> Response {
+        (StatusCode::CREATED, json(self.0)).into_response()
+    }
+}
+
+impl From<OrganizationInvitation> for ServerPostOrganizationInvitationResponse {
+    fn from(value: OrganizationInvitation) -> Self {
+        Self(PostOrganizationInvitationResponse {
+            organization_id: value.organization_id,
+            user_id: value.user_id,
+            invited_at: value.created_at,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerDeleteOrganizationInvitationResponse(pub DeleteOrganizationInvitationResponse);
+
+impl IntoResponse for ServerDeleteOrganizationInvitationResponse {
+    fn into_response(self) -> Response {
+        StatusCode::NO_CONTENT.into_response()
+    }
+}
+
+impl From<OrganizationInvitation> for ServerDeleteOrganizationInvitationResponse {
+    fn from(value: OrganizationInvitation) -> Self {
+        Self(DeleteOrganizationInvitationResponse {
+            organization_id: value.organization_id,
+        })
+    }
+}
+
+fn member_response(member: OrganizationMember, user: User) -> GetOrganizationMemberResponse {
+    GetOrganizationMemberResponse {
+        user_id: user.id,
+        email: user.email,
+        joined_at: member.created_at,
+    }
+}
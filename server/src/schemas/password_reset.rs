@@ -0,0 +1,48 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use open_erase_lib::schemas::password_reset::{
+    PostPasswordResetConfirmRequest, PostPasswordResetRequest,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::validation::{Validate, Validator};
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerPostPasswordResetRequest(pub PostPasswordResetRequest);
+
+impl Validate for ServerPostPasswordResetRequest {
+    fn validate(&self, validator: &mut Validator) {
+        validator.email("email", &self.0.email);
+    }
+}
+
+/// Sent whether or not the email belongs to an account.
+pub struct ServerPostPasswordResetResponse;
+
+impl IntoResponse for ServerPostPasswordResetResponse {
+    fn into_response(self) -> Response {
+        StatusCode::ACCEPTED.into_response()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerPostPasswordResetConfirmRequest(pub PostPasswordResetConfirmRequest);
+
+impl Validate for ServerPostPasswordResetConfirmRequest {
+    fn validate(&self, validator: &mut Validator) {
+        validator.text("token", &self.0.token, usize::MAX);
+        validator.password("new_password", &self.0.new_password);
+    }
+}
+
+pub struct ServerPostPasswordResetConfirmResponse;
+
+impl IntoResponse for ServerPostPasswordResetConfirmResponse {
+    fn into_response(self) -> Response {
+        StatusCode::NO_CONTENT.into_response()
+    }
+}
@@ -0,0 +1,108 @@
+use axum::{
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use open_erase_lib::schemas::report::{
+    GetReportResponse, GetReportsResponse, PostReportRequest, PostReportResponse,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    models::Report,
+    schemas::json,
+    validation::{MAX_TEXT_LENGTH, Validate, Validator},
+};
+
+impl From<Report> for GetReportResponse {
+    fn from(value: Report) -> Self {
+        Self {
+            id: value.id,
+            device_id: value.device_id,
+            method: value.method,
+            result: value.result.into(),
+            started_at: value.started_at,
+            finished_at: value.finished_at,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerGetReportResponse(pub GetReportResponse);
+
+impl IntoResponse for ServerGetReportResponse {
+    fn into_response(self) -> Response {
+        (StatusCode::OK, json(self.0)).into_response()
+    }
+}
+
+impl From<Report> for ServerGetReportResponse {
+    fn from(value: Report) -> Self {
+        Self(value.into())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerGetReportsResponse(pub GetReportsResponse);
+
+impl IntoResponse for ServerGetReportsResponse {
+    fn into_response(self) -> Response {
+        (StatusCode::OK, json(self.0)).into_response()
+    }
+}
+
+impl From<Vec<Report>> for ServerGetReportsResponse {
+    fn from(value: Vec<Report>) -> Self {
+        let reports = value
+            .into_iter()
+            .map(GetReportResponse::from)
+            .collect::<Vec<GetReportResponse>>();
+        Self(GetReportsResponse(reports))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerPostReportRequest(pub PostReportRequest);
+
+impl Validate for ServerPostReportRequest {
+    fn validate(&self, validator: &mut Validator) {
+        validator.text("method", &self.0.method, MAX_TEXT_LENGTH);
+        if self.0.finished_at < self.0.started_at {
+            validator.error("finished_at", "must not be before started_at");
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerPostReportResponse(pub PostReportResponse);
+
+impl IntoResponse for ServerPostReportResponse {
+    fn into_response(self) -> Response {
+        (
+            StatusCode::CREATED,
+            [(header::LOCATION, format!("/{}", self.0.id))],
+            json(self.0),
+        )
+            .into_response()
+    }
+}
+
+impl From<Report> for ServerPostReportResponse {
+    fn from(value: Report) -> Self {
+        Self(PostReportResponse {
+            id: value.id,
+            device_id: value.device_id,
+            method: value.method,
+            result: value.result.into(),
+            started_at: value.started_at,
+            finished_at: value.finished_at,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        })
+    }
+}
@@ -0,0 +1,95 @@
+use std::{convert::Infallible, net::SocketAddr};
+
+use axum::{
+    extract::{ConnectInfo, FromRequestParts},
+    http::{StatusCode, header, request::Parts},
+    response::{IntoResponse, Response},
+};
+use open_erase_lib::schemas::session::{
+    DeleteSessionResponse, GetSessionResponse, GetSessionsResponse,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{models::Session, schemas::json};
+
+/// User agent and peer address of the client, recorded on its session.
+pub struct ClientInfo {
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for ClientInfo {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let user_agent = parts
+            .headers
+            .get(header::USER_AGENT)
+            .and_then(|user_agent| user_agent.to_str().ok())
+            .map(String::from);
+        let ip_address = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(address)| address.ip().to_string());
+        Ok(Self {
+            user_agent,
+            ip_address,
+        })
+    }
+}
+
+impl From<Session> for GetSessionResponse {
+    fn from(value: Session) -> Self {
+        Self {
+            id: value.id,
+            user_agent: value.user_agent,
+            ip_address: value.ip_address,
+            last_used_at: value.last_used_at,
+            created_at: value.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerGetSessionsResponse(pub GetSessionsResponse);
+
+impl IntoResponse for ServerGetSessionsResponse {
+    fn into_response(self) -> Response {
+        (StatusCode::OK, json(self.0)).into_response()
+    }
+}
+
+impl From<Vec<Session>> for ServerGetSessionsResponse {
+    fn from(value: Vec<Session>) -> Self {
+        let sessions = value
+            .into_iter()
+            .map(GetSessionResponse::from)
+            .collect::<Vec<GetSessionResponse>>();
+        Self(GetSessionsResponse(sessions))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerDeleteSessionResponse(pub DeleteSessionResponse);
+
+impl IntoResponse for ServerDeleteSessionResponse {
+    fn into_response(self) -> Response {
+        StatusCode::NO_CONTENT.into_response()
+    }
+}
+
+impl From<Session> for ServerDeleteSessionResponse {
+    fn from(value: Session) -> Self {
+        Self(DeleteSessionResponse { id: value.id })
+    }
+}
+
+pub struct ServerDeleteSessionsResponse;
+
+impl IntoResponse for ServerDeleteSessionsResponse {
+    fn into_response(self) -> Response {
+        StatusCode::NO_CONTENT.into_response()
+    }
+}
@@ -0,0 +1,113 @@
+use axum::{
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use base64::{Engine, prelude::BASE64_URL_SAFE_NO_PAD};
+use open_erase_lib::schemas::signing_key::{
+    GetJwksResponse, GetSigningKeyResponse, GetSigningKeysResponse, Jwk, PostSigningKeyRequest,
+    PostSigningKeyResponse,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    models::{SigningAlgorithm, SigningKey},
+    schemas::json,
+    validation::Validate,
+};
+
+impl From<SigningKey> for GetSigningKeyResponse {
+    fn from(value: SigningKey) -> Self {
+        Self {
+            id: value.id,
+            algorithm: value.algorithm.into(),
+            retires_at: value.retires_at,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerGetSigningKeysResponse(pub GetSigningKeysResponse);
+
+impl IntoResponse for ServerGetSigningKeysResponse {
+    fn into_response(self) -> Response {
+        (StatusCode::OK, json(self.0)).into_response()
+    }
+}
+
+impl From<Vec<SigningKey>> for ServerGetSigningKeysResponse {
+    fn from(value: Vec<SigningKey>) -> Self {
+        let signing_keys = value
+            .into_iter()
+            .map(GetSigningKeyResponse::from)
+            .collect::<Vec<GetSigningKeyResponse>>();
+        Self(GetSigningKeysResponse(signing_keys))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerPostSigningKeyRequest(pub PostSigningKeyRequest);
+
+impl Validate for ServerPostSigningKeyRequest {}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerPostSigningKeyResponse(pub PostSigningKeyResponse);
+
+impl IntoResponse for ServerPostSigningKeyResponse {
+    fn into_response(self) -> Response {
+        (StatusCode::CREATED, json(self.0)).into_response()
+    }
+}
+
+impl From<SigningKey> for ServerPostSigningKeyResponse {
+    fn from(value: SigningKey) -> Self {
+        Self(PostSigningKeyResponse {
+            id: value.id,
+            algorithm: value.algorithm.into(),
+            retires_at: value.retires_at,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerGetJwksResponse(pub GetJwksResponse);
+
+impl IntoResponse for ServerGetJwksResponse {
+    fn into_response(self) -> Response {
+        (
+            StatusCode::OK,
+            [(header::CACHE_CONTROL, "public, max-age=60")],
+            json(self.0),
+        )
+            .into_response()
+    }
+}
+
+/// Only asymmetric keys are published, HMAC secrets never leave the server.
+impl From<Vec<SigningKey>> for ServerGetJwksResponse {
+    fn from(value: Vec<SigningKey>) -> Self {
+        let keys = value
+            .into_iter()
+            .filter(|signing_key| signing_key.algorithm == SigningAlgorithm::EdDsa)
+            .filter_map(|signing_key| {
+                let public_key = signing_key.public_key?;
+                Some(Jwk {
+                    kty: String::from("OKP"),
+                    crv: String::from("Ed25519"),
+                    alg: String::from("EdDSA"),
+                    key_use: String::from("sig"),
+                    kid: signing_key.id.to_string(),
+                    x: BASE64_URL_SAFE_NO_PAD.encode(public_key),
+                })
+            })
+            .collect::<Vec<Jwk>>();
+        Self(GetJwksResponse { keys })
+    }
+}
@@ -0,0 +1,209 @@
+use axum::{
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use open_erase_lib::schemas::station::{
+    GetStationResponse, GetStationsResponse, PatchStationRequest, PatchStationResponse,
+    PostEnrollRequest, PostEnrollResponse, PostStationEnrollmentCodeResponse, PostStationRequest,
+    PostStationResponse,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    models::Station,
+    schemas::json,
+    validation::{MAX_TEXT_LENGTH, Validate, Validator},
+};
+
+const MAX_HARDWARE_FINGERPRINT_LENGTH: usize = 128;
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerGetStationResponse(pub GetStationResponse);
+
+impl IntoResponse for ServerGetStationResponse {
+    fn into_response(self) -> Response {
+        (StatusCode::OK, json(self.0)).into_response()
+    }
+}
+
+impl From<Station> for GetStationResponse {
+    fn from(value: Station) -> Self {
+        Self {
+            id: value.id,
+            user_id: value.user_id,
+            is_enrolled: value.enrolled_at.is_some(),
+            is_disabled: value.is_disabled(),
+            name: value.name,
+            enrollment_code_expires_at: value.enrollment_code_expires_at,
+            enrolled_at: value.enrolled_at,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+impl From<Station> for ServerGetStationResponse {
+    fn from(value: Station) -> Self {
+        Self(value.into())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerGetStationsResponse(pub GetStationsResponse);
+
+impl IntoResponse for ServerGetStationsResponse {
+    fn into_response(self) -> Response {
+        (StatusCode::OK, json(self.0)).into_response()
+    }
+}
+
+impl From<Vec<Station>> for ServerGetStationsResponse {
+    fn from(value: Vec<Station>) -> Self {
+        let stations = value
+            .into_iter()
+            .map(GetStationResponse::from)
+            .collect::<Vec<GetStationResponse>>();
+        Self(GetStationsResponse(stations))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerPostStationRequest(pub PostStationRequest);
+
+impl Validate for ServerPostStationRequest {
+    fn validate(&self, validator: &mut Validator) {
+        validator.text("name", &self.0.name, MAX_TEXT_LENGTH);
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerPostStationResponse(pub PostStationResponse);
+
+impl ServerPostStationResponse {
+    pub fn new(station: Station, enrollment_code: String) -> Self {
+        Self(PostStationResponse {
+            id: station.id,
+            name: station.name,
+            enrollment_code,
+            enrollment_code_expires_at: station.enrollment_code_expires_at.unwrap_or_default(),
+            created_at: station.created_at,
+        })
+    }
+}
+
+impl IntoResponse for ServerPostStationResponse {
+    fn into_response(self) -> Response {
+        (
+            StatusCode::CREATED,
+            [(header::CACHE_CONTROL, "no-store")],
+            json(self.0),
+        )
+            .into_response()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerPatchStationRequest(pub PatchStationRequest);
+
+impl Validate for ServerPatchStationRequest {
+    fn validate(&self, validator: &mut Validator) {
+        if let Some(name) = &self.0.name {
+            validator.text("name", name, MAX_TEXT_LENGTH);
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerPatchStationResponse(pub PatchStationResponse);
+
+impl IntoResponse for ServerPatchStationResponse {
+    fn into_response(self) -> Response {
+        (StatusCode::OK, json(self.0)).into_response()
+    }
+}
+
+impl From<Station> for ServerPatchStationResponse {
+    fn from(value: Station) -> Self {
+        Self(PatchStationResponse {
+            id: value.id,
+            user_id: value.user_id,
+            is_enrolled: value.enrolled_at.is_some(),
+            is_disabled: value.is_disabled(),
+            name: value.name,
+            enrollment_code_expires_at: value.enrollment_code_expires_at,
+            enrolled_at: value.enrolled_at,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerPostStationEnrollmentCodeResponse(pub PostStationEnrollmentCodeResponse);
+
+impl ServerPostStationEnrollmentCodeResponse {
+    pub fn new(station: Station, enrollment_code: String) -> Self {
+        Self(PostStationEnrollmentCodeResponse {
+            id: station.id,
+            enrollment_code,
+            enrollment_code_expires_at: station.enrollment_code_expires_at.unwrap_or_default(),
+        })
+    }
+}
+
+impl IntoResponse for ServerPostStationEnrollmentCodeResponse {
+    fn into_response(self) -> Response {
+        (
+            StatusCode::CREATED,
+            [(header::CACHE_CONTROL, "no-store")],
+            json(self.0),
+        )
+            .into_response()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerPostEnrollRequest(pub PostEnrollRequest);
+
+impl Validate for ServerPostEnrollRequest {
+    fn validate(&self, validator: &mut Validator) {
+        validator.text("enrollment_code", &self.0.enrollment_code, MAX_TEXT_LENGTH);
+        validator.text(
+            "hardware_fingerprint",
+            &self.0.hardware_fingerprint,
+            MAX_HARDWARE_FINGERPRINT_LENGTH,
+        );
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerPostEnrollResponse(pub PostEnrollResponse);
+
+impl ServerPostEnrollResponse {
+    pub fn new(station: Station, api_key: String) -> Self {
+        Self(PostEnrollResponse {
+            station_id: station.id,
+            api_key,
+        })
+    }
+}
+
+impl IntoResponse for ServerPostEnrollResponse {
+    fn into_response(self) -> Response {
+        (
+            StatusCode::CREATED,
+            [(header::CACHE_CONTROL, "no-store")],
+            json(self.0),
+        )
+            .into_response()
+    }
+}
@@ -2,10 +2,12 @@ use axum::{
     http::{StatusCode, header},
     response::{IntoResponse, Response},
 };
-use open_erase_lib::schemas::token::{LoginResponse, RefreshResponse};
-use serde::Serialize;
+use open_erase_lib::schemas::token::{
+    LoginResponse, PostSwitchOrganizationRequest, RefreshResponse,
+};
+use serde::{Deserialize, Serialize};
 
-use crate::{middleware::auth::REFRESH_TOKEN_COOKIE, schemas::json};
+use crate::{middleware::auth::REFRESH_TOKEN_COOKIE, schemas::json, validation::Validate};
 
 #[derive(Serialize)]
 #[serde(transparent)]
@@ -63,7 +65,13 @@ impl IntoResponse for ServerRefreshResponse {
     }
 }
 
-fn set_refresh_token_cookie(refresh_token: &str) -> String {
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerPostSwitchOrganizationRequest(pub PostSwitchOrganizationRequest);
+
+impl Validate for ServerPostSwitchOrganizationRequest {}
+
+pub(crate) fn set_refresh_token_cookie(refresh_token: &str) -> String {
     format!(
         "{}={}; HttpOnly; Secure; SameSite=Strict",
         REFRESH_TOKEN_COOKIE, refresh_token
@@ -3,12 +3,16 @@ use axum::{
     response::{IntoResponse, Response},
 };
 use open_erase_lib::schemas::user::{
-    DeleteUserResponse, GetUserResponse, PatchUserRequest, PatchUserResponse, PostUserRequest,
-    PostUserResponse,
+    DeleteUserResponse, GetUserResponse, PatchUserRequest, PatchUserResponse, PostPasswordRequest,
+    PostUserRequest, PostUserResponse,
 };
 use serde::{Deserialize, Serialize};
 
-use crate::{models::User, schemas::json};
+use crate::{
+    models::User,
+    schemas::json,
+    validation::{Validate, Validator},
+};
 
 #[derive(Serialize, Deserialize)]
 #[serde(transparent)]
@@ -25,6 +29,7 @@ impl From<User> for ServerGetUserResponse {
         Self(GetUserResponse {
             id: value.id,
             email: value.email,
+            role: value.role.into(),
             created_at: value.created_at,
             updated_at: value.updated_at,
         })
@@ -35,6 +40,13 @@ impl From<User> for ServerGetUserResponse {
 #[serde(transparent)]
 pub struct ServerPostUserRequest(pub PostUserRequest);
 
+impl Validate for ServerPostUserRequest {
+    fn validate(&self, validator: &mut Validator) {
+        validator.email("email", &self.0.email);
+        validator.password("password", &self.0.password);
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(transparent)]
 pub struct ServerPostUserResponse(pub PostUserResponse);
@@ -55,6 +67,7 @@ impl From<User> for ServerPostUserResponse {
         Self(PostUserResponse {
             id: value.id,
             email: value.email,
+            role: value.role.into(),
             created_at: value.created_at,
             updated_at: value.updated_at,
         })
@@ -65,6 +78,14 @@ impl From<User> for ServerPostUserResponse {
 #[serde(transparent)]
 pub struct ServerPatchUserRequest(pub PatchUserRequest);
 
+impl Validate for ServerPatchUserRequest {
+    fn validate(&self, validator: &mut Validator) {
+        if let Some(email) = &self.0.email {
+            validator.email("email", email);
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(transparent)]
 pub struct ServerPatchUserResponse(pub PatchUserResponse);
@@ -80,6 +101,7 @@ impl From<User> for ServerPatchUserResponse {
         Self(PatchUserResponse {
             id: value.id,
             email: value.email,
+            role: value.role.into(),
             created_at: value.created_at,
             updated_at: value.updated_at,
         })
@@ -101,3 +123,30 @@ impl From<User> for ServerDeleteUserResponse {
         Self(DeleteUserResponse { id: value.id })
     }
 }
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerPostPasswordRequest(pub PostPasswordRequest);
+
+impl Validate for ServerPostPasswordRequest {
+    fn validate(&self, validator: &mut Validator) {
+        validator.text("current_password", &self.0.current_password, usize::MAX);
+        validator.password("new_password", &self.0.new_password);
+    }
+}
+
+pub struct ServerPostPasswordResponse;
+
+impl IntoResponse for ServerPostPasswordResponse {
+    fn into_response(self) -> Response {
+        StatusCode::NO_CONTENT.into_response()
+    }
+}
+
+pub struct ServerPostUnlockUserResponse;
+
+impl IntoResponse for ServerPostUnlockUserResponse {
+    fn into_response(self) -> Response {
+        StatusCode::NO_CONTENT.into_response()
+    }
+}
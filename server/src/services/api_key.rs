@@ -0,0 +1,143 @@
+use std::sync::Arc;
+
+use base64::{Engine, prelude::BASE64_URL_SAFE_NO_PAD};
+use chrono::{DateTime, TimeDelta, Utc};
+use data_encoding::HEXLOWER;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::{
+    error::ServiceResult,
+    models::{ApiKey, ApiKeyScope},
+    repositories::{api_key::ApiKeyRepository, station::StationRepository, user::UserRepository},
+    services::auth::{Claims, generate_byte_key},
+};
+
+// marks the keys of this server, so they are easy to spot in configs and secret scanners
+const KEY_MARKER: &str = "oe";
+const PREFIX_LENGTH: usize = 6;
+const SECRET_LENGTH: usize = 32;
+// keeps a key in constant use from writing on every request
+const LAST_USED_PRECISION: TimeDelta = TimeDelta::minutes(1);
+
+#[derive(Clone)]
+pub struct ApiKeyService {
+    api_key_repository: Arc<dyn ApiKeyRepository>,
+    user_repository: Arc<dyn UserRepository>,
+    station_repository: Arc<dyn StationRepository>,
+}
+
+impl ApiKeyService {
+    pub fn new(
+        api_key_repository: Arc<dyn ApiKeyRepository>,
+        user_repository: Arc<dyn UserRepository>,
+        station_repository: Arc<dyn StationRepository>,
+    ) -> Self {
+        Self {
+            api_key_repository,
+            user_repository,
+            station_repository,
+        }
+    }
+}
+
+impl ApiKeyService {
+    pub async fn find_by_organization_id(
+        &self,
+        organization_id: Uuid,
+    ) -> ServiceResult<Vec<ApiKey>> {
+        Ok(self
+            .api_key_repository
+            .find_by_organization_id(organization_id)
+            .await?)
+    }
+
+    /// Keys look like `oe_<prefix>_<secret>`. The prefix identifies the key and is kept
+    /// in plain text, only a hash of the secret is stored.
+    pub async fn create(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+        station_id: Option<Uuid>,
+        name: String,
+        scopes: Vec<ApiKeyScope>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> ServiceResult<(ApiKey, String)> {
+        let prefix = HEXLOWER.encode(&generate_byte_key::<PREFIX_LENGTH>());
+        let secret = BASE64_URL_SAFE_NO_PAD.encode(generate_byte_key::<SECRET_LENGTH>());
+        let api_key = self
+            .api_key_repository
+            .create(
+                organization_id,
+                user_id,
+                station_id,
+                name,
+                prefix.clone(),
+                hash_secret(&secret),
+                scopes,
+                expires_at,
+            )
+            .await?;
+        Ok((api_key, format!("{KEY_MARKER}_{prefix}_{secret}")))
+    }
+
+    pub async fn revoke(&self, organization_id: Uuid, id: Uuid) -> ServiceResult<Option<ApiKey>> {
+        Ok(self.api_key_repository.revoke(organization_id, id).await?)
+    }
+
+    pub async fn revoke_all_by_station_id(&self, station_id: Uuid) -> ServiceResult<()> {
+        Ok(self
+            .api_key_repository
+            .revoke_all_by_station_id(station_id)
+            .await?)
+    }
+
+    /// Claims acting with the current role of the user who created the key, limited to
+    /// its scopes. The key of a station is only accepted from the hardware it was
+    /// enrolled on while the station is enabled.
+    pub async fn get_valid_api_key_claims(
+        &self,
+        raw_key: &str,
+        hardware_fingerprint: Option<&str>,
+    ) -> ServiceResult<Option<Claims>> {
+        let mut parts = raw_key.splitn(3, '_');
+        let (Some(KEY_MARKER), Some(prefix), Some(secret)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            return Ok(None);
+        };
+        let Some(api_key) = self.api_key_repository.find_by_prefix(prefix).await? else {
+            return Ok(None);
+        };
+        if !api_key.is_valid() || api_key.key_hash != hash_secret(secret) {
+            return Ok(None);
+        }
+        if let Some(station_id) = api_key.station_id {
+            let Some(station) = self.station_repository.find_by_id(station_id).await? else {
+                return Ok(None);
+            };
+            if station.is_disabled()
+                || hardware_fingerprint.is_none()
+                || station.hardware_fingerprint.as_deref() != hardware_fingerprint
+            {
+                return Ok(None);
+            }
+        }
+        let Some(user) = self.user_repository.find_by_id(api_key.user_id).await? else {
+            return Ok(None);
+        };
+        if api_key
+            .last_used_at
+            .is_none_or(|last_used_at| Utc::now() - last_used_at > LAST_USED_PRECISION)
+        {
+            self.api_key_repository.touch(api_key.id).await?;
+        }
+        Ok(Some(Claims::from_api_key(&user, &api_key)))
+    }
+}
+
+/// Secrets are random 256 bit values, which a fast hash protects as well as a password
+/// hash would, without slowing down every request made with a key.
+fn hash_secret(secret: &str) -> String {
+    HEXLOWER.encode(&Sha256::digest(secret.as_bytes()))
+}
@@ -4,8 +4,8 @@ use argon2::{
 };
 use base64::{Engine, prelude::BASE64_URL_SAFE_NO_PAD};
 use chrono::Utc;
-use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation, decode, encode};
-use serde::{Deserialize, Serialize};
+use jsonwebtoken::{Header, Validation, decode, decode_header, encode};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use std::{
     sync::{Arc, LazyLock},
     time::Duration,
@@ -13,96 +13,378 @@ use std::{
 use uuid::Uuid;
 
 use crate::{
+    auth_provider::AuthProvider,
+    config::AuthConfig,
     error::ServiceResult,
-    models::{RefreshToken, User},
-    repositories::{refresh_token::RefreshTokenRepository, user::UserRepository},
+    models::{ApiKey, ApiKeyScope, Organization, Permission, RefreshToken, Role, User},
+    repositories::{
+        organization::OrganizationRepository, refresh_token::RefreshTokenRepository,
+        session::SessionRepository, user::UserRepository,
+    },
+    schemas::session::ClientInfo,
+    services::signing_key::SigningKeyService,
 };
 
-const ISSUER: &str = "open-erase";
-const ACCESS_TOKEN_VALIDITY_DURATION: Duration = Duration::from_secs(60 * 15); // 15 minutes
+pub(crate) const ISSUER: &str = "open-erase";
+// audience of challenge tokens, which keeps them from passing as access tokens
+const MFA_CHALLENGE_AUDIENCE: &str = "mfa-challenge";
 const KEY_LENGTH: usize = 32;
 
 static ARGON2: LazyLock<Argon2<'static>> = LazyLock::new(Argon2::default);
-static ENCRYPTION_KEY: LazyLock<[u8; KEY_LENGTH]> = LazyLock::new(generate_byte_key::<KEY_LENGTH>);
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: String,
+    pub org: Uuid,
+    pub role: Role,
+    /// Whether the login behind this token passed a second factor.
+    #[serde(default)]
+    pub mfa: bool,
+    /// Whether the user is a server admin, see [`Permission::is_server_wide`].
+    #[serde(default)]
+    pub server_admin: bool,
+    /// Set for requests made with an API key, which only grant what their scopes allow.
+    #[serde(skip)]
+    pub scopes: Option<Vec<ApiKeyScope>>,
     pub iss: String,
     pub exp: usize,
     pub iat: usize,
 }
 
 impl Claims {
-    pub fn new(user_id: Uuid) -> Self {
+    pub fn new(
+        user: &User,
+        organization_id: Uuid,
+        lifetime: Duration,
+        is_mfa_verified: bool,
+    ) -> Self {
         let now = Utc::now();
-        let access_token_expires_at = now + ACCESS_TOKEN_VALIDITY_DURATION;
-        let sub = user_id.to_string();
+        let access_token_expires_at = now + lifetime;
+        let sub = user.id.to_string();
         let exp = access_token_expires_at.timestamp() as usize;
         let iat = now.timestamp() as usize;
         let iss = String::from(ISSUER);
-        Self { sub, iss, exp, iat }
+        Self {
+            sub,
+            org: organization_id,
+            role: user.role,
+            mfa: is_mfa_verified,
+            server_admin: user.is_server_admin,
+            scopes: None,
+            iss,
+            exp,
+            iat,
+        }
+    }
+
+    /// Claims of a request authenticated with an API key, never issued as a token.
+    pub fn from_api_key(user: &User, api_key: &ApiKey) -> Self {
+        let now = Utc::now().timestamp() as usize;
+        Self {
+            sub: user.id.to_string(),
+            org: api_key.organization_id,
+            role: user.role,
+            mfa: false,
+            server_admin: false,
+            scopes: Some(api_key.scopes.clone()),
+            iss: String::from(ISSUER),
+            exp: now,
+            iat: now,
+        }
+    }
+
+    /// The role has to grant `permission` and, for API keys, one of the scopes too.
+    /// Destructive permissions also need a login that passed a second factor, server-wide
+    /// ones a server admin.
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        self.role.has_permission(permission)
+            && (!permission.requires_mfa() || self.mfa)
+            && (!permission.is_server_wide() || self.server_admin)
+            && self
+                .scopes
+                .as_ref()
+                .is_none_or(|scopes| scopes.iter().any(|scope| scope.grants(permission)))
+    }
+
+    pub fn user_id(&self) -> ServiceResult<Uuid> {
+        Ok(Uuid::parse_str(&self.sub)?)
     }
 }
 
+/// Proves the password of `sub` was accepted, exchanged for tokens with a second factor.
+#[derive(Serialize, Deserialize)]
+struct MfaChallengeClaims {
+    sub: Uuid,
+    aud: String,
+    iss: String,
+    exp: usize,
+    iat: usize,
+}
+
 #[derive(Clone)]
 #[allow(dead_code)]
 pub struct AuthService {
     user_repository: Arc<dyn UserRepository>,
     refresh_token_repository: Arc<dyn RefreshTokenRepository>,
+    organization_repository: Arc<dyn OrganizationRepository>,
+    session_repository: Arc<dyn SessionRepository>,
+    auth_providers: Vec<Arc<dyn AuthProvider>>,
+    signing_key_service: SigningKeyService,
+    auth_config: AuthConfig,
 }
 
 impl AuthService {
     pub fn new(
         user_repository: Arc<dyn UserRepository>,
         refresh_token_repository: Arc<dyn RefreshTokenRepository>,
+        organization_repository: Arc<dyn OrganizationRepository>,
+        session_repository: Arc<dyn SessionRepository>,
+        auth_providers: Vec<Arc<dyn AuthProvider>>,
+        signing_key_service: SigningKeyService,
+        auth_config: AuthConfig,
     ) -> Self {
         Self {
             user_repository,
             refresh_token_repository,
+            organization_repository,
+            session_repository,
+            auth_providers,
+            signing_key_service,
+            auth_config,
         }
     }
 
-    pub async fn get_user_from_basic_auth(
+    /// Asks the auth providers in turn, the first to accept the credentials wins. A
+    /// provider that fails, such as an unreachable directory, counts as not accepting
+    /// them, so the attempt is still throttled like any other failed login.
+    pub async fn get_user_from_basic_auth(&self, email: &str, password: &str) -> Option<User> {
+        for auth_provider in &self.auth_providers {
+            match auth_provider.authenticate(email, password).await {
+                Ok(Some(user)) => return Some(user),
+                Ok(None) => {}
+                Err(service_error) => tracing::error!("{:#?}", service_error),
+            }
+        }
+        None
+    }
+
+    /// Replaces the password of a user after checking their current one and revokes
+    /// every refresh token they hold, so other sessions have to log in again.
+    pub async fn change_password(
         &self,
-        email: &str,
-        password: &str,
+        user_id: Uuid,
+        current_password: &str,
+        new_password: &str,
     ) -> ServiceResult<Option<User>> {
-        if let Some(user) = self.user_repository.find_by_email(email).await?
-            && is_valid_password(password, &user.password_hash)
+        let Some(user) = self.user_repository.find_by_id(user_id).await? else {
+            return Ok(None);
+        };
+        if !is_valid_password(current_password, &user.password_hash) {
+            return Ok(None);
+        }
+        let password_hash = generate_hash(new_password)?;
+        let user = self
+            .user_repository
+            .update_password_hash(user.id, password_hash)
+            .await?;
+        self.refresh_token_repository
+            .revoke_all_by_user_id(user.id)
+            .await?;
+        Ok(Some(user))
+    }
+
+    /// The organization a user acts for after login is the one they joined first.
+    pub async fn find_default_organization(
+        &self,
+        user_id: Uuid,
+    ) -> ServiceResult<Option<Organization>> {
+        Ok(self
+            .organization_repository
+            .find_by_user_id(user_id)
+            .await?
+            .into_iter()
+            .next())
+    }
+
+    /// The organization the session of the refresh token was switched to, as long as its
+    /// user is still a member, and the default organization otherwise.
+    pub async fn find_session_organization(
+        &self,
+        refresh_token: &RefreshToken,
+    ) -> ServiceResult<Option<Organization>> {
+        if let Some(session_id) = refresh_token.session_id
+            && let Some(session) = self.session_repository.find_by_id(session_id).await?
+            && let Some(organization_id) = session.organization_id
+            && self
+                .organization_repository
+                .find_member(organization_id, refresh_token.user_id)
+                .await?
+                .is_some()
         {
-            return Ok(Some(user));
+            return Ok(self
+                .organization_repository
+                .find_by_id(organization_id)
+                .await?);
+        }
+        self.find_default_organization(refresh_token.user_id).await
+    }
+
+    /// Makes the session of the refresh token act for another organization of its user.
+    /// Returns `None` if the user isn't a member or the token belongs to no session.
+    pub async fn switch_organization(
+        &self,
+        refresh_token: &RefreshToken,
+        organization_id: Uuid,
+    ) -> ServiceResult<Option<Organization>> {
+        let Some(session_id) = refresh_token.session_id else {
+            return Ok(None);
         };
-        Ok(None)
+        if self
+            .organization_repository
+            .find_member(organization_id, refresh_token.user_id)
+            .await?
+            .is_none()
+        {
+            return Ok(None);
+        }
+        self.session_repository
+            .set_organization(session_id, organization_id)
+            .await?;
+        Ok(self
+            .organization_repository
+            .find_by_id(organization_id)
+            .await?)
+    }
+
+    pub async fn generate_access_token(
+        &self,
+        user: &User,
+        organization_id: Uuid,
+        is_mfa_verified: bool,
+    ) -> ServiceResult<String> {
+        let claims = Claims::new(
+            user,
+            organization_id,
+            self.auth_config.access_token_lifetime(),
+            is_mfa_verified,
+        );
+        self.sign(&claims).await
+    }
+
+    pub async fn get_valid_access_token_claims(
+        &self,
+        access_token: &str,
+    ) -> ServiceResult<Option<Claims>> {
+        self.verify(access_token, None).await
+    }
+
+    /// Short-lived token handed out instead of a login response while a second factor is
+    /// outstanding.
+    pub async fn generate_mfa_challenge_token(&self, user: &User) -> ServiceResult<String> {
+        let now = Utc::now();
+        let claims = MfaChallengeClaims {
+            sub: user.id,
+            aud: String::from(MFA_CHALLENGE_AUDIENCE),
+            iss: String::from(ISSUER),
+            exp: (now + self.auth_config.mfa_challenge_lifetime()).timestamp() as usize,
+            iat: now.timestamp() as usize,
+        };
+        self.sign(&claims).await
+    }
+
+    pub async fn get_mfa_challenge_user_id(
+        &self,
+        challenge_token: &str,
+    ) -> ServiceResult<Option<Uuid>> {
+        Ok(self
+            .verify::<MfaChallengeClaims>(challenge_token, Some(MFA_CHALLENGE_AUDIENCE))
+            .await?
+            .map(|claims| claims.sub))
+    }
+
+    pub(crate) async fn sign<T: Serialize>(&self, claims: &T) -> ServiceResult<String> {
+        let signing_key = self.signing_key_service.current_signing_key().await?;
+        let mut header = Header::new(signing_key.jwt_algorithm());
+        header.kid = Some(signing_key.id.to_string());
+        Ok(encode(&header, claims, &signing_key.encoding_key())?)
     }
 
-    pub fn generate_access_token(&self, user_id: Uuid) -> ServiceResult<String> {
-        let claims = Claims::new(user_id);
-        let key = EncodingKey::from_secret(&*ENCRYPTION_KEY);
-        Ok(encode(&Header::default(), &claims, &key)?)
+    /// `None` if the token is invalid, expired or meant for another audience.
+    pub(crate) async fn verify<T: DeserializeOwned>(
+        &self,
+        token: &str,
+        audience: Option<&str>,
+    ) -> ServiceResult<Option<T>> {
+        if let Ok(header) = decode_header(token)
+            && let Some(kid) = header.kid
+            && let Ok(kid) = Uuid::parse_str(&kid)
+            && let Some(signing_key) = self.signing_key_service.find_verification_key(kid).await?
+        {
+            let mut validation = Validation::new(signing_key.jwt_algorithm());
+            validation.set_issuer(&[ISSUER]);
+            if let Some(audience) = audience {
+                validation.set_audience(&[audience]);
+            }
+            return Ok(decode::<T>(token, &signing_key.decoding_key(), &validation)
+                .ok()
+                .map(|token_data| token_data.claims));
+        }
+        Ok(None)
     }
 
-    pub fn get_valid_access_token_claims(&self, access_token: &str) -> Option<Claims> {
-        let key = DecodingKey::from_secret(&*ENCRYPTION_KEY);
-        let mut validation = Validation::new(jsonwebtoken::Algorithm::HS256);
-        validation.set_issuer(&[ISSUER]);
-        decode::<Claims>(access_token, &key, &validation)
-            .ok()
-            .map(|token_data| token_data.claims)
+    /// Every login opens a new session that the refresh tokens cycled from it belong to.
+    pub async fn generate_refresh_token_from_login(
+        &self,
+        user_id: Uuid,
+        client_info: ClientInfo,
+        is_mfa_verified: bool,
+    ) -> ServiceResult<String> {
+        let session = self
+            .session_repository
+            .create(
+                user_id,
+                client_info.user_agent,
+                client_info.ip_address,
+                is_mfa_verified,
+            )
+            .await?;
+        self.generate_refresh_token(user_id, None, Some(session.id))
+            .await
     }
 
-    pub async fn generate_refresh_token_from_login(&self, user_id: Uuid) -> ServiceResult<String> {
-        self.generate_refresh_token(user_id, None).await
+    /// Access tokens cycled from a refresh token keep the second factor of its login.
+    pub async fn is_mfa_verified(&self, refresh_token: &RefreshToken) -> ServiceResult<bool> {
+        let Some(session_id) = refresh_token.session_id else {
+            return Ok(false);
+        };
+        Ok(self
+            .session_repository
+            .find_by_id(session_id)
+            .await?
+            .is_some_and(|session| session.is_mfa_verified))
     }
 
-    pub async fn cycle_refresh_token(&self, refresh_token: &RefreshToken) -> ServiceResult<String> {
+    pub async fn cycle_refresh_token(
+        &self,
+        refresh_token: &RefreshToken,
+        client_info: ClientInfo,
+    ) -> ServiceResult<String> {
         let _ = self
             .refresh_token_repository
             .mark_as_used(refresh_token.id)
             .await?;
+        if let Some(session_id) = refresh_token.session_id {
+            self.session_repository
+                .touch(session_id, client_info.user_agent, client_info.ip_address)
+                .await?;
+        }
 
-        self.generate_refresh_token(refresh_token.user_id, Some(refresh_token.id))
-            .await
+        self.generate_refresh_token(
+            refresh_token.user_id,
+            Some(refresh_token.id),
+            refresh_token.session_id,
+        )
+        .await
     }
 
     pub async fn mark_refresh_token_as_used(
@@ -120,18 +402,28 @@ impl AuthService {
         &self,
         user_id: Uuid,
         parent_id: Option<Uuid>,
+        session_id: Option<Uuid>,
     ) -> ServiceResult<String> {
         let opaque_token_bytes = generate_byte_key::<KEY_LENGTH>();
         let opaque_token_raw = BASE64_URL_SAFE_NO_PAD.encode(opaque_token_bytes);
         let opaque_token_hash = generate_hash(&opaque_token_raw)?;
+        let expires_at = Utc::now() + self.auth_config.refresh_token_lifetime();
         let refresh_token = self
             .refresh_token_repository
-            .create(user_id, parent_id, opaque_token_hash)
+            .create(
+                user_id,
+                parent_id,
+                session_id,
+                opaque_token_hash,
+                expires_at,
+            )
             .await?;
         let composite_refresh_token = format!("{}.{}", refresh_token.id, opaque_token_raw);
         Ok(composite_refresh_token)
     }
 
+    /// Presenting a refresh token that was already cycled means it has leaked, so the
+    /// whole family it belongs to is revoked and its owner has to log in again.
     pub async fn get_valid_refresh_token(
         &self,
         composite_refresh_token: &str,
@@ -143,16 +435,30 @@ impl AuthService {
                 .refresh_token_repository
                 .find_by_id(refresh_token_id)
                 .await?
-            && refresh_token.is_valid()
             && is_valid_password(opaque_token_raw, &refresh_token.opaque_token_hash)
         {
-            return Ok(Some(refresh_token));
+            if refresh_token.is_used {
+                let revoked_refresh_tokens = self
+                    .refresh_token_repository
+                    .revoke_family(refresh_token.id)
+                    .await?;
+                tracing::warn!(
+                    user_id = %refresh_token.user_id,
+                    refresh_token_id = %refresh_token.id,
+                    revoked_count = revoked_refresh_tokens.len(),
+                    "refresh token reused, revoked its token family"
+                );
+                return Ok(None);
+            }
+            if refresh_token.is_valid() {
+                return Ok(Some(refresh_token));
+            }
         };
         Ok(None)
     }
 }
 
-fn is_valid_password(password: &str, password_hash: &str) -> bool {
+pub(crate) fn is_valid_password(password: &str, password_hash: &str) -> bool {
     if let Ok(parsed_hash) = PasswordHash::new(password_hash)
         && ARGON2
             .verify_password(password.as_bytes(), &parsed_hash)
@@ -163,14 +469,20 @@ fn is_valid_password(password: &str, password_hash: &str) -> bool {
     false
 }
 
-fn generate_hash(password: &str) -> ServiceResult<String> {
+pub(crate) fn generate_hash(password: &str) -> ServiceResult<String> {
     let salt_string = SaltString::generate(&mut OsRng);
     Ok(ARGON2
         .hash_password(password.as_bytes(), &salt_string)
         .map(|hash| hash.to_string())?)
 }
 
-fn generate_byte_key<const N: usize>() -> [u8; N] {
+/// Hash for accounts that sign in through an external provider, whose password nobody
+/// knows until it's reset.
+pub(crate) fn generate_unusable_password_hash() -> ServiceResult<String> {
+    generate_hash(&BASE64_URL_SAFE_NO_PAD.encode(generate_byte_key::<KEY_LENGTH>()))
+}
+
+pub(crate) fn generate_byte_key<const N: usize>() -> [u8; N] {
     let mut key = [0u8; N];
     getrandom::fill(&mut key).unwrap();
     key
@@ -0,0 +1,212 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use open_erase_lib::schemas::error::FieldError;
+use uuid::Uuid;
+
+use crate::{
+    error::{ServiceError, ServiceResult},
+    models::{Batch, Device, ErasureResult, Report},
+    repositories::{batch::BatchRepository, device::DeviceRepository, report::ReportRepository},
+    schemas::{
+        batch::{ServerPatchBatchRequest, ServerPostBatchRequest},
+        device::ServerPostDeviceRequest,
+    },
+};
+
+pub struct BatchSummary {
+    pub batch: Batch,
+    pub device_count: u32,
+    pub erased_device_count: u32,
+    pub failed_device_count: u32,
+}
+
+impl BatchSummary {
+    pub fn pending_device_count(&self) -> u32 {
+        self.device_count - self.erased_device_count - self.failed_device_count
+    }
+
+    pub fn outstanding_device_count(&self) -> u32 {
+        (self.batch.expected_device_count.max(0) as u32).saturating_sub(self.erased_device_count)
+    }
+}
+
+pub struct BatchCertificate {
+    pub issued_at: DateTime<Utc>,
+    pub summary: BatchSummary,
+    pub entries: Vec<(Device, Option<Report>)>,
+}
+
+#[derive(Clone)]
+pub struct BatchService {
+    batch_repository: Arc<dyn BatchRepository>,
+    device_repository: Arc<dyn DeviceRepository>,
+    report_repository: Arc<dyn ReportRepository>,
+}
+
+impl BatchService {
+    pub fn new(
+        batch_repository: Arc<dyn BatchRepository>,
+        device_repository: Arc<dyn DeviceRepository>,
+        report_repository: Arc<dyn ReportRepository>,
+    ) -> Self {
+        Self {
+            batch_repository,
+            device_repository,
+            report_repository,
+        }
+    }
+}
+
+impl BatchService {
+    pub async fn get_all(&self, organization_id: Uuid) -> ServiceResult<Vec<Batch>> {
+        Ok(self.batch_repository.find_all(organization_id).await?)
+    }
+
+    pub async fn find_batch_by_id(
+        &self,
+        organization_id: Uuid,
+        id: Uuid,
+    ) -> ServiceResult<Option<Batch>> {
+        Ok(self
+            .batch_repository
+            .find_by_id(organization_id, id)
+            .await?)
+    }
+
+    pub async fn create_batch(
+        &self,
+        organization_id: Uuid,
+        batch: ServerPostBatchRequest,
+    ) -> ServiceResult<Batch> {
+        let expected_device_count = device_count(batch.0.expected_device_count)?;
+        Ok(self
+            .batch_repository
+            .create(
+                organization_id,
+                batch.0.customer_reference,
+                expected_device_count,
+            )
+            .await?)
+    }
+
+    pub async fn update_batch(
+        &self,
+        organization_id: Uuid,
+        id: Uuid,
+        batch: ServerPatchBatchRequest,
+    ) -> ServiceResult<Batch> {
+        let expected_device_count = batch
+            .0
+            .expected_device_count
+            .map(device_count)
+            .transpose()?;
+        Ok(self
+            .batch_repository
+            .update(
+                organization_id,
+                id,
+                batch.0.customer_reference,
+                expected_device_count,
+                batch.0.status.map(Into::into),
+            )
+            .await?)
+    }
+
+    pub async fn delete_batch(&self, organization_id: Uuid, id: Uuid) -> ServiceResult<Batch> {
+        Ok(self.batch_repository.delete(organization_id, id).await?)
+    }
+
+    pub async fn find_devices(&self, batch: &Batch) -> ServiceResult<Vec<Device>> {
+        Ok(self
+            .device_repository
+            .find_by_batch_id(batch.organization_id, batch.id)
+            .await?)
+    }
+
+    pub async fn add_device(
+        &self,
+        batch: &Batch,
+        device: ServerPostDeviceRequest,
+    ) -> ServiceResult<Device> {
+        Ok(self
+            .device_repository
+            .create(
+                batch.organization_id,
+                Some(batch.id),
+                device.0.serial_number,
+                device.0.manufacturer,
+                device.0.model,
+            )
+            .await?)
+    }
+
+    pub async fn find_reports(&self, batch: &Batch) -> ServiceResult<Vec<Report>> {
+        Ok(self
+            .report_repository
+            .find_by_batch_id(batch.organization_id, batch.id)
+            .await?)
+    }
+
+    pub async fn summarize(&self, batch: Batch) -> ServiceResult<BatchSummary> {
+        let entries = self.latest_reports(&batch).await?;
+        Ok(summarize(batch, &entries))
+    }
+
+    pub async fn issue_certificate(&self, batch: Batch) -> ServiceResult<BatchCertificate> {
+        let entries = self.latest_reports(&batch).await?;
+        let summary = summarize(batch, &entries);
+        Ok(BatchCertificate {
+            issued_at: Utc::now(),
+            summary,
+            entries,
+        })
+    }
+
+    /// Pairs every device of the batch with its most recently finished report.
+    async fn latest_reports(&self, batch: &Batch) -> ServiceResult<Vec<(Device, Option<Report>)>> {
+        let devices = self.find_devices(batch).await?;
+        let reports = self.find_reports(batch).await?;
+        let entries = devices
+            .into_iter()
+            .map(|device| {
+                let report = reports
+                    .iter()
+                    .filter(|report| report.device_id == device.id)
+                    .max_by_key(|report| report.finished_at)
+                    .cloned();
+                (device, report)
+            })
+            .collect();
+        Ok(entries)
+    }
+}
+
+fn summarize(batch: Batch, entries: &[(Device, Option<Report>)]) -> BatchSummary {
+    let count_results = |result: ErasureResult| {
+        entries
+            .iter()
+            .filter(|(_, report)| {
+                report
+                    .as_ref()
+                    .is_some_and(|report| report.result == result)
+            })
+            .count() as u32
+    };
+    BatchSummary {
+        batch,
+        device_count: entries.len() as u32,
+        erased_device_count: count_results(ErasureResult::Passed),
+        failed_device_count: count_results(ErasureResult::Failed),
+    }
+}
+
+/// Counts are stored as `INTEGER`.
+fn device_count(count: u32) -> ServiceResult<i32> {
+    i32::try_from(count).map_err(|_| {
+        ServiceError::Validation(vec![FieldError {
+            field: String::from("expected_device_count"),
+            message: format!("must be at most {}", i32::MAX),
+        }])
+    })
+}
@@ -0,0 +1,156 @@
+use std::io::{self, SeekFrom};
+
+use tokio::{
+    fs,
+    io::{AsyncReadExt, AsyncSeekExt, Take},
+};
+use uuid::Uuid;
+
+use crate::{
+    config::{BootConfig, ServerConfig},
+    error::ServiceResult,
+    iso9660::{self, Extent},
+    models::Architecture,
+    services::image::ImageService,
+};
+
+/// `install_dir` of the archiso profile, the only part of an image that is served.
+const ARCHISO_BASE_DIR: &str = "arch";
+const KERNEL: &str = "vmlinuz-linux";
+const INITRAMFS: &str = "initramfs-linux.img";
+/// Loaded before the initramfs if the image ships them.
+const MICROCODE: [&str; 2] = ["intel-ucode.img", "amd-ucode.img"];
+/// Names iPXE looks for on the TFTP server.
+const CHAIN_SCRIPTS: [&str; 2] = ["boot.ipxe", "autoexec.ipxe"];
+
+#[derive(Clone)]
+pub struct BootService {
+    image_service: ImageService,
+    boot_config: BootConfig,
+    public_url: String,
+}
+
+/// A file inside an image, positioned at its start.
+pub struct BootArtifact {
+    pub size: u64,
+    pub content: Take<fs::File>,
+}
+
+impl BootService {
+    pub fn new(
+        image_service: ImageService,
+        boot_config: BootConfig,
+        server_config: &ServerConfig,
+    ) -> Self {
+        Self {
+            image_service,
+            boot_config,
+            public_url: server_config.public_url.trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+impl BootService {
+    /// Script that boots the active image of `architecture` over HTTP, `None` if there is
+    /// none or it lacks a kernel.
+    pub async fn ipxe_script(&self, architecture: Architecture) -> ServiceResult<Option<String>> {
+        if !self.boot_config.enabled {
+            return Ok(None);
+        }
+        let Some(image) = self.image_service.find_active(architecture).await? else {
+            return Ok(None);
+        };
+        let boot_dir = format!("{ARCHISO_BASE_DIR}/boot");
+        let kernel = format!("{boot_dir}/{}/{KERNEL}", architecture.as_str());
+        let mut file = fs::File::open(self.image_service.path(&image)).await?;
+        if iso9660::find(&mut file, &kernel).await?.is_none() {
+            return Ok(None);
+        }
+        let mut initrds = Vec::new();
+        for microcode in MICROCODE {
+            let path = format!("{boot_dir}/{microcode}");
+            if iso9660::find(&mut file, &path).await?.is_some() {
+                initrds.push(path);
+            }
+        }
+        initrds.push(format!("{boot_dir}/{}/{INITRAMFS}", architecture.as_str()));
+
+        let base_url = format!("{}/api/boot/images/{}", self.public_url, image.id);
+        // EFI kernels pick their initrds by the file names iPXE registered
+        let initrd_parameters: Vec<String> = initrds
+            .iter()
+            .map(|path| format!("initrd={}", file_name(path)))
+            .collect();
+        let mut script = format!(
+            "#!ipxe\n# {}\nkernel {base_url}/{kernel} {} archisobasedir={ARCHISO_BASE_DIR} \
+             archiso_http_srv={base_url}/ ip=dhcp",
+            image.download_name(),
+            initrd_parameters.join(" "),
+        );
+        if !self.boot_config.kernel_parameters.is_empty() {
+            script.push(' ');
+            script.push_str(&self.boot_config.kernel_parameters);
+        }
+        script.push('\n');
+        for initrd in initrds {
+            script.push_str(&format!("initrd {base_url}/{initrd}\n"));
+        }
+        script.push_str("boot\n");
+        Ok(Some(script))
+    }
+
+    /// Opens the file at `path` inside the image, as long as the image is active and
+    /// the file belongs to the archiso install directory.
+    pub async fn open_artifact(&self, id: Uuid, path: &str) -> ServiceResult<Option<BootArtifact>> {
+        if !self.boot_config.enabled || !path.starts_with(&format!("{ARCHISO_BASE_DIR}/")) {
+            return Ok(None);
+        }
+        let Some(image) = self.image_service.find_by_id(id).await? else {
+            return Ok(None);
+        };
+        if !image.is_active {
+            return Ok(None);
+        }
+        let mut file = fs::File::open(self.image_service.path(&image)).await?;
+        let Some(Extent { offset, size }) = iso9660::find(&mut file, path).await? else {
+            return Ok(None);
+        };
+        file.seek(SeekFrom::Start(offset)).await?;
+        Ok(Some(BootArtifact {
+            size,
+            content: file.take(size),
+        }))
+    }
+
+    /// A file for the TFTP responder: a script that chains to the HTTP boot, or one of
+    /// the iPXE binaries.
+    pub async fn tftp_file(&self, name: &str) -> ServiceResult<Option<Vec<u8>>> {
+        let name = name.trim_start_matches('/');
+        if CHAIN_SCRIPTS.contains(&name) {
+            return Ok(Some(self.chain_script().into_bytes()));
+        }
+        if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+            return Ok(None);
+        }
+        match fs::read(self.boot_config.ipxe_directory.join(name)).await {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// Hands over from TFTP to HTTP. 32-bit BIOS builds of iPXE boot the x86_64 image.
+    fn chain_script(&self) -> String {
+        format!(
+            "#!ipxe\niseq ${{buildarch}} arm64 && set arch {} || set arch {}\n\
+             chain --autofree {}/api/boot/${{arch}}/boot.ipxe\n",
+            Architecture::Aarch64.as_str(),
+            Architecture::X86_64.as_str(),
+            self.public_url,
+        )
+    }
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
@@ -1,20 +1,349 @@
-use std::sync::Arc;
+use std::{io, path::PathBuf, sync::Arc};
 
-use crate::{error::ServiceResult, models::Image, repositories::image::ImageRepository};
+use data_encoding::HEXLOWER;
+use open_erase_lib::schemas::{
+    error::FieldError,
+    image::{CLIENT_CONFIG_PATH, ClientConfig},
+};
+use sha2::{Digest, Sha256};
+use tokio::{
+    fs,
+    io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt, SeekFrom, Take},
+};
+use uuid::Uuid;
+
+use crate::{
+    config::ImagesConfig,
+    error::{ServiceError, ServiceResult},
+    iso9660,
+    models::{Architecture, Image},
+    repositories::image::ImageRepository,
+};
+
+const COPY_BUFFER_SIZE: usize = 1024 * 1024;
 
 #[derive(Clone)]
 pub struct ImageService {
-    pub image_repository: Arc<dyn ImageRepository>,
+    image_repository: Arc<dyn ImageRepository>,
+    images_config: ImagesConfig,
 }
 
 impl ImageService {
-    pub fn new(image_repository: Arc<dyn ImageRepository>) -> Self {
-        Self { image_repository }
+    pub fn new(image_repository: Arc<dyn ImageRepository>, images_config: ImagesConfig) -> Self {
+        Self {
+            image_repository,
+            images_config,
+        }
     }
 }
 
 impl ImageService {
-    pub async fn get_all(&self) -> ServiceResult<Vec<Image>> {
-        Ok(self.image_repository.get_all().await?)
+    pub async fn find_all(&self) -> ServiceResult<Vec<Image>> {
+        Ok(self.image_repository.find_all().await?)
+    }
+
+    pub async fn find_by_id(&self, id: Uuid) -> ServiceResult<Option<Image>> {
+        Ok(self.image_repository.find_by_id(id).await?)
+    }
+
+    /// The image unless it is a site variant of another organization.
+    pub async fn find_visible(
+        &self,
+        organization_id: Uuid,
+        id: Uuid,
+    ) -> ServiceResult<Option<Image>> {
+        Ok(self
+            .image_repository
+            .find_by_id(id)
+            .await?
+            .filter(|image| image.is_visible_to(organization_id)))
+    }
+
+    pub async fn find_active(&self, architecture: Architecture) -> ServiceResult<Option<Image>> {
+        Ok(self.image_repository.find_active(architecture).await?)
+    }
+
+    pub async fn find_variants(
+        &self,
+        organization_id: Uuid,
+        base_image_id: Uuid,
+    ) -> ServiceResult<Vec<Image>> {
+        Ok(self
+            .image_repository
+            .find_variants(base_image_id, organization_id)
+            .await?)
+    }
+
+    pub fn path(&self, image: &Image) -> PathBuf {
+        image.path(&self.images_config.directory)
+    }
+
+    /// Reads `length` bytes of the ISO from `offset` on.
+    pub async fn open(
+        &self,
+        image: &Image,
+        offset: u64,
+        length: u64,
+    ) -> ServiceResult<Take<fs::File>> {
+        let mut file = fs::File::open(self.path(image)).await?;
+        file.seek(SeekFrom::Start(offset)).await?;
+        Ok(file.take(length))
+    }
+
+    /// Starts writing an uploaded ISO to a temporary file next to the images.
+    pub async fn start_upload(&self) -> ServiceResult<ImageUpload> {
+        fs::create_dir_all(&self.images_config.directory).await?;
+        let id = Uuid::now_v7();
+        let path = self.images_config.directory.join(format!("{id}.iso.part"));
+        let file = fs::File::create(&path).await?;
+        Ok(ImageUpload {
+            id,
+            file,
+            path,
+            is_registered: false,
+            hasher: Sha256::new(),
+            size_bytes: 0,
+            max_size_bytes: self.images_config.max_upload_size_bytes,
+        })
+    }
+
+    /// Moves a complete upload into place and registers it. Returns `None` if the
+    /// version already exists for the architecture.
+    pub async fn finish_upload(
+        &self,
+        upload: ImageUpload,
+        user_id: Uuid,
+        version: String,
+        architecture: Architecture,
+        release_notes: Option<String>,
+    ) -> ServiceResult<Option<Image>> {
+        if self
+            .image_repository
+            .find_by_version(&version, architecture)
+            .await?
+            .is_some()
+        {
+            return Ok(None);
+        }
+        Ok(Some(
+            self.register(upload, user_id, version, architecture, release_notes, None)
+                .await?,
+        ))
+    }
+
+    /// Copies the base image with `config` written over the placeholder at
+    /// `CLIENT_CONFIG_PATH`. The ISO keeps its layout and boot records, so mkarchiso
+    /// doesn't have to run again. Variants of a variant belong to its base image.
+    /// `station_id` names the station whose enrollment code is in `config`.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_variant(
+        &self,
+        base_image: &Image,
+        organization_id: Uuid,
+        user_id: Uuid,
+        variant_name: String,
+        config: &ClientConfig,
+        station_id: Option<Uuid>,
+        release_notes: Option<String>,
+    ) -> ServiceResult<Result<Image, VariantError>> {
+        let base_image_id = base_image.base_image_id.unwrap_or(base_image.id);
+        if self
+            .image_repository
+            .find_variant_by_name(base_image_id, organization_id, &variant_name)
+            .await?
+            .is_some()
+        {
+            return Ok(Err(VariantError::NameTaken));
+        }
+        let mut base = fs::File::open(self.path(base_image)).await?;
+        let Some(placeholder) = iso9660::find(&mut base, CLIENT_CONFIG_PATH).await? else {
+            return Ok(Err(VariantError::MissingPlaceholder));
+        };
+        let mut content = toml::to_string(config)
+            .map_err(io::Error::other)?
+            .into_bytes();
+        if content.len() as u64 > placeholder.size {
+            return Ok(Err(VariantError::ConfigTooLarge(placeholder.size)));
+        }
+        // TOML ignores the trailing whitespace
+        content.resize(placeholder.size as usize, b' ');
+
+        base.seek(SeekFrom::Start(0)).await?;
+        let mut upload = self.start_upload().await?;
+        let mut buffer = vec![0; COPY_BUFFER_SIZE];
+        let mut position = 0;
+        loop {
+            let length = base.read(&mut buffer).await?;
+            if length == 0 {
+                break;
+            }
+            let chunk = &mut buffer[..length];
+            overlay(chunk, position, placeholder.offset, &content);
+            upload.append(chunk).await?;
+            position += length as u64;
+        }
+        let image = self
+            .register(
+                upload,
+                user_id,
+                base_image.version.clone(),
+                base_image.architecture,
+                release_notes,
+                Some(Variant {
+                    base_image_id,
+                    organization_id,
+                    name: variant_name,
+                    station_id,
+                }),
+            )
+            .await?;
+        Ok(Ok(image))
+    }
+
+    /// Only base images can be activated, variants are meant to be downloaded.
+    pub async fn update(
+        &self,
+        organization_id: Uuid,
+        id: Uuid,
+        release_notes: Option<String>,
+        is_active: Option<bool>,
+    ) -> ServiceResult<Option<Image>> {
+        let Some(image) = self.find_visible(organization_id, id).await? else {
+            return Ok(None);
+        };
+        if image.base_image_id.is_some() && is_active == Some(true) {
+            return Err(ServiceError::Validation(vec![FieldError {
+                field: String::from("is_active"),
+                message: String::from("site variants cannot be activated"),
+            }]));
+        }
+        Ok(Some(
+            self.image_repository
+                .update(id, release_notes, is_active)
+                .await?,
+        ))
+    }
+
+    /// Moves the written ISO into place and stores it as a base image or, with `variant`,
+    /// as the named variant an organization made of a base image.
+    async fn register(
+        &self,
+        mut upload: ImageUpload,
+        user_id: Uuid,
+        version: String,
+        architecture: Architecture,
+        release_notes: Option<String>,
+        variant: Option<Variant>,
+    ) -> ServiceResult<Image> {
+        upload.file.flush().await?;
+        upload.file.sync_all().await?;
+        let path = self
+            .images_config
+            .directory
+            .join(format!("{}.iso", upload.id));
+        fs::rename(&upload.path, &path).await?;
+        // dropping the upload from here on, also when the request is cancelled, removes
+        // the renamed file until the image is stored
+        upload.path = path;
+        let sha256 = HEXLOWER.encode(&upload.hasher.clone().finalize());
+        let (base_image_id, organization_id, variant_name, station_id) = match variant {
+            Some(variant) => (
+                Some(variant.base_image_id),
+                Some(variant.organization_id),
+                Some(variant.name),
+                variant.station_id,
+            ),
+            None => (None, None, None, None),
+        };
+        let image = self
+            .image_repository
+            .create(
+                upload.id,
+                version,
+                architecture,
+                upload.size_bytes as i64,
+                sha256,
+                release_notes,
+                user_id,
+                base_image_id,
+                organization_id,
+                variant_name,
+                station_id,
+            )
+            .await?;
+        upload.is_registered = true;
+        Ok(image)
+    }
+}
+
+/// Why a site variant could not be built from a base image.
+#[derive(Debug, PartialEq, Eq)]
+pub enum VariantError {
+    NameTaken,
+    /// The base image has no room reserved at `CLIENT_CONFIG_PATH`.
+    MissingPlaceholder,
+    /// The config exceeds the placeholder, which has this many bytes.
+    ConfigTooLarge(u64),
+}
+
+/// What sets a variant apart from the base image it copies.
+struct Variant {
+    base_image_id: Uuid,
+    organization_id: Uuid,
+    name: String,
+    station_id: Option<Uuid>,
+}
+
+/// Copies the part of `replacement`, which belongs at `offset` of the file, that
+/// overlaps `chunk` read from `position`.
+fn overlay(chunk: &mut [u8], position: u64, offset: u64, replacement: &[u8]) {
+    let start = offset.max(position);
+    let end = (offset + replacement.len() as u64).min(position + chunk.len() as u64);
+    if start < end {
+        chunk[(start - position) as usize..(end - position) as usize]
+            .copy_from_slice(&replacement[(start - offset) as usize..(end - offset) as usize]);
+    }
+}
+
+/// ISO being received, hashed as it is written. The file is removed unless the image
+/// gets registered.
+pub struct ImageUpload {
+    id: Uuid,
+    file: fs::File,
+    /// The temporary file, or the ISO once it has been moved into place.
+    path: PathBuf,
+    is_registered: bool,
+    hasher: Sha256,
+    size_bytes: u64,
+    max_size_bytes: u64,
+}
+
+impl ImageUpload {
+    /// Returns `false` without writing once the upload would exceed the size limit.
+    pub async fn write(&mut self, chunk: &[u8]) -> ServiceResult<bool> {
+        if self.size_bytes + chunk.len() as u64 > self.max_size_bytes {
+            return Ok(false);
+        }
+        self.append(chunk).await?;
+        Ok(true)
+    }
+
+    async fn append(&mut self, chunk: &[u8]) -> ServiceResult<()> {
+        self.file.write_all(chunk).await?;
+        self.hasher.update(chunk);
+        self.size_bytes += chunk.len() as u64;
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size_bytes == 0
+    }
+}
+
+impl Drop for ImageUpload {
+    fn drop(&mut self) {
+        if !self.is_registered {
+            let _ = std::fs::remove_file(&self.path);
+        }
     }
 }
@@ -0,0 +1,142 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::{DateTime, Utc};
+
+use crate::{
+    config::LockoutConfig,
+    error::ServiceResult,
+    models::{LoginThrottle, LoginThrottleKind},
+    repositories::login_throttle::LoginThrottleRepository,
+    validation::MAX_TEXT_LENGTH,
+};
+
+/// Tracks failed logins per account and per client address and locks either out with an
+/// exponentially growing delay once their free attempts are used up. Attempts count
+/// within a window from the first failure, so old failures do not count forever.
+#[derive(Clone)]
+pub struct LoginThrottleService {
+    login_throttle_repository: Arc<dyn LoginThrottleRepository>,
+    lockout_config: LockoutConfig,
+}
+
+impl LoginThrottleService {
+    pub fn new(
+        login_throttle_repository: Arc<dyn LoginThrottleRepository>,
+        lockout_config: LockoutConfig,
+    ) -> Self {
+        Self {
+            login_throttle_repository,
+            lockout_config,
+        }
+    }
+}
+
+impl LoginThrottleService {
+    /// Returns how long the account or the address still has to wait, if it is locked.
+    pub async fn find_lockout(
+        &self,
+        email: &str,
+        ip_address: Option<&str>,
+    ) -> ServiceResult<Option<Duration>> {
+        let mut lockout = None;
+        for (kind, subject) in subjects(email, ip_address) {
+            if let Some(login_throttle) =
+                self.login_throttle_repository.find(kind, &subject).await?
+            {
+                lockout = lockout.max(remaining_lockout(&login_throttle));
+            }
+        }
+        Ok(lockout)
+    }
+
+    /// Counts a failed login and returns the lockout it caused, if any.
+    pub async fn record_failure(
+        &self,
+        email: &str,
+        ip_address: Option<&str>,
+    ) -> ServiceResult<Option<Duration>> {
+        let mut lockout = None;
+        for (kind, subject) in subjects(email, ip_address) {
+            let login_throttle = self
+                .login_throttle_repository
+                .increment(kind, &subject, self.window_started_before())
+                .await?;
+            let free_attempts = match kind {
+                LoginThrottleKind::Account => self.lockout_config.account_free_attempts,
+                LoginThrottleKind::IpAddress => self.lockout_config.ip_address_free_attempts,
+            };
+            let failed_attempts = login_throttle.failed_attempts as u32;
+            if let Some(excess_attempts) =
+                failed_attempts.checked_sub(free_attempts.saturating_add(1))
+            {
+                let duration = self.lockout_duration(excess_attempts);
+                let locked_until = chrono::Duration::from_std(duration)
+                    .ok()
+                    .and_then(|duration| Utc::now().checked_add_signed(duration))
+                    .unwrap_or(DateTime::<Utc>::MAX_UTC);
+                self.login_throttle_repository
+                    .lock(kind, &subject, locked_until)
+                    .await?;
+                lockout = lockout.max(Some(duration));
+            }
+        }
+        Ok(lockout)
+    }
+
+    /// Resets the failed attempts of the account. The address keeps its count, so a
+    /// valid login cannot be used to keep guessing other accounts.
+    pub async fn record_success(&self, email: &str) -> ServiceResult<()> {
+        self.unlock_account(email).await
+    }
+
+    pub async fn unlock_account(&self, email: &str) -> ServiceResult<()> {
+        self.login_throttle_repository
+            .delete(LoginThrottleKind::Account, &account_subject(email))
+            .await?;
+        Ok(())
+    }
+
+    /// Deletes the throttles whose window has passed and that are not locked, most of
+    /// them left behind by mistyped or made up emails.
+    pub async fn purge_stale(&self) -> ServiceResult<u64> {
+        Ok(self
+            .login_throttle_repository
+            .delete_stale(self.window_started_before())
+            .await?)
+    }
+
+    fn window_started_before(&self) -> DateTime<Utc> {
+        Utc::now() - self.lockout_config.failure_window()
+    }
+
+    fn lockout_duration(&self, excess_attempts: u32) -> Duration {
+        let factor = 1u64.checked_shl(excess_attempts).unwrap_or(u64::MAX);
+        let secs = self
+            .lockout_config
+            .base_lockout_secs
+            .saturating_mul(factor)
+            .min(self.lockout_config.max_lockout_secs);
+        Duration::from_secs(secs)
+    }
+}
+
+fn subjects(email: &str, ip_address: Option<&str>) -> Vec<(LoginThrottleKind, String)> {
+    let mut subjects = vec![(LoginThrottleKind::Account, account_subject(email))];
+    if let Some(ip_address) = ip_address {
+        subjects.push((LoginThrottleKind::IpAddress, String::from(ip_address)));
+    }
+    subjects
+}
+
+/// Cut to fit the column. Emails that long cannot belong to an account, so whatever
+/// they collide with cannot either.
+fn account_subject(email: &str) -> String {
+    email.to_lowercase().chars().take(MAX_TEXT_LENGTH).collect()
+}
+
+fn remaining_lockout(login_throttle: &LoginThrottle) -> Option<Duration> {
+    login_throttle
+        .locked_until
+        .filter(|_| login_throttle.is_locked())
+        .and_then(|locked_until| (locked_until - Utc::now()).to_std().ok())
+}
@@ -0,0 +1,247 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use data_encoding::{BASE32_NOPAD, BASE32HEX_NOPAD};
+use hmac::{Hmac, Mac};
+use percent_encoding::{NON_ALPHANUMERIC, utf8_percent_encode};
+use sha1::Sha1;
+use uuid::Uuid;
+
+use crate::{
+    config::AuthConfig,
+    error::ServiceResult,
+    models::User,
+    repositories::{
+        recovery_code::RecoveryCodeRepository, totp_credential::TotpCredentialRepository,
+    },
+    services::auth::{generate_byte_key, generate_hash, is_valid_password},
+};
+
+// RFC 6238 defaults, the only parameters most authenticator apps support
+const TOTP_DIGITS: usize = 6;
+const TOTP_STEP_SECS: i64 = 30;
+// codes from one step before or after are accepted to allow for clock drift
+const TOTP_ALLOWED_SKEW_STEPS: i64 = 1;
+const TOTP_SECRET_LENGTH: usize = 20;
+const RECOVERY_CODE_COUNT: usize = 10;
+const RECOVERY_CODE_LENGTH: usize = 10;
+
+pub struct MfaStatus {
+    pub is_totp_enabled: bool,
+    pub recovery_codes_remaining: usize,
+}
+
+pub struct TotpEnrollment {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+#[derive(Clone)]
+pub struct MfaService {
+    totp_credential_repository: Arc<dyn TotpCredentialRepository>,
+    recovery_code_repository: Arc<dyn RecoveryCodeRepository>,
+    auth_config: AuthConfig,
+}
+
+impl MfaService {
+    pub fn new(
+        totp_credential_repository: Arc<dyn TotpCredentialRepository>,
+        recovery_code_repository: Arc<dyn RecoveryCodeRepository>,
+        auth_config: AuthConfig,
+    ) -> Self {
+        Self {
+            totp_credential_repository,
+            recovery_code_repository,
+            auth_config,
+        }
+    }
+}
+
+impl MfaService {
+    pub async fn is_enabled(&self, user_id: Uuid) -> ServiceResult<bool> {
+        Ok(self
+            .totp_credential_repository
+            .find_by_user_id(user_id)
+            .await?
+            .is_some_and(|totp_credential| totp_credential.is_confirmed()))
+    }
+
+    pub async fn find_status(&self, user_id: Uuid) -> ServiceResult<MfaStatus> {
+        let is_totp_enabled = self.is_enabled(user_id).await?;
+        let recovery_codes_remaining = self
+            .recovery_code_repository
+            .find_unused_by_user_id(user_id)
+            .await?
+            .len();
+        Ok(MfaStatus {
+            is_totp_enabled,
+            recovery_codes_remaining,
+        })
+    }
+
+    /// Generates a fresh secret that has to be confirmed with a code before it is used.
+    /// Returns `None` if the user already has a confirmed one.
+    pub async fn start_totp_enrollment(
+        &self,
+        user: &User,
+    ) -> ServiceResult<Option<TotpEnrollment>> {
+        if self.is_enabled(user.id).await? {
+            return Ok(None);
+        }
+        let secret = generate_byte_key::<TOTP_SECRET_LENGTH>().to_vec();
+        let encoded_secret = BASE32_NOPAD.encode(&secret);
+        self.totp_credential_repository
+            .upsert(user.id, secret)
+            .await?;
+        let issuer = utf8_percent_encode(&self.auth_config.totp_issuer, NON_ALPHANUMERIC);
+        let account = utf8_percent_encode(&user.email, NON_ALPHANUMERIC);
+        let otpauth_uri = format!(
+            "otpauth://totp/{issuer}:{account}?secret={encoded_secret}&issuer={issuer}&algorithm=SHA1&digits={TOTP_DIGITS}&period={TOTP_STEP_SECS}"
+        );
+        Ok(Some(TotpEnrollment {
+            secret: encoded_secret,
+            otpauth_uri,
+        }))
+    }
+
+    /// Enables the pending secret if `code` matches it and hands out a new set of recovery
+    /// codes. Returns `None` if there is nothing pending or the code is wrong.
+    pub async fn confirm_totp_enrollment(
+        &self,
+        user_id: Uuid,
+        code: &str,
+    ) -> ServiceResult<Option<Vec<String>>> {
+        match self
+            .totp_credential_repository
+            .find_by_user_id(user_id)
+            .await?
+        {
+            Some(totp_credential) if !totp_credential.is_confirmed() => {}
+            _ => return Ok(None),
+        }
+        if !self.verify_totp(user_id, code).await? {
+            return Ok(None);
+        }
+        self.totp_credential_repository.confirm(user_id).await?;
+        Ok(Some(self.replace_recovery_codes(user_id).await?))
+    }
+
+    /// Checks `code` as a TOTP code and, failing that, as an unused recovery code, which
+    /// is used up by a successful check.
+    pub async fn verify(&self, user_id: Uuid, code: &str) -> ServiceResult<bool> {
+        if !self.is_enabled(user_id).await? {
+            return Ok(false);
+        }
+        if self.verify_totp(user_id, code).await? {
+            return Ok(true);
+        }
+        let normalized_code = normalize_recovery_code(code);
+        for recovery_code in self
+            .recovery_code_repository
+            .find_unused_by_user_id(user_id)
+            .await?
+        {
+            if is_valid_password(&normalized_code, &recovery_code.code_hash) {
+                return Ok(self
+                    .recovery_code_repository
+                    .mark_as_used(recovery_code.id)
+                    .await?
+                    .is_some());
+            }
+        }
+        Ok(false)
+    }
+
+    /// Returns `None` if the user has no second factor the codes could stand in for.
+    pub async fn regenerate_recovery_codes(
+        &self,
+        user_id: Uuid,
+    ) -> ServiceResult<Option<Vec<String>>> {
+        if !self.is_enabled(user_id).await? {
+            return Ok(None);
+        }
+        Ok(Some(self.replace_recovery_codes(user_id).await?))
+    }
+
+    /// Removes the TOTP secret and recovery codes. Returns `false` if there were none.
+    pub async fn disable(&self, user_id: Uuid) -> ServiceResult<bool> {
+        let totp_credential = self.totp_credential_repository.delete(user_id).await?;
+        let deleted_recovery_codes = self
+            .recovery_code_repository
+            .delete_all_by_user_id(user_id)
+            .await?;
+        Ok(totp_credential.is_some() || deleted_recovery_codes > 0)
+    }
+
+    async fn verify_totp(&self, user_id: Uuid, code: &str) -> ServiceResult<bool> {
+        let code: String = code.chars().filter(|c| !c.is_whitespace()).collect();
+        if code.len() != TOTP_DIGITS || !code.chars().all(|c| c.is_ascii_digit()) {
+            return Ok(false);
+        }
+        let Some(totp_credential) = self
+            .totp_credential_repository
+            .find_by_user_id(user_id)
+            .await?
+        else {
+            return Ok(false);
+        };
+        let current_step = Utc::now().timestamp() / TOTP_STEP_SECS;
+        let Some(step) = (current_step - TOTP_ALLOWED_SKEW_STEPS
+            ..=current_step + TOTP_ALLOWED_SKEW_STEPS)
+            .find(|step| totp_code(&totp_credential.secret, *step) == code)
+        else {
+            return Ok(false);
+        };
+        Ok(self
+            .totp_credential_repository
+            .use_step(user_id, step)
+            .await?
+            .is_some())
+    }
+
+    async fn replace_recovery_codes(&self, user_id: Uuid) -> ServiceResult<Vec<String>> {
+        let mut recovery_codes = Vec::with_capacity(RECOVERY_CODE_COUNT);
+        let mut code_hashes = Vec::with_capacity(RECOVERY_CODE_COUNT);
+        for _ in 0..RECOVERY_CODE_COUNT {
+            let raw_code = BASE32HEX_NOPAD
+                .encode(&generate_byte_key::<6>())
+                .to_lowercase();
+            let raw_code = &raw_code[..RECOVERY_CODE_LENGTH];
+            code_hashes.push(generate_hash(raw_code)?);
+            let (head, tail) = raw_code.split_at(RECOVERY_CODE_LENGTH / 2);
+            recovery_codes.push(format!("{head}-{tail}"));
+        }
+        self.recovery_code_repository
+            .replace_all(user_id, code_hashes)
+            .await?;
+        Ok(recovery_codes)
+    }
+}
+
+/// HOTP value (RFC 4226) of `secret` for the time step `step`, zero padded.
+pub(crate) fn totp_code(secret: &[u8], step: i64) -> String {
+    let mut mac = Hmac::<Sha1>::new_from_slice(secret).expect("HMAC can take a key of any size");
+    mac.update(&step.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+    let offset = usize::from(digest[digest.len() - 1] & 0x0f);
+    let truncated = u32::from_be_bytes([
+        digest[offset],
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]) & 0x7fff_ffff;
+    format!(
+        "{:0width$}",
+        truncated % 10u32.pow(TOTP_DIGITS as u32),
+        width = TOTP_DIGITS
+    )
+}
+
+/// Recovery codes are handed out as `xxxxx-xxxxx` but accepted in any case and without
+/// the dash.
+fn normalize_recovery_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_lowercase()
+}
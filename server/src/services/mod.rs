@@ -1,3 +1,16 @@
+pub mod api_key;
 pub mod auth;
+pub mod batch;
+pub mod boot;
 pub mod image;
+pub mod login_throttle;
+pub mod mfa;
+pub mod oidc;
+pub mod organization;
+pub mod password_reset;
+pub mod rate_limit;
+pub mod report;
+pub mod session;
+pub mod signing_key;
+pub mod station;
 pub mod user;
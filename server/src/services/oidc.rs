@@ -0,0 +1,418 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use base64::{Engine, prelude::BASE64_URL_SAFE_NO_PAD};
+use chrono::Utc;
+use jsonwebtoken::{
+    Algorithm, DecodingKey, Validation, decode, decode_header,
+    errors::ErrorKind,
+    jwk::{Jwk, JwkSet},
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    config::{OidcConfig, ServerConfig},
+    error::ServiceResult,
+    models::{Role, User},
+    repositories::{
+        organization::OrganizationRepository, user::UserRepository,
+        user_identity::UserIdentityRepository,
+    },
+    services::auth::{AuthService, ISSUER, generate_byte_key, generate_unusable_password_hash},
+};
+
+const DISCOVERY_PATH: &str = "/.well-known/openid-configuration";
+// where the api router serves `oidc_callback`, registered at the provider as redirect uri
+const CALLBACK_PATH: &str = "/api/auth/oidc/callback";
+// audience of login state tokens, which keeps them from passing as any other token
+const OIDC_LOGIN_AUDIENCE: &str = "oidc-login";
+const LOGIN_LIFETIME: Duration = Duration::from_secs(60 * 10);
+const KEY_LENGTH: usize = 32;
+// RFC 8176 method reported by providers for logins that passed a second factor
+const MFA_METHOD: &str = "mfa";
+
+#[derive(Clone, Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+#[derive(Default)]
+struct ProviderCache {
+    metadata: Option<ProviderMetadata>,
+    jwks: Option<JwkSet>,
+}
+
+/// Carries what the callback needs to finish a login through the browser of the user,
+/// signed so it can't be swapped out.
+#[derive(Serialize, Deserialize)]
+struct OidcLoginClaims {
+    state: String,
+    nonce: String,
+    code_verifier: String,
+    aud: String,
+    iss: String,
+    exp: usize,
+    iat: usize,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+#[derive(Deserialize)]
+struct IdTokenClaims {
+    iss: String,
+    sub: String,
+    email: Option<String>,
+    #[serde(default)]
+    email_verified: bool,
+    nonce: Option<String>,
+    #[serde(default)]
+    amr: Vec<String>,
+    #[serde(flatten)]
+    other: HashMap<String, serde_json::Value>,
+}
+
+impl IdTokenClaims {
+    /// Providers send a list of groups, or a single string for users in one group.
+    fn groups(&self, claim: &str) -> Vec<&str> {
+        match self.other.get(claim) {
+            Some(serde_json::Value::Array(groups)) => {
+                groups.iter().filter_map(|group| group.as_str()).collect()
+            }
+            Some(serde_json::Value::String(group)) => vec![group.as_str()],
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Where to send the browser to sign in at the provider, along with the login state
+/// to hand back to `finish_login` once it returns.
+pub struct OidcAuthorization {
+    pub url: String,
+    pub state_token: String,
+}
+
+pub struct OidcLogin {
+    pub user: User,
+    pub is_mfa_verified: bool,
+}
+
+#[derive(Clone)]
+pub struct OidcService {
+    user_repository: Arc<dyn UserRepository>,
+    user_identity_repository: Arc<dyn UserIdentityRepository>,
+    organization_repository: Arc<dyn OrganizationRepository>,
+    auth_service: AuthService,
+    http_client: reqwest::Client,
+    cache: Arc<RwLock<ProviderCache>>,
+    oidc_config: OidcConfig,
+    redirect_url: String,
+}
+
+impl OidcService {
+    pub fn new(
+        user_repository: Arc<dyn UserRepository>,
+        user_identity_repository: Arc<dyn UserIdentityRepository>,
+        organization_repository: Arc<dyn OrganizationRepository>,
+        auth_service: AuthService,
+        oidc_config: OidcConfig,
+        server_config: &ServerConfig,
+    ) -> Self {
+        let redirect_url = format!(
+            "{}{}",
+            server_config.public_url.trim_end_matches('/'),
+            CALLBACK_PATH
+        );
+        Self {
+            user_repository,
+            user_identity_repository,
+            organization_repository,
+            auth_service,
+            http_client: reqwest::Client::new(),
+            cache: Arc::new(RwLock::new(ProviderCache::default())),
+            oidc_config,
+            redirect_url,
+        }
+    }
+}
+
+impl OidcService {
+    pub fn is_enabled(&self) -> bool {
+        self.oidc_config.enabled
+    }
+
+    /// Starts an authorization code flow with PKCE.
+    pub async fn start_login(&self) -> ServiceResult<OidcAuthorization> {
+        let metadata = self.metadata().await?;
+        let now = Utc::now();
+        let claims = OidcLoginClaims {
+            state: random_token(),
+            nonce: random_token(),
+            code_verifier: random_token(),
+            aud: String::from(OIDC_LOGIN_AUDIENCE),
+            iss: String::from(ISSUER),
+            exp: (now + LOGIN_LIFETIME).timestamp() as usize,
+            iat: now.timestamp() as usize,
+        };
+        let code_challenge =
+            BASE64_URL_SAFE_NO_PAD.encode(Sha256::digest(claims.code_verifier.as_bytes()));
+        let scope = self.oidc_config.scopes.join(" ");
+        let request = self
+            .http_client
+            .get(&metadata.authorization_endpoint)
+            .query(&[
+                ("response_type", "code"),
+                ("client_id", &self.oidc_config.client_id),
+                ("redirect_uri", &self.redirect_url),
+                ("scope", &scope),
+                ("state", &claims.state),
+                ("nonce", &claims.nonce),
+                ("code_challenge", &code_challenge),
+                ("code_challenge_method", "S256"),
+            ])
+            .build()?;
+        Ok(OidcAuthorization {
+            url: request.url().to_string(),
+            state_token: self.auth_service.sign(&claims).await?,
+        })
+    }
+
+    /// Exchanges the code the provider redirected back with and resolves the user it
+    /// signed in. `None` if the login can't be completed or the user isn't known and
+    /// can't be provisioned.
+    pub async fn finish_login(
+        &self,
+        state_token: &str,
+        state: &str,
+        code: &str,
+    ) -> ServiceResult<Option<OidcLogin>> {
+        let Some(login) = self
+            .auth_service
+            .verify::<OidcLoginClaims>(state_token, Some(OIDC_LOGIN_AUDIENCE))
+            .await?
+        else {
+            return Ok(None);
+        };
+        if login.state != state {
+            return Ok(None);
+        }
+        let metadata = self.metadata().await?;
+        let response = self
+            .http_client
+            .post(&metadata.token_endpoint)
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", &self.redirect_url),
+                ("client_id", &self.oidc_config.client_id),
+                ("client_secret", &self.oidc_config.client_secret),
+                ("code_verifier", &login.code_verifier),
+            ])
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            tracing::warn!(status = %response.status(), "oidc provider rejected the code");
+            return Ok(None);
+        }
+        let token_response = response.json::<TokenResponse>().await?;
+        let Some(claims) = self
+            .validate_id_token(&metadata, &token_response.id_token)
+            .await?
+        else {
+            tracing::warn!("oidc provider returned an invalid id token");
+            return Ok(None);
+        };
+        if claims.nonce.as_deref() != Some(login.nonce.as_str()) {
+            return Ok(None);
+        }
+        let is_mfa_verified = claims.amr.iter().any(|method| method == MFA_METHOD);
+        Ok(self.resolve_user(&claims).await?.map(|user| OidcLogin {
+            user,
+            is_mfa_verified,
+        }))
+    }
+
+    async fn validate_id_token(
+        &self,
+        metadata: &ProviderMetadata,
+        id_token: &str,
+    ) -> ServiceResult<Option<IdTokenClaims>> {
+        let Ok(header) = decode_header(id_token) else {
+            return Ok(None);
+        };
+        // shared secret algorithms would let anyone holding the client secret sign tokens
+        if matches!(
+            header.alg,
+            Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512
+        ) {
+            return Ok(None);
+        }
+        let Some(jwk) = self.find_jwk(metadata, header.kid.as_deref()).await? else {
+            return Ok(None);
+        };
+        let Ok(decoding_key) = DecodingKey::from_jwk(&jwk) else {
+            return Ok(None);
+        };
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&metadata.issuer]);
+        validation.set_audience(&[&self.oidc_config.client_id]);
+        Ok(
+            decode::<IdTokenClaims>(id_token, &decoding_key, &validation)
+                .ok()
+                .map(|token_data| token_data.claims),
+        )
+    }
+
+    /// Users are found through the identity linked to their subject. Unlinked subjects
+    /// are linked to the account with their verified email, or provisioned if enabled.
+    async fn resolve_user(&self, claims: &IdTokenClaims) -> ServiceResult<Option<User>> {
+        let mapped_role = self.mapped_role(claims);
+        let user = match self
+            .user_identity_repository
+            .find_by_subject(&claims.iss, &claims.sub)
+            .await?
+        {
+            Some(user_identity) => {
+                self.user_repository
+                    .find_by_id(user_identity.user_id)
+                    .await?
+            }
+            None => self.provision_user(claims, mapped_role).await?,
+        };
+        let Some(user) = user else {
+            return Ok(None);
+        };
+        match mapped_role {
+            Some(role) if role != user.role => Ok(Some(
+                self.user_repository
+                    .update(user.id, None, Some(role))
+                    .await?,
+            )),
+            _ => Ok(Some(user)),
+        }
+    }
+
+    /// A local account that already uses the email is left alone, as it may belong to
+    /// anyone who controls an identity with that email at the provider.
+    async fn provision_user(
+        &self,
+        claims: &IdTokenClaims,
+        mapped_role: Option<Role>,
+    ) -> ServiceResult<Option<User>> {
+        let Some(email) = claims.email.clone().filter(|_| claims.email_verified) else {
+            tracing::warn!(subject = %claims.sub, "oidc login without a verified email");
+            return Ok(None);
+        };
+        if self.user_repository.find_by_email(&email).await?.is_some() {
+            tracing::warn!(subject = %claims.sub, "oidc identity matches a local account that is not linked to it");
+            return Ok(None);
+        }
+        let (true, Some(organization_id)) = (
+            self.oidc_config.jit_provisioning,
+            self.oidc_config.jit_organization_id,
+        ) else {
+            return Ok(None);
+        };
+        let password_hash = generate_unusable_password_hash()?;
+        let role = mapped_role.unwrap_or(self.oidc_config.default_role);
+        let user = self
+            .user_repository
+            .create(email, password_hash, role)
+            .await?;
+        self.organization_repository
+            .add_member(organization_id, user.id)
+            .await?;
+        self.user_identity_repository
+            .create(user.id, claims.iss.clone(), claims.sub.clone())
+            .await?;
+        tracing::info!(user_id = %user.id, "provisioned user from oidc login");
+        Ok(Some(user))
+    }
+
+    fn mapped_role(&self, claims: &IdTokenClaims) -> Option<Role> {
+        let role_mappings = &self.oidc_config.role_mappings;
+        if role_mappings.is_empty() {
+            return None;
+        }
+        let groups = claims.groups(&self.oidc_config.groups_claim);
+        let role = Role::highest_mapped(role_mappings, groups);
+        Some(role.unwrap_or(self.oidc_config.default_role))
+    }
+
+    async fn metadata(&self) -> ServiceResult<ProviderMetadata> {
+        if let Some(metadata) = self.cache.read().unwrap().metadata.clone() {
+            return Ok(metadata);
+        }
+        let discovery_url = format!(
+            "{}{}",
+            self.oidc_config.issuer_url.trim_end_matches('/'),
+            DISCOVERY_PATH
+        );
+        let metadata = self
+            .http_client
+            .get(discovery_url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<ProviderMetadata>()
+            .await?;
+        // the issuer vouches for the endpoints and keys, so it must be the one configured
+        if metadata.issuer.trim_end_matches('/')
+            != self.oidc_config.issuer_url.trim_end_matches('/')
+        {
+            tracing::error!(
+                issuer = metadata.issuer,
+                "oidc discovery names another issuer"
+            );
+            return Err(jsonwebtoken::errors::Error::from(ErrorKind::InvalidIssuer).into());
+        }
+        self.cache.write().unwrap().metadata = Some(metadata.clone());
+        Ok(metadata)
+    }
+
+    /// Refetches the key set when the key isn't known, since providers rotate keys.
+    async fn find_jwk(
+        &self,
+        metadata: &ProviderMetadata,
+        kid: Option<&str>,
+    ) -> ServiceResult<Option<Jwk>> {
+        if let Some(jwks) = &self.cache.read().unwrap().jwks
+            && let Some(jwk) = find_jwk(jwks, kid)
+        {
+            return Ok(Some(jwk));
+        }
+        let jwks = self
+            .http_client
+            .get(&metadata.jwks_uri)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<JwkSet>()
+            .await?;
+        let jwk = find_jwk(&jwks, kid);
+        self.cache.write().unwrap().jwks = Some(jwks);
+        Ok(jwk)
+    }
+}
+
+/// Tokens without a key id may only be verified against a key set of one.
+fn find_jwk(jwks: &JwkSet, kid: Option<&str>) -> Option<Jwk> {
+    match kid {
+        Some(kid) => jwks.find(kid).cloned(),
+        None if jwks.keys.len() == 1 => jwks.keys.first().cloned(),
+        None => None,
+    }
+}
+
+fn random_token() -> String {
+    BASE64_URL_SAFE_NO_PAD.encode(generate_byte_key::<KEY_LENGTH>())
+}
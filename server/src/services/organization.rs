@@ -0,0 +1,215 @@
+use std::sync::Arc;
+
+use uuid::Uuid;
+
+use crate::{
+    error::ServiceResult,
+    models::{Organization, OrganizationInvitation, OrganizationMember, User},
+    repositories::{organization::OrganizationRepository, user::UserRepository},
+    schemas::organization::ServerPostOrganizationRequest,
+};
+
+#[derive(Clone)]
+pub struct OrganizationService {
+    organization_repository: Arc<dyn OrganizationRepository>,
+    user_repository: Arc<dyn UserRepository>,
+}
+
+impl OrganizationService {
+    pub fn new(
+        organization_repository: Arc<dyn OrganizationRepository>,
+        user_repository: Arc<dyn UserRepository>,
+    ) -> Self {
+        Self {
+            organization_repository,
+            user_repository,
+        }
+    }
+}
+
+impl OrganizationService {
+    pub async fn find_organizations_by_user_id(
+        &self,
+        user_id: Uuid,
+    ) -> ServiceResult<Vec<Organization>> {
+        Ok(self
+            .organization_repository
+            .find_by_user_id(user_id)
+            .await?)
+    }
+
+    /// Returns the organization only if the given user is one of its members.
+    pub async fn find_organization_for_member(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> ServiceResult<Option<Organization>> {
+        if self
+            .organization_repository
+            .find_member(id, user_id)
+            .await?
+            .is_none()
+        {
+            return Ok(None);
+        }
+        Ok(self.organization_repository.find_by_id(id).await?)
+    }
+
+    pub async fn create_organization(
+        &self,
+        organization: ServerPostOrganizationRequest,
+        creator_id: Uuid,
+    ) -> ServiceResult<Organization> {
+        let organization = self
+            .organization_repository
+            .create(organization.0.name)
+            .await?;
+        self.organization_repository
+            .add_member(organization.id, creator_id)
+            .await?;
+        Ok(organization)
+    }
+
+    pub async fn find_members(
+        &self,
+        organization: &Organization,
+    ) -> ServiceResult<Vec<(OrganizationMember, User)>> {
+        let mut members = Vec::new();
+        for member in self
+            .organization_repository
+            .find_members(organization.id)
+            .await?
+        {
+            if let Some(user) = self.user_repository.find_by_id(member.user_id).await? {
+                members.push((member, user));
+            }
+        }
+        Ok(members)
+    }
+
+    /// Adds a user who already shares an organization with `admin_id`. Anyone else
+    /// has to be invited, which keeps admins from taking over accounts of other tenants.
+    pub async fn add_member(
+        &self,
+        organization: &Organization,
+        user_id: Uuid,
+        admin_id: Uuid,
+    ) -> ServiceResult<Result<(OrganizationMember, User), MembershipError>> {
+        let Some(user) = self.user_repository.find_by_id(user_id).await? else {
+            return Ok(Err(MembershipError::UnknownUser));
+        };
+        if !self
+            .organization_repository
+            .share_organization(admin_id, user.id)
+            .await?
+        {
+            return Ok(Err(MembershipError::UnknownUser));
+        }
+        let Some(member) = self
+            .organization_repository
+            .add_member(organization.id, user.id)
+            .await?
+        else {
+            return Ok(Err(MembershipError::AlreadyMember));
+        };
+        Ok(Ok((member, user)))
+    }
+
+    pub async fn remove_member(
+        &self,
+        organization: &Organization,
+        user_id: Uuid,
+    ) -> ServiceResult<Option<OrganizationMember>> {
+        Ok(self
+            .organization_repository
+            .remove_member(organization.id, user_id)
+            .await?)
+    }
+
+    pub async fn invite(
+        &self,
+        organization: &Organization,
+        user_id: Uuid,
+        admin_id: Uuid,
+    ) -> ServiceResult<Result<OrganizationInvitation, MembershipError>> {
+        if self.user_repository.find_by_id(user_id).await?.is_none() {
+            return Ok(Err(MembershipError::UnknownUser));
+        }
+        if self
+            .organization_repository
+            .find_member(organization.id, user_id)
+            .await?
+            .is_some()
+        {
+            return Ok(Err(MembershipError::AlreadyMember));
+        }
+        Ok(self
+            .organization_repository
+            .create_invitation(organization.id, user_id, admin_id)
+            .await?
+            .ok_or(MembershipError::AlreadyInvited))
+    }
+
+    /// Invitations to the user along with the organizations they are for.
+    pub async fn find_invitations(
+        &self,
+        user_id: Uuid,
+    ) -> ServiceResult<Vec<(OrganizationInvitation, Organization)>> {
+        let mut invitations = Vec::new();
+        for invitation in self
+            .organization_repository
+            .find_invitations_by_user_id(user_id)
+            .await?
+        {
+            if let Some(organization) = self
+                .organization_repository
+                .find_by_id(invitation.organization_id)
+                .await?
+            {
+                invitations.push((invitation, organization));
+            }
+        }
+        Ok(invitations)
+    }
+
+    /// Returns `None` if the user has no invitation to the organization.
+    pub async fn accept_invitation(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+    ) -> ServiceResult<Option<Organization>> {
+        if self
+            .organization_repository
+            .accept_invitation(organization_id, user_id)
+            .await?
+            .is_none()
+        {
+            return Ok(None);
+        }
+        Ok(self
+            .organization_repository
+            .find_by_id(organization_id)
+            .await?)
+    }
+
+    pub async fn decline_invitation(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+    ) -> ServiceResult<Option<OrganizationInvitation>> {
+        Ok(self
+            .organization_repository
+            .delete_invitation(organization_id, user_id)
+            .await?)
+    }
+}
+
+/// Why a user could not be added to or invited into an organization.
+#[derive(Debug, PartialEq, Eq)]
+pub enum MembershipError {
+    /// The user doesn't exist or, when added directly, shares no organization with the
+    /// admin.
+    UnknownUser,
+    AlreadyMember,
+    AlreadyInvited,
+}
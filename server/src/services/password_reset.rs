@@ -0,0 +1,167 @@
+use std::sync::Arc;
+
+use base64::{Engine, prelude::BASE64_URL_SAFE_NO_PAD};
+use chrono::Utc;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use crate::{
+    config::{AuthConfig, ServerConfig},
+    error::ServiceResult,
+    mailer::{Email, Mailer},
+    models::User,
+    repositories::{
+        password_reset_token::PasswordResetTokenRepository, refresh_token::RefreshTokenRepository,
+        user::UserRepository, user_identity::UserIdentityRepository,
+    },
+    services::auth::{generate_byte_key, generate_hash, is_valid_password},
+};
+
+const KEY_LENGTH: usize = 32;
+const RESET_PASSWORD_PATH: &str = "/reset-password";
+
+#[derive(Clone)]
+pub struct PasswordResetService {
+    user_repository: Arc<dyn UserRepository>,
+    password_reset_token_repository: Arc<dyn PasswordResetTokenRepository>,
+    refresh_token_repository: Arc<dyn RefreshTokenRepository>,
+    user_identity_repository: Arc<dyn UserIdentityRepository>,
+    mailer: Option<Arc<dyn Mailer>>,
+    auth_config: AuthConfig,
+    public_url: String,
+}
+
+impl PasswordResetService {
+    pub fn new(
+        user_repository: Arc<dyn UserRepository>,
+        password_reset_token_repository: Arc<dyn PasswordResetTokenRepository>,
+        refresh_token_repository: Arc<dyn RefreshTokenRepository>,
+        user_identity_repository: Arc<dyn UserIdentityRepository>,
+        mailer: Option<Arc<dyn Mailer>>,
+        auth_config: AuthConfig,
+        server_config: &ServerConfig,
+    ) -> Self {
+        Self {
+            user_repository,
+            password_reset_token_repository,
+            refresh_token_repository,
+            user_identity_repository,
+            mailer,
+            auth_config,
+            public_url: server_config.public_url.trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+impl PasswordResetService {
+    /// Resets need a way to send the link.
+    pub fn is_enabled(&self) -> bool {
+        self.mailer.is_some()
+    }
+
+    /// Emails a reset link to `email` if it belongs to a user who signs in with a local
+    /// password and silently does nothing otherwise. The work happens in the background so
+    /// neither the answer nor its timing tells which accounts exist. Links sent earlier
+    /// stop working.
+    pub fn request_reset(&self, email: &str) -> JoinHandle<()> {
+        let password_reset_service = self.clone();
+        let email = email.to_string();
+        tokio::spawn(async move {
+            if let Err(service_error) = password_reset_service.send_reset(&email).await {
+                tracing::error!("{:#?}", service_error);
+            }
+        })
+    }
+
+    async fn send_reset(&self, email: &str) -> ServiceResult<()> {
+        let Some(mailer) = &self.mailer else {
+            return Ok(());
+        };
+        let Some(user) = self.user_repository.find_by_email(email).await? else {
+            return Ok(());
+        };
+        // the password of a directory or single sign-on account is not ours to reset
+        if !self
+            .user_identity_repository
+            .find_all_by_user_id(user.id)
+            .await?
+            .is_empty()
+        {
+            return Ok(());
+        }
+        self.password_reset_token_repository
+            .delete_all_by_user_id(user.id)
+            .await?;
+        let opaque_token_raw = BASE64_URL_SAFE_NO_PAD.encode(generate_byte_key::<KEY_LENGTH>());
+        let token_hash = generate_hash(&opaque_token_raw)?;
+        let expires_at = Utc::now() + self.auth_config.password_reset_token_lifetime();
+        let password_reset_token = self
+            .password_reset_token_repository
+            .create(user.id, token_hash, expires_at)
+            .await?;
+        let composite_token = format!("{}.{}", password_reset_token.id, opaque_token_raw);
+        let lifetime_minutes = self.auth_config.password_reset_token_lifetime().as_secs() / 60;
+        let body = format!(
+            "A password reset was requested for your Open Erase account.\n\n\
+             Follow this link within {lifetime_minutes} minutes to choose a new password:\n\
+             {}{RESET_PASSWORD_PATH}?token={composite_token}\n\n\
+             If you did not ask for this, you can ignore this email.\n",
+            self.public_url
+        );
+        if let Err(mail_error) = mailer
+            .send(Email {
+                to: user.email,
+                subject: String::from("Reset your Open Erase password"),
+                body,
+            })
+            .await
+        {
+            tracing::error!(user_id = %user.id, "could not send password reset email: {mail_error}");
+        }
+        Ok(())
+    }
+
+    /// Sets `new_password` if `composite_token` is valid and unused, then signs the user
+    /// out everywhere. Returns `None` for any invalid token.
+    pub async fn confirm_reset(
+        &self,
+        composite_token: &str,
+        new_password: &str,
+    ) -> ServiceResult<Option<User>> {
+        let Some((token_id_raw, opaque_token_raw)) = composite_token.split_once('.') else {
+            return Ok(None);
+        };
+        let Ok(token_id) = Uuid::parse_str(token_id_raw) else {
+            return Ok(None);
+        };
+        let Some(password_reset_token) = self
+            .password_reset_token_repository
+            .find_by_id(token_id)
+            .await?
+        else {
+            return Ok(None);
+        };
+        if !password_reset_token.is_valid()
+            || !is_valid_password(opaque_token_raw, &password_reset_token.token_hash)
+        {
+            return Ok(None);
+        }
+        if self
+            .password_reset_token_repository
+            .mark_as_used(password_reset_token.id)
+            .await?
+            .is_none()
+        {
+            return Ok(None);
+        }
+        let password_hash = generate_hash(new_password)?;
+        let user = self
+            .user_repository
+            .update_password_hash(password_reset_token.user_id, password_hash)
+            .await?;
+        self.refresh_token_repository
+            .revoke_all_by_user_id(user.id)
+            .await?;
+        Ok(Some(user))
+    }
+}
@@ -0,0 +1,116 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::config::{BucketConfig, RateLimitConfig};
+
+// buckets are evicted once this many clients are tracked
+const MAX_TRACKED_BUCKETS: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RateLimitGroup {
+    Auth,
+    Reports,
+    Default,
+}
+
+/// Outcome of taking one request from a bucket, mirrored in the `RateLimit-*` headers.
+pub struct RateLimitDecision {
+    pub is_allowed: bool,
+    pub limit: u32,
+    pub remaining: u32,
+    /// Time until the bucket is full again.
+    pub reset: Duration,
+    /// Time until the next request would be allowed.
+    pub retry_after: Duration,
+}
+
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+#[derive(Clone)]
+pub struct RateLimitService {
+    config: RateLimitConfig,
+    buckets: Arc<Mutex<HashMap<(RateLimitGroup, String), Bucket>>>,
+}
+
+impl RateLimitService {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            buckets: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+impl RateLimitService {
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// Takes one request from the bucket of `key` in `group`.
+    pub fn check(&self, group: RateLimitGroup, key: String) -> RateLimitDecision {
+        let bucket_config = self.bucket_config(group);
+        let capacity = f64::from(bucket_config.capacity);
+        let refill_per_sec = f64::from(bucket_config.refill_per_minute) / 60.0;
+        let now = Instant::now();
+
+        let mut buckets = self.buckets.lock().unwrap();
+        let key = (group, key);
+        if buckets.len() >= MAX_TRACKED_BUCKETS && !buckets.contains_key(&key) {
+            self.evict(&mut buckets, now);
+        }
+        let bucket = buckets.entry(key).or_insert(Bucket {
+            tokens: capacity,
+            updated_at: now,
+        });
+        let elapsed = now.duration_since(bucket.updated_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * refill_per_sec).min(capacity);
+        bucket.updated_at = now;
+
+        let is_allowed = bucket.tokens >= 1.0;
+        if is_allowed {
+            bucket.tokens -= 1.0;
+        }
+        let reset = Duration::from_secs_f64((capacity - bucket.tokens) / refill_per_sec);
+        let retry_after = Duration::from_secs_f64((1.0 - bucket.tokens).max(0.0) / refill_per_sec);
+        RateLimitDecision {
+            is_allowed,
+            limit: bucket_config.capacity,
+            remaining: bucket.tokens.floor() as u32,
+            reset,
+            retry_after,
+        }
+    }
+
+    /// Drops the buckets that refilled completely, then the least recently used half if
+    /// that was not enough, so made up credentials cannot grow the map without bound.
+    fn evict(&self, buckets: &mut HashMap<(RateLimitGroup, String), Bucket>, now: Instant) {
+        buckets.retain(|(group, _), bucket| {
+            let bucket_config = self.bucket_config(*group);
+            let refill_per_sec = f64::from(bucket_config.refill_per_minute) / 60.0;
+            bucket.tokens + now.duration_since(bucket.updated_at).as_secs_f64() * refill_per_sec
+                < f64::from(bucket_config.capacity)
+        });
+        if buckets.len() >= MAX_TRACKED_BUCKETS {
+            let mut updated_ats: Vec<Instant> =
+                buckets.values().map(|bucket| bucket.updated_at).collect();
+            let middle = updated_ats.len() / 2;
+            let (_, cutoff, _) = updated_ats.select_nth_unstable(middle);
+            let cutoff = *cutoff;
+            buckets.retain(|_, bucket| bucket.updated_at > cutoff);
+        }
+    }
+
+    fn bucket_config(&self, group: RateLimitGroup) -> BucketConfig {
+        match group {
+            RateLimitGroup::Auth => self.config.auth,
+            RateLimitGroup::Reports => self.config.reports,
+            RateLimitGroup::Default => self.config.default,
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use std::sync::Arc;
+
+use uuid::Uuid;
+
+use crate::{
+    error::ServiceResult,
+    models::Report,
+    repositories::{device::DeviceRepository, report::ReportRepository},
+    schemas::report::ServerPostReportRequest,
+};
+
+#[derive(Clone)]
+pub struct ReportService {
+    report_repository: Arc<dyn ReportRepository>,
+    device_repository: Arc<dyn DeviceRepository>,
+}
+
+impl ReportService {
+    pub fn new(
+        report_repository: Arc<dyn ReportRepository>,
+        device_repository: Arc<dyn DeviceRepository>,
+    ) -> Self {
+        Self {
+            report_repository,
+            device_repository,
+        }
+    }
+}
+
+impl ReportService {
+    pub async fn find_report_by_id(
+        &self,
+        organization_id: Uuid,
+        id: Uuid,
+    ) -> ServiceResult<Option<Report>> {
+        Ok(self
+            .report_repository
+            .find_by_id(organization_id, id)
+            .await?)
+    }
+
+    /// Returns `None` if the device the report refers to does not exist.
+    pub async fn create_report(
+        &self,
+        organization_id: Uuid,
+        report: ServerPostReportRequest,
+    ) -> ServiceResult<Option<Report>> {
+        let report = report.0;
+        if self
+            .device_repository
+            .find_by_id(organization_id, report.device_id)
+            .await?
+            .is_none()
+        {
+            return Ok(None);
+        }
+        let report = self
+            .report_repository
+            .create(
+                organization_id,
+                report.device_id,
+                report.method,
+                report.result.into(),
+                report.started_at,
+                report.finished_at,
+            )
+            .await?;
+        Ok(Some(report))
+    }
+}
@@ -0,0 +1,86 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+use crate::{
+    error::ServiceResult,
+    models::Session,
+    repositories::{refresh_token::RefreshTokenRepository, session::SessionRepository},
+};
+
+pub struct PurgedSessions {
+    pub refresh_tokens: u64,
+    pub sessions: u64,
+}
+
+#[derive(Clone)]
+pub struct SessionService {
+    session_repository: Arc<dyn SessionRepository>,
+    refresh_token_repository: Arc<dyn RefreshTokenRepository>,
+}
+
+impl SessionService {
+    pub fn new(
+        session_repository: Arc<dyn SessionRepository>,
+        refresh_token_repository: Arc<dyn RefreshTokenRepository>,
+    ) -> Self {
+        Self {
+            session_repository,
+            refresh_token_repository,
+        }
+    }
+}
+
+impl SessionService {
+    pub async fn find_active_sessions(&self, user_id: Uuid) -> ServiceResult<Vec<Session>> {
+        Ok(self
+            .session_repository
+            .find_active_by_user_id(user_id)
+            .await?)
+    }
+
+    /// Only returns the session if it belongs to `user_id`.
+    pub async fn find_session(&self, user_id: Uuid, id: Uuid) -> ServiceResult<Option<Session>> {
+        Ok(self
+            .session_repository
+            .find_by_id(id)
+            .await?
+            .filter(|session| session.user_id == user_id))
+    }
+
+    pub async fn revoke_session(&self, session: &Session) -> ServiceResult<()> {
+        self.refresh_token_repository
+            .revoke_all_by_session_id(session.id)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn revoke_all_sessions(&self, user_id: Uuid) -> ServiceResult<()> {
+        self.refresh_token_repository
+            .revoke_all_by_user_id(user_id)
+            .await?;
+        Ok(())
+    }
+
+    /// Deletes refresh tokens that expired more than `retention` ago together with the
+    /// sessions left without any token.
+    pub async fn purge_expired(&self, retention: Duration) -> ServiceResult<PurgedSessions> {
+        let refresh_tokens = self
+            .refresh_token_repository
+            .delete_expired(
+                Utc::now()
+                    .checked_sub_signed(retention)
+                    .unwrap_or(DateTime::<Utc>::MIN_UTC),
+            )
+            .await?;
+        let sessions = self
+            .session_repository
+            .delete_without_refresh_tokens()
+            .await?;
+        Ok(PurgedSessions {
+            refresh_tokens,
+            sessions,
+        })
+    }
+}
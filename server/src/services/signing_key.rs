@@ -0,0 +1,311 @@
+use std::{
+    path::Path,
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+use aes_gcm::{
+    Aes256Gcm, KeyInit, Nonce,
+    aead::{Aead, AeadCore, OsRng},
+};
+use chrono::{DateTime, Utc};
+use ed25519_dalek::pkcs8::{DecodePrivateKey, EncodePrivateKey};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::{
+    config::AuthConfig,
+    error::ServiceResult,
+    models::{SigningAlgorithm, SigningKey},
+    repositories::signing_key::SigningKeyRepository,
+    services::auth::generate_byte_key,
+};
+
+const KEY_LENGTH: usize = 32;
+// other replicas pick up a rotated key at most this long after it was created
+const KEY_RING_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+// guards the database against tokens carrying made up key ids
+const KEY_RING_MIN_RELOAD_INTERVAL: Duration = Duration::from_secs(5);
+const NONCE_LENGTH: usize = 12;
+
+#[derive(Default)]
+struct KeyRing {
+    keys: Vec<SigningKey>,
+    loaded_at: Option<Instant>,
+}
+
+impl KeyRing {
+    fn is_older_than(&self, duration: Duration) -> bool {
+        self.loaded_at
+            .is_none_or(|loaded_at| loaded_at.elapsed() > duration)
+    }
+}
+
+/// Caches the active signing keys of the database. The newest key signs new access
+/// tokens while every key that has not retired yet is accepted for verification.
+/// A key from the config takes the place of the newest key for good.
+#[derive(Clone)]
+pub struct SigningKeyService {
+    signing_key_repository: Arc<dyn SigningKeyRepository>,
+    key_ring: Arc<RwLock<KeyRing>>,
+    access_token_lifetime: Duration,
+    configured_key: Option<SigningKey>,
+    key_encryption_key: Option<Aes256Gcm>,
+}
+
+impl SigningKeyService {
+    pub fn new(
+        signing_key_repository: Arc<dyn SigningKeyRepository>,
+        auth_config: &AuthConfig,
+    ) -> Result<Self, ed25519_dalek::pkcs8::Error> {
+        let configured_key = auth_config
+            .signing_key_file
+            .as_deref()
+            .map(read_signing_key_file)
+            .transpose()?;
+        Ok(Self {
+            signing_key_repository,
+            key_ring: Arc::new(RwLock::new(KeyRing::default())),
+            access_token_lifetime: auth_config.access_token_lifetime(),
+            configured_key,
+            key_encryption_key: auth_config
+                .key_encryption_key()
+                .map(|key| Aes256Gcm::new(&key.into())),
+        })
+    }
+}
+
+impl SigningKeyService {
+    pub async fn find_active_signing_keys(&self) -> ServiceResult<Vec<SigningKey>> {
+        let mut signing_keys: Vec<SigningKey> = self.configured_key.iter().cloned().collect();
+        for signing_key in self.signing_key_repository.find_active().await? {
+            signing_keys.push(self.decrypt(signing_key)?);
+        }
+        Ok(signing_keys)
+    }
+
+    /// Returns the key new access tokens are signed with, creating one on first use.
+    pub async fn current_signing_key(&self) -> ServiceResult<SigningKey> {
+        if let Some(signing_key) = &self.configured_key {
+            return Ok(signing_key.clone());
+        }
+        if self
+            .read_key_ring()
+            .is_older_than(KEY_RING_REFRESH_INTERVAL)
+        {
+            self.reload().await?;
+        }
+        if let Some(signing_key) = self.current_cached_key() {
+            return Ok(signing_key);
+        }
+        let (private_key, public_key) = generate_key_material(SigningAlgorithm::EdDsa)?;
+        let (private_key, private_key_encrypted) = self.encrypt(private_key)?;
+        let signing_key = self
+            .signing_key_repository
+            .create_first(
+                SigningAlgorithm::EdDsa,
+                private_key,
+                private_key_encrypted,
+                public_key,
+            )
+            .await?;
+        self.reload().await?;
+        self.decrypt(signing_key)
+    }
+
+    /// The keys published for verification, served from the key ring so that
+    /// fetching them neither reaches the database nor decrypts on every request.
+    pub async fn find_verification_keys(&self) -> ServiceResult<Vec<SigningKey>> {
+        if self.configured_key.is_none()
+            && self
+                .read_key_ring()
+                .is_older_than(KEY_RING_REFRESH_INTERVAL)
+        {
+            self.reload().await?;
+        }
+        let now = Utc::now();
+        let mut signing_keys: Vec<SigningKey> = self.configured_key.iter().cloned().collect();
+        signing_keys.extend(
+            self.read_key_ring()
+                .keys
+                .iter()
+                .filter(|signing_key| {
+                    signing_key
+                        .retires_at
+                        .is_none_or(|retires_at| retires_at > now)
+                })
+                .cloned(),
+        );
+        Ok(signing_keys)
+    }
+
+    /// Looks up the key an access token claims to be signed with. Unknown key ids
+    /// trigger a reload, as another replica may have rotated in the meantime.
+    pub async fn find_verification_key(&self, kid: Uuid) -> ServiceResult<Option<SigningKey>> {
+        if let Some(signing_key) = self
+            .configured_key
+            .as_ref()
+            .filter(|signing_key| signing_key.id == kid)
+        {
+            return Ok(Some(signing_key.clone()));
+        }
+        if let Some(signing_key) = self.cached_key(kid) {
+            return Ok(Some(signing_key));
+        }
+        if self
+            .read_key_ring()
+            .is_older_than(KEY_RING_MIN_RELOAD_INTERVAL)
+        {
+            self.reload().await?;
+        }
+        Ok(self.cached_key(kid))
+    }
+
+    /// Creates a new signing key. Previous keys keep verifying until every access
+    /// token they signed has expired. `None` if the signing key comes from the config.
+    pub async fn rotate(&self, algorithm: SigningAlgorithm) -> ServiceResult<Option<SigningKey>> {
+        if self.configured_key.is_some() {
+            return Ok(None);
+        }
+        let retires_at = Utc::now() + self.access_token_lifetime + KEY_RING_REFRESH_INTERVAL;
+        let (private_key, public_key) = generate_key_material(algorithm)?;
+        let (private_key, private_key_encrypted) = self.encrypt(private_key)?;
+        let signing_key = self
+            .signing_key_repository
+            .rotate(
+                algorithm,
+                private_key,
+                private_key_encrypted,
+                public_key,
+                retires_at,
+            )
+            .await?;
+        self.reload().await?;
+        Ok(Some(self.decrypt(signing_key)?))
+    }
+
+    /// Also encrypts keys stored before a key encryption key was configured.
+    async fn reload(&self) -> ServiceResult<()> {
+        let mut keys = Vec::new();
+        for mut signing_key in self.signing_key_repository.find_active().await? {
+            if self.key_encryption_key.is_some() && !signing_key.private_key_encrypted {
+                let (private_key, private_key_encrypted) = self.encrypt(signing_key.private_key)?;
+                signing_key = self
+                    .signing_key_repository
+                    .update_private_key(signing_key.id, private_key, private_key_encrypted)
+                    .await?;
+            }
+            keys.push(self.decrypt(signing_key)?);
+        }
+        let mut key_ring = self.key_ring.write().unwrap();
+        key_ring.keys = keys;
+        key_ring.loaded_at = Some(Instant::now());
+        Ok(())
+    }
+
+    /// Seals a private key for storage if a key encryption key is configured.
+    fn encrypt(&self, private_key: Vec<u8>) -> ServiceResult<(Vec<u8>, bool)> {
+        let Some(cipher) = &self.key_encryption_key else {
+            return Ok((private_key, false));
+        };
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let mut sealed = nonce.to_vec();
+        sealed.extend(cipher.encrypt(&nonce, private_key.as_slice())?);
+        Ok((sealed, true))
+    }
+
+    /// Fails for encrypted keys if the key encryption key is missing or a different one.
+    fn decrypt(&self, mut signing_key: SigningKey) -> ServiceResult<SigningKey> {
+        if !signing_key.private_key_encrypted {
+            return Ok(signing_key);
+        }
+        let cipher = self.key_encryption_key.as_ref().ok_or(aes_gcm::Error)?;
+        let (nonce, ciphertext) = signing_key
+            .private_key
+            .split_at_checked(NONCE_LENGTH)
+            .ok_or(aes_gcm::Error)?;
+        signing_key.private_key = cipher.decrypt(Nonce::from_slice(nonce), ciphertext)?;
+        signing_key.private_key_encrypted = false;
+        Ok(signing_key)
+    }
+
+    fn read_key_ring(&self) -> std::sync::RwLockReadGuard<'_, KeyRing> {
+        self.key_ring.read().unwrap()
+    }
+
+    fn current_cached_key(&self) -> Option<SigningKey> {
+        self.read_key_ring()
+            .keys
+            .iter()
+            .find(|signing_key| signing_key.retires_at.is_none())
+            .cloned()
+    }
+
+    fn cached_key(&self, kid: Uuid) -> Option<SigningKey> {
+        let now = Utc::now();
+        self.read_key_ring()
+            .keys
+            .iter()
+            .find(|signing_key| {
+                signing_key.id == kid
+                    && signing_key
+                        .retires_at
+                        .is_none_or(|retires_at| retires_at > now)
+            })
+            .cloned()
+    }
+}
+
+impl SigningKey {
+    pub fn jwt_algorithm(&self) -> Algorithm {
+        match self.algorithm {
+            SigningAlgorithm::Hs256 => Algorithm::HS256,
+            SigningAlgorithm::EdDsa => Algorithm::EdDSA,
+        }
+    }
+
+    pub fn encoding_key(&self) -> EncodingKey {
+        match self.algorithm {
+            SigningAlgorithm::Hs256 => EncodingKey::from_secret(&self.private_key),
+            SigningAlgorithm::EdDsa => EncodingKey::from_ed_der(&self.private_key),
+        }
+    }
+
+    pub fn decoding_key(&self) -> DecodingKey {
+        match (self.algorithm, &self.public_key) {
+            (SigningAlgorithm::EdDsa, Some(public_key)) => DecodingKey::from_ed_der(public_key),
+            _ => DecodingKey::from_secret(&self.private_key),
+        }
+    }
+}
+
+/// The key id is derived from the public key, so every replica publishes the same one.
+fn read_signing_key_file(path: &Path) -> Result<SigningKey, ed25519_dalek::pkcs8::Error> {
+    let signing_key = ed25519_dalek::SigningKey::read_pkcs8_pem_file(path)?;
+    let public_key = signing_key.verifying_key().to_bytes();
+    let digest: [u8; 16] = Sha256::digest(public_key)[..16].try_into().unwrap();
+    Ok(SigningKey {
+        id: uuid::Builder::from_random_bytes(digest).into_uuid(),
+        algorithm: SigningAlgorithm::EdDsa,
+        private_key: signing_key.to_pkcs8_der()?.as_bytes().to_vec(),
+        private_key_encrypted: false,
+        public_key: Some(public_key.to_vec()),
+        retires_at: None,
+        created_at: DateTime::default(),
+        updated_at: DateTime::default(),
+    })
+}
+
+fn generate_key_material(algorithm: SigningAlgorithm) -> ServiceResult<(Vec<u8>, Option<Vec<u8>>)> {
+    let secret = generate_byte_key::<KEY_LENGTH>();
+    match algorithm {
+        SigningAlgorithm::Hs256 => Ok((secret.to_vec(), None)),
+        SigningAlgorithm::EdDsa => {
+            let signing_key = ed25519_dalek::SigningKey::from_bytes(&secret);
+            let private_key = signing_key.to_pkcs8_der()?.as_bytes().to_vec();
+            let public_key = signing_key.verifying_key().to_bytes().to_vec();
+            Ok((private_key, Some(public_key)))
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use data_encoding::{BASE32_NOPAD, HEXLOWER};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::{
+    config::AuthConfig,
+    error::ServiceResult,
+    models::{ApiKeyScope, Station},
+    repositories::station::StationRepository,
+    services::{api_key::ApiKeyService, auth::generate_byte_key},
+};
+
+const ENROLLMENT_CODE_LENGTH: usize = 10;
+const ENROLLMENT_CODE_GROUP_LENGTH: usize = 4;
+const STATION_SCOPES: [ApiKeyScope; 2] = [ApiKeyScope::UploadReports, ApiKeyScope::DownloadImages];
+
+#[derive(Clone)]
+pub struct StationService {
+    station_repository: Arc<dyn StationRepository>,
+    api_key_service: ApiKeyService,
+    auth_config: AuthConfig,
+}
+
+impl StationService {
+    pub fn new(
+        station_repository: Arc<dyn StationRepository>,
+        api_key_service: ApiKeyService,
+        auth_config: AuthConfig,
+    ) -> Self {
+        Self {
+            station_repository,
+            api_key_service,
+            auth_config,
+        }
+    }
+}
+
+impl StationService {
+    pub async fn find_by_organization_id(
+        &self,
+        organization_id: Uuid,
+    ) -> ServiceResult<Vec<Station>> {
+        Ok(self
+            .station_repository
+            .find_by_organization_id(organization_id)
+            .await?)
+    }
+
+    pub async fn find_by_id(
+        &self,
+        organization_id: Uuid,
+        id: Uuid,
+    ) -> ServiceResult<Option<Station>> {
+        Ok(self
+            .station_repository
+            .find_by_id(id)
+            .await?
+            .filter(|station| station.organization_id == organization_id))
+    }
+
+    /// Registers a station and returns the code to enroll it with. Once enrolled, the
+    /// station acts with the role of `user_id`.
+    pub async fn create(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+        name: String,
+    ) -> ServiceResult<(Station, String)> {
+        let enrollment_code = generate_enrollment_code();
+        let station = self
+            .station_repository
+            .create(
+                organization_id,
+                user_id,
+                name,
+                hash_enrollment_code(&enrollment_code),
+                Utc::now() + self.auth_config.enrollment_code_lifetime(),
+            )
+            .await?;
+        Ok((station, enrollment_code))
+    }
+
+    /// A disabled station keeps its credential, which is refused until it is enabled
+    /// again.
+    pub async fn update(
+        &self,
+        organization_id: Uuid,
+        id: Uuid,
+        name: Option<String>,
+        is_disabled: Option<bool>,
+    ) -> ServiceResult<Option<Station>> {
+        if self.find_by_id(organization_id, id).await?.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(
+            self.station_repository
+                .update(id, name, is_disabled)
+                .await?,
+        ))
+    }
+
+    /// Also revokes the credential of the station.
+    pub async fn delete(&self, organization_id: Uuid, id: Uuid) -> ServiceResult<Option<Station>> {
+        if self.find_by_id(organization_id, id).await?.is_none() {
+            return Ok(None);
+        }
+        Ok(self.station_repository.delete(id).await?)
+    }
+
+    /// Revokes the credential of the station and returns a new code, for moving the
+    /// station to other hardware or recovering from a lost credential.
+    pub async fn reenroll(
+        &self,
+        organization_id: Uuid,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> ServiceResult<Option<(Station, String)>> {
+        if self.find_by_id(organization_id, id).await?.is_none() {
+            return Ok(None);
+        }
+        self.api_key_service.revoke_all_by_station_id(id).await?;
+        let enrollment_code = generate_enrollment_code();
+        let station = self
+            .station_repository
+            .reset_enrollment(
+                id,
+                user_id,
+                hash_enrollment_code(&enrollment_code),
+                Utc::now() + self.auth_config.enrollment_code_lifetime(),
+            )
+            .await?;
+        Ok(Some((station, enrollment_code)))
+    }
+
+    /// Exchanges an enrollment code for the credential of the station, which only works
+    /// together with `hardware_fingerprint`. Returns `None` for an unknown, used or
+    /// expired code.
+    pub async fn enroll(
+        &self,
+        enrollment_code: &str,
+        hardware_fingerprint: &str,
+    ) -> ServiceResult<Option<(Station, String)>> {
+        let Some(station) = self
+            .station_repository
+            .find_by_enrollment_code_hash(&hash_enrollment_code(enrollment_code))
+            .await?
+        else {
+            return Ok(None);
+        };
+        if !station.is_enrollable() {
+            return Ok(None);
+        }
+        let Some(station) = self
+            .station_repository
+            .enroll(station.id, hardware_fingerprint.to_string())
+            .await?
+        else {
+            return Ok(None);
+        };
+        let (_, key) = self
+            .api_key_service
+            .create(
+                station.organization_id,
+                station.user_id,
+                Some(station.id),
+                station.name.clone(),
+                STATION_SCOPES.to_vec(),
+                None,
+            )
+            .await?;
+        Ok(Some((station, key)))
+    }
+}
+
+/// Codes are typed in by hand on the bench, so they are shown in groups of upper case
+/// letters and digits, e.g. `ABCD-EFGH-IJKL-MNOP`.
+fn generate_enrollment_code() -> String {
+    let code = BASE32_NOPAD.encode(&generate_byte_key::<ENROLLMENT_CODE_LENGTH>());
+    code.as_bytes()
+        .chunks(ENROLLMENT_CODE_GROUP_LENGTH)
+        .map(|group| std::str::from_utf8(group).unwrap())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Ignores grouping and case, so the code may be entered however it was written down.
+fn hash_enrollment_code(enrollment_code: &str) -> String {
+    let normalized = enrollment_code
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_uppercase())
+        .collect::<String>();
+    HEXLOWER.encode(&Sha256::digest(normalized.as_bytes()))
+}
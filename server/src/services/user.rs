@@ -5,18 +5,26 @@ use uuid::Uuid;
 use crate::{
     error::ServiceResult,
     models::User,
-    repositories::user::UserRepository,
+    repositories::{organization::OrganizationRepository, user::UserRepository},
     schemas::user::{ServerPatchUserRequest, ServerPostUserRequest},
+    services::auth::generate_hash,
 };
 
 #[derive(Clone)]
 pub struct UserService {
     user_repository: Arc<dyn UserRepository>,
+    organization_repository: Arc<dyn OrganizationRepository>,
 }
 
 impl UserService {
-    pub fn new(user_repository: Arc<dyn UserRepository>) -> Self {
-        Self { user_repository }
+    pub fn new(
+        user_repository: Arc<dyn UserRepository>,
+        organization_repository: Arc<dyn OrganizationRepository>,
+    ) -> Self {
+        Self {
+            user_repository,
+            organization_repository,
+        }
     }
 }
 
@@ -25,21 +33,49 @@ impl UserService {
         Ok(self.user_repository.find_by_id(id).await?)
     }
 
+    /// Returns the user only if they are a member of the organization.
+    pub async fn find_member_by_id(
+        &self,
+        organization_id: Uuid,
+        id: Uuid,
+    ) -> ServiceResult<Option<User>> {
+        if self
+            .organization_repository
+            .find_member(organization_id, id)
+            .await?
+            .is_none()
+        {
+            return Ok(None);
+        }
+        Ok(self.user_repository.find_by_id(id).await?)
+    }
+
     pub async fn find_user_by_email(&self, email: &str) -> ServiceResult<Option<User>> {
         Ok(self.user_repository.find_by_email(email).await?)
     }
 
-    pub async fn create_user(&self, user: ServerPostUserRequest) -> ServiceResult<User> {
-        // TODO: Hash Password
-        let password_hash = user.0.password;
-        Ok(self
+    /// Creates the user as a member of the organization, which logging in requires.
+    pub async fn create_user(
+        &self,
+        organization_id: Uuid,
+        user: ServerPostUserRequest,
+    ) -> ServiceResult<User> {
+        let password_hash = generate_hash(&user.0.password)?;
+        let user = self
             .user_repository
-            .create(user.0.email, password_hash)
-            .await?)
+            .create(user.0.email, password_hash, user.0.role.into())
+            .await?;
+        self.organization_repository
+            .add_member(organization_id, user.id)
+            .await?;
+        Ok(user)
     }
 
     pub async fn update_user(&self, id: Uuid, user: ServerPatchUserRequest) -> ServiceResult<User> {
-        Ok(self.user_repository.update(id, user.0.email).await?)
+        Ok(self
+            .user_repository
+            .update(id, user.0.email, user.0.role.map(Into::into))
+            .await?)
     }
 
     pub async fn delete_user(&self, id: Uuid) -> ServiceResult<User> {
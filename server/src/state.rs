@@ -1,62 +1,294 @@
-use std::{env, sync::Arc};
+use std::sync::Arc;
 
 use sqlx::postgres::PgPoolOptions;
 
 use crate::{
-    repositories::{refresh_token::PostgresRefreshTokenRepository, user::PostgresUserRepository},
-    services::{auth::AuthService, image::ImageService, user::UserService},
+    auth_provider,
+    config::Config,
+    mailer,
+    repositories::{
+        api_key::PostgresApiKeyRepository, batch::PostgresBatchRepository,
+        device::PostgresDeviceRepository, image::PostgresImageRepository,
+        login_throttle::PostgresLoginThrottleRepository,
+        organization::PostgresOrganizationRepository,
+        password_reset_token::PostgresPasswordResetTokenRepository,
+        recovery_code::PostgresRecoveryCodeRepository,
+        refresh_token::PostgresRefreshTokenRepository, report::PostgresReportRepository,
+        session::PostgresSessionRepository, signing_key::PostgresSigningKeyRepository,
+        station::PostgresStationRepository, totp_credential::PostgresTotpCredentialRepository,
+        user::PostgresUserRepository, user_identity::PostgresUserIdentityRepository,
+    },
+    services::{
+        api_key::ApiKeyService, auth::AuthService, batch::BatchService, boot::BootService,
+        image::ImageService, login_throttle::LoginThrottleService, mfa::MfaService,
+        oidc::OidcService, organization::OrganizationService, password_reset::PasswordResetService,
+        rate_limit::RateLimitService, report::ReportService, session::SessionService,
+        signing_key::SigningKeyService, station::StationService, user::UserService,
+    },
 };
 
 #[derive(Clone)]
 pub struct AppState {
+    pub config: Arc<Config>,
+    pub api_key_service: ApiKeyService,
     pub auth_service: AuthService,
+    pub batch_service: BatchService,
+    pub boot_service: BootService,
     pub image_service: ImageService,
+    pub login_throttle_service: LoginThrottleService,
+    pub mfa_service: MfaService,
+    pub oidc_service: OidcService,
+    pub organization_service: OrganizationService,
+    pub password_reset_service: PasswordResetService,
+    pub rate_limit_service: RateLimitService,
+    pub report_service: ReportService,
+    pub session_service: SessionService,
+    pub signing_key_service: SigningKeyService,
+    pub station_service: StationService,
     pub user_service: UserService,
 }
 
 impl AppState {
-    pub async fn postgres() -> Result<Self, Box<dyn std::error::Error>> {
-        let db_url = db_url_from_envs();
-        let pool = PgPoolOptions::new().connect(&db_url).await?;
+    pub async fn postgres(config: Config) -> Result<Self, Box<dyn std::error::Error>> {
+        let pool = PgPoolOptions::new().connect(&config.database.url()).await?;
         sqlx::migrate!("./migrations").run(&pool).await?;
         let user_repository = Arc::new(PostgresUserRepository::new(pool.clone()));
         let refresh_token_repository = Arc::new(PostgresRefreshTokenRepository::new(pool.clone()));
-        let image_repository = Arc::new(crate::repositories::image::FsImageRepository);
-        let auth_service =
-            AuthService::new(user_repository.clone(), refresh_token_repository.clone());
-        let image_service = ImageService::new(image_repository.clone());
-        let user_service = UserService::new(user_repository.clone());
+        let image_repository = Arc::new(PostgresImageRepository::new(pool.clone()));
+        let batch_repository = Arc::new(PostgresBatchRepository::new(pool.clone()));
+        let device_repository = Arc::new(PostgresDeviceRepository::new(pool.clone()));
+        let report_repository = Arc::new(PostgresReportRepository::new(pool.clone()));
+        let organization_repository = Arc::new(PostgresOrganizationRepository::new(pool.clone()));
+        let session_repository = Arc::new(PostgresSessionRepository::new(pool.clone()));
+        let login_throttle_repository =
+            Arc::new(PostgresLoginThrottleRepository::new(pool.clone()));
+        let signing_key_repository = Arc::new(PostgresSigningKeyRepository::new(pool.clone()));
+        let totp_credential_repository =
+            Arc::new(PostgresTotpCredentialRepository::new(pool.clone()));
+        let recovery_code_repository = Arc::new(PostgresRecoveryCodeRepository::new(pool.clone()));
+        let password_reset_token_repository =
+            Arc::new(PostgresPasswordResetTokenRepository::new(pool.clone()));
+        let user_identity_repository = Arc::new(PostgresUserIdentityRepository::new(pool.clone()));
+        let api_key_repository = Arc::new(PostgresApiKeyRepository::new(pool.clone()));
+        let station_repository = Arc::new(PostgresStationRepository::new(pool.clone()));
+        let mailer = mailer::from_config(&config.mail)?;
+        let signing_key_service =
+            SigningKeyService::new(signing_key_repository.clone(), &config.auth)?;
+        let auth_service = AuthService::new(
+            user_repository.clone(),
+            refresh_token_repository.clone(),
+            organization_repository.clone(),
+            session_repository.clone(),
+            auth_provider::from_config(
+                &config.ldap,
+                user_repository.clone(),
+                user_identity_repository.clone(),
+                organization_repository.clone(),
+            ),
+            signing_key_service.clone(),
+            config.auth.clone(),
+        );
+        let oidc_service = OidcService::new(
+            user_repository.clone(),
+            user_identity_repository.clone(),
+            organization_repository.clone(),
+            auth_service.clone(),
+            config.oidc.clone(),
+            &config.server,
+        );
+        let password_reset_service = PasswordResetService::new(
+            user_repository.clone(),
+            password_reset_token_repository.clone(),
+            refresh_token_repository.clone(),
+            user_identity_repository.clone(),
+            mailer.clone(),
+            config.auth.clone(),
+            &config.server,
+        );
+        let api_key_service = ApiKeyService::new(
+            api_key_repository.clone(),
+            user_repository.clone(),
+            station_repository.clone(),
+        );
+        let station_service = StationService::new(
+            station_repository.clone(),
+            api_key_service.clone(),
+            config.auth.clone(),
+        );
+        let batch_service = BatchService::new(
+            batch_repository.clone(),
+            device_repository.clone(),
+            report_repository.clone(),
+        );
+        let image_service = ImageService::new(image_repository.clone(), config.images.clone());
+        let boot_service =
+            BootService::new(image_service.clone(), config.boot.clone(), &config.server);
+        let organization_service =
+            OrganizationService::new(organization_repository.clone(), user_repository.clone());
+        let report_service =
+            ReportService::new(report_repository.clone(), device_repository.clone());
+        let login_throttle_service =
+            LoginThrottleService::new(login_throttle_repository.clone(), config.lockout.clone());
+        let mfa_service = MfaService::new(
+            totp_credential_repository.clone(),
+            recovery_code_repository.clone(),
+            config.auth.clone(),
+        );
+        let rate_limit_service = RateLimitService::new(config.rate_limit.clone());
+        let session_service =
+            SessionService::new(session_repository.clone(), refresh_token_repository.clone());
+        let user_service =
+            UserService::new(user_repository.clone(), organization_repository.clone());
         Ok(Self {
+            config: Arc::new(config),
+            api_key_service,
             auth_service,
+            batch_service,
+            boot_service,
             image_service,
+            login_throttle_service,
+            mfa_service,
+            oidc_service,
+            organization_service,
+            password_reset_service,
+            rate_limit_service,
+            report_service,
+            session_service,
+            signing_key_service,
+            station_service,
             user_service,
         })
     }
 }
 
-fn db_url_from_envs() -> String {
-    let username = env::var("POSTGRES_USER").unwrap_or("postgres".into());
-    let password = env::var("POSTGRES_PASSWORD").unwrap_or("postgres".into());
-    let host = env::var("POSTGRES_HOST").unwrap_or("postgres".into());
-    let port = env::var("POSTGRES_PORT").unwrap_or("5432".into());
-    let db = env::var("POSTGRES_DB").unwrap_or("postgres".into());
-    format!("postgres://{username}:{password}@{host}:{port}/{db}")
-}
-
 #[cfg(test)]
 impl AppState {
     pub fn mock() -> Self {
+        let mut config = Config::default();
+        config.mail.transport = crate::config::MailTransport::Log;
+        Self::mock_with_config(config)
+    }
+
+    pub fn mock_with_config(mut config: Config) -> Self {
+        config.mail.directory =
+            std::env::temp_dir().join(format!("open-erase-mail-{}", uuid::Uuid::now_v7()));
+        config.images.directory =
+            std::env::temp_dir().join(format!("open-erase-images-{}", uuid::Uuid::now_v7()));
         let user_repository = Arc::new(crate::repositories::mocks::MockUserRepository::new());
         let refresh_token_repository =
-            Arc::new(crate::repositories::mocks::MockRefreshTokenRepository::new());
-        let image_repository = Arc::new(crate::repositories::image::FsImageRepository);
-        let auth_service =
-            AuthService::new(user_repository.clone(), refresh_token_repository.clone());
-        let user_service = UserService::new(user_repository.clone());
-        let image_service = ImageService::new(image_repository.clone());
+            crate::repositories::mocks::MockRefreshTokenRepository::new();
+        let session_repository = Arc::new(crate::repositories::mocks::MockSessionRepository::new(
+            refresh_token_repository.clone(),
+        ));
+        let refresh_token_repository = Arc::new(refresh_token_repository);
+        let image_repository = Arc::new(crate::repositories::mocks::MockImageRepository::new());
+        let device_repository = crate::repositories::mocks::MockDeviceRepository::new();
+        let report_repository = Arc::new(crate::repositories::mocks::MockReportRepository::new(
+            device_repository.clone(),
+        ));
+        let device_repository = Arc::new(device_repository);
+        let batch_repository = Arc::new(crate::repositories::mocks::MockBatchRepository::new());
+        let organization_repository =
+            Arc::new(crate::repositories::mocks::MockOrganizationRepository::new());
+        let signing_key_repository =
+            Arc::new(crate::repositories::mocks::MockSigningKeyRepository::new());
+        let login_throttle_repository =
+            Arc::new(crate::repositories::mocks::MockLoginThrottleRepository::new());
+        let totp_credential_repository =
+            Arc::new(crate::repositories::mocks::MockTotpCredentialRepository::new());
+        let recovery_code_repository =
+            Arc::new(crate::repositories::mocks::MockRecoveryCodeRepository::new());
+        let password_reset_token_repository =
+            Arc::new(crate::repositories::mocks::MockPasswordResetTokenRepository::new());
+        let user_identity_repository =
+            Arc::new(crate::repositories::mocks::MockUserIdentityRepository::new());
+        let api_key_repository = Arc::new(crate::repositories::mocks::MockApiKeyRepository::new());
+        let station_repository = Arc::new(crate::repositories::mocks::MockStationRepository::new());
+        let mailer = mailer::from_config(&config.mail).unwrap();
+        let signing_key_service =
+            SigningKeyService::new(signing_key_repository.clone(), &config.auth).unwrap();
+        let auth_service = AuthService::new(
+            user_repository.clone(),
+            refresh_token_repository.clone(),
+            organization_repository.clone(),
+            session_repository.clone(),
+            auth_provider::from_config(
+                &config.ldap,
+                user_repository.clone(),
+                user_identity_repository.clone(),
+                organization_repository.clone(),
+            ),
+            signing_key_service.clone(),
+            config.auth.clone(),
+        );
+        let oidc_service = OidcService::new(
+            user_repository.clone(),
+            user_identity_repository.clone(),
+            organization_repository.clone(),
+            auth_service.clone(),
+            config.oidc.clone(),
+            &config.server,
+        );
+        let password_reset_service = PasswordResetService::new(
+            user_repository.clone(),
+            password_reset_token_repository.clone(),
+            refresh_token_repository.clone(),
+            user_identity_repository.clone(),
+            mailer.clone(),
+            config.auth.clone(),
+            &config.server,
+        );
+        let api_key_service = ApiKeyService::new(
+            api_key_repository.clone(),
+            user_repository.clone(),
+            station_repository.clone(),
+        );
+        let station_service = StationService::new(
+            station_repository.clone(),
+            api_key_service.clone(),
+            config.auth.clone(),
+        );
+        let batch_service = BatchService::new(
+            batch_repository.clone(),
+            device_repository.clone(),
+            report_repository.clone(),
+        );
+        let login_throttle_service =
+            LoginThrottleService::new(login_throttle_repository.clone(), config.lockout.clone());
+        let mfa_service = MfaService::new(
+            totp_credential_repository.clone(),
+            recovery_code_repository.clone(),
+            config.auth.clone(),
+        );
+        let rate_limit_service = RateLimitService::new(config.rate_limit.clone());
+        let session_service =
+            SessionService::new(session_repository.clone(), refresh_token_repository.clone());
+        let user_service =
+            UserService::new(user_repository.clone(), organization_repository.clone());
+        let image_service = ImageService::new(image_repository.clone(), config.images.clone());
+        let boot_service =
+            BootService::new(image_service.clone(), config.boot.clone(), &config.server);
+        let organization_service =
+            OrganizationService::new(organization_repository.clone(), user_repository.clone());
+        let report_service =
+            ReportService::new(report_repository.clone(), device_repository.clone());
         Self {
+            config: Arc::new(config),
+            api_key_service,
             auth_service,
+            batch_service,
+            boot_service,
             image_service,
+            login_throttle_service,
+            mfa_service,
+            oidc_service,
+            organization_service,
+            password_reset_service,
+            rate_limit_service,
+            report_service,
+            session_service,
+            signing_key_service,
+            station_service,
             user_service,
         }
     }
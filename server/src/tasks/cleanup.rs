@@ -0,0 +1,47 @@
+use tokio::{
+    sync::watch,
+    task::JoinHandle,
+    time::{self, MissedTickBehavior},
+};
+
+use crate::{
+    config::CleanupConfig,
+    services::{login_throttle::LoginThrottleService, session::SessionService},
+};
+
+/// Periodically purges expired refresh tokens and stale login throttles until `shutdown`
+/// fires.
+pub fn spawn(
+    session_service: SessionService,
+    login_throttle_service: LoginThrottleService,
+    config: CleanupConfig,
+    mut shutdown: watch::Receiver<()>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let retention =
+            chrono::Duration::from_std(config.retention()).unwrap_or(chrono::Duration::MAX);
+        let mut interval = time::interval(config.interval());
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = shutdown.changed() => break,
+            }
+            match session_service.purge_expired(retention).await {
+                Ok(purged) => tracing::info!(
+                    refresh_tokens = purged.refresh_tokens,
+                    sessions = purged.sessions,
+                    "purged expired refresh tokens"
+                ),
+                Err(service_error) => tracing::error!("{:#?}", service_error),
+            }
+            match login_throttle_service.purge_stale().await {
+                Ok(purged) => {
+                    tracing::info!(login_throttles = purged, "purged stale login throttles")
+                }
+                Err(service_error) => tracing::error!("{:#?}", service_error),
+            }
+        }
+        tracing::info!("refresh token cleanup stopped");
+    })
+}
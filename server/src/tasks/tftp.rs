@@ -0,0 +1,229 @@
+use std::{
+    io,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
+
+use tokio::{
+    net::UdpSocket,
+    sync::{Semaphore, watch},
+    task::JoinHandle,
+    time,
+};
+
+use crate::services::boot::BootService;
+
+const OPCODE_READ_REQUEST: u16 = 1;
+const OPCODE_WRITE_REQUEST: u16 = 2;
+const OPCODE_DATA: u16 = 3;
+const OPCODE_ACK: u16 = 4;
+const OPCODE_ERROR: u16 = 5;
+const OPCODE_OPTION_ACK: u16 = 6;
+
+const ERROR_NOT_DEFINED: u16 = 0;
+const ERROR_FILE_NOT_FOUND: u16 = 1;
+const ERROR_ACCESS_VIOLATION: u16 = 2;
+const ERROR_ILLEGAL_OPERATION: u16 = 4;
+
+const DEFAULT_BLOCK_SIZE: usize = 512;
+/// Bounds of the `blksize` option from RFC 2348.
+const MIN_BLOCK_SIZE: usize = 8;
+const MAX_BLOCK_SIZE: usize = 65464;
+const RETRANSMIT_TIMEOUT: Duration = Duration::from_secs(1);
+const MAX_RETRANSMITS: u32 = 5;
+/// Each transfer holds its file in memory and answers a single unauthenticated packet,
+/// so requests beyond this many at once are turned away.
+const MAX_CONCURRENT_TRANSFERS: usize = 32;
+
+#[derive(Debug)]
+struct ReadRequest {
+    filename: String,
+    block_size: Option<usize>,
+    transfer_size: bool,
+}
+
+/// Read-only TFTP responder (RFC 1350, with the `blksize` and `tsize` options) that hands
+/// out iPXE until `shutdown` fires. Every transfer runs on its own port like the RFC asks.
+pub fn spawn(
+    socket: UdpSocket,
+    boot_service: BootService,
+    mut shutdown: watch::Receiver<()>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let local_ip = match socket.local_addr() {
+            Ok(address) => address.ip(),
+            Err(error) => {
+                tracing::error!("{:#?}", error);
+                return;
+            }
+        };
+        let transfers = Arc::new(Semaphore::new(MAX_CONCURRENT_TRANSFERS));
+        let mut buffer = [0; 2048];
+        loop {
+            let received = tokio::select! {
+                received = socket.recv_from(&mut buffer) => received,
+                _ = shutdown.changed() => break,
+            };
+            let (length, peer) = match received {
+                Ok(received) => received,
+                Err(error) => {
+                    tracing::warn!("tftp receive failed: {error}");
+                    continue;
+                }
+            };
+            let request = match parse_request(&buffer[..length]) {
+                Ok(request) => request,
+                Err((code, message)) => {
+                    let _ = socket.send_to(&error_packet(code, message), peer).await;
+                    continue;
+                }
+            };
+            let Ok(permit) = transfers.clone().try_acquire_owned() else {
+                tracing::warn!(%peer, "tftp request rejected, too many transfers running");
+                let _ = socket
+                    .send_to(&error_packet(ERROR_NOT_DEFINED, "server busy"), peer)
+                    .await;
+                continue;
+            };
+            let boot_service = boot_service.clone();
+            tokio::spawn(async move {
+                if let Err(error) = transfer(local_ip, peer, request, boot_service).await {
+                    tracing::warn!(%peer, "tftp transfer failed: {error}");
+                }
+                drop(permit);
+            });
+        }
+        tracing::info!("tftp responder stopped");
+    })
+}
+
+fn parse_request(packet: &[u8]) -> Result<ReadRequest, (u16, &'static str)> {
+    let opcode = packet
+        .get(..2)
+        .map(|opcode| u16::from_be_bytes([opcode[0], opcode[1]]));
+    match opcode {
+        Some(OPCODE_READ_REQUEST) => {}
+        Some(OPCODE_WRITE_REQUEST) => return Err((ERROR_ACCESS_VIOLATION, "read only")),
+        _ => return Err((ERROR_ILLEGAL_OPERATION, "expected a read request")),
+    }
+    let mut fields = packet[2..]
+        .split(|byte| *byte == 0)
+        .map(|field| String::from_utf8_lossy(field).into_owned());
+    let (Some(filename), Some(mode)) = (fields.next(), fields.next()) else {
+        return Err((ERROR_ILLEGAL_OPERATION, "malformed read request"));
+    };
+    if !mode.eq_ignore_ascii_case("octet") && !mode.eq_ignore_ascii_case("netascii") {
+        return Err((ERROR_ILLEGAL_OPERATION, "unsupported mode"));
+    }
+    let mut request = ReadRequest {
+        filename,
+        block_size: None,
+        transfer_size: false,
+    };
+    // unknown options are ignored, as RFC 2347 allows
+    while let (Some(name), Some(value)) = (fields.next(), fields.next()) {
+        if name.eq_ignore_ascii_case("blksize") {
+            request.block_size = value
+                .parse::<usize>()
+                .ok()
+                .map(|size| size.clamp(MIN_BLOCK_SIZE, MAX_BLOCK_SIZE));
+        } else if name.eq_ignore_ascii_case("tsize") {
+            request.transfer_size = true;
+        }
+    }
+    Ok(request)
+}
+
+async fn transfer(
+    local_ip: IpAddr,
+    peer: SocketAddr,
+    request: ReadRequest,
+    boot_service: BootService,
+) -> io::Result<()> {
+    let socket = UdpSocket::bind((local_ip, 0)).await?;
+    socket.connect(peer).await?;
+    let file = match boot_service.tftp_file(&request.filename).await {
+        Ok(Some(file)) => file,
+        Ok(None) => {
+            socket
+                .send(&error_packet(ERROR_FILE_NOT_FOUND, "file not found"))
+                .await?;
+            return Ok(());
+        }
+        Err(service_error) => {
+            tracing::error!("{:#?}", service_error);
+            socket
+                .send(&error_packet(ERROR_NOT_DEFINED, "internal error"))
+                .await?;
+            return Ok(());
+        }
+    };
+    let block_size = request.block_size.unwrap_or(DEFAULT_BLOCK_SIZE);
+    let mut options = Vec::new();
+    if request.block_size.is_some() {
+        options.push(("blksize", block_size.to_string()));
+    }
+    if request.transfer_size {
+        options.push(("tsize", file.len().to_string()));
+    }
+    if !options.is_empty() {
+        let mut option_ack = OPCODE_OPTION_ACK.to_be_bytes().to_vec();
+        for (name, value) in options {
+            option_ack.extend_from_slice(name.as_bytes());
+            option_ack.push(0);
+            option_ack.extend_from_slice(value.as_bytes());
+            option_ack.push(0);
+        }
+        if !send_until_acked(&socket, &option_ack, 0).await? {
+            return Ok(());
+        }
+    }
+    // a block shorter than the block size ends the transfer, even an empty one
+    let mut block: u16 = 1;
+    for index in 0..=file.len() / block_size {
+        let start = index * block_size;
+        let chunk = &file[start..file.len().min(start + block_size)];
+        let mut data = OPCODE_DATA.to_be_bytes().to_vec();
+        data.extend_from_slice(&block.to_be_bytes());
+        data.extend_from_slice(chunk);
+        if !send_until_acked(&socket, &data, block).await? {
+            return Ok(());
+        }
+        block = block.wrapping_add(1);
+    }
+    tracing::info!(%peer, filename = request.filename, "served over tftp");
+    Ok(())
+}
+
+/// Sends `packet` until the peer acknowledges `block`, `false` if it gives up or never does.
+async fn send_until_acked(socket: &UdpSocket, packet: &[u8], block: u16) -> io::Result<bool> {
+    let mut buffer = [0; 516];
+    for _ in 0..=MAX_RETRANSMITS {
+        socket.send(packet).await?;
+        let deadline = time::Instant::now() + RETRANSMIT_TIMEOUT;
+        // duplicate acks of earlier blocks are dropped rather than answered, so that
+        // they cannot double the traffic (the sorcerer's apprentice bug of RFC 1350)
+        while let Ok(received) = time::timeout_at(deadline, socket.recv(&mut buffer)).await {
+            if received? < 4 {
+                continue;
+            }
+            let opcode = u16::from_be_bytes([buffer[0], buffer[1]]);
+            let number = u16::from_be_bytes([buffer[2], buffer[3]]);
+            match opcode {
+                OPCODE_ACK if number == block => return Ok(true),
+                OPCODE_ERROR => return Ok(false),
+                _ => {}
+            }
+        }
+    }
+    Ok(false)
+}
+
+fn error_packet(code: u16, message: &str) -> Vec<u8> {
+    let mut packet = OPCODE_ERROR.to_be_bytes().to_vec();
+    packet.extend_from_slice(&code.to_be_bytes());
+    packet.extend_from_slice(message.as_bytes());
+    packet.push(0);
+    packet
+}
@@ -7,3 +7,143 @@ pub async fn test_request(request: Request) -> Response {
     let app = crate::routes::app(state.clone());
     app.oneshot(request).await.unwrap()
 }
+
+const ISO_SECTOR_SIZE: usize = 2048;
+
+/// Builds an ISO 9660 image with Rock Ridge names, like xorriso does for archiso, holding
+/// `files` under their paths. Every directory has to fit into a single sector.
+pub fn iso9660_image(files: &[(&str, &[u8])]) -> Vec<u8> {
+    use std::collections::BTreeSet;
+
+    let mut directories = BTreeSet::from([String::new()]);
+    for (path, _) in files {
+        let mut parent = String::new();
+        for component in path
+            .split('/')
+            .rev()
+            .skip(1)
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+        {
+            parent = if parent.is_empty() {
+                component.to_string()
+            } else {
+                format!("{parent}/{component}")
+            };
+            directories.insert(parent.clone());
+        }
+    }
+    let directories: Vec<String> = directories.into_iter().collect();
+    let directory_sector = |directory: &str| {
+        18 + directories
+            .iter()
+            .position(|other| other == directory)
+            .unwrap()
+    };
+    let mut next_sector = 18 + directories.len();
+    let file_sectors: Vec<usize> = files
+        .iter()
+        .map(|(_, content)| {
+            let sector = next_sector;
+            next_sector += content.len().div_ceil(ISO_SECTOR_SIZE).max(1);
+            sector
+        })
+        .collect();
+    let parent_of = |path: &str| {
+        path.rsplit_once('/')
+            .map_or("", |(parent, _)| parent)
+            .to_string()
+    };
+    let name_of = |path: &str| path.rsplit('/').next().unwrap().to_string();
+
+    let mut image = vec![0; next_sector * ISO_SECTOR_SIZE];
+    let mut primary = vec![1];
+    primary.extend_from_slice(b"CD001\x01");
+    primary.resize(156, 0);
+    primary.extend(iso9660_record(18, ISO_SECTOR_SIZE, true, &[0], None));
+    image[16 * ISO_SECTOR_SIZE..][..primary.len()].copy_from_slice(&primary);
+    image[17 * ISO_SECTOR_SIZE..][..7].copy_from_slice(b"\xffCD001\x01");
+    for directory in &directories {
+        let parent = if directory.is_empty() {
+            String::new()
+        } else {
+            parent_of(directory)
+        };
+        let mut records = iso9660_record(
+            directory_sector(directory),
+            ISO_SECTOR_SIZE,
+            true,
+            &[0],
+            None,
+        );
+        records.extend(iso9660_record(
+            directory_sector(&parent),
+            ISO_SECTOR_SIZE,
+            true,
+            &[1],
+            None,
+        ));
+        for child in directories.iter().filter(|child| {
+            !child.is_empty() && parent_of(child) == *directory && *child != directory
+        }) {
+            let name = name_of(child);
+            let identifier = name.to_uppercase().replace(['-', '.'], "_");
+            records.extend(iso9660_record(
+                directory_sector(child),
+                ISO_SECTOR_SIZE,
+                true,
+                identifier.as_bytes(),
+                Some(&name),
+            ));
+        }
+        for ((path, content), sector) in files.iter().zip(&file_sectors) {
+            if parent_of(path) != *directory {
+                continue;
+            }
+            let name = name_of(path);
+            let identifier = format!("{};1", name.to_uppercase().replace('-', "_"));
+            records.extend(iso9660_record(
+                *sector,
+                content.len(),
+                false,
+                identifier.as_bytes(),
+                Some(&name),
+            ));
+        }
+        assert!(records.len() <= ISO_SECTOR_SIZE);
+        image[directory_sector(directory) * ISO_SECTOR_SIZE..][..records.len()]
+            .copy_from_slice(&records);
+    }
+    for ((_, content), sector) in files.iter().zip(&file_sectors) {
+        image[sector * ISO_SECTOR_SIZE..][..content.len()].copy_from_slice(content);
+    }
+    image
+}
+
+fn iso9660_record(
+    sector: usize,
+    size: usize,
+    is_directory: bool,
+    identifier: &[u8],
+    rock_ridge_name: Option<&str>,
+) -> Vec<u8> {
+    let mut record = vec![0, 0];
+    record.extend_from_slice(&(sector as u32).to_le_bytes());
+    record.extend_from_slice(&(sector as u32).to_be_bytes());
+    record.extend_from_slice(&(size as u32).to_le_bytes());
+    record.extend_from_slice(&(size as u32).to_be_bytes());
+    record.extend_from_slice(&[0; 7]);
+    record.extend_from_slice(&[if is_directory { 2 } else { 0 }, 0, 0, 1, 0, 0, 1]);
+    record.push(identifier.len() as u8);
+    record.extend_from_slice(identifier);
+    if identifier.len().is_multiple_of(2) {
+        record.push(0);
+    }
+    if let Some(name) = rock_ridge_name {
+        record.extend_from_slice(&[b'N', b'M', 5 + name.len() as u8, 1, 0]);
+        record.extend_from_slice(name.as_bytes());
+    }
+    record[0] = record.len() as u8;
+    record
+}
@@ -0,0 +1,265 @@
+use std::str::FromStr;
+
+use axum::{
+    body::Bytes,
+    extract::{FromRequest, FromRequestParts, OptionalFromRequestParts, Path, Query, Request},
+    http::{HeaderMap, StatusCode, header, request::Parts},
+};
+use axum_extra::{TypedHeader, headers::Header};
+use open_erase_lib::schemas::error::FieldError;
+use serde::de::DeserializeOwned;
+
+use crate::{
+    config::AuthConfig,
+    error::{AppError, ClientError},
+    state::AppState,
+};
+
+pub const MAX_EMAIL_LENGTH: usize = 254;
+pub const MAX_PASSWORD_LENGTH: usize = 128;
+/// Limit of the `VARCHAR(255)` columns most text ends up in.
+pub const MAX_TEXT_LENGTH: usize = 255;
+
+/// Rules a request body has to follow beyond deserializing.
+pub trait Validate {
+    fn validate(&self, _validator: &mut Validator) {}
+}
+
+/// Collects every violation of a request, so clients can show them all at once.
+pub struct Validator<'a> {
+    auth_config: &'a AuthConfig,
+    errors: Vec<FieldError>,
+}
+
+impl<'a> Validator<'a> {
+    pub fn new(auth_config: &'a AuthConfig) -> Self {
+        Self {
+            auth_config,
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn error(&mut self, field: &str, message: impl Into<String>) {
+        self.errors.push(FieldError {
+            field: String::from(field),
+            message: message.into(),
+        });
+    }
+
+    /// Non-blank text of at most `max_length` characters.
+    pub fn text(&mut self, field: &str, value: &str, max_length: usize) {
+        if value.trim().is_empty() {
+            self.error(field, "must not be blank");
+        } else {
+            self.max_length(field, value, max_length);
+        }
+    }
+
+    pub fn max_length(&mut self, field: &str, value: &str, max_length: usize) {
+        if value.chars().count() > max_length {
+            self.error(
+                field,
+                format!("must be at most {max_length} characters long"),
+            );
+        }
+    }
+
+    pub fn email(&mut self, field: &str, value: &str) {
+        if value.len() > MAX_EMAIL_LENGTH || lettre::Address::from_str(value).is_err() {
+            self.error(field, "must be a valid email address");
+        }
+    }
+
+    pub fn password(&mut self, field: &str, value: &str) {
+        let min_length = self.auth_config.password_min_length;
+        let length = value.chars().count();
+        if length < min_length {
+            self.error(
+                field,
+                format!("must be at least {min_length} characters long"),
+            );
+        } else if length > MAX_PASSWORD_LENGTH {
+            self.error(
+                field,
+                format!("must be at most {MAX_PASSWORD_LENGTH} characters long"),
+            );
+        }
+    }
+
+    pub fn finish(self) -> Result<(), ClientError> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ClientError::Validation(self.errors))
+        }
+    }
+}
+
+/// `Json` that answers with the error envelope instead of plain text and rejects bodies
+/// that break the rules of `T` with 422.
+pub struct ValidatedJson<T>(pub T);
+
+impl<T> FromRequest<AppState> for ValidatedJson<T>
+where
+    T: DeserializeOwned + Validate,
+{
+    type Rejection = AppError;
+
+    async fn from_request(request: Request, state: &AppState) -> Result<Self, Self::Rejection> {
+        if !has_json_content_type(request.headers()) {
+            return Err(ClientError::UnsupportedMediaType.into());
+        }
+        let bytes =
+            Bytes::from_request(request, state)
+                .await
+                .map_err(|rejection| match rejection.status() {
+                    StatusCode::PAYLOAD_TOO_LARGE => ClientError::PayloadTooLarge,
+                    _ => ClientError::MalformedBody,
+                })?;
+        let value = deserialize::<T>(&bytes)?;
+        let mut validator = Validator::new(&state.config.auth);
+        value.validate(&mut validator);
+        validator.finish()?;
+        Ok(Self(value))
+    }
+}
+
+/// `Path` that answers with the error envelope. A parameter that doesn't parse, like an
+/// id that isn't a UUID, can't name an existing resource and is reported as not found.
+pub struct ValidatedPath<T>(pub T);
+
+impl<T> FromRequestParts<AppState> for ValidatedPath<T>
+where
+    T: DeserializeOwned + Send,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let Path(value) = <Path<T> as FromRequestParts<AppState>>::from_request_parts(parts, state)
+            .await
+            .map_err(|_| ClientError::NotFound)?;
+        Ok(Self(value))
+    }
+}
+
+/// `Query` that answers with the error envelope.
+pub struct ValidatedQuery<T>(pub T);
+
+impl<T> FromRequestParts<AppState> for ValidatedQuery<T>
+where
+    T: DeserializeOwned,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let Query(value) = Query::<T>::from_request_parts(parts, state)
+            .await
+            .map_err(|_| ClientError::MalformedRequest)?;
+        Ok(Self(value))
+    }
+}
+
+/// `TypedHeader` that answers with the error envelope. As an `Option` a missing header
+/// is `None` while one that doesn't parse is still rejected.
+pub struct ValidatedHeader<T>(pub T);
+
+impl<T> FromRequestParts<AppState> for ValidatedHeader<T>
+where
+    T: Header,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let TypedHeader(value) =
+            <TypedHeader<T> as FromRequestParts<AppState>>::from_request_parts(parts, state)
+                .await
+                .map_err(|_| ClientError::MalformedRequest)?;
+        Ok(Self(value))
+    }
+}
+
+impl<T> OptionalFromRequestParts<AppState> for ValidatedHeader<T>
+where
+    T: Header,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Option<Self>, Self::Rejection> {
+        let header = <TypedHeader<T> as OptionalFromRequestParts<AppState>>::from_request_parts(
+            parts, state,
+        )
+        .await
+        .map_err(|_| ClientError::MalformedRequest)?;
+        Ok(header.map(|TypedHeader(value)| Self(value)))
+    }
+}
+
+fn has_json_content_type(headers: &HeaderMap) -> bool {
+    let Some(content_type) = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+    else {
+        return false;
+    };
+    let mime_type = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    mime_type == "application/json" || mime_type.ends_with("+json")
+}
+
+/// Reports values of the wrong type or missing fields for the offending field, while
+/// anything that isn't JSON at all is a malformed body.
+fn deserialize<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, ClientError> {
+    let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+    let value = serde_path_to_error::deserialize(&mut deserializer).map_err(|error| {
+        let path = error.path().to_string();
+        let error = error.into_inner();
+        if !error.is_data() {
+            return ClientError::MalformedBody;
+        }
+        let message = error.to_string();
+        // serde reports missing fields on the object holding them
+        let field_error = match message
+            .strip_prefix("missing field `")
+            .and_then(|rest| rest.split_once('`'))
+        {
+            Some((field, _)) if path == "." => FieldError {
+                field: String::from(field),
+                message: String::from("is required"),
+            },
+            Some((field, _)) => FieldError {
+                field: format!("{path}.{field}"),
+                message: String::from("is required"),
+            },
+            None => FieldError {
+                field: path,
+                message: strip_position(&message),
+            },
+        };
+        ClientError::Validation(vec![field_error])
+    })?;
+    deserializer.end().map_err(|_| ClientError::MalformedBody)?;
+    Ok(value)
+}
+
+fn strip_position(message: &str) -> String {
+    message
+        .rsplit_once(" at line ")
+        .map_or(message, |(message, _)| message)
+        .to_string()
+}
@@ -2,9 +2,9 @@ use leptos::prelude::*;
 use leptos_router::{components::*, path};
 
 use crate::{
-    input::Input,
     login::{AuthContext, AuthProvider, Login},
     navbar::NavBar,
+    password_reset::{ForgotPassword, ResetPassword},
 };
 
 #[component]
@@ -26,6 +26,8 @@ pub fn AppRoutes() -> impl IntoView {
     view! {
         <Routes fallback=NotFound>
             <Route path=path!("login") view=Login/>
+            <Route path=path!("forgot-password") view=ForgotPassword/>
+            <Route path=path!("reset-password") view=ResetPassword/>
             <ProtectedParentRoute path=path!("")
                 view=AppLayout
                 condition=is_valid_user
@@ -1,101 +1,139 @@
-use base64::{Engine, prelude::BASE64_STANDARD};
-use gloo_net::http::Request;
 use leptos::{ev::SubmitEvent, prelude::*};
 use leptos_router::{NavigateOptions, hooks::use_navigate};
-use open_erase_lib::schemas::{
-    token::{LoginResponse, RefreshResponse},
-    user::GetUserResponse,
-};
+use open_erase_lib::client::{self, GlooBackend, LoginOutcome};
 
 use crate::{input::Input, navbar::Logo};
 
-async fn get_access_token(
-    email: String,
-    password: String,
-) -> Result<LoginResponse, gloo_net::Error> {
-    let encoded_credentials = BASE64_STANDARD.encode(format!("{}:{}", email, password));
-    Request::post("/api/auth/login")
-        .header(
-            "Authorization",
-            format!("Basic {}", encoded_credentials).as_str(),
-        )
-        .send()
-        .await?
-        .json::<LoginResponse>()
-        .await
-}
+/// Client of the server on the origin of the page.
+pub type ApiClient = client::ApiClient<GlooBackend>;
 
-async fn get_me(token: &str) -> Result<GetUserResponse, gloo_net::Error> {
-    Request::get("/api/users/me")
-        .header("Authorization", format!("Bearer {}", token).as_str())
-        .send()
-        .await?
-        .json::<GetUserResponse>()
-        .await
+async fn is_oidc_enabled(client: ApiClient) -> bool {
+    client.get_oidc().await.is_ok_and(|oidc| oidc.enabled)
 }
 
-async fn refresh() -> Result<RefreshResponse, gloo_net::Error> {
-    Request::post("/api/auth/refresh")
-        .send()
-        .await?
-        .json::<RefreshResponse>()
-        .await
+#[component]
+pub fn Login() -> impl IntoView {
+    let auth_context = use_context::<AuthContext>().unwrap();
+
+    Effect::new(move || {
+        auth_context.refresh.dispatch("/".to_string());
+    });
+
+    view! {
+        <LoginBackground>
+            <Show when=move || auth_context.mfa_challenge.get().is_some()
+                fallback=CredentialsForm
+            >
+                <MfaForm/>
+            </Show>
+        </LoginBackground>
+    }
 }
 
-async fn logout_backend() -> Result<(), gloo_net::Error> {
-    Request::post("/api/auth/logout").send().await?;
-    Ok(())
+/// Grid backdrop with the form centered, shared by the pages reachable without login.
+#[component]
+pub fn LoginBackground(children: Children) -> impl IntoView {
+    view! {
+
+        <div class="w-full h-full bg-[url('data:image/svg+xml,%3Csvg%20xmlns=%22http://www.w3.org/2000/svg%22%20width=%2220%22%20height=%2220%22%3E%3Cpath%20d=%22M0%200%20L20%200%22%20stroke=%22%23eee%22%20stroke-width=%221%22/%3E%3Cpath%20d=%22M0%200%20L0%2020%22%20stroke=%22%23eee%22%20stroke-width=%221%22/%3E%3C/svg%3E')]">
+            <div class="w-full h-full bg-radial from-transparent to-white">
+                <div class="flex items-center justify-center h-screen">
+                    {children()}
+                </div>
+            </div>
+        </div>
+    }
 }
 
 #[component]
-pub fn Login() -> impl IntoView {
+fn CredentialsForm() -> impl IntoView {
     let auth_context = use_context::<AuthContext>().unwrap();
 
     let email = RwSignal::new(String::default());
     let password = RwSignal::new(String::default());
+    let oidc_enabled = LocalResource::new(move || is_oidc_enabled(auth_context.client.get_value()));
 
     let on_submit = move |ev: SubmitEvent| {
         ev.prevent_default();
         auth_context.login.dispatch((email.get(), password.get()));
     };
 
-    Effect::new(move || {
-        auth_context.refresh.dispatch("/".to_string());
-    });
-
     view! {
+        <form class="flex flex-col p-4 rounded-lg gap-y-1 bg-white shadow-xl"
+            on:submit=on_submit
+        >
+            <Logo/>
+            <Input name="email"
+                ty="text"
+                bind=email
+                label="Email"
+                autofocus=true
+            />
+            <Input name="password"
+                ty="password"
+                bind=password
+                label="Password"
+            />
+            <div class="flex justify-end text-xs text-dark-blue hover:underline">
+                <a href="/forgot-password" class="rounded-sm">
+                    "Forgot your password?"
+                </a>
+            </div>
+            <input class="mt-1 text-dark-gray cursor-pointer py-1 bg-blue rounded-sm hover:bg-light-blue"
+                type="submit"
+                value="Submit"
+            />
+            <Show when=move || oidc_enabled.get().unwrap_or(false)>
+                // the provider login is a full page navigation, not a client side route
+                <a href="/api/auth/oidc/login"
+                    rel="external"
+                    class="mt-1 text-center text-dark-gray py-1 border border-gray rounded-sm hover:bg-light-blue"
+                >
+                    "Sign in with SSO"
+                </a>
+            </Show>
+        </form>
+    }
+}
 
-        <div class="w-full h-full bg-[url('data:image/svg+xml,%3Csvg%20xmlns=%22http://www.w3.org/2000/svg%22%20width=%2220%22%20height=%2220%22%3E%3Cpath%20d=%22M0%200%20L20%200%22%20stroke=%22%23eee%22%20stroke-width=%221%22/%3E%3Cpath%20d=%22M0%200%20L0%2020%22%20stroke=%22%23eee%22%20stroke-width=%221%22/%3E%3C/svg%3E')]">
-            <div class="w-full h-full bg-radial from-transparent to-white">
-                <div class="flex items-center justify-center h-screen">
-                    <form class="flex flex-col p-4 rounded-lg gap-y-1 bg-white shadow-xl"
-                        on:submit=on_submit
-                    >
-                        <Logo/>
-                        <Input name="email"
-                            ty="text"
-                            bind=email
-                            label="Email"
-                            autofocus=true
-                        />
-                        <Input name="password"
-                            ty="password"
-                            bind=password
-                            label="Password"
-                        />
-                        <div class="flex justify-end text-xs text-dark-blue hover:underline">
-                            <a href="/" class="rounded-sm">
-                                "Forgot your password?"
-                            </a>
-                        </div>
-                        <input class="mt-1 text-dark-gray cursor-pointer py-1 bg-blue rounded-sm hover:bg-light-blue"
-                            type="submit"
-                            value="Submit"
-                        />
-                    </form>
-                </div>
+#[component]
+fn MfaForm() -> impl IntoView {
+    let auth_context = use_context::<AuthContext>().unwrap();
+
+    let code = RwSignal::new(String::default());
+
+    let on_submit = move |ev: SubmitEvent| {
+        ev.prevent_default();
+        auth_context.verify_mfa.dispatch(code.get());
+        code.set(String::default());
+    };
+
+    let on_cancel = move |_| {
+        auth_context.mfa_challenge.set(None);
+    };
+
+    view! {
+        <form class="flex flex-col p-4 rounded-lg gap-y-1 bg-white shadow-xl"
+            on:submit=on_submit
+        >
+            <Logo/>
+            <Input name="code"
+                ty="text"
+                bind=code
+                label="Authentication code"
+                placeholder="123456 or a recovery code"
+                autofocus=true
+            />
+            <div class="flex justify-end text-xs text-dark-blue hover:underline">
+                <button type="button" class="rounded-sm cursor-pointer" on:click=on_cancel>
+                    "Back to login"
+                </button>
             </div>
-        </div>
+            <input class="mt-1 text-dark-gray cursor-pointer py-1 bg-blue rounded-sm hover:bg-light-blue"
+                type="submit"
+                value="Verify"
+            />
+        </form>
     }
 }
 
@@ -106,43 +144,75 @@ pub struct User {
 
 #[derive(Clone)]
 pub struct AuthContext {
+    /// Holds the tokens of the logged in user and refreshes them when they expire.
+    pub client: StoredValue<ApiClient>,
     pub user: RwSignal<Option<User>>,
-    pub access_token: RwSignal<Option<String>>,
+    /// Set while a login waits for its second factor.
+    pub mfa_challenge: RwSignal<Option<String>>,
     pub login: Action<(String, String), ()>,
+    pub verify_mfa: Action<String, ()>,
     pub refresh: Action<String, ()>,
     pub logout: Action<(), ()>,
 }
 
 #[component]
 pub fn AuthProvider(children: Children) -> impl IntoView {
+    let client = StoredValue::new(ApiClient::new(GlooBackend, ""));
     let user = RwSignal::new(None::<User>);
-    let access_token = RwSignal::new(None::<String>);
+    let mfa_challenge = RwSignal::new(None::<String>);
 
     let reset = move || {
+        client.read_value().clear_credentials();
         user.set(None);
-        access_token.set(None);
+        mfa_challenge.set(None);
+    };
+
+    let complete_login = move || async move {
+        let navigate = use_navigate();
+        if let Ok(user_response) = client.get_value().get_me().await {
+            user.set(Some(User {
+                email: user_response.email,
+            }));
+            mfa_challenge.set(None);
+            navigate(
+                "/",
+                NavigateOptions {
+                    resolve: false,
+                    ..Default::default()
+                },
+            );
+        } else {
+            reset();
+        }
     };
 
     let login = Action::new_local(move |input: &(String, String)| {
         let (email, password) = input.clone();
-        let navigate = use_navigate();
         async move {
-            if let Ok(login_response) = get_access_token(email, password).await
-                && let Ok(user_response) = get_me(&login_response.access_token).await
+            match client.get_value().login(&email, &password).await {
+                Ok(LoginOutcome::LoggedIn) => complete_login().await,
+                Ok(LoginOutcome::MfaRequired(challenge)) => {
+                    mfa_challenge.set(Some(challenge.challenge_token));
+                }
+                Err(_) => reset(),
+            }
+        }
+    });
+
+    // a wrong code keeps the challenge so the user can try again until it expires
+    let verify_mfa = Action::new_local(move |input: &String| {
+        let code = input.clone();
+        async move {
+            let Some(challenge_token) = mfa_challenge.get_untracked() else {
+                return;
+            };
+            if client
+                .get_value()
+                .verify_mfa(challenge_token, code)
+                .await
+                .is_ok()
             {
-                access_token.set(Some(login_response.access_token));
-                user.set(Some(User {
-                    email: user_response.email,
-                }));
-                navigate(
-                    "/",
-                    NavigateOptions {
-                        resolve: false,
-                        ..Default::default()
-                    },
-                );
-            } else {
-                reset();
+                complete_login().await;
             }
         }
     });
@@ -151,13 +221,13 @@ pub fn AuthProvider(children: Children) -> impl IntoView {
         let navigate = use_navigate();
         let input = input.clone();
         async move {
-            if let Ok(refresh_response) = refresh().await
-                && let Ok(user_response) = get_me(&refresh_response.access_token).await
+            let client = client.get_value();
+            if client.refresh().await.is_ok()
+                && let Ok(user_response) = client.get_me().await
             {
                 user.set(Some(User {
                     email: user_response.email,
                 }));
-                access_token.set(Some(refresh_response.access_token));
                 navigate(
                     input.as_str(),
                     NavigateOptions {
@@ -172,7 +242,7 @@ pub fn AuthProvider(children: Children) -> impl IntoView {
     let logout = Action::new_local(move |_| {
         let navigate = use_navigate();
         async move {
-            let _ = logout_backend().await;
+            let _ = client.get_value().logout().await;
             reset();
             navigate(
                 "/login",
@@ -185,9 +255,11 @@ pub fn AuthProvider(children: Children) -> impl IntoView {
     });
 
     let context = AuthContext {
+        client,
         user,
-        access_token,
+        mfa_challenge,
         login,
+        verify_mfa,
         refresh,
         logout,
     };
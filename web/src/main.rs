@@ -4,6 +4,7 @@ mod app;
 mod input;
 mod login;
 mod navbar;
+mod password_reset;
 
 fn main() {
     console_error_panic_hook::set_once();
@@ -0,0 +1,139 @@
+use leptos::{ev::SubmitEvent, prelude::*};
+use leptos_router::hooks::use_query_map;
+
+use crate::{
+    input::Input,
+    login::{AuthContext, LoginBackground},
+    navbar::Logo,
+};
+
+#[component]
+pub fn ForgotPassword() -> impl IntoView {
+    let auth_context = use_context::<AuthContext>().unwrap();
+    let email = RwSignal::new(String::default());
+    let is_sent = RwSignal::new(false);
+
+    let request = Action::new_local(move |email: &String| {
+        let email = email.clone();
+        async move {
+            let client = auth_context.client.get_value();
+            if client.request_password_reset(email).await.is_ok() {
+                is_sent.set(true);
+            }
+        }
+    });
+
+    let on_submit = move |ev: SubmitEvent| {
+        ev.prevent_default();
+        request.dispatch(email.get());
+    };
+
+    view! {
+        <LoginBackground>
+            <form class="flex flex-col p-4 rounded-lg gap-y-1 bg-white shadow-xl max-w-xs"
+                on:submit=on_submit
+            >
+                <Logo/>
+                <Show when=move || is_sent.get()
+                    fallback=move || view! {
+                        <Input name="email"
+                            ty="text"
+                            bind=email
+                            label="Email"
+                            autofocus=true
+                        />
+                        <input class="mt-1 text-dark-gray cursor-pointer py-1 bg-blue rounded-sm hover:bg-light-blue"
+                            type="submit"
+                            value="Send reset link"
+                        />
+                    }
+                >
+                    <p class="text-sm text-dark-gray">
+                        "If an account exists for this email, a link to reset the password is on its way."
+                    </p>
+                </Show>
+                <BackToLogin/>
+            </form>
+        </LoginBackground>
+    }
+}
+
+#[component]
+pub fn ResetPassword() -> impl IntoView {
+    let auth_context = use_context::<AuthContext>().unwrap();
+    let query = use_query_map();
+    let new_password = RwSignal::new(String::default());
+    let repeated_password = RwSignal::new(String::default());
+    let is_reset = RwSignal::new(false);
+    let error = RwSignal::new(None::<&'static str>);
+
+    let confirm = Action::new_local(move |input: &(String, String)| {
+        let (token, new_password) = input.clone();
+        async move {
+            let client = auth_context.client.get_value();
+            match client.confirm_password_reset(token, new_password).await {
+                Ok(()) => is_reset.set(true),
+                _ => error.set(Some("This link is invalid or has expired.")),
+            }
+        }
+    });
+
+    let on_submit = move |ev: SubmitEvent| {
+        ev.prevent_default();
+        if new_password.get() != repeated_password.get() {
+            error.set(Some("The passwords do not match."));
+            return;
+        }
+        error.set(None);
+        let token = query.get().get("token").unwrap_or_default();
+        confirm.dispatch((token, new_password.get()));
+    };
+
+    view! {
+        <LoginBackground>
+            <form class="flex flex-col p-4 rounded-lg gap-y-1 bg-white shadow-xl max-w-xs"
+                on:submit=on_submit
+            >
+                <Logo/>
+                <Show when=move || is_reset.get()
+                    fallback=move || view! {
+                        <Input name="new_password"
+                            ty="password"
+                            bind=new_password
+                            label="New password"
+                            autofocus=true
+                        />
+                        <Input name="repeated_password"
+                            ty="password"
+                            bind=repeated_password
+                            label="Repeat new password"
+                        />
+                        {move || error.get().map(|error| view! {
+                            <p class="text-sm text-red-600">{error}</p>
+                        })}
+                        <input class="mt-1 text-dark-gray cursor-pointer py-1 bg-blue rounded-sm hover:bg-light-blue"
+                            type="submit"
+                            value="Set password"
+                        />
+                    }
+                >
+                    <p class="text-sm text-dark-gray">
+                        "Your password was changed, you can log in with it now."
+                    </p>
+                </Show>
+                <BackToLogin/>
+            </form>
+        </LoginBackground>
+    }
+}
+
+#[component]
+fn BackToLogin() -> impl IntoView {
+    view! {
+        <div class="flex justify-end text-xs text-dark-blue hover:underline">
+            <a href="/login" class="rounded-sm">
+                "Back to login"
+            </a>
+        </div>
+    }
+}